@@ -0,0 +1,36 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Probes the compiler for `core::hint::cold_path`, which the escape-path shims use when it is
+/// available (it is newer than the crate's minimum supported Rust version). Setting
+/// `EARLY_RETURNS_NO_COLD_PATH` skips the probe, so the `cold_path` benchmark can be built without
+/// the hint for comparison.
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(has_cold_path)");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=EARLY_RETURNS_NO_COLD_PATH");
+    if env::var_os("EARLY_RETURNS_NO_COLD_PATH").is_some() {
+        return;
+    }
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let probe = out_dir.join("probe_cold_path.rs");
+    if fs::write(&probe, "pub const fn probe() { ::core::hint::cold_path() }\n").is_err() {
+        return;
+    }
+
+    let compiled = Command::new(rustc)
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata"])
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(&probe)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if compiled {
+        println!("cargo:rustc-cfg=has_cold_path");
+    }
+}
@@ -0,0 +1,197 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, Ident, Lifetime, LitStr, Token};
+
+const LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+pub(crate) struct Early {
+    from: Expr,
+    residual: Option<Ident>,
+    escape: Escape,
+    instruments: Vec<Instrument>,
+}
+
+enum Escape {
+    Return(Option<Expr>),
+    Break(Option<Lifetime>),
+    Continue(Option<Lifetime>),
+}
+
+enum Instrument {
+    Log(Ident, LitStr),
+    Trace(Ident, LitStr),
+    Count(Expr),
+    Hook(Expr),
+}
+
+impl Parse for Early {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let from = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let residual = if input.peek(Token![|]) {
+            input.parse::<Token![|]>()?;
+            let residual = input.parse()?;
+            input.parse::<Token![|]>()?;
+            Some(residual)
+        } else {
+            None
+        };
+        let escape = input.parse()?;
+        let mut instruments = Vec::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            instruments.push(input.parse()?);
+        }
+        Ok(Early {
+            from,
+            residual,
+            escape,
+            instruments,
+        })
+    }
+}
+
+impl Parse for Escape {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![return]) {
+            input.parse::<Token![return]>()?;
+            let value = if input.is_empty() || input.peek(Token![,]) {
+                None
+            } else {
+                Some(input.parse()?)
+            };
+            Ok(Escape::Return(value))
+        } else if lookahead.peek(Token![break]) {
+            input.parse::<Token![break]>()?;
+            Ok(Escape::Break(input.parse()?))
+        } else if lookahead.peek(Token![continue]) {
+            input.parse::<Token![continue]>()?;
+            Ok(Escape::Continue(input.parse()?))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for Instrument {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        match name.to_string().as_str() {
+            "log" => Ok(Instrument::Log(parse_level(input)?, input.parse()?)),
+            "trace" => Ok(Instrument::Trace(parse_level(input)?, input.parse()?)),
+            "count" => Ok(Instrument::Count(input.parse()?)),
+            "hook" => Ok(Instrument::Hook(input.parse()?)),
+            _ => Err(syn::Error::new(
+                name.span(),
+                "expected one of `log`, `trace`, `count` or `hook`",
+            )),
+        }
+    }
+}
+
+fn parse_level(input: ParseStream) -> syn::Result<Ident> {
+    let level: Ident = input.parse()?;
+    if LEVELS.contains(&level.to_string().as_str()) {
+        Ok(level)
+    } else {
+        Err(syn::Error::new(
+            level.span(),
+            "expected one of `error`, `warn`, `info`, `debug` or `trace`",
+        ))
+    }
+}
+
+pub(crate) fn expand(early: Early) -> TokenStream {
+    let Early {
+        from,
+        residual,
+        escape,
+        instruments,
+    } = early;
+    // The generated code has the span of the invocation, so lints about the escape (such as a
+    // needless `return` at the end of a function) treat it as macro-generated, and the location
+    // reported by the instrumentation is that of the invocation.
+    let (kind, kind_variant, silent, escape) = match escape {
+        Escape::Return(value) => (
+            "return",
+            quote!(Return),
+            quote!(silent_return),
+            quote!(::early_returns::__mark_early_exit!(); return #value),
+        ),
+        Escape::Break(label) => (
+            "break",
+            quote!(Break),
+            quote!(silent_break),
+            quote!(break #label),
+        ),
+        Escape::Continue(label) => (
+            "continue",
+            quote!(Continue),
+            quote!(silent_continue),
+            quote!(continue #label),
+        ),
+    };
+
+    // Like the `*_logged`, `*_counted` and `*_hooked` macros, instrumented escapes are not silent.
+    let instruments: Vec<_> = instruments
+        .iter()
+        .map(|instrument| match instrument {
+            Instrument::Log(level, message) => {
+                let format = format!("{{}} [early {kind} at {{}}]");
+                quote! {
+                    ::early_returns::__private::log::#level!(
+                        #format,
+                        ::core::format_args!(#message),
+                        ::early_returns::__private::caller_location(),
+                    );
+                }
+            }
+            Instrument::Trace(level, message) => quote! {
+                ::early_returns::__private::tracing::#level!(
+                    early_exit = #kind,
+                    location = %::early_returns::__private::caller_location(),
+                    #message
+                );
+            },
+            Instrument::Count(metric) => quote! {
+                ::early_returns::__private::metrics::counter!(
+                    #metric,
+                    "early_exit" => #kind,
+                    "location" => ::early_returns::__private::caller_location().to_string(),
+                )
+                .increment(1);
+            },
+            Instrument::Hook(reason) => quote! {
+                ::early_returns::hooks::call_hook(
+                    ::early_returns::hooks::EarlyReturnKind::#kind_variant,
+                    #reason,
+                );
+            },
+        })
+        .collect();
+    let silent = if instruments.is_empty() {
+        quote!(::early_returns::__private::#silent();)
+    } else {
+        TokenStream::new()
+    };
+    let residual = match residual {
+        Some(residual) => residual.into_token_stream(),
+        None => quote!(_),
+    };
+
+    quote! {
+        match ::early_returns::__private::Engaged::into_engaged(#from) {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err(#residual) => {
+                #(#instruments)*
+                #silent
+                #escape
+            }
+        }
+    }
+}
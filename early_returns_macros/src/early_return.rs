@@ -0,0 +1,67 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_quote, Expr, ExprAsync, ExprClosure, Item, ItemFn, Token};
+
+pub(crate) struct Args {
+    default: Option<Expr>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(Args { default: None });
+        }
+        let name: syn::Ident = input.parse()?;
+        if name != "default" {
+            return Err(syn::Error::new(name.span(), "expected `default = <expr>`"));
+        }
+        input.parse::<Token![=]>()?;
+        let default = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Args {
+            default: Some(default),
+        })
+    }
+}
+
+pub(crate) fn expand(args: Args, mut function: ItemFn) -> TokenStream {
+    Questions {
+        default: args.default,
+    }
+    .visit_block_mut(&mut function.block);
+    quote!(#function)
+}
+
+struct Questions {
+    default: Option<Expr>,
+}
+
+impl VisitMut for Questions {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_mut::visit_expr_mut(self, expr);
+        let Expr::Try(question) = expr else {
+            return;
+        };
+        let from = &question.expr;
+        let default = &self.default;
+        *expr = parse_quote! {
+            match ::early_returns::__private::Engaged::into_engaged(#from) {
+                ::core::result::Result::Ok(value) => value,
+                ::core::result::Result::Err(_) => {
+                    ::early_returns::__private::silent_return();
+                    ::early_returns::__mark_early_exit!();
+                    return #default
+                }
+            }
+        };
+    }
+
+    // A `?` in a closure, an async block or a nested item belongs to that, not to the function.
+    fn visit_expr_closure_mut(&mut self, _: &mut ExprClosure) {}
+
+    fn visit_expr_async_mut(&mut self, _: &mut ExprAsync) {}
+
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+}
@@ -0,0 +1,140 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident};
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "EarlyExtract can only be derived for enums",
+        ));
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut accessors = Vec::new();
+    let mut names = Vec::new();
+    let mut name_arms = Vec::new();
+    for variant in &data.variants {
+        let variant_ident = &variant.ident;
+        let variant_name = variant_ident.to_string();
+        let snake = to_snake_case(&variant_name);
+        let as_ref = format_ident!("as_{}", snake);
+        let as_mut = format_ident!("as_{}_mut", snake);
+        let into = format_ident!("into_{}", snake);
+
+        let bindings: Vec<Ident> = (0..variant.fields.len())
+            .map(|i| format_ident!("f{}", i))
+            .collect();
+        let pattern = match &variant.fields {
+            Fields::Unit => quote!(#name::#variant_ident),
+            Fields::Unnamed(_) => quote!(#name::#variant_ident(#(#bindings),*)),
+            Fields::Named(fields) => {
+                let field_names = fields.named.iter().map(|field| &field.ident);
+                quote!(#name::#variant_ident { #(#field_names: #bindings),* })
+            }
+        };
+        let types: Vec<_> = variant.fields.iter().map(|field| &field.ty).collect();
+        let (value, ref_ty, mut_ty, owned_ty) = match types.as_slice() {
+            [] => (quote!(()), quote!(()), quote!(()), quote!(())),
+            [ty] => (
+                quote!(#(#bindings)*),
+                quote!(&#ty),
+                quote!(&mut #ty),
+                quote!(#ty),
+            ),
+            types => (
+                quote!((#(#bindings),*)),
+                quote!((#(&#types),*)),
+                quote!((#(&mut #types),*)),
+                quote!((#(#types),*)),
+            ),
+        };
+
+        let ref_doc = format!(
+            "Returns references to the fields of `{variant_name}`, if `self` is that variant."
+        );
+        let mut_doc = format!("Returns mutable references to the fields of `{variant_name}`, if `self` is that variant.");
+        let into_doc =
+            format!("Returns the fields of `{variant_name}`, if `self` is that variant.");
+        accessors.push(quote! {
+            #[doc = #ref_doc]
+            #[allow(unused_variables)]
+            pub fn #as_ref(&self) -> ::core::option::Option<#ref_ty> {
+                match self {
+                    #pattern => ::core::option::Option::Some(#value),
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            #[doc = #mut_doc]
+            #[allow(unused_variables)]
+            pub fn #as_mut(&mut self) -> ::core::option::Option<#mut_ty> {
+                match self {
+                    #pattern => ::core::option::Option::Some(#value),
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            #[doc = #into_doc]
+            #[allow(unused_variables)]
+            pub fn #into(self) -> ::core::option::Option<#owned_ty> {
+                match self {
+                    #pattern => ::core::option::Option::Some(#value),
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::option::Option::None,
+                }
+            }
+        });
+
+        let wildcard = match &variant.fields {
+            Fields::Unit => quote!(#name::#variant_ident),
+            Fields::Unnamed(_) => quote!(#name::#variant_ident(..)),
+            Fields::Named(_) => quote!(#name::#variant_ident { .. }),
+        };
+        name_arms.push(quote!(#wildcard => #variant_name));
+        names.push(variant_name);
+    }
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The names of all variants, in declaration order.
+            pub const VARIANT_NAMES: &'static [&'static str] = &[#(#names),*];
+
+            /// Returns the name of the variant `self` is.
+            pub const fn variant_name(&self) -> &'static str {
+                match *self {
+                    #(#name_arms,)*
+                }
+            }
+
+            #(#accessors)*
+        }
+    })
+}
+
+/// Converts a `CamelCase` variant name into `snake_case`, keeping acronyms together
+/// (`IOError` becomes `io_error`).
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let after_lower =
+                i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_ascii_digit());
+            let ends_acronym = i > 0
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).map_or(false, |next| next.is_lowercase());
+            if after_lower || ends_acronym {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
@@ -0,0 +1,107 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::visit_mut::{self, VisitMut};
+use syn::{Expr, Ident, Pat, PatIdent, PatOr, Token, Type};
+
+pub(crate) struct Guard {
+    pattern: Pat,
+    ty: Option<Type>,
+    from: Expr,
+    otherwise: Expr,
+}
+
+impl Parse for Guard {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![let]>()?;
+        let pattern = Pat::parse_multi_with_leading_vert(input)?;
+        let ty = if input.parse::<Option<Token![:]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        input.parse::<Token![=]>()?;
+        let from = input.parse()?;
+        input.parse::<Token![else]>()?;
+        let otherwise = input.parse()?;
+        Ok(Guard {
+            pattern,
+            ty,
+            from,
+            otherwise,
+        })
+    }
+}
+
+pub(crate) fn expand(guard: Guard) -> TokenStream {
+    let Guard {
+        mut pattern,
+        ty,
+        from,
+        otherwise,
+    } = guard;
+    let mut bindings = Bindings::default();
+    bindings.visit_pat_mut(&mut pattern);
+    let (names, outer): (Vec<_>, Vec<_>) = bindings
+        .found
+        .iter()
+        .map(|(name, mutable)| {
+            let outer = if *mutable {
+                quote!(mut #name)
+            } else {
+                quote!(#name)
+            };
+            (name, outer)
+        })
+        .unzip();
+    let from = match ty {
+        Some(ty) => quote!(::core::convert::identity::<#ty>(#from)),
+        None => quote!(#from),
+    };
+
+    // The bindings are wrapped in a type of their own, so an `else` that does not diverge is a type
+    // error even when there are no bindings or they happen to be `()`.
+    quote! {
+        let ::early_returns::__private::GuardBindings((#(#outer,)*)) = match #from {
+            #pattern => ::early_returns::__private::GuardBindings((#(#names,)*)),
+            _ => #otherwise,
+        };
+    }
+}
+
+/// The names a pattern binds, in order, and whether they are bound with `mut`. The `mut` is moved
+/// from the pattern to the outer binding, since only that one is ever mutated.
+#[derive(Default)]
+struct Bindings {
+    found: Vec<(Ident, bool)>,
+    in_alternative: bool,
+}
+
+impl VisitMut for Bindings {
+    fn visit_pat_ident_mut(&mut self, pat: &mut PatIdent) {
+        // Without `ref`, `mut` or a subpattern an identifier may just as well name a constant or a
+        // unit variant (`None`), which by convention start with an uppercase letter.
+        let plain = pat.by_ref.is_none() && pat.mutability.is_none() && pat.subpat.is_none();
+        let constant = pat.ident.to_string().starts_with(char::is_uppercase);
+        if !(plain && constant) {
+            let mutable = pat.by_ref.is_none() && pat.mutability.take().is_some();
+            if !self.in_alternative {
+                self.found.push((pat.ident.clone(), mutable));
+            }
+        }
+        visit_mut::visit_pat_ident_mut(self, pat);
+    }
+
+    fn visit_pat_or_mut(&mut self, pat: &mut PatOr) {
+        // Every alternative binds the same names, so they are only collected from the first one.
+        for (i, case) in pat.cases.iter_mut().enumerate() {
+            let in_alternative = self.in_alternative;
+            self.in_alternative = in_alternative || i > 0;
+            self.visit_pat_mut(case);
+            self.in_alternative = in_alternative;
+        }
+    }
+
+    // Range bounds and constants in a pattern are expressions, not bindings.
+    fn visit_expr_mut(&mut self, _: &mut Expr) {}
+}
@@ -0,0 +1,270 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, Block, Expr, ExprAsync, ExprClosure, Ident, Item, ItemFn, Macro, Stmt, Token,
+};
+
+const LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+pub(crate) struct Args {
+    backend: Ident,
+    level: Ident,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let backend: Ident = input.parse()?;
+        if backend != "log" && backend != "tracing" {
+            return Err(syn::Error::new(
+                backend.span(),
+                "expected `log` or `tracing`",
+            ));
+        }
+        let level = if input.parse::<Option<Token![=]>>()?.is_some() {
+            let level: Ident = input.parse()?;
+            if !LEVELS.contains(&level.to_string().as_str()) {
+                return Err(syn::Error::new(
+                    level.span(),
+                    "expected one of `error`, `warn`, `info`, `debug` or `trace`",
+                ));
+            }
+            level
+        } else {
+            Ident::new("debug", Span::call_site())
+        };
+        Ok(Args { backend, level })
+    }
+}
+
+pub(crate) fn expand(args: Args, mut function: ItemFn) -> TokenStream {
+    // Hygienic, so the tracker and the saved records cannot clash with the names in the function.
+    let tracker = Ident::new("__early_exits", Span::mixed_site());
+    Exits { tracker: &tracker }.visit_block_mut(&mut function.block);
+
+    let Args { backend, level } = args;
+    let name = function.sig.ident.to_string();
+    let init: Stmt = parse_quote! {
+        let #tracker = ::early_returns::__private::ExitTracker::new(#name, |report| {
+            ::early_returns::__private::#backend::#level!("{}", report);
+        });
+    };
+    function.block.stmts.insert(0, init);
+    quote!(#function)
+}
+
+struct Exits<'a> {
+    tracker: &'a Ident,
+}
+
+impl Exits<'_> {
+    // `enter` is `#[track_caller]`, so the call gets the span of the exit to report its location.
+    fn enter(&self, through: &str, span: Span) -> TokenStream {
+        let tracker = self.tracker;
+        quote_spanned!(span=> #tracker.enter(#through))
+    }
+}
+
+/// The macros of the crate that may return from the function they are used in, besides
+/// `return_early!`, which always does, and `early!` and `guard!`, which do when their escape is a
+/// `return`. Some of them only do so once they run out of attempts (`retry!`, `loop_until_some!`)
+/// or when the error they are given is handled with a `return` (`ok_or_continue_if!`). The `*_par`
+/// escapes of the `rayon` feature only return from the closure of a parallel loop.
+const RETURNING: &[&str] = &[
+    "acquire_or_return",
+    "all_ok_or_return",
+    "all_some_or_return",
+    "bind_all_or_return",
+    "cached_or_compute_or_return",
+    "char_or_return",
+    "checked_or_return",
+    "cstr_or_return",
+    "cstring_or_return",
+    "debug_ok_or_return",
+    "debug_some_or_return",
+    "digit_or_return",
+    "div_or_return",
+    "downcast_or_return",
+    "env_or_return",
+    "env_parse_or_return",
+    "ffi_ok_or_return",
+    "first_or_return",
+    "first_some_or_return",
+    "get_or_return",
+    "hr_ok_or_return",
+    "join_or_return",
+    "last_or_return",
+    "left_or_return",
+    "let_or_return",
+    "lock_or_return",
+    "loop_until_some",
+    "nb_ok_or_continue",
+    "nb_ok_or_return",
+    "next_or_return",
+    "nom_or_return",
+    "nonnull_or_return",
+    "nonzero_or_return",
+    "not_cancelled_or_return",
+    "ok_mut_or_return",
+    "ok_or_continue_if",
+    "ok_or_return",
+    "ok_or_return_boxed",
+    "ok_or_return_cleanup",
+    "ok_or_return_context",
+    "ok_or_return_counted",
+    "ok_or_return_diag",
+    "ok_or_return_eyre",
+    "ok_or_return_fmt",
+    "ok_or_return_hooked",
+    "ok_or_return_if",
+    "ok_or_return_inspect_err",
+    "ok_or_return_logged",
+    "ok_or_return_mapped",
+    "ok_or_return_reason",
+    "ok_or_return_recorded",
+    "ok_or_return_store",
+    "ok_or_return_traced",
+    "ok_some_or_return",
+    "oneshot_or_return",
+    "osstr_or_return",
+    "parse_or_return",
+    "path_str_or_return",
+    "peek_or_return",
+    "push_or_return",
+    "read_or_return",
+    "ready_or_return",
+    "recv_or_return",
+    "retry",
+    "retry_async",
+    "retry_backoff",
+    "retry_on_interrupted",
+    "right_or_return",
+    "send_or_return",
+    "some_cloned_or_return",
+    "some_copied_or_return",
+    "some_deref_or_return",
+    "some_mut_or_return",
+    "some_ok_or_return",
+    "some_or_return",
+    "some_or_return_cleanup",
+    "some_or_return_context",
+    "some_or_return_counted",
+    "some_or_return_diag",
+    "some_or_return_eyre",
+    "some_or_return_hooked",
+    "some_or_return_inspect",
+    "some_or_return_logged",
+    "some_or_return_reason",
+    "some_or_return_recorded",
+    "some_or_return_traced",
+    "split_first_or_return",
+    "split_last_or_return",
+    "take_or_return",
+    "timeout_or_return",
+    "try_into_or_return",
+    "try_lock_or_return",
+    "try_next_or_break",
+    "try_next_or_return",
+    "try_read_or_return",
+    "try_write_or_return",
+    "upgrade_or_return",
+    "utf8_or_return",
+    "variant_or_return",
+    "write_or_return",
+];
+
+/// The name of a macro that may return from the function, and whether it always does.
+fn returning_macro(mac: &Macro) -> Option<(String, bool)> {
+    let name = mac.path.segments.last()?.ident.to_string();
+    let always = name == "return_early";
+    let returning = match name.as_str() {
+        "early" | "guard" => writes_return(&mac.tokens),
+        name => always || RETURNING.contains(&name),
+    };
+    returning.then(|| (format!("{name}!"), always))
+}
+
+fn writes_return(tokens: &TokenStream) -> bool {
+    tokens.clone().into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "return",
+        TokenTree::Group(group) => writes_return(&group.stream()),
+        _ => false,
+    })
+}
+
+impl VisitMut for Exits<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_mut::visit_expr_mut(self, expr);
+        let tracker = self.tracker;
+        match expr {
+            Expr::Macro(call) => {
+                let Some((through, always)) = returning_macro(&call.mac) else {
+                    return;
+                };
+                let enter = self.enter(&through, call.mac.path.span());
+                *expr = if always {
+                    parse_quote!({ #enter; #call })
+                } else {
+                    parse_quote!(#tracker.leave(#enter, #call))
+                };
+            }
+            Expr::Try(question) => {
+                let enter = self.enter("?", question.question_token.span);
+                *expr = parse_quote!(#tracker.leave(#enter, #question));
+            }
+            Expr::Return(ret) => {
+                let through = quote_spanned!(ret.return_token.span=> #tracker.taken("return"));
+                let value = ret
+                    .expr
+                    .take()
+                    .map_or_else(|| quote!(()), |value| quote!(#value));
+                ret.expr = Some(parse_quote!(#tracker.leave(#through, #value)));
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        let tracker = self.tracker;
+        let previous = Ident::new("__previous_exit", Span::mixed_site());
+        let mut stmts = Vec::with_capacity(block.stmts.len());
+        for mut stmt in block.stmts.drain(..) {
+            self.visit_stmt_mut(&mut stmt);
+            let Stmt::Macro(call) = &stmt else {
+                stmts.push(stmt);
+                continue;
+            };
+            let Some((through, always)) = returning_macro(&call.mac) else {
+                stmts.push(stmt);
+                continue;
+            };
+            let enter = self.enter(&through, call.mac.path.span());
+            // There is nothing to restore after an exit that is always taken.
+            if always {
+                stmts.push(parse_quote!(#enter;));
+                stmts.push(stmt);
+                continue;
+            }
+            if call.semi_token.is_none() {
+                let mac = &call.mac;
+                stmts.push(Stmt::Expr(parse_quote!(#tracker.leave(#enter, #mac)), None));
+                continue;
+            }
+            // The statement form of a macro binds names in the block, so it is instrumented with
+            // statements around it instead of being wrapped.
+            stmts.push(parse_quote!(let #previous = #enter;));
+            stmts.push(stmt);
+            stmts.push(parse_quote!(#tracker.leave(#previous, ());));
+        }
+        block.stmts = stmts;
+    }
+
+    // Returns in a closure, an async block or a nested item do not leave the function.
+    fn visit_expr_closure_mut(&mut self, _: &mut ExprClosure) {}
+
+    fn visit_expr_async_mut(&mut self, _: &mut ExprAsync) {}
+
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+}
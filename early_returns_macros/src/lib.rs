@@ -0,0 +1,284 @@
+//! Procedural macros for the [`early_returns`](https://docs.rs/early_returns) crate.
+//!
+//! These are re-exported by `early_returns` behind cargo features and are not meant to be used
+//! directly.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, Item, ItemFn};
+
+mod early;
+mod early_return;
+mod extract;
+mod guard;
+mod instrument;
+mod loop_exits;
+
+/// Get the value from an Option or a Result, or take an escape path, optionally instrumented.
+///
+/// The escape is written after a `=>` just like it is in plain code: `return` with an optional
+/// value, or `break` or `continue` with an optional loop label. The `None` or error can be bound
+/// with `|name|` before the escape, so it can be used in the returned value and in messages.
+/// Instrumentation follows the escape, separated by commas, and runs before it:
+/// * `log <level> "message"` emits a `log` record (requires the `log` feature of `early_returns`),
+/// * `trace <level> "message"` emits a `tracing` event (requires the `tracing` feature),
+/// * `count "metric"` increments a `metrics` counter (requires the `metrics` feature),
+/// * `hook "reason"` calls the hook installed with `early_returns::hooks::set_hook`.
+///
+/// Messages can capture variables (`"skipping row {i}"`). Like the `*_logged`, `*_counted` and
+/// `*_hooked` macros, the record, event and counter carry the kind of escape and the location of
+/// the invocation, and an instrumented escape is not silent.
+///
+/// Errors in the arguments (such as an unknown instrumentation or a mismatched return value) point
+/// at the offending tokens, and errors about the escape itself (such as a `break` outside of a
+/// loop) point at the invocation.
+/// ```
+/// use early_returns::early;
+///
+/// fn port(value: &str) -> Result<u16, String> {
+///     let port = early!(value.parse::<u16>() => |e| return Err(format!("{value:?}: {e}")));
+///     Ok(port)
+/// }
+///
+/// let mut read = Vec::new();
+/// for (i, value) in [Some(1), None, Some(3), None].into_iter().enumerate() {
+///     if i == 3 {
+///         early!(value => break, hook "end of input");
+///     }
+///     read.push(early!(value => continue, log warn "skipping row {i}"));
+/// }
+///
+/// assert_eq!(port("80"), Ok(80));
+/// assert!(port("http").is_err());
+/// assert_eq!(read, [1, 3]);
+/// ```
+#[proc_macro]
+pub fn early(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as early::Early);
+    early::expand(input).into()
+}
+
+/// Rewrite every `?` on an Option or a Result in a function into an early return, with the given
+/// default value (`#[early_return(default = ...)]`) or without one for functions returning `()`.
+///
+/// This allows the terse `?` syntax in functions whose return type is neither an Option nor a
+/// Result. Like the other silent macros, the escape is reported by the `strict` and
+/// `lint-silent-exits` features. The default value is only evaluated when a `?` returns early. A
+/// `?` in a closure, an async block or a nested item is left alone, as is one inside another macro
+/// invocation (such as `println!`), whose arguments are opaque to the attribute.
+/// ```
+/// use early_returns::early_return;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Reply {
+///     Port(u16),
+///     Invalid,
+/// }
+///
+/// #[early_return(default = Reply::Invalid)]
+/// fn port(config: &[(&str, &str)]) -> Reply {
+///     let (_, value) = config.iter().find(|(key, _)| *key == "port")?;
+///     Reply::Port(value.parse()?)
+/// }
+///
+/// #[early_return]
+/// fn print_port(config: &[(&str, &str)]) {
+///     let (_, value) = config.iter().find(|(key, _)| *key == "port")?;
+///     println!("{value}");
+/// }
+///
+/// assert_eq!(port(&[("port", "80")]), Reply::Port(80));
+/// assert_eq!(port(&[("port", "http")]), Reply::Invalid);
+/// assert_eq!(port(&[]), Reply::Invalid);
+/// print_port(&[]);
+/// ```
+#[proc_macro_attribute]
+pub fn early_return(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as early_return::Args);
+    let function = parse_macro_input!(input as ItemFn);
+    early_return::expand(args, function).into()
+}
+
+/// Derive per-variant extraction helpers for an enum, designed to compose with
+/// `some_or_return!` and friends.
+///
+/// For every variant `Foo` this generates:
+/// * `as_foo(&self) -> Option<..>` returning references to the variant's fields,
+/// * `as_foo_mut(&mut self) -> Option<..>` returning mutable references to the fields,
+/// * `into_foo(self) -> Option<..>` returning the fields by value.
+///
+/// A variant with a single field yields that field directly, a unit variant yields `()` and any
+/// other variant yields a tuple of its fields in declaration order.
+///
+/// It also generates `VARIANT_NAMES`, the names of all variants in declaration order, and a
+/// `const fn variant_name(&self)` for use in log messages.
+/// ```
+/// use early_returns::some_or_return;
+/// use early_returns::EarlyExtract;
+///
+/// #[derive(EarlyExtract)]
+/// enum Msg {
+///     Ping,
+///     Data(Vec<u8>),
+///     Move { x: i32, y: i32 },
+/// }
+///
+/// fn payload_len(msg: &Msg) -> usize {
+///     let payload = some_or_return!(msg.as_data(), 0);
+///     payload.len()
+/// }
+///
+/// fn distance(msg: Msg) -> i32 {
+///     let (x, y) = some_or_return!(msg.into_move(), 0);
+///     x.abs() + y.abs()
+/// }
+///
+/// assert_eq!(payload_len(&Msg::Data(vec![1, 2])), 2);
+/// assert_eq!(distance(Msg::Ping), 0);
+/// assert_eq!(Msg::Ping.variant_name(), "Ping");
+/// assert_eq!(Msg::VARIANT_NAMES, &["Ping", "Data", "Move"]);
+/// ```
+#[proc_macro_derive(EarlyExtract)]
+pub fn derive_early_extract(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    extract::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// `let ... else` for compilers that predate it: bind every variable of a refutable pattern in
+/// the current scope, or run the `else` branch, which has to diverge (`return`, `break`,
+/// `continue`, a panic or a block ending in one of those).
+///
+/// It expands to a `match` rather than to `let ... else`, so it works on any compiler the crate
+/// builds with. The pattern can be anything `let ... else` accepts, including alternatives and a
+/// type (`guard!(let Ok(port): Result<u16, _> = value.parse() else return 0)`). An `else` branch
+/// that does not diverge is a type error. Since the pattern is only parsed, a plain identifier is
+/// taken to name a constant or a unit variant (such as `None`) if it starts with an uppercase
+/// letter, and to be a binding otherwise.
+/// ```
+/// use early_returns::guard;
+///
+/// enum Shape {
+///     Circle { radius: u32 },
+///     Square(u32),
+///     Dot,
+/// }
+///
+/// fn grown(shape: Shape) -> u32 {
+///     guard!(let Shape::Circle { mut radius } | Shape::Square(mut radius) = shape else return 0);
+///     radius += 1;
+///     radius
+/// }
+///
+/// let mut sizes = Vec::new();
+/// for text in ["3", "x", "5"] {
+///     guard!(let Ok(size): Result<u32, _> = text.parse() else { continue });
+///     sizes.push(size);
+/// }
+///
+/// assert_eq!(grown(Shape::Circle { radius: 1 }), 2);
+/// assert_eq!(grown(Shape::Square(2)), 3);
+/// assert_eq!(grown(Shape::Dot), 0);
+/// assert_eq!(sizes, [3, 5]);
+/// ```
+/// ```compile_fail
+/// use early_returns::guard;
+///
+/// fn first(values: &[u32]) -> u32 {
+///     // error: the `else` branch does not diverge
+///     guard!(let [first, ..] = values else { println!("empty") });
+///     *first
+/// }
+/// ```
+#[proc_macro]
+pub fn guard(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as guard::Guard);
+    guard::expand(input).into()
+}
+
+/// Report which exit path a function took whenever it is left, through `log` or `tracing`
+/// (`#[instrument_early_returns(log)]` or `#[instrument_early_returns(tracing = info)]`, at the
+/// `debug` level unless another one is given).
+///
+/// The exit paths are the macros of `early_returns` that may return (the `*_or_return!` macros,
+/// `return_early!`, the `retry!` family and `loop_until_some!` once they give up, and `early!`
+/// and `guard!` with a `return`), the `?` operator and `return`, which are reported with the
+/// location they were written at, and the end of the function:
+/// ```text
+/// `load_port` returned through `some_or_return!` at src/config.rs:12:16
+/// `load_port` returned at its end
+/// ```
+/// The backend has to be enabled as a feature of `early_returns`, as does `std` (which is enabled
+/// by default). Exits of closures, async blocks and nested items are not reported, nor are macros
+/// and `?` nested in other macro invocations, whose arguments are opaque to the attribute.
+/// ```
+/// use early_returns::{instrument_early_returns, ok_or_return, some_or_return};
+///
+/// #[instrument_early_returns(log = info)]
+/// fn load_port(config: &[(&str, &str)]) -> u16 {
+///     let (_, value) = some_or_return!(config.iter().find(|(key, _)| *key == "port"), 80);
+///     if value.is_empty() {
+///         return 80;
+///     }
+///     ok_or_return!(value.parse(), 80)
+/// }
+///
+/// assert_eq!(load_port(&[("port", "8080")]), 8080);
+/// assert_eq!(load_port(&[]), 80);
+/// ```
+#[proc_macro_attribute]
+pub fn instrument_early_returns(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as instrument::Args);
+    let function = parse_macro_input!(input as ItemFn);
+    instrument::expand(args, function).into()
+}
+
+/// Report `*_or_break!` and `*_or_continue!` calls that are not inside a loop at the call site,
+/// naming the macro and suggesting its `_or_return` counterpart.
+///
+/// Without it the compiler only reports a `break` or `continue` outside of a loop, coming from the
+/// expansion of the macro. Calls with a label are left to the compiler, as are calls nested in
+/// other macro invocations (such as `for_some!`), whose bodies are opaque to the attribute.
+/// Closures, async blocks and nested items start outside of any loop, just like they do for the
+/// compiler. The `_par` escapes (`ok_or_break_par!` and friends) are left alone, since they return
+/// from the closure of a parallel loop instead.
+///
+/// It can be put on a function, an `impl` block or a module.
+/// ```
+/// use early_returns::{check_loop_exits, some_or_break};
+///
+/// #[check_loop_exits]
+/// fn first_gap(values: &[Option<u32>]) -> usize {
+///     let mut count = 0;
+///     for value in values {
+///         some_or_break!(value);
+///         count += 1;
+///     }
+///     count
+/// }
+///
+/// assert_eq!(first_gap(&[Some(1), Some(2), None, Some(4)]), 2);
+/// ```
+/// ```compile_fail
+/// use early_returns::{check_loop_exits, some_or_break};
+///
+/// #[check_loop_exits]
+/// fn frobnicate(frobnicator: Option<&str>) -> usize {
+///     // error: `some_or_break!` is not inside a loop, so there is no loop to break out of; use
+///     // `some_or_return!` to return from the function instead
+///     let frobnicator = some_or_break!(frobnicator);
+///     frobnicator.len()
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn check_loop_exits(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    if !args.is_empty() {
+        return syn::Error::new_spanned(args, "check_loop_exits takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+    let mut item = parse_macro_input!(input as Item);
+    loop_exits::check(&mut item);
+    quote::quote!(#item).into()
+}
@@ -0,0 +1,133 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote_spanned;
+use syn::spanned::Spanned;
+use syn::token::Paren;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote_spanned, Block, ExprAsync, ExprClosure, ExprForLoop, ExprLoop, ExprWhile, Item,
+    Macro, MacroDelimiter,
+};
+
+/// The escape macros whose `_or_return` counterpart has a different name, or does not exist.
+const SUGGESTIONS: &[(&str, Option<&str>)] = &[
+    ("branch_or_break", None),
+    ("ensure_or_break", Some("return_early")),
+    ("ensure_or_continue", Some("return_early")),
+    ("ok_or_continue_collect", Some("ok_or_return")),
+    ("pop_back_or_break", Some("some_or_return")),
+    ("pop_front_or_break", Some("some_or_return")),
+    ("pop_or_break", Some("some_or_return")),
+    ("recv_timeout_or_continue", Some("ok_or_return")),
+    ("some_or_continue_collect", Some("some_or_return")),
+    ("try_acquire_or_continue", Some("acquire_or_return")),
+    ("try_recv_or_continue", Some("ok_or_return")),
+];
+
+/// The escape macros that break or continue a parallel loop by returning from its closure, so
+/// they do not need a loop around them.
+const CLOSURE_EXITS: &[&str] = &[
+    "ok_or_break_par",
+    "ok_or_continue_par",
+    "some_or_break_par",
+    "some_or_continue_par",
+];
+
+pub(crate) fn check(item: &mut Item) {
+    LoopExits { depth: 0 }.visit_item_mut(item);
+}
+
+struct LoopExits {
+    // The number of loops around the current expression, counted up to the nearest closure, async
+    // block or item, since `break` and `continue` cannot reach past those.
+    depth: usize,
+}
+
+impl LoopExits {
+    fn nested(&mut self, depth: usize, visit: impl FnOnce(&mut Self)) {
+        let outer = std::mem::replace(&mut self.depth, depth);
+        visit(self);
+        self.depth = outer;
+    }
+
+    fn visit_loop_body(&mut self, body: &mut Block) {
+        self.nested(self.depth + 1, |v| v.visit_block_mut(body));
+    }
+}
+
+impl VisitMut for LoopExits {
+    fn visit_expr_for_loop_mut(&mut self, expr: &mut ExprForLoop) {
+        self.visit_expr_mut(&mut expr.expr);
+        self.visit_loop_body(&mut expr.body);
+    }
+
+    fn visit_expr_while_mut(&mut self, expr: &mut ExprWhile) {
+        self.visit_expr_mut(&mut expr.cond);
+        self.visit_loop_body(&mut expr.body);
+    }
+
+    fn visit_expr_loop_mut(&mut self, expr: &mut ExprLoop) {
+        self.visit_loop_body(&mut expr.body);
+    }
+
+    fn visit_expr_closure_mut(&mut self, expr: &mut ExprClosure) {
+        self.nested(0, |v| visit_mut::visit_expr_closure_mut(v, expr));
+    }
+
+    fn visit_expr_async_mut(&mut self, expr: &mut ExprAsync) {
+        self.nested(0, |v| visit_mut::visit_expr_async_mut(v, expr));
+    }
+
+    fn visit_item_mut(&mut self, item: &mut Item) {
+        self.nested(0, |v| visit_mut::visit_item_mut(v, item));
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        if self.depth > 0 || has_label(&mac.tokens) {
+            return;
+        }
+        let Some(name) = mac.path.segments.last().map(|s| s.ident.to_string()) else {
+            return;
+        };
+        if CLOSURE_EXITS.contains(&name.as_str()) {
+            return;
+        }
+        let (escape, action) = if name.contains("_or_break") {
+            ("_or_break", "break out of")
+        } else if name.contains("_or_continue") {
+            ("_or_continue", "continue")
+        } else {
+            return;
+        };
+        let suggestion = SUGGESTIONS
+            .iter()
+            .find(|(from, _)| *from == name)
+            .map_or_else(
+                || Some(name.replacen(escape, "_or_return", 1)),
+                |(_, to)| to.map(str::to_string),
+            );
+        let message = match suggestion {
+            Some(suggestion) => format!(
+                "`{name}!` is not inside a loop, so there is no loop to {action}; \
+                 use `{suggestion}!` to return from the function instead"
+            ),
+            None => format!("`{name}!` is not inside a loop, so there is no loop to {action}"),
+        };
+
+        let span = mac.path.span();
+        mac.path = parse_quote_spanned!(span=> ::core::compile_error);
+        mac.bang_token.span = span;
+        mac.delimiter = MacroDelimiter::Paren(Paren(span));
+        mac.tokens = quote_spanned!(span=> #message);
+    }
+}
+
+// A lifetime right after a top-level comma names a label, which the compiler checks itself.
+fn has_label(tokens: &TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = tokens.clone().into_iter().collect();
+    tokens.windows(2).any(|pair| match pair {
+        [TokenTree::Punct(comma), TokenTree::Punct(tick)] => {
+            comma.as_char() == ',' && tick.as_char() == '\''
+        }
+        _ => false,
+    })
+}
@@ -0,0 +1,105 @@
+// The silent exits are deprecated under `lint-silent-exits`, which `--all-features` enables.
+#![allow(deprecated)]
+
+use early_returns::{
+    check_loop_exits, ok_or_break_par, ok_or_continue, ok_or_continue_par, some_or_break,
+    some_or_continue, some_or_return,
+};
+
+#[check_loop_exits]
+fn sum_until_gap(values: &[Option<i32>]) -> i32 {
+    let mut sum = 0;
+    for value in values {
+        sum += some_or_break!(value);
+    }
+    sum
+}
+
+#[check_loop_exits]
+fn sum_parsed(rows: &[&str]) -> i32 {
+    let mut sum = 0;
+    let mut rows = rows.to_vec();
+    while let Some(row) = rows.pop() {
+        sum += ok_or_continue!(row.parse::<i32>());
+    }
+    sum
+}
+
+#[check_loop_exits]
+fn first_full_row(rows: &[Vec<Option<i32>>]) -> Option<usize> {
+    let mut found = None;
+    'rows: for (i, row) in rows.iter().enumerate() {
+        for value in row {
+            some_or_continue!(value, 'rows);
+        }
+        found = Some(i);
+        break;
+    }
+    found
+}
+
+#[check_loop_exits]
+fn labeled_block(value: Option<i32>) -> i32 {
+    let mut found = 0;
+    'found: {
+        found = some_or_break!(value, 'found);
+    }
+    found
+}
+
+struct Totals;
+
+#[check_loop_exits]
+impl Totals {
+    fn closures_inside_loops(values: &[Option<i32>]) -> i32 {
+        let mut sum = 0;
+        for _ in 0..2 {
+            let first = || -> i32 {
+                let mut first = 0;
+                for value in values {
+                    first = *some_or_continue!(value);
+                    break;
+                }
+                first
+            };
+            sum += first();
+        }
+        sum
+    }
+
+    fn returning(value: Option<i32>) -> i32 {
+        some_or_return!(value, -1)
+    }
+}
+
+// The `_par` escapes return from the closure of a parallel loop, so they need no loop around them.
+#[check_loop_exits]
+fn sum_parallel(rows: &[&str]) -> Result<u32, String> {
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let sum = AtomicU32::new(0);
+    rows.par_iter().try_for_each(|row| {
+        let value = ok_or_continue_par!(row.parse::<i32>());
+        let value: u32 = ok_or_break_par!(value.try_into(), _e => format!("negative {row}"));
+        sum.fetch_add(value, Ordering::Relaxed);
+        Ok(())
+    })?;
+    Ok(sum.into_inner())
+}
+
+#[test]
+fn should_leave_escapes_inside_loops_untouched() {
+    assert_eq!(sum_until_gap(&[Some(1), Some(2), None, Some(4)]), 3);
+    assert_eq!(sum_parsed(&["1", "x", "3"]), 4);
+    assert_eq!(
+        first_full_row(&[vec![Some(1), None], vec![Some(2)]]),
+        Some(1)
+    );
+    assert_eq!(labeled_block(Some(3)), 3);
+    assert_eq!(labeled_block(None), 0);
+    assert_eq!(Totals::closures_inside_loops(&[None, Some(5)]), 10);
+    assert_eq!(Totals::returning(None), -1);
+    assert_eq!(sum_parallel(&["1", "x", "3"]), Ok(4));
+    assert_eq!(sum_parallel(&["1", "-2"]), Err("negative -2".to_string()));
+}
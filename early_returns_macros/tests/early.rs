@@ -0,0 +1,54 @@
+// The silent exits are deprecated under `lint-silent-exits`, which `--all-features` enables.
+#![allow(deprecated)]
+
+use early_returns::early;
+
+fn first_char(value: Option<&str>) -> char {
+    let value = early!(value => return '-');
+    early!(value.chars().next() => return '?')
+}
+
+fn parse_all(values: &[&str]) -> Result<Vec<u8>, String> {
+    let mut parsed = Vec::new();
+    for value in values {
+        parsed.push(early!(value.parse::<u8>() => |e| return Err(e.to_string())));
+    }
+    Ok(parsed)
+}
+
+fn unit(value: Result<i32, ()>, seen: &mut Vec<i32>) {
+    seen.push(early!(value => return, count "early.unit"));
+}
+
+#[test]
+fn should_return_early() {
+    assert_eq!(first_char(Some("abc")), 'a');
+    assert_eq!(first_char(Some("")), '?');
+    assert_eq!(first_char(None), '-');
+    assert_eq!(parse_all(&["1", "2"]), Ok(vec![1, 2]));
+    assert_eq!(
+        parse_all(&["1", "x"]),
+        Err("invalid digit found in string".to_string())
+    );
+    let mut seen = Vec::new();
+    unit(Ok(1), &mut seen);
+    unit(Err(()), &mut seen);
+    assert_eq!(seen, [1]);
+}
+
+#[test]
+fn should_break_and_continue() {
+    let mut sum = 0;
+    'l: for value in [Some(1), None, Some(3)] {
+        for _i in 0..2 {
+            sum += early!(value => continue 'l, log debug "skipping");
+        }
+    }
+    for value in [Ok(4), Err("eof"), Ok(6)] {
+        sum += early!(value => |e| break, trace info "stopped at {e}", hook "end of input");
+    }
+    for value in &[Some(7), None] {
+        sum += early!(value => break);
+    }
+    assert_eq!(sum, 8 + 4 + 7);
+}
@@ -0,0 +1,92 @@
+// The silent exits are deprecated under `lint-silent-exits`, which `--all-features` enables.
+#![allow(deprecated)]
+
+use early_returns::{some_or_continue, some_or_return};
+use early_returns_macros::EarlyExtract;
+
+#[derive(Debug, EarlyExtract, PartialEq)]
+enum Msg {
+    Ping,
+    Data(Vec<u8>),
+    Pair(i32, i32),
+    Move { x: i32, y: i32 },
+    IOError(String),
+}
+
+#[derive(EarlyExtract)]
+enum Wrapper<'a, T: Clone> {
+    Borrowed(&'a T),
+    Owned(T),
+}
+
+fn payload_len(msg: &Msg) -> usize {
+    let payload = some_or_return!(msg.as_data(), 0);
+    payload.len()
+}
+
+#[test]
+fn should_extract_single_field_by_reference() {
+    assert_eq!(Msg::Data(vec![1, 2, 3]).as_data(), Some(&vec![1, 2, 3]));
+    assert_eq!(Msg::Ping.as_data(), None);
+    assert_eq!(payload_len(&Msg::Data(vec![1])), 1);
+    assert_eq!(payload_len(&Msg::Ping), 0);
+}
+
+#[test]
+fn should_extract_multiple_fields_as_tuple() {
+    assert_eq!(Msg::Pair(1, 2).as_pair(), Some((&1, &2)));
+    assert_eq!(Msg::Move { x: 3, y: 4 }.into_move(), Some((3, 4)));
+    assert_eq!(Msg::Ping.into_move(), None);
+}
+
+#[test]
+fn should_extract_unit_variant_as_unit() {
+    assert_eq!(Msg::Ping.as_ping(), Some(()));
+    assert_eq!(Msg::Pair(1, 2).as_ping(), None);
+}
+
+#[test]
+fn should_extract_mutable_references() {
+    let mut msg = Msg::Move { x: 1, y: 2 };
+    if let Some((x, _)) = msg.as_move_mut() {
+        *x = 10;
+    }
+    assert_eq!(msg, Msg::Move { x: 10, y: 2 });
+}
+
+#[test]
+fn should_keep_acronyms_together_in_accessor_names() {
+    let msg = Msg::IOError("disk".to_string());
+    assert_eq!(msg.as_io_error(), Some(&"disk".to_string()));
+}
+
+#[test]
+fn should_provide_variant_names() {
+    assert_eq!(
+        Msg::VARIANT_NAMES,
+        &["Ping", "Data", "Pair", "Move", "IOError"]
+    );
+    assert_eq!(Msg::Ping.variant_name(), "Ping");
+    assert_eq!(Msg::Move { x: 0, y: 0 }.variant_name(), "Move");
+}
+
+#[test]
+fn should_support_generic_enums() {
+    let value = 5;
+    let borrowed = Wrapper::Borrowed(&value);
+    let owned = Wrapper::Owned(6);
+    assert_eq!(borrowed.as_borrowed(), Some(&&5));
+    assert_eq!(owned.into_owned(), Some(6));
+    assert_eq!(borrowed.variant_name(), "Borrowed");
+}
+
+#[test]
+fn should_compose_with_loop_macros() {
+    let msgs = vec![Msg::Data(vec![1]), Msg::Ping, Msg::Data(vec![2, 3])];
+    let mut total = 0;
+    for msg in &msgs {
+        let payload = some_or_continue!(msg.as_data());
+        total += payload.len();
+    }
+    assert_eq!(total, 3);
+}
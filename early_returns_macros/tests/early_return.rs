@@ -0,0 +1,57 @@
+// The silent exits are deprecated under `lint-silent-exits`, which `--all-features` enables.
+#![allow(deprecated)]
+
+use early_returns::early_return;
+
+struct Config {
+    entries: Vec<(String, String)>,
+}
+
+impl Config {
+    fn get(&self, key: &str) -> Option<&str> {
+        let (_, value) = self.entries.iter().find(|(k, _)| k == key)?;
+        Some(value)
+    }
+
+    #[early_return(default = 0)]
+    fn sum(&self, keys: &[&str]) -> u32 {
+        let mut sum = 0;
+        for key in keys {
+            sum += self.get(key)?.parse::<u32>()?;
+        }
+        sum
+    }
+
+    #[early_return(default = Vec::new())]
+    fn lengths(&self, keys: &[&str]) -> Vec<usize> {
+        // The closure keeps its own `?`.
+        let length = |key: &str| Some(self.get(key)?.len());
+        keys.iter().map(|key| length(key)).collect::<Option<_>>()?
+    }
+}
+
+#[early_return]
+fn push_first(values: &[Option<i32>], out: &mut Vec<i32>) {
+    out.push(*values.first()??);
+}
+
+#[test]
+fn should_return_default_on_question_mark() {
+    let config = Config {
+        entries: vec![("a".into(), "1".into()), ("b".into(), "x".into())],
+    };
+    assert_eq!(config.sum(&["a", "a"]), 2);
+    assert_eq!(config.sum(&["a", "b"]), 0);
+    assert_eq!(config.sum(&["c"]), 0);
+    assert_eq!(config.lengths(&["a", "b"]), [1, 1]);
+    assert_eq!(config.lengths(&["a", "c"]), Vec::<usize>::new());
+}
+
+#[test]
+fn should_return_unit_on_question_mark() {
+    let mut out = Vec::new();
+    push_first(&[Some(1)], &mut out);
+    push_first(&[None], &mut out);
+    push_first(&[], &mut out);
+    assert_eq!(out, [1]);
+}
@@ -0,0 +1,57 @@
+// The silent exits are deprecated under `lint-silent-exits`, which `--all-features` enables.
+#![allow(deprecated)]
+
+use early_returns::guard;
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn manhattan(point: Option<Point>) -> i32 {
+    guard!(let Some(Point { x, y: mut vertical }) = point else return -1);
+    vertical = vertical.abs();
+    x.abs() + vertical
+}
+
+fn bump_first(values: &mut [i32]) -> bool {
+    guard!(let [ref mut first, ..] = *values else { return false });
+    *first += 1;
+    true
+}
+
+fn digit(value: Result<u8, u8>) -> Option<u8> {
+    guard!(let Ok(digit @ 0..=9) | Err(digit @ 0..=9) = value else return None);
+    Some(digit)
+}
+
+#[test]
+fn should_bind_like_let_else() {
+    assert_eq!(manhattan(Some(Point { x: -1, y: -2 })), 3);
+    assert_eq!(manhattan(None), -1);
+    let mut values = [1, 2];
+    assert!(bump_first(&mut values));
+    assert_eq!(values, [2, 2]);
+    assert!(!bump_first(&mut []));
+}
+
+const LIMIT: u32 = 3;
+
+fn is_limit(value: u32) -> bool {
+    guard!(let LIMIT = value else return false);
+    true
+}
+
+#[test]
+fn should_take_alternatives_constants_and_types() {
+    let mut taken = Vec::new();
+    for text in ["1", "x", "2", "3", "4"] {
+        guard!(let Ok(value): Result<u32, _> = text.parse() else continue);
+        guard!(let false = is_limit(value) else break);
+        taken.push(value);
+    }
+    assert_eq!(taken, [1, 2]);
+    assert_eq!(digit(Ok(3)), Some(3));
+    assert_eq!(digit(Err(4)), Some(4));
+    assert_eq!(digit(Ok(30)), None);
+}
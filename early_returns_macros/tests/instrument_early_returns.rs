@@ -0,0 +1,191 @@
+// The silent exits are deprecated under `lint-silent-exits`, which `--all-features` enables.
+#![allow(deprecated)]
+
+use std::sync::Mutex;
+
+use std::io;
+use std::time::Duration;
+
+use early_returns::{
+    early, instrument_early_returns, let_or_return, loop_until_some, ok_or_return, retry,
+    retry_backoff, retry_on_interrupted, return_early, some_or_return,
+};
+
+struct Recorder;
+
+static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+impl log::Log for Recorder {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let line = format!("{} {}", record.level(), record.args());
+        RECORDS.lock().unwrap().push(line);
+    }
+
+    fn flush(&self) {}
+}
+
+// The tests run in parallel, so each one only looks at the records of its own function.
+fn last_record(function: &str, call: impl FnOnce()) -> String {
+    let _ = log::set_logger(&Recorder);
+    log::set_max_level(log::LevelFilter::Trace);
+    call();
+    let mut records = RECORDS.lock().unwrap();
+    let function = format!("`{function}`");
+    let last = records.iter().rposition(|r| r.contains(&function)).unwrap();
+    records.remove(last)
+}
+
+const FIND_LINE: u32 = line!() + 4;
+const PARSE_LINE: u32 = line!() + 9;
+#[instrument_early_returns(log = info)]
+fn load_port(config: &[(&str, &str)]) -> u16 {
+    let (_, value) = some_or_return!(config.iter().find(|(key, _)| *key == "port"), 0);
+    if value.is_empty() {
+        return 1;
+    }
+    let_or_return!(Some(first) = value.chars().next(), 2);
+    if first == '+' {
+        return ok_or_return!(value.parse::<u16>(), 3) + 1;
+    }
+    value.parse().unwrap_or(4)
+}
+
+#[instrument_early_returns(log)]
+fn parse_sum(values: &[&str]) -> Result<u32, std::num::ParseIntError> {
+    let mut sum = 0;
+    for value in values {
+        sum += value.parse::<u32>()?;
+    }
+    // A closure has exits of its own.
+    let double = |sum: u32| -> Option<u32> { sum.checked_mul(2) };
+    Ok(double(sum).unwrap_or(sum))
+}
+
+#[test]
+fn should_report_the_exit_taken() {
+    let file = file!();
+    let record = last_record("load_port", || assert_eq!(load_port(&[]), 0));
+    assert_eq!(
+        record,
+        format!("INFO `load_port` returned through `some_or_return!` at {file}:{FIND_LINE}:22")
+    );
+    let record = last_record("load_port", || assert_eq!(load_port(&[("port", "")]), 1));
+    assert!(record.starts_with("INFO `load_port` returned through `return` at "));
+    let record = last_record("load_port", || assert_eq!(load_port(&[("port", "+x")]), 3));
+    assert_eq!(
+        record,
+        format!("INFO `load_port` returned through `ok_or_return!` at {file}:{PARSE_LINE}:16")
+    );
+    let record = last_record("load_port", || assert_eq!(load_port(&[("port", "+8")]), 9));
+    assert!(record.starts_with("INFO `load_port` returned through `return` at "));
+    let record = last_record("load_port", || assert_eq!(load_port(&[("port", "80")]), 80));
+    assert_eq!(record, "INFO `load_port` returned at its end");
+}
+
+#[test]
+fn should_report_question_marks() {
+    let record = last_record("parse_sum", || assert!(parse_sum(&["1", "x"]).is_err()));
+    assert!(record.starts_with("DEBUG `parse_sum` returned through `?` at "));
+    let record = last_record("parse_sum", || assert_eq!(parse_sum(&["1", "2"]), Ok(6)));
+    assert_eq!(record, "DEBUG `parse_sum` returned at its end");
+}
+
+const CAP_LINE: u32 = line!() + 5;
+const ZERO_LINE: u32 = line!() + 7;
+#[instrument_early_returns(log)]
+fn capped(value: i32) -> i32 {
+    if value > 10 {
+        return_early!(10);
+    }
+    match value {
+        0 => return_early!(1),
+        _ => value,
+    }
+}
+
+#[test]
+fn should_report_unconditional_returns() {
+    let file = file!();
+    let record = last_record("capped", || assert_eq!(capped(11), 10));
+    let through = "returned through `return_early!`";
+    assert_eq!(record, format!("DEBUG `capped` {through} at {file}:{CAP_LINE}:9"));
+    let record = last_record("capped", || assert_eq!(capped(0), 1));
+    assert_eq!(record, format!("DEBUG `capped` {through} at {file}:{ZERO_LINE}:14"));
+    let record = last_record("capped", || assert_eq!(capped(5), 5));
+    assert_eq!(record, "DEBUG `capped` returned at its end");
+}
+
+#[instrument_early_returns(tracing = warn)]
+fn first_word(text: &str) -> &str {
+    some_or_return!(text.split_whitespace().next(), "")
+}
+
+#[test]
+fn should_report_through_tracing() {
+    assert_eq!(first_word("hello world"), "hello");
+    assert_eq!(first_word(" "), "");
+}
+
+#[instrument_early_returns(log)]
+fn parse_retried(text: &str) -> u8 {
+    retry!(2, text.parse::<u8>(), 0)
+}
+
+#[instrument_early_returns(log)]
+fn parse_backed_off(text: &str) -> u8 {
+    retry_backoff!(attempts = 2, base = Duration::ZERO, text.parse::<u8>(), 0)
+}
+
+#[instrument_early_returns(log)]
+fn read_uninterrupted(result: fn() -> io::Result<u8>) -> u8 {
+    retry_on_interrupted!(result(), 0)
+}
+
+#[instrument_early_returns(log)]
+fn first_even(values: &[u8]) -> u8 {
+    let mut values = values.iter();
+    loop_until_some!(values.next().filter(|v| *v % 2 == 0).copied(), 2, 1)
+}
+
+#[instrument_early_returns(log)]
+fn doubled(value: Option<u8>) -> u8 {
+    let value = early!(value => return 0);
+    value * 2
+}
+
+#[test]
+fn should_report_the_macros_returning_once_they_give_up() {
+    let through = |function: &str, macro_name: &str| {
+        format!("DEBUG `{function}` returned through `{macro_name}!` at {}:", file!())
+    };
+    let record = last_record("parse_retried", || assert_eq!(parse_retried("x"), 0));
+    assert!(record.starts_with(&through("parse_retried", "retry")), "{record}");
+    let record = last_record("parse_retried", || assert_eq!(parse_retried("7"), 7));
+    assert_eq!(record, "DEBUG `parse_retried` returned at its end");
+
+    let record = last_record("parse_backed_off", || assert_eq!(parse_backed_off("x"), 0));
+    assert!(record.starts_with(&through("parse_backed_off", "retry_backoff")), "{record}");
+    let record = last_record("parse_backed_off", || assert_eq!(parse_backed_off("7"), 7));
+    assert_eq!(record, "DEBUG `parse_backed_off` returned at its end");
+
+    let failed = || Err(io::ErrorKind::NotFound.into());
+    let record = last_record("read_uninterrupted", || assert_eq!(read_uninterrupted(failed), 0));
+    let macro_name = "retry_on_interrupted";
+    assert!(record.starts_with(&through("read_uninterrupted", macro_name)), "{record}");
+    let record = last_record("read_uninterrupted", || assert_eq!(read_uninterrupted(|| Ok(7)), 7));
+    assert_eq!(record, "DEBUG `read_uninterrupted` returned at its end");
+
+    let record = last_record("first_even", || assert_eq!(first_even(&[1, 3, 4]), 1));
+    assert!(record.starts_with(&through("first_even", "loop_until_some")), "{record}");
+    let record = last_record("first_even", || assert_eq!(first_even(&[1, 4]), 4));
+    assert_eq!(record, "DEBUG `first_even` returned at its end");
+
+    let record = last_record("doubled", || assert_eq!(doubled(None), 0));
+    assert!(record.starts_with(&through("doubled", "early")), "{record}");
+    let record = last_record("doubled", || assert_eq!(doubled(Some(2)), 4));
+    assert_eq!(record, "DEBUG `doubled` returned at its end");
+}
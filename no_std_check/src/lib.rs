@@ -0,0 +1,83 @@
+//! Exercises every core macro in a `#![no_std]` crate. Besides the host tests, CI checks it for an
+//! embedded target without `std`:
+//!
+//! `cargo check -p no_std_check --target thumbv7em-none-eabihf`
+#![cfg_attr(not(test), no_std)]
+// The silent exits are deprecated under `lint-silent-exits`, which `--all-features` enables.
+#![allow(deprecated)]
+
+use early_returns::{
+    ok_or_break, ok_or_continue, ok_or_return, some_or_break, some_or_continue, some_or_return,
+};
+
+/// The first digit of the input, or `0xFF` if there is none.
+pub fn first_digit(input: &[u8]) -> u8 {
+    let first = some_or_return!(input.first(), 0xFF);
+    ok_or_return!(char_digit(*first), 0xFF)
+}
+
+/// Sums the leading digits of the input and stores the sum, leaving `sum` untouched if there are
+/// none.
+pub fn store_digit_sum(input: &[u8], sum: &mut u32) {
+    some_or_return!(let Some(&first) = input.first());
+    ok_or_return!(let mut total: u8 = char_digit(first));
+    for byte in &input[1..] {
+        let digit = ok_or_break!(char_digit(*byte));
+        total += digit;
+    }
+    *sum = u32::from(total);
+}
+
+/// Sums every digit of the input, skipping everything else.
+pub fn sum_digits(input: &[u8]) -> u32 {
+    let mut sum = 0;
+    for byte in input {
+        sum += u32::from(ok_or_continue!(char_digit(*byte)));
+    }
+    sum
+}
+
+/// Sums the values of the rows up to the first missing one, skipping the missing values within a
+/// row.
+pub fn sum_rows(rows: &[&[Option<u8>]]) -> u32 {
+    let mut sum = 0;
+    'rows: for row in rows {
+        let first = some_or_break!(row.first(), 'rows);
+        for value in row.iter().skip(1) {
+            sum += u32::from(*some_or_continue!(value));
+        }
+        sum += u32::from(*some_or_break!(first));
+    }
+    sum
+}
+
+fn char_digit(byte: u8) -> Result<u8, ()> {
+    match byte {
+        b'0'..=b'9' => Ok(byte - b'0'),
+        _ => Err(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_extract_or_return() {
+        assert_eq!(first_digit(b"42"), 4);
+        assert_eq!(first_digit(b"x"), 0xFF);
+        assert_eq!(first_digit(b""), 0xFF);
+        let mut sum = 0;
+        store_digit_sum(b"12x3", &mut sum);
+        assert_eq!(sum, 3);
+        store_digit_sum(b"x", &mut sum);
+        assert_eq!(sum, 3);
+    }
+
+    #[test]
+    fn should_break_or_continue() {
+        assert_eq!(sum_digits(b"1x2y3"), 6);
+        let rows: [&[Option<u8>]; 3] = [&[Some(1), None, Some(2)], &[], &[Some(5)]];
+        assert_eq!(sum_rows(&rows), 3);
+    }
+}
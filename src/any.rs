@@ -0,0 +1,235 @@
+/// Either downcast a `dyn Any` (or `dyn Error`) to a concrete type or return from the current
+/// function if it is of a different type. A default return value can be provided.
+///
+/// By default, a reference is "extracted" with `downcast_ref`. With the `mut` marker (e.g.
+/// `downcast_or_return!(mut value, T)`), a mutable reference is "extracted" with `downcast_mut`.
+/// With the `owned` marker (e.g. `downcast_or_return!(owned boxed, T)`), a `Box<dyn Any>` or
+/// `Box<dyn Error>` is consumed with `downcast` and a `Box<T>` is "extracted", in which case the
+/// original box can be bound and used to produce the return value.
+/// ```
+/// use early_returns::downcast_or_return;
+/// use std::any::Any;
+///
+/// fn panic_message(payload: &(dyn Any + Send)) -> Option<&str> {
+///     let message = downcast_or_return!(payload, &str, None);
+///     Some(message)
+/// }
+///
+/// fn into_string(payload: Box<dyn Any + Send>) -> Result<String, Box<dyn Any + Send>> {
+///     let message = downcast_or_return!(owned payload, String, original => Err(original));
+///     Ok(*message)
+/// }
+///
+/// let payload = std::panic::catch_unwind(|| panic!("oops")).unwrap_err();
+/// assert_eq!(panic_message(&*payload), Some("oops"));
+/// assert!(into_string(payload).is_err());
+///
+/// let payload: Box<dyn Any + Send> = Box::new("owned".to_string());
+/// assert_eq!(panic_message(&*payload), None);
+/// assert_eq!(into_string(payload).unwrap(), "owned");
+/// ```
+#[macro_export]
+macro_rules! downcast_or_return {
+    (mut $from:expr, $to:ty) => {
+        $crate::some_or_return!($from.downcast_mut::<$to>())
+    };
+
+    (mut $from:expr, $to:ty, $default_result:expr) => {
+        $crate::some_or_return!($from.downcast_mut::<$to>(), $default_result)
+    };
+
+    (owned $from:expr, $to:ty, $original:ident => $result:expr) => {{
+        match $from.downcast::<$to>() {
+            Ok(f) => f,
+            Err($original) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    (owned $from:expr, $to:ty) => {
+        $crate::ok_or_return!($from.downcast::<$to>())
+    };
+
+    (owned $from:expr, $to:ty, $default_result:expr) => {
+        $crate::ok_or_return!($from.downcast::<$to>(), $default_result)
+    };
+
+    ($from:expr, $to:ty) => {
+        $crate::some_or_return!($from.downcast_ref::<$to>())
+    };
+
+    ($from:expr, $to:ty, $default_result:expr) => {
+        $crate::some_or_return!($from.downcast_ref::<$to>(), $default_result)
+    };
+}
+
+/// Either downcast a `dyn Any` (or `dyn Error`) to a concrete type or continue in a loop if it is
+/// of a different type. If a loop lifetime is specified, that loop will be "continued", otherwise
+/// the immediate loop is "continued".
+///
+/// By default, a reference is "extracted" with `downcast_ref`. With the `mut` marker (e.g.
+/// `downcast_or_continue!(mut value, T)`), a mutable reference is "extracted" with
+/// `downcast_mut`. With the `owned` marker (e.g. `downcast_or_continue!(owned boxed, T)`), a
+/// `Box<dyn Any>` or `Box<dyn Error>` is consumed with `downcast` and a `Box<T>` is "extracted",
+/// in which case the original box can be bound and handled before continuing.
+/// ```
+/// use early_returns::downcast_or_continue;
+/// use std::any::Any;
+///
+/// let plugins: Vec<Box<dyn Any>> = vec![Box::new(1u32), Box::new("two"), Box::new(3u32)];
+/// let mut numbers = Vec::new();
+/// let mut others = Vec::new();
+/// for plugin in plugins {
+///     let number = downcast_or_continue!(owned plugin, u32, original => others.push(original));
+///     numbers.push(*number);
+/// }
+/// assert_eq!(numbers, vec![1, 3]);
+/// assert_eq!(others.len(), 1);
+/// ```
+#[macro_export]
+macro_rules! downcast_or_continue {
+    (mut $from:expr, $to:ty) => {
+        $crate::some_or_continue!($from.downcast_mut::<$to>())
+    };
+
+    (mut $from:expr, $to:ty, $lt:lifetime) => {
+        $crate::some_or_continue!($from.downcast_mut::<$to>(), $lt)
+    };
+
+    (owned $from:expr, $to:ty, $original:ident => $handler:expr) => {{
+        match $from.downcast::<$to>() {
+            Ok(f) => f,
+            Err($original) => {
+                $handler;
+                continue;
+            }
+        }
+    }};
+
+    (owned $from:expr, $to:ty, $lt:lifetime, $original:ident => $handler:expr) => {{
+        match $from.downcast::<$to>() {
+            Ok(f) => f,
+            Err($original) => {
+                $handler;
+                continue $lt;
+            }
+        }
+    }};
+
+    (owned $from:expr, $to:ty) => {
+        $crate::ok_or_continue!($from.downcast::<$to>())
+    };
+
+    (owned $from:expr, $to:ty, $lt:lifetime) => {
+        $crate::ok_or_continue!($from.downcast::<$to>(), $lt)
+    };
+
+    ($from:expr, $to:ty) => {
+        $crate::some_or_continue!($from.downcast_ref::<$to>())
+    };
+
+    ($from:expr, $to:ty, $lt:lifetime) => {
+        $crate::some_or_continue!($from.downcast_ref::<$to>(), $lt)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::any::Any;
+    use std::error::Error;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct NotFound;
+
+    impl fmt::Display for NotFound {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "not found")
+        }
+    }
+
+    impl Error for NotFound {}
+
+    struct Tester {
+        value: i32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester { value: 0 }
+        }
+
+        fn add(&mut self, value: &dyn Any) {
+            self.value += downcast_or_return!(value, i32);
+        }
+
+        fn increment(&mut self, value: &mut dyn Any) {
+            *downcast_or_return!(mut value, i32) += 1;
+        }
+
+        fn add_owned(&mut self, value: Box<dyn Any>) {
+            self.value += *downcast_or_return!(owned value, i32);
+        }
+
+        fn add_all(&mut self, values: &mut [Box<dyn Any>]) {
+            for value in values.iter() {
+                self.value += downcast_or_continue!(value, i32);
+            }
+            'l: for value in values.iter_mut() {
+                for _i in 0..1 {
+                    *downcast_or_continue!(mut value, i32, 'l) *= 10;
+                }
+            }
+        }
+
+        fn add_all_owned(&mut self, values: Vec<Box<dyn Any>>) -> Vec<Box<dyn Any>> {
+            let mut others = Vec::new();
+            'l: for value in values {
+                let value = *downcast_or_continue!(owned value, i32, 'l, original => others.push(original));
+                self.value += value;
+            }
+            others
+        }
+    }
+
+    fn ref_or_default(value: &dyn Any) -> &str {
+        downcast_or_return!(value, String, "other")
+    }
+
+    fn is_not_found(error: Box<dyn Error>) -> Result<bool, Box<dyn Error>> {
+        downcast_or_return!(owned error, NotFound, original => Err(original));
+        Ok(true)
+    }
+
+    #[test]
+    fn should_return_early_on_type_mismatch() {
+        let mut tester = Tester::new();
+        tester.add(&1);
+        tester.add(&"one");
+        tester.add_owned(Box::new(2));
+        tester.add_owned(Box::new("two"));
+        assert_eq!(tester.value, 3);
+        let mut value = 5;
+        tester.increment(&mut value);
+        tester.increment(&mut "five");
+        assert_eq!(value, 6);
+        assert_eq!(ref_or_default(&"str".to_string()), "str");
+        assert_eq!(ref_or_default(&1), "other");
+        assert!(is_not_found(Box::new(NotFound)).unwrap());
+        let error = is_not_found("other".into()).unwrap_err();
+        assert_eq!(error.to_string(), "other");
+    }
+
+    #[test]
+    fn should_continue_on_type_mismatch() {
+        let mut tester = Tester::new();
+        let mut values: Vec<Box<dyn Any>> = vec![Box::new(1), Box::new("two"), Box::new(3)];
+        tester.add_all(&mut values);
+        assert_eq!(tester.value, 4);
+        let others = tester.add_all_owned(values);
+        assert_eq!(tester.value, 44);
+        assert_eq!(others.len(), 1);
+    }
+}
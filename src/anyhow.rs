@@ -0,0 +1,138 @@
+/// Either get the Ok value from a Result type or return the error from the current function, with
+/// a context message attached. The message accepts format arguments and is only formatted on the
+/// escape path.
+///
+/// The error is converted into an `anyhow::Error` with `anyhow::Context` and then into the error
+/// type of the function with `From`, so this works in functions returning `anyhow::Result`.
+/// ```
+/// use early_returns::ok_or_return_context;
+///
+/// fn port(value: &str) -> anyhow::Result<u16> {
+///     let port: u16 = ok_or_return_context!(value.parse(), "invalid port {value:?}");
+///     Ok(port)
+/// }
+///
+/// assert_eq!(port("8080").unwrap(), 8080);
+/// let e = port("http").unwrap_err();
+/// assert_eq!(e.to_string(), "invalid port \"http\"");
+/// assert_eq!(e.root_cause().to_string(), "invalid digit found in string");
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_context {
+    ($from:expr, $($arg:tt)+) => {{
+        match $crate::__private::anyhow::Context::with_context($from, || {
+            ::std::format!($($arg)+)
+        }) {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from(e));
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or return an error built with `anyhow!` from the
+/// given message from the current function. The message accepts format arguments and is only
+/// formatted on the escape path.
+/// ```
+/// use early_returns::some_or_return_context;
+/// use std::collections::HashMap;
+///
+/// fn lookup(users: &HashMap<u32, String>, id: u32) -> anyhow::Result<&str> {
+///     let user = some_or_return_context!(users.get(&id), "no user with id {id}");
+///     Ok(user)
+/// }
+///
+/// let users = HashMap::from([(1, "ada".to_string())]);
+/// assert_eq!(lookup(&users, 1).unwrap(), "ada");
+/// assert_eq!(lookup(&users, 2).unwrap_err().to_string(), "no user with id 2");
+/// ```
+#[macro_export]
+macro_rules! some_or_return_context {
+    ($from:expr, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from(
+                    $crate::__private::anyhow::anyhow!($($arg)+),
+                ))
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use std::cell::Cell;
+
+    fn parse_all(values: &[&str]) -> Result<Vec<i32>> {
+        let mut parsed = Vec::new();
+        for (i, value) in values.iter().enumerate() {
+            parsed.push(ok_or_return_context!(value.parse(), "value {} is invalid", i));
+        }
+        Ok(parsed)
+    }
+
+    fn first_even(values: &[i32]) -> Result<i32> {
+        let even = some_or_return_context!(values.iter().find(|v| *v % 2 == 0), "no even value");
+        Ok(*even)
+    }
+
+    #[derive(Debug)]
+    struct AppError(String);
+
+    impl From<anyhow::Error> for AppError {
+        fn from(e: anyhow::Error) -> Self {
+            AppError(format!("{e:#}"))
+        }
+    }
+
+    fn app(value: Option<&str>) -> std::result::Result<i32, AppError> {
+        let value = some_or_return_context!(value, "missing value");
+        Ok(ok_or_return_context!(value.parse::<i32>(), "bad value"))
+    }
+
+    #[test]
+    fn should_return_error_with_context() {
+        assert_eq!(parse_all(&["1", "2"]).unwrap(), vec![1, 2]);
+        let e = parse_all(&["1", "x"]).unwrap_err();
+        assert_eq!(e.to_string(), "value 1 is invalid");
+        assert!(e.root_cause().is::<std::num::ParseIntError>());
+        assert_eq!(first_even(&[1, 4]).unwrap(), 4);
+        assert_eq!(first_even(&[1, 3]).unwrap_err().to_string(), "no even value");
+    }
+
+    #[test]
+    fn should_convert_into_function_error_type() {
+        assert_eq!(app(Some("3")).unwrap(), 3);
+        assert_eq!(app(None).unwrap_err().0, "missing value");
+        assert_eq!(app(Some("x")).unwrap_err().0, "bad value: invalid digit found in string");
+    }
+
+    #[test]
+    fn should_only_format_message_on_escape_path() {
+        struct Counted<'a>(&'a Cell<i32>);
+
+        impl std::fmt::Display for Counted<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.set(self.0.get() + 1);
+                write!(f, "formatted")
+            }
+        }
+
+        fn check(value: Option<i32>, formatted: &Cell<i32>) -> Result<i32> {
+            let value = some_or_return_context!(value, "{}", Counted(formatted));
+            Ok(ok_or_return_context!(u8::try_from(value), "{}", Counted(formatted)).into())
+        }
+
+        let formatted = Cell::new(0);
+        assert_eq!(check(Some(1), &formatted).unwrap(), 1);
+        assert_eq!(formatted.get(), 0);
+        assert!(check(None, &formatted).is_err());
+        assert!(check(Some(300), &formatted).is_err());
+        assert_eq!(formatted.get(), 2);
+    }
+}
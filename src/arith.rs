@@ -0,0 +1,255 @@
+/// Either get the value of a checked arithmetic operation or return from the current function if
+/// it overflowed (or divided by zero). A default return value can be provided.
+///
+/// Works with any expression producing an `Option`, such as the `checked_add`, `checked_sub`,
+/// `checked_mul` and `checked_div` methods of the integer types.
+/// ```
+/// use early_returns::checked_or_return;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Overflow;
+///
+/// fn buffer_size(width: usize, height: usize) -> Result<usize, Overflow> {
+///     let pixels = checked_or_return!(width.checked_mul(height), Err(Overflow));
+///     let bytes = checked_or_return!(pixels.checked_mul(4), Err(Overflow));
+///     Ok(bytes)
+/// }
+///
+/// assert_eq!(buffer_size(2, 3), Ok(24));
+/// assert_eq!(buffer_size(usize::MAX, 2), Err(Overflow));
+/// ```
+#[macro_export]
+macro_rules! checked_or_return {
+    ($checked:expr) => {
+        $crate::some_or_return!($checked)
+    };
+
+    ($checked:expr, $default_result:expr) => {
+        $crate::some_or_return!($checked, $default_result)
+    };
+}
+
+/// Either get the value of a checked arithmetic operation or break out of a loop if it overflowed
+/// (or divided by zero). If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+/// ```
+/// use early_returns::checked_or_break;
+///
+/// let mut total: u8 = 0;
+/// for value in [100, 100, 100] {
+///     total = checked_or_break!(total.checked_add(value));
+/// }
+/// assert_eq!(total, 200);
+/// ```
+#[macro_export]
+macro_rules! checked_or_break {
+    ($checked:expr) => {
+        $crate::some_or_break!($checked)
+    };
+
+    ($checked:expr, $lt:lifetime) => {
+        $crate::some_or_break!($checked, $lt)
+    };
+}
+
+/// Either get the value of a checked arithmetic operation or continue in a loop if it overflowed
+/// (or divided by zero). If a loop lifetime is specified, that loop will be "continued",
+/// otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::checked_or_continue;
+///
+/// let mut doubled = Vec::new();
+/// for value in [1u8, 200, 3] {
+///     doubled.push(checked_or_continue!(value.checked_mul(2)));
+/// }
+/// assert_eq!(doubled, vec![2, 6]);
+/// ```
+#[macro_export]
+macro_rules! checked_or_continue {
+    ($checked:expr) => {
+        $crate::some_or_continue!($checked)
+    };
+
+    ($checked:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($checked, $lt)
+    };
+}
+
+/// Either divide two integers or return from the current function if the divisor is zero (or the
+/// division overflows, as `i32::MIN / -1` does). A default return value can be provided.
+///
+/// Works with every integer type, as the division is done with its `checked_div` method.
+/// ```
+/// use early_returns::div_or_return;
+///
+/// fn average(values: &[u32]) -> Option<u32> {
+///     let sum: u32 = values.iter().sum();
+///     let average = div_or_return!(sum, values.len() as u32, None);
+///     Some(average)
+/// }
+///
+/// assert_eq!(average(&[1, 2, 3]), Some(2));
+/// assert_eq!(average(&[]), None);
+/// ```
+#[macro_export]
+macro_rules! div_or_return {
+    ($dividend:expr, $divisor:expr) => {
+        $crate::some_or_return!($dividend.checked_div($divisor))
+    };
+
+    ($dividend:expr, $divisor:expr, $default_result:expr) => {
+        $crate::some_or_return!($dividend.checked_div($divisor), $default_result)
+    };
+}
+
+/// Either convert an integer into the matching `NonZero` type or return from the current function
+/// if it is zero. A default return value can be provided.
+///
+/// Works with every type that has a `new` constructor returning an `Option`, such as
+/// `core::num::NonZeroUsize` and the other `NonZero*` types.
+/// ```
+/// use early_returns::nonzero_or_return;
+/// use std::num::NonZeroUsize;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct EmptyInput;
+///
+/// fn chunk_count(input: &[u8]) -> Result<NonZeroUsize, EmptyInput> {
+///     let n = nonzero_or_return!(NonZeroUsize, input.len(), Err(EmptyInput));
+///     Ok(n)
+/// }
+///
+/// assert_eq!(chunk_count(b"abc").map(NonZeroUsize::get), Ok(3));
+/// assert_eq!(chunk_count(b""), Err(EmptyInput));
+/// ```
+#[macro_export]
+macro_rules! nonzero_or_return {
+    ($nonzero:ty, $value:expr) => {
+        $crate::some_or_return!(<$nonzero>::new($value))
+    };
+
+    ($nonzero:ty, $value:expr, $default_result:expr) => {
+        $crate::some_or_return!(<$nonzero>::new($value), $default_result)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::{NonZeroI64, NonZeroU8};
+
+    struct Tester {
+        value: u8,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester { value: 0 }
+        }
+
+        fn add(&mut self, value: u8) {
+            self.value = checked_or_return!(self.value.checked_add(value));
+        }
+
+        fn add_with_break(&mut self, values: Vec<u8>) {
+            for value in values {
+                self.value = checked_or_break!(self.value.checked_add(value));
+            }
+        }
+
+        fn add_with_break_with_lifetime(&mut self, values: Vec<u8>) {
+            'l: for value in values {
+                for _i in 0..1 {
+                    self.value = checked_or_break!(self.value.checked_add(value), 'l);
+                }
+            }
+        }
+
+        fn add_with_continue(&mut self, values: Vec<u8>) {
+            for value in values {
+                self.value = checked_or_continue!(self.value.checked_add(value));
+            }
+        }
+
+        fn add_with_continue_with_lifetime(&mut self, values: Vec<u8>) {
+            'l: for value in values {
+                for _i in 0..1 {
+                    self.value = checked_or_continue!(self.value.checked_add(value), 'l);
+                }
+            }
+        }
+
+        fn divide(&mut self, divisor: u8) {
+            self.value = div_or_return!(self.value, divisor);
+        }
+
+        fn divide_by_nonzero(&mut self, divisor: u8) {
+            let divisor = nonzero_or_return!(NonZeroU8, divisor);
+            self.value /= divisor;
+        }
+    }
+
+    fn product_or_default(a: i32, b: i32) -> i32 {
+        checked_or_return!(a.checked_mul(b), -1)
+    }
+
+    fn quotient_or_default(a: i32, b: i32) -> i32 {
+        div_or_return!(a, b, 0)
+    }
+
+    #[test]
+    fn should_return_early_on_overflow() {
+        let mut tester = Tester::new();
+        tester.add(200);
+        tester.add(100);
+        assert_eq!(tester.value, 200);
+        assert_eq!(product_or_default(6, 7), 42);
+        assert_eq!(product_or_default(i32::MAX, 2), -1);
+    }
+
+    #[test]
+    fn should_break_on_overflow() {
+        let mut tester = Tester::new();
+        tester.add_with_break(vec![200, 100, 1]);
+        assert_eq!(tester.value, 200);
+        tester.add_with_break_with_lifetime(vec![50, 10, 1]);
+        assert_eq!(tester.value, 250);
+    }
+
+    #[test]
+    fn should_continue_on_overflow() {
+        let mut tester = Tester::new();
+        tester.add_with_continue(vec![200, 100, 1]);
+        assert_eq!(tester.value, 201);
+        tester.add_with_continue_with_lifetime(vec![100, 4]);
+        assert_eq!(tester.value, 205);
+    }
+
+    #[test]
+    fn should_return_early_on_division_by_zero() {
+        let mut tester = Tester::new();
+        tester.value = 10;
+        tester.divide(0);
+        assert_eq!(tester.value, 10);
+        tester.divide(2);
+        assert_eq!(tester.value, 5);
+        assert_eq!(quotient_or_default(9, 3), 3);
+        assert_eq!(quotient_or_default(9, 0), 0);
+        assert_eq!(quotient_or_default(i32::MIN, -1), 0);
+    }
+
+    fn nonzero_or_default(value: i64) -> i64 {
+        nonzero_or_return!(NonZeroI64, value, 0).get()
+    }
+
+    #[test]
+    fn should_return_early_on_zero() {
+        let mut tester = Tester::new();
+        tester.value = 10;
+        tester.divide_by_nonzero(0);
+        assert_eq!(tester.value, 10);
+        tester.divide_by_nonzero(5);
+        assert_eq!(tester.value, 2);
+        assert_eq!(nonzero_or_default(-3), -3);
+        assert_eq!(nonzero_or_default(0), 0);
+    }
+}
@@ -0,0 +1,189 @@
+/// Either get the Ok value from a Result type or, inside an `async_stream::stream!` block, yield an
+/// item for the error and continue in a loop. The escape can be configured instead with a trailing
+/// `continue` or `break` (either with an optional loop lifetime), or `return`, which ends the
+/// stream.
+///
+/// `stream!` only turns the `yield`s written in its block into items, so the `yield` is spelled
+/// out in the invocation, with the error bound (`e => yield Err(e)`).
+/// ```
+/// use async_stream::stream;
+/// use early_returns::ok_or_yield;
+/// use futures_util::{pin_mut, StreamExt};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let parsed = stream! {
+///     for row in ["1", "x", "3"] {
+///         let value: u32 = ok_or_yield!(row.parse(), e => yield Err(e.to_string()));
+///         yield Ok(value);
+///     }
+/// };
+/// pin_mut!(parsed);
+/// assert_eq!(parsed.next().await, Some(Ok(1)));
+/// assert_eq!(parsed.next().await, Some(Err("invalid digit found in string".to_string())));
+/// assert_eq!(parsed.next().await, Some(Ok(3)));
+/// assert_eq!(parsed.next().await, None);
+/// # });
+/// ```
+#[macro_export]
+macro_rules! ok_or_yield {
+    ($from:expr, $e:ident => $yielded:expr) => {
+        $crate::ok_or_yield!($from, $e => $yielded, continue)
+    };
+
+    ($from:expr, $e:ident => $yielded:expr, continue $($lt:lifetime)?) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $yielded;
+                continue $($lt)?
+            }
+        }
+    }};
+
+    ($from:expr, $e:ident => $yielded:expr, break $($lt:lifetime)?) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $yielded;
+                break $($lt)?
+            }
+        }
+    }};
+
+    ($from:expr, $e:ident => $yielded:expr, return) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $yielded;
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or, inside an `async_stream::stream!` block, yield an
+/// item and continue in a loop. The escape can be configured instead with a trailing `continue` or
+/// `break` (either with an optional loop lifetime), or `return`, which ends the stream.
+///
+/// `stream!` only turns the `yield`s written in its block into items, so the `yield` is spelled
+/// out in the invocation.
+/// ```
+/// use async_stream::stream;
+/// use early_returns::some_or_yield;
+/// use futures_util::StreamExt;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let entries = ["a=1", "b", "c=3"];
+/// let names = stream! {
+///     for entry in entries {
+///         let (name, _value) = some_or_yield!(entry.split_once('='), yield Err(entry), return);
+///         yield Ok(name);
+///     }
+/// };
+/// let names: Vec<_> = Box::pin(names).collect().await;
+/// assert_eq!(names, [Ok("a"), Err("b")]);
+/// # });
+/// ```
+#[macro_export]
+macro_rules! some_or_yield {
+    ($from:expr, $yielded:expr) => {
+        $crate::some_or_yield!($from, $yielded, continue)
+    };
+
+    ($from:expr, $yielded:expr, continue $($lt:lifetime)?) => {
+        $crate::__extract_or!(Some, $from, {
+            $yielded;
+            continue $($lt)?
+        })
+    };
+
+    ($from:expr, $yielded:expr, break $($lt:lifetime)?) => {
+        $crate::__extract_or!(Some, $from, {
+            $yielded;
+            break $($lt)?
+        })
+    };
+
+    ($from:expr, $yielded:expr, return) => {
+        $crate::__extract_or!(Some, $from, {
+            $yielded;
+            $crate::__mark_early_exit!();
+            return;
+        })
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use async_stream::stream;
+    use futures_util::{Stream, StreamExt};
+
+    async fn items<S: Stream>(stream: S) -> Vec<S::Item> {
+        Box::pin(stream).collect().await
+    }
+
+    #[tokio::test]
+    async fn should_yield_errors_and_continue() {
+        let values = [Ok(1), Err("a"), Ok(2)];
+        let continued = stream! {
+            for value in values {
+                yield Ok(ok_or_yield!(value, e => yield Err(e)));
+            }
+        };
+        assert_eq!(items(continued).await, [Ok(1), Err("a"), Ok(2)]);
+        let labeled = stream! {
+            'l: for value in values {
+                for _i in 0..2 {
+                    yield Ok(ok_or_yield!(value, e => yield Err(e), continue 'l));
+                }
+            }
+        };
+        assert_eq!(items(labeled).await, [Ok(1), Ok(1), Err("a"), Ok(2), Ok(2)]);
+    }
+
+    #[tokio::test]
+    async fn should_yield_errors_and_stop() {
+        let values = [Ok(1), Err("a"), Ok(2)];
+        let broken = stream! {
+            for value in values {
+                yield Ok(ok_or_yield!(value, e => yield Err(e), break));
+            }
+            yield Ok(0);
+        };
+        assert_eq!(items(broken).await, [Ok(1), Err("a"), Ok(0)]);
+        let returned = stream! {
+            for value in values {
+                yield Ok(ok_or_yield!(value, e => yield Err(e), return));
+            }
+            yield Ok(0);
+        };
+        assert_eq!(items(returned).await, [Ok(1), Err("a")]);
+    }
+
+    #[tokio::test]
+    async fn should_yield_when_none() {
+        let values = [Some(1), None, Some(2)];
+        let continued = stream! {
+            for value in values {
+                yield some_or_yield!(value, yield -1);
+            }
+        };
+        assert_eq!(items(continued).await, [1, -1, 2]);
+        let labeled = stream! {
+            'l: for value in values {
+                for _i in 0..2 {
+                    yield some_or_yield!(value, yield -1, break 'l);
+                }
+            }
+        };
+        assert_eq!(items(labeled).await, [1, 1, -1]);
+        let returned = stream! {
+            for value in values {
+                yield some_or_yield!(value, yield -1, return);
+            }
+            yield 0;
+        };
+        assert_eq!(items(returned).await, [1, -1]);
+    }
+}
@@ -0,0 +1,106 @@
+/// Either get the Ok value from a Result type or return the error from the current function,
+/// boxed into the function's dynamic error type (such as `Box<dyn Error + Send + Sync>`) with
+/// `From`.
+///
+/// The error can also be bound and mapped before it is boxed (e.g. into a `String` or another
+/// error type), which is handy for adding a message without pulling in an error-handling crate.
+/// ```
+/// use early_returns::ok_or_return_boxed;
+/// use std::error::Error;
+///
+/// fn port(value: &str) -> Result<u16, Box<dyn Error + Send + Sync>> {
+///     let port: u16 = ok_or_return_boxed!(value.parse());
+///     Ok(port)
+/// }
+///
+/// fn host(value: &str) -> Result<std::net::Ipv4Addr, Box<dyn Error + Send + Sync>> {
+///     let host = ok_or_return_boxed!(value.parse(), e => format!("invalid host {value:?}: {e}"));
+///     Ok(host)
+/// }
+///
+/// assert_eq!(port("8080").unwrap(), 8080);
+/// assert_eq!(port("http").unwrap_err().to_string(), "invalid digit found in string");
+/// assert_eq!(
+///     host("localhost").unwrap_err().to_string(),
+///     "invalid host \"localhost\": invalid IPv4 address syntax"
+/// );
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_boxed {
+    ($from:expr, $e:ident => $mapped:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from($mapped));
+            }
+        }
+    }};
+
+    ($from:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from(e));
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+    use std::fmt;
+
+    type BoxError = Box<dyn Error + Send + Sync>;
+
+    #[derive(Debug)]
+    struct Invalid(i32);
+
+    impl fmt::Display for Invalid {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid value {}", self.0)
+        }
+    }
+
+    impl Error for Invalid {}
+
+    fn validate(value: i32) -> Result<i32, Invalid> {
+        if value < 0 {
+            Err(Invalid(value))
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn sum(values: &[i32]) -> Result<i32, BoxError> {
+        let mut sum = 0;
+        for value in values {
+            sum += ok_or_return_boxed!(validate(*value));
+        }
+        Ok(sum)
+    }
+
+    fn sum_mapped(values: &[i32]) -> Result<i32, Box<dyn Error>> {
+        let mut sum = 0;
+        for (i, value) in values.iter().enumerate() {
+            sum += ok_or_return_boxed!(validate(*value), e => format!("value {i}: {e}"));
+        }
+        Ok(sum)
+    }
+
+    #[test]
+    fn should_return_boxed_error() {
+        assert_eq!(sum(&[1, 2]).unwrap(), 3);
+        let e = sum(&[1, -2]).unwrap_err();
+        assert_eq!(e.downcast_ref::<Invalid>().unwrap().0, -2);
+    }
+
+    #[test]
+    fn should_return_mapped_boxed_error() {
+        assert_eq!(sum_mapped(&[1, 2]).unwrap(), 3);
+        let e = sum_mapped(&[1, -2]).unwrap_err();
+        assert_eq!(e.to_string(), "value 1: invalid value -2");
+    }
+}
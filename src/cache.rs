@@ -0,0 +1,147 @@
+/// Either get a value from a cache lookup, or compute it and insert it into the cache, or return
+/// from the current function if the computation fails. A default return value can be provided.
+///
+/// The lookup is an `Option` expression that is evaluated first. Only if it is `None` is the
+/// compute closure called; if it returns `Ok`, the insert closure is called with a reference to
+/// the computed value before it is returned. If it returns `Err`, nothing is inserted.
+/// ```
+/// use early_returns::cached_or_compute_or_return;
+/// use std::collections::HashMap;
+///
+/// fn expensive(key: &str) -> Result<usize, ()> {
+///     Ok(key.len())
+/// }
+///
+/// fn length(cache: &mut HashMap<String, usize>, key: &str) -> Option<usize> {
+///     let len = cached_or_compute_or_return!(
+///         cache.get(key).copied(),
+///         || expensive(key),
+///         |len: &usize| { cache.insert(key.to_string(), *len); },
+///         None
+///     );
+///     Some(len)
+/// }
+/// ```
+#[macro_export]
+macro_rules! cached_or_compute_or_return {
+    ($lookup:expr, $compute:expr, $insert:expr) => {
+        $crate::__extract_or!(Some, $lookup, or {
+            let f = $crate::ok_or_return!(($compute)());
+            ($insert)(&f);
+            f
+        })
+    };
+
+    ($lookup:expr, $compute:expr, $insert:expr, $default_result:expr) => {
+        $crate::__extract_or!(Some, $lookup, or {
+            let f = $crate::ok_or_return!(($compute)(), $default_result);
+            ($insert)(&f);
+            f
+        })
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    struct Tester {
+        cache: HashMap<i32, i32>,
+        computations: i32,
+        value: i32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester {
+                cache: HashMap::new(),
+                computations: 0,
+                value: 0,
+            }
+        }
+
+        fn compute(&mut self, key: i32) -> Result<i32, ()> {
+            self.computations += 1;
+            if key < 0 {
+                Err(())
+            } else {
+                Ok(key * 10)
+            }
+        }
+
+        fn increment_with_cached(&mut self, key: i32) {
+            let value = cached_or_compute_or_return!(
+                self.cache.get(&key).copied(),
+                || self.compute(key),
+                |value: &i32| {
+                    self.cache.insert(key, *value);
+                }
+            );
+            self.value += value;
+        }
+
+        fn cached_or_default(&mut self, key: i32) -> i32 {
+            cached_or_compute_or_return!(
+                self.cache.get(&key).copied(),
+                || self.compute(key),
+                |value: &i32| {
+                    self.cache.insert(key, *value);
+                },
+                -1
+            )
+        }
+    }
+
+    #[test]
+    fn should_compute_and_insert_when_not_cached() {
+        let mut tester = Tester::new();
+        tester.increment_with_cached(1);
+        assert_eq!(tester.value, 10);
+        assert_eq!(tester.computations, 1);
+        assert_eq!(tester.cache.get(&1), Some(&10));
+    }
+
+    #[test]
+    fn should_not_compute_when_cached() {
+        let mut tester = Tester::new();
+        tester.increment_with_cached(1);
+        tester.increment_with_cached(1);
+        assert_eq!(tester.value, 20);
+        assert_eq!(tester.computations, 1);
+    }
+
+    #[test]
+    fn should_return_early_without_inserting_when_compute_fails() {
+        let mut tester = Tester::new();
+        tester.increment_with_cached(-1);
+        assert_eq!(tester.value, 0);
+        assert_eq!(tester.computations, 1);
+        assert!(tester.cache.is_empty());
+    }
+
+    #[test]
+    fn should_return_default_when_compute_fails() {
+        let mut tester = Tester::new();
+        assert_eq!(tester.cached_or_default(2), 20);
+        assert_eq!(tester.cached_or_default(-2), -1);
+        assert_eq!(tester.cache.len(), 1);
+    }
+
+    #[cfg(feature = "let-else")]
+    #[test]
+    fn should_release_lock_before_computing() {
+        let cache = std::sync::Mutex::new(HashMap::new());
+        let cached = || {
+            cached_or_compute_or_return!(
+                cache.lock().unwrap().get(&1).copied(),
+                || cache.try_lock().map(|_| 10).map_err(|_| ()),
+                |value: &i32| {
+                    cache.lock().unwrap().insert(1, *value);
+                },
+                -1
+            )
+        };
+        assert_eq!(cached(), 10);
+        assert_eq!(cached(), 10);
+    }
+}
@@ -0,0 +1,135 @@
+/// Return from the current function if a `tokio_util::sync::CancellationToken` has been cancelled,
+/// so long-running work can stop at a graceful-shutdown check. A default return value can be
+/// provided. Requires the `tokio-util` feature.
+/// ```
+/// use early_returns::not_cancelled_or_return;
+/// use tokio_util::sync::CancellationToken;
+///
+/// fn flush(token: &CancellationToken, pending: &mut Vec<u32>) -> Result<usize, &'static str> {
+///     not_cancelled_or_return!(token, Err("shutting down"));
+///     let flushed = pending.len();
+///     pending.clear();
+///     Ok(flushed)
+/// }
+///
+/// let token = CancellationToken::new();
+/// assert_eq!(flush(&token, &mut vec![1, 2]), Ok(2));
+/// token.cancel();
+/// assert_eq!(flush(&token, &mut vec![1, 2]), Err("shutting down"));
+/// ```
+#[macro_export]
+macro_rules! not_cancelled_or_return {
+    ($token:expr) => {
+        if $crate::__private::tokio_util::sync::CancellationToken::is_cancelled(&$token) {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return;
+        }
+    };
+
+    ($token:expr, $default_result:expr) => {
+        if $crate::__private::tokio_util::sync::CancellationToken::is_cancelled(&$token) {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        }
+    };
+}
+
+/// Break out of a loop if a `tokio_util::sync::CancellationToken` has been cancelled. Placed at
+/// the top of the loop body, the token is checked before every iteration. If a loop lifetime is
+/// specified, that loop will be exited, otherwise the immediate loop is exited. Requires the
+/// `tokio-util` feature.
+/// ```
+/// use early_returns::not_cancelled_or_break;
+/// use tokio::sync::mpsc;
+/// use tokio_util::sync::CancellationToken;
+///
+/// async fn worker(token: CancellationToken, mut jobs: mpsc::Receiver<u32>) -> u32 {
+///     let mut done = 0;
+///     while let Some(job) = jobs.recv().await {
+///         not_cancelled_or_break!(token);
+///         done += job;
+///     }
+///     done
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let token = CancellationToken::new();
+/// let (tx, rx) = mpsc::channel(4);
+/// tx.send(1).await.unwrap();
+/// tx.send(2).await.unwrap();
+/// token.cancel();
+/// assert_eq!(worker(token, rx).await, 0);
+/// # });
+/// ```
+#[macro_export]
+macro_rules! not_cancelled_or_break {
+    ($token:expr) => {
+        if $crate::__private::tokio_util::sync::CancellationToken::is_cancelled(&$token) {
+            $crate::__private::silent_break();
+            break;
+        }
+    };
+
+    ($token:expr, $lt:lifetime) => {
+        if $crate::__private::tokio_util::sync::CancellationToken::is_cancelled(&$token) {
+            $crate::__private::silent_break();
+            break $lt;
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use tokio_util::sync::CancellationToken;
+
+    fn step(token: &CancellationToken, steps: &mut u32) {
+        not_cancelled_or_return!(token);
+        *steps += 1;
+    }
+
+    fn remaining(token: CancellationToken) -> Option<u32> {
+        not_cancelled_or_return!(token, None);
+        Some(1)
+    }
+
+    #[test]
+    fn should_return_once_cancelled() {
+        let token = CancellationToken::new();
+        let mut steps = 0;
+        step(&token, &mut steps);
+        assert_eq!(remaining(token.clone()), Some(1));
+        token.cancel();
+        step(&token, &mut steps);
+        assert_eq!(steps, 1);
+        assert_eq!(remaining(token), None);
+    }
+
+    #[test]
+    fn should_break_once_cancelled() {
+        let token = CancellationToken::new();
+        let child = token.child_token();
+        let mut iterations = 0;
+        for i in 0..5 {
+            not_cancelled_or_break!(child);
+            iterations += 1;
+            if i == 1 {
+                token.cancel();
+            }
+        }
+        assert_eq!(iterations, 2);
+        let token = CancellationToken::new();
+        let mut iterations = 0;
+        'l: for i in 0..5 {
+            for _j in 0..2 {
+                not_cancelled_or_break!(&token, 'l);
+                iterations += 1;
+            }
+            if i == 1 {
+                token.cancel();
+            }
+        }
+        assert_eq!(iterations, 4);
+    }
+}
@@ -0,0 +1,680 @@
+/// Either receive a value from a channel, blocking until one is available, or return from the
+/// current function once the channel is disconnected. A default return value can be provided.
+///
+/// Works with `std::sync::mpsc` receivers and requires the `std` feature (enabled by default).
+/// With the `async` marker, the receiver's `recv()` is awaited instead and the channel is closed
+/// when it resolves to `None`, which is the case for `tokio::sync::mpsc` receivers.
+/// ```
+/// use early_returns::recv_or_return;
+/// use std::sync::mpsc;
+///
+/// fn sum_two(rx: &mpsc::Receiver<i32>) -> Option<i32> {
+///     let a = recv_or_return!(rx, None);
+///     let b = recv_or_return!(rx, None);
+///     Some(a + b)
+/// }
+///
+/// let (tx, rx) = mpsc::channel();
+/// tx.send(1).unwrap();
+/// tx.send(2).unwrap();
+/// tx.send(3).unwrap();
+/// drop(tx);
+/// assert_eq!(sum_two(&rx), Some(3));
+/// assert_eq!(sum_two(&rx), None);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! recv_or_return {
+    (async $rx:expr) => {
+        $crate::some_or_return!($rx.recv().await)
+    };
+
+    (async $rx:expr, $default_result:expr) => {
+        $crate::some_or_return!($rx.recv().await, $default_result)
+    };
+
+    ($rx:expr) => {
+        $crate::ok_or_return!($rx.recv())
+    };
+
+    ($rx:expr, $default_result:expr) => {
+        $crate::ok_or_return!($rx.recv(), $default_result)
+    };
+}
+
+/// Either receive a value from a channel, blocking until one is available, or break out of a loop
+/// once the channel is disconnected. If a loop lifetime is specified, that loop will be exited,
+/// otherwise the immediate loop is exited.
+///
+/// Works with `std::sync::mpsc` receivers and requires the `std` feature (enabled by default).
+/// With the `async` marker, the receiver's `recv()` is awaited instead and the channel is closed
+/// when it resolves to `None`, which is the case for `tokio::sync::mpsc` receivers.
+/// ```
+/// use early_returns::recv_or_break;
+/// use std::sync::mpsc;
+/// use std::thread;
+///
+/// let (tx, rx) = mpsc::channel();
+/// let worker = thread::spawn(move || {
+///     let mut sum = 0;
+///     loop {
+///         let value: i32 = recv_or_break!(rx);
+///         sum += value;
+///     }
+///     sum
+/// });
+/// for value in 1..=3 {
+///     tx.send(value).unwrap();
+/// }
+/// drop(tx);
+/// assert_eq!(worker.join().unwrap(), 6);
+/// ```
+///
+/// In an async actor loop:
+/// ```
+/// use early_returns::recv_or_break;
+/// use tokio::sync::mpsc;
+///
+/// async fn actor(mut rx: mpsc::Receiver<i32>) -> i32 {
+///     let mut sum = 0;
+///     loop {
+///         sum += recv_or_break!(async rx);
+///     }
+///     sum
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (tx, rx) = mpsc::channel(4);
+/// tx.send(1).await.unwrap();
+/// tx.send(2).await.unwrap();
+/// drop(tx);
+/// assert_eq!(actor(rx).await, 3);
+/// # });
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! recv_or_break {
+    (async $rx:expr) => {
+        $crate::some_or_break!($rx.recv().await)
+    };
+
+    (async $rx:expr, $lt:lifetime) => {
+        $crate::some_or_break!($rx.recv().await, $lt)
+    };
+
+    ($rx:expr) => {
+        $crate::ok_or_break!($rx.recv())
+    };
+
+    ($rx:expr, $lt:lifetime) => {
+        $crate::ok_or_break!($rx.recv(), $lt)
+    };
+}
+
+/// Either receive a value from a channel without blocking, or continue in a loop when no value is
+/// available yet, or break out of the loop once the channel is disconnected. If a loop lifetime is
+/// specified, that loop will be "continued" or exited, otherwise the immediate loop is.
+///
+/// Works with `std::sync::mpsc` receivers, and with `crossbeam_channel` receivers when the
+/// `crossbeam` feature is enabled. Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::try_recv_or_continue;
+/// use std::sync::mpsc;
+///
+/// let (tx, rx) = mpsc::channel();
+/// tx.send(1).unwrap();
+/// tx.send(2).unwrap();
+/// drop(tx);
+///
+/// let mut received = Vec::new();
+/// loop {
+///     let value: i32 = try_recv_or_continue!(rx);
+///     received.push(value);
+/// }
+/// assert_eq!(received, vec![1, 2]);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! try_recv_or_continue {
+    ($rx:expr) => {
+        $crate::recv_timeout_or_continue!(@recv $rx.try_recv())
+    };
+
+    ($rx:expr, $lt:lifetime) => {
+        $crate::recv_timeout_or_continue!(@recv $rx.try_recv(), $lt)
+    };
+}
+
+/// Either receive a value from a channel, blocking for at most the given timeout, or continue in
+/// a loop when the timeout elapses, or break out of the loop once the channel is disconnected. If
+/// a loop lifetime is specified, that loop will be "continued" or exited, otherwise the immediate
+/// loop is.
+///
+/// Works with `std::sync::mpsc` receivers, and with `crossbeam_channel` receivers when the
+/// `crossbeam` feature is enabled. Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::recv_timeout_or_continue;
+/// use std::sync::mpsc;
+/// use std::time::Duration;
+///
+/// let (tx, rx) = mpsc::channel();
+/// let mut sender = Some(tx);
+/// let mut polls = 0;
+/// let mut received = Vec::new();
+/// loop {
+///     polls += 1;
+///     match polls {
+///         1 => sender.as_ref().unwrap().send("first").unwrap(),
+///         3 => sender = None,
+///         _ => {}
+///     }
+///     let value = recv_timeout_or_continue!(rx, Duration::from_millis(1));
+///     received.push(value);
+/// }
+/// assert_eq!(received, vec!["first"]);
+/// assert_eq!(polls, 3);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! recv_timeout_or_continue {
+    (@recv $from:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                if $crate::__private::ChannelError::is_disconnected(&e) {
+                    $crate::__private::silent_break();
+                    break;
+                }
+                $crate::__private::silent_continue();
+                continue;
+            }
+        }
+    }};
+
+    (@recv $from:expr, $lt:lifetime) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                if $crate::__private::ChannelError::is_disconnected(&e) {
+                    $crate::__private::silent_break();
+                    break $lt;
+                }
+                $crate::__private::silent_continue();
+                continue $lt;
+            }
+        }
+    }};
+
+    ($rx:expr, $timeout:expr) => {
+        $crate::recv_timeout_or_continue!(@recv $rx.recv_timeout($timeout))
+    };
+
+    ($rx:expr, $timeout:expr, $lt:lifetime) => {
+        $crate::recv_timeout_or_continue!(@recv $rx.recv_timeout($timeout), $lt)
+    };
+}
+
+/// The errors of non-blocking and timed receives, which either mean that no value is available
+/// (yet) or that the channel is disconnected.
+#[doc(hidden)]
+pub trait ChannelError {
+    fn is_disconnected(&self) -> bool;
+}
+
+impl ChannelError for std::sync::mpsc::TryRecvError {
+    fn is_disconnected(&self) -> bool {
+        matches!(self, std::sync::mpsc::TryRecvError::Disconnected)
+    }
+}
+
+impl ChannelError for std::sync::mpsc::RecvTimeoutError {
+    fn is_disconnected(&self) -> bool {
+        matches!(self, std::sync::mpsc::RecvTimeoutError::Disconnected)
+    }
+}
+
+#[cfg(feature = "crossbeam")]
+impl ChannelError for crossbeam_channel::TryRecvError {
+    fn is_disconnected(&self) -> bool {
+        self.is_disconnected()
+    }
+}
+
+#[cfg(feature = "crossbeam")]
+impl ChannelError for crossbeam_channel::RecvTimeoutError {
+    fn is_disconnected(&self) -> bool {
+        self.is_disconnected()
+    }
+}
+
+/// Either get the Ok value of a channel send, or return from the current function if the send
+/// failed because the channel is disconnected. A default return value can be provided, or the
+/// unsent message (carried in the error) can be bound and used to produce the return value.
+///
+/// Works with the send errors of `std::sync::mpsc`, `crossbeam_channel` and `tokio::sync::mpsc`,
+/// which all carry the unsent message as their field. Requires the `std` feature (enabled by
+/// default).
+/// ```
+/// use early_returns::send_or_return;
+/// use std::sync::mpsc;
+///
+/// fn forward(tx: &mpsc::Sender<String>, message: String) -> Result<(), String> {
+///     send_or_return!(tx.send(message), unsent => Err(unsent));
+///     Ok(())
+/// }
+///
+/// let (tx, rx) = mpsc::channel();
+/// assert_eq!(forward(&tx, "hello".to_string()), Ok(()));
+/// drop(rx);
+/// assert_eq!(forward(&tx, "bye".to_string()), Err("bye".to_string()));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! send_or_return {
+    ($from:expr, $unsent:ident => $result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                let $unsent = e.0;
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_return!($from)
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::ok_or_return!($from, $default_result)
+    };
+}
+
+/// Either get the Ok value of a channel send, or break out of a loop if the send failed because
+/// the channel is disconnected. If a loop lifetime is specified, that loop will be exited,
+/// otherwise the immediate loop is exited. The unsent message (carried in the error) can be bound
+/// and handled before breaking, e.g. to requeue it.
+///
+/// Works with the send errors of `std::sync::mpsc`, `crossbeam_channel` and `tokio::sync::mpsc`,
+/// which all carry the unsent message as their field. Requires the `std` feature (enabled by
+/// default).
+/// ```
+/// use early_returns::send_or_break;
+/// use std::collections::VecDeque;
+/// use std::sync::mpsc;
+///
+/// let (tx, rx) = mpsc::channel();
+/// drop(rx);
+///
+/// let mut queue = VecDeque::from([1, 2, 3]);
+/// while let Some(message) = queue.pop_front() {
+///     send_or_break!(tx.send(message), unsent => queue.push_front(unsent));
+/// }
+/// assert_eq!(queue, VecDeque::from([1, 2, 3]));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! send_or_break {
+    ($from:expr, $unsent:ident => $handler:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                let $unsent = e.0;
+                $handler;
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $unsent:ident => $handler:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                let $unsent = e.0;
+                $handler;
+                break $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_break!($from)
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::ok_or_break!($from, $lt)
+    };
+}
+
+/// Either await the value of a oneshot receiver, or return from the current async function if the
+/// sender was dropped without sending a value. A default return value can be provided.
+///
+/// Requires the `tokio` feature.
+/// ```
+/// use early_returns::oneshot_or_return;
+/// use tokio::sync::oneshot;
+///
+/// async fn double(rx: oneshot::Receiver<i32>) -> Option<i32> {
+///     let value = oneshot_or_return!(rx, None);
+///     Some(value * 2)
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (tx, rx) = oneshot::channel();
+/// tx.send(21).unwrap();
+/// assert_eq!(double(rx).await, Some(42));
+///
+/// let (tx, rx) = oneshot::channel::<i32>();
+/// drop(tx);
+/// assert_eq!(double(rx).await, None);
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+#[macro_export]
+macro_rules! oneshot_or_return {
+    ($rx:expr) => {
+        $crate::ok_or_return!($rx.await)
+    };
+
+    ($rx:expr, $default_result:expr) => {
+        $crate::ok_or_return!($rx.await, $default_result)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn should_receive_until_disconnected() {
+        let (tx, rx) = mpsc::channel();
+        for value in 1..=3 {
+            tx.send(value).unwrap();
+        }
+        drop(tx);
+        let mut received = Vec::new();
+        loop {
+            received.push(recv_or_break!(rx));
+        }
+        assert_eq!(received, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn should_break_labeled_loop_when_disconnected() {
+        let (tx, rx) = mpsc::channel();
+        tx.send(1).unwrap();
+        drop(tx);
+        let mut received = Vec::new();
+        'l: loop {
+            for _i in 0..2 {
+                received.push(recv_or_break!(rx, 'l));
+            }
+        }
+        assert_eq!(received, vec![1]);
+    }
+
+    #[test]
+    fn should_continue_when_empty_and_break_when_disconnected() {
+        let (tx, rx) = mpsc::channel();
+        let mut sender = Some(tx);
+        let mut polls = 0;
+        let mut received = Vec::new();
+        loop {
+            polls += 1;
+            match polls {
+                2 => sender.as_ref().unwrap().send(2).unwrap(),
+                4 => sender = None,
+                _ => {}
+            }
+            received.push(try_recv_or_continue!(rx));
+        }
+        assert_eq!(received, vec![2]);
+        assert_eq!(polls, 4);
+    }
+
+    #[test]
+    fn should_continue_and_break_labeled_loop() {
+        let (tx, rx) = mpsc::channel::<i32>();
+        let mut sender = Some(tx);
+        let mut polls = 0;
+        let mut received = Vec::new();
+        'l: loop {
+            polls += 1;
+            if polls == 3 {
+                sender = None;
+            }
+            for _i in 0..2 {
+                received.push(try_recv_or_continue!(rx, 'l));
+            }
+        }
+        assert!(received.is_empty());
+        assert!(sender.is_none());
+        assert_eq!(polls, 3);
+    }
+
+    fn sum_two(rx: &mpsc::Receiver<i32>) -> Option<i32> {
+        let a = recv_or_return!(rx, None);
+        let b = recv_or_return!(rx, None);
+        Some(a + b)
+    }
+
+    fn add_received(rx: &mpsc::Receiver<i32>, value: &mut i32) {
+        *value += recv_or_return!(rx);
+    }
+
+    #[test]
+    fn should_receive_or_return_when_disconnected() {
+        let (tx, rx) = mpsc::channel();
+        for value in 1..=3 {
+            tx.send(value).unwrap();
+        }
+        drop(tx);
+        assert_eq!(sum_two(&rx), Some(3));
+        let mut value = 0;
+        add_received(&rx, &mut value);
+        assert_eq!(value, 3);
+        add_received(&rx, &mut value);
+        assert_eq!(value, 3);
+    }
+
+    async fn sum_two_async(rx: &mut tokio::sync::mpsc::Receiver<i32>) -> Option<i32> {
+        let a = recv_or_return!(async rx, None);
+        let b = recv_or_return!(async rx, None);
+        Some(a + b)
+    }
+
+    #[tokio::test]
+    async fn should_receive_async_or_return_when_closed() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        for value in 1..=3 {
+            tx.send(value).await.unwrap();
+        }
+        drop(tx);
+        assert_eq!(sum_two_async(&mut rx).await, Some(3));
+        assert_eq!(sum_two_async(&mut rx).await, None);
+    }
+
+    #[tokio::test]
+    async fn should_receive_async_until_closed() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        drop(tx);
+        let mut received = Vec::new();
+        'l: loop {
+            for _i in 0..2 {
+                received.push(recv_or_break!(async rx, 'l));
+            }
+        }
+        assert_eq!(received, vec![1, 2]);
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn received_once(rx: tokio::sync::oneshot::Receiver<i32>, value: &mut i32) {
+        *value += oneshot_or_return!(rx);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn should_await_oneshot_or_return_when_dropped() {
+        let mut value = 0;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tx.send(2).unwrap();
+        received_once(rx, &mut value).await;
+        assert_eq!(value, 2);
+
+        let (tx, rx) = tokio::sync::oneshot::channel::<i32>();
+        drop(tx);
+        received_once(rx, &mut value).await;
+        assert_eq!(value, 2);
+    }
+
+    #[test]
+    fn should_continue_on_timeout_and_break_when_disconnected() {
+        let (tx, rx) = mpsc::channel();
+        let mut sender = Some(tx);
+        let mut polls = 0;
+        let mut received = Vec::new();
+        loop {
+            polls += 1;
+            match polls {
+                2 => sender.as_ref().unwrap().send(2).unwrap(),
+                4 => sender = None,
+                _ => {}
+            }
+            received.push(recv_timeout_or_continue!(rx, Duration::from_millis(1)));
+        }
+        assert_eq!(received, vec![2]);
+        assert_eq!(polls, 4);
+    }
+
+    #[test]
+    fn should_continue_on_timeout_and_break_labeled_loop() {
+        let (tx, rx) = mpsc::channel::<i32>();
+        let mut sender = Some(tx);
+        let mut polls = 0;
+        'l: loop {
+            polls += 1;
+            if polls == 3 {
+                sender = None;
+            }
+            for _i in 0..2 {
+                recv_timeout_or_continue!(rx, Duration::from_millis(1), 'l);
+            }
+        }
+        assert!(sender.is_none());
+        assert_eq!(polls, 3);
+    }
+
+    #[cfg(feature = "crossbeam")]
+    #[test]
+    fn should_receive_from_crossbeam_until_disconnected() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        drop(tx);
+        let mut received = Vec::new();
+        loop {
+            received.push(recv_or_break!(rx));
+        }
+        assert_eq!(received, vec![1, 2]);
+    }
+
+    #[cfg(feature = "crossbeam")]
+    #[test]
+    fn should_distinguish_crossbeam_empty_from_disconnected() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut sender = Some(tx);
+        let mut polls = 0;
+        let mut received = Vec::new();
+        loop {
+            polls += 1;
+            match polls {
+                2 => sender.as_ref().unwrap().send(2).unwrap(),
+                4 => sender = None,
+                _ => {}
+            }
+            received.push(try_recv_or_continue!(rx));
+        }
+        assert_eq!(received, vec![2]);
+        assert_eq!(polls, 4);
+    }
+
+    #[cfg(feature = "crossbeam")]
+    #[test]
+    fn should_distinguish_crossbeam_timeout_from_disconnected() {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let mut sender = Some(tx);
+        let mut polls = 0;
+        let mut received = Vec::new();
+        loop {
+            polls += 1;
+            match polls {
+                2 => sender.as_ref().unwrap().send(2).unwrap(),
+                4 => sender = None,
+                _ => {}
+            }
+            received.push(recv_timeout_or_continue!(rx, Duration::from_millis(1)));
+        }
+        assert_eq!(received, vec![2]);
+        assert_eq!(polls, 4);
+    }
+
+    fn forward(tx: &mpsc::Sender<i32>, message: i32) -> Result<(), i32> {
+        send_or_return!(tx.send(message), unsent => Err(unsent));
+        Ok(())
+    }
+
+    fn forward_or_default(tx: &mpsc::Sender<i32>, message: i32) -> bool {
+        send_or_return!(tx.send(message), false);
+        true
+    }
+
+    #[test]
+    fn should_send_or_return_unsent_message() {
+        let (tx, rx) = mpsc::channel();
+        assert_eq!(forward(&tx, 1), Ok(()));
+        assert!(forward_or_default(&tx, 2));
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+        drop(rx);
+        assert_eq!(forward(&tx, 3), Err(3));
+        assert!(!forward_or_default(&tx, 4));
+    }
+
+    #[test]
+    fn should_send_or_break_with_unsent_message() {
+        let (tx, rx) = mpsc::channel();
+        drop(rx);
+        let mut requeued = Vec::new();
+        let mut attempts = 0;
+        for message in [1, 2] {
+            attempts += 1;
+            send_or_break!(tx.send(message), unsent => requeued.push(unsent));
+        }
+        assert_eq!(requeued, vec![1]);
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn should_send_or_break_labeled_loop() {
+        let (tx, rx) = mpsc::channel();
+        let mut requeued = Vec::new();
+        let mut sent = 0;
+        'l: for message in [1, 2, 3] {
+            for _i in 0..1 {
+                send_or_break!(tx.send(message), 'l);
+                sent += 1;
+            }
+        }
+        assert_eq!(sent, 3);
+        drop(rx);
+        'm: for message in [4, 5] {
+            for _i in 0..1 {
+                send_or_break!(tx.send(message), 'm, unsent => requeued.push(unsent));
+            }
+        }
+        assert_eq!(requeued, vec![4]);
+    }
+}
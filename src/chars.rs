@@ -0,0 +1,293 @@
+/// Either convert a code point into a `char` with `char::from_u32` or return from the current
+/// function if it is not a valid `char`. A default return value can be provided.
+///
+/// With the `digit` marker, a digit is converted into a `char` with `char::from_digit` in the
+/// given radix instead (e.g. `char_or_return!(digit n, 16)`).
+/// ```
+/// use early_returns::char_or_return;
+///
+/// fn decode(code: u32) -> Option<char> {
+///     let c = char_or_return!(code, None);
+///     Some(c)
+/// }
+///
+/// fn hex_digit(n: u32) -> char {
+///     char_or_return!(digit n, 16, '?')
+/// }
+///
+/// assert_eq!(decode(0x41), Some('A'));
+/// assert_eq!(decode(0xD800), None);
+/// assert_eq!(hex_digit(11), 'b');
+/// assert_eq!(hex_digit(16), '?');
+/// ```
+#[macro_export]
+macro_rules! char_or_return {
+    (digit $digit:expr, $radix:expr) => {
+        $crate::some_or_return!(::core::char::from_digit($digit, $radix))
+    };
+
+    (digit $digit:expr, $radix:expr, $default_result:expr) => {
+        $crate::some_or_return!(::core::char::from_digit($digit, $radix), $default_result)
+    };
+
+    ($code:expr) => {
+        $crate::some_or_return!(::core::char::from_u32($code))
+    };
+
+    ($code:expr, $default_result:expr) => {
+        $crate::some_or_return!(::core::char::from_u32($code), $default_result)
+    };
+}
+
+/// Either convert a code point into a `char` with `char::from_u32` or break out of a loop if it
+/// is not a valid `char`. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+///
+/// With the `digit` marker, a digit is converted into a `char` with `char::from_digit` in the
+/// given radix instead (e.g. `char_or_break!(digit n, 16)`).
+/// ```
+/// use early_returns::char_or_break;
+///
+/// let mut text = String::new();
+/// for code in [0x68, 0x69, 0xD800, 0x21] {
+///     text.push(char_or_break!(code));
+/// }
+/// assert_eq!(text, "hi");
+/// ```
+#[macro_export]
+macro_rules! char_or_break {
+    (digit $digit:expr, $radix:expr) => {
+        $crate::some_or_break!(::core::char::from_digit($digit, $radix))
+    };
+
+    (digit $digit:expr, $radix:expr, $lt:lifetime) => {
+        $crate::some_or_break!(::core::char::from_digit($digit, $radix), $lt)
+    };
+
+    ($code:expr) => {
+        $crate::some_or_break!(::core::char::from_u32($code))
+    };
+
+    ($code:expr, $lt:lifetime) => {
+        $crate::some_or_break!(::core::char::from_u32($code), $lt)
+    };
+}
+
+/// Either convert a code point into a `char` with `char::from_u32` or continue in a loop if it is
+/// not a valid `char`. If a loop lifetime is specified, that loop will be "continued", otherwise
+/// the immediate loop is "continued".
+///
+/// With the `digit` marker, a digit is converted into a `char` with `char::from_digit` in the
+/// given radix instead (e.g. `char_or_continue!(digit n, 16)`).
+/// ```
+/// use early_returns::char_or_continue;
+///
+/// let mut text = String::new();
+/// for n in [1, 20, 2] {
+///     text.push(char_or_continue!(digit n, 10));
+/// }
+/// assert_eq!(text, "12");
+/// ```
+#[macro_export]
+macro_rules! char_or_continue {
+    (digit $digit:expr, $radix:expr) => {
+        $crate::some_or_continue!(::core::char::from_digit($digit, $radix))
+    };
+
+    (digit $digit:expr, $radix:expr, $lt:lifetime) => {
+        $crate::some_or_continue!(::core::char::from_digit($digit, $radix), $lt)
+    };
+
+    ($code:expr) => {
+        $crate::some_or_continue!(::core::char::from_u32($code))
+    };
+
+    ($code:expr, $lt:lifetime) => {
+        $crate::some_or_continue!(::core::char::from_u32($code), $lt)
+    };
+}
+
+/// Either convert a `char` into its digit value in the given radix with `char::to_digit` or
+/// return from the current function if it is not a digit. A default return value can be
+/// provided.
+/// ```
+/// use early_returns::digit_or_return;
+///
+/// fn parse_hex_byte(high: char, low: char) -> Option<u8> {
+///     let high = digit_or_return!(high, 16, None);
+///     let low = digit_or_return!(low, 16, None);
+///     Some((high * 16 + low) as u8)
+/// }
+///
+/// assert_eq!(parse_hex_byte('f', 'f'), Some(255));
+/// assert_eq!(parse_hex_byte('f', 'g'), None);
+/// ```
+#[macro_export]
+macro_rules! digit_or_return {
+    ($c:expr, $radix:expr) => {
+        $crate::some_or_return!($c.to_digit($radix))
+    };
+
+    ($c:expr, $radix:expr, $default_result:expr) => {
+        $crate::some_or_return!($c.to_digit($radix), $default_result)
+    };
+}
+
+/// Either convert a `char` into its digit value in the given radix with `char::to_digit` or break
+/// out of a loop if it is not a digit. If a loop lifetime is specified, that loop will be exited,
+/// otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::digit_or_break;
+///
+/// let mut value = 0;
+/// for c in "123px".chars() {
+///     value = value * 10 + digit_or_break!(c, 10);
+/// }
+/// assert_eq!(value, 123);
+/// ```
+#[macro_export]
+macro_rules! digit_or_break {
+    ($c:expr, $radix:expr) => {
+        $crate::some_or_break!($c.to_digit($radix))
+    };
+
+    ($c:expr, $radix:expr, $lt:lifetime) => {
+        $crate::some_or_break!($c.to_digit($radix), $lt)
+    };
+}
+
+/// Either convert a `char` into its digit value in the given radix with `char::to_digit` or
+/// continue in a loop if it is not a digit. If a loop lifetime is specified, that loop will be
+/// "continued", otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::digit_or_continue;
+///
+/// let mut sum = 0;
+/// for c in "1-2-3".chars() {
+///     sum += digit_or_continue!(c, 10);
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+#[macro_export]
+macro_rules! digit_or_continue {
+    ($c:expr, $radix:expr) => {
+        $crate::some_or_continue!($c.to_digit($radix))
+    };
+
+    ($c:expr, $radix:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($c.to_digit($radix), $lt)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    struct Tester {
+        text: String,
+        value: u32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester {
+                text: String::new(),
+                value: 0,
+            }
+        }
+
+        fn push_code(&mut self, code: u32) {
+            self.text.push(char_or_return!(code));
+        }
+
+        fn push_digit(&mut self, digit: u32) {
+            self.text.push(char_or_return!(digit digit, 10));
+        }
+
+        fn push_codes_with_break(&mut self, codes: Vec<u32>) {
+            for code in codes {
+                self.text.push(char_or_break!(code));
+            }
+            'l: for code in [0x61, 0xD800, 0x62] {
+                for _i in 0..1 {
+                    self.text.push(char_or_break!(code, 'l));
+                }
+            }
+        }
+
+        fn push_digits_with_continue(&mut self, digits: Vec<u32>) {
+            for digit in &digits {
+                self.text.push(char_or_continue!(digit *digit, 10));
+            }
+            'l: for digit in digits {
+                for _i in 0..1 {
+                    self.text.push(char_or_continue!(digit digit, 16, 'l));
+                }
+            }
+        }
+
+        fn add_digit(&mut self, c: char) {
+            self.value += digit_or_return!(c, 10);
+        }
+
+        fn add_digits_with_break(&mut self, text: &str) {
+            'l: for c in text.chars() {
+                for _i in 0..1 {
+                    self.value += digit_or_break!(c, 10, 'l);
+                }
+            }
+        }
+
+        fn add_digits_with_continue(&mut self, text: &str) {
+            for c in text.chars() {
+                self.value += digit_or_continue!(c, 10);
+            }
+            'l: for c in text.chars() {
+                for _i in 0..1 {
+                    self.value += digit_or_continue!(c, 16, 'l) * 10;
+                }
+            }
+        }
+    }
+
+    fn char_or_default(code: u32) -> char {
+        char_or_return!(code, '?')
+    }
+
+    fn digit_or_default(c: char) -> u32 {
+        digit_or_return!(c, 8, 0)
+    }
+
+    #[test]
+    fn should_return_early_on_invalid_chars() {
+        let mut tester = Tester::new();
+        tester.push_code(0x41);
+        tester.push_code(0x110000);
+        tester.push_digit(7);
+        tester.push_digit(10);
+        assert_eq!(tester.text, "A7");
+        tester.add_digit('4');
+        tester.add_digit('x');
+        assert_eq!(tester.value, 4);
+        assert_eq!(char_or_default(0x7A), 'z');
+        assert_eq!(char_or_default(0xDFFF), '?');
+        assert_eq!(digit_or_default('7'), 7);
+        assert_eq!(digit_or_default('8'), 0);
+    }
+
+    #[test]
+    fn should_break_on_invalid_chars() {
+        let mut tester = Tester::new();
+        tester.push_codes_with_break(vec![0x31, 0xD800, 0x32]);
+        assert_eq!(tester.text, "1a");
+        tester.add_digits_with_break("12x3");
+        assert_eq!(tester.value, 3);
+    }
+
+    #[test]
+    fn should_continue_on_invalid_chars() {
+        let mut tester = Tester::new();
+        tester.push_digits_with_continue(vec![1, 12, 2]);
+        assert_eq!(tester.text, "121c2");
+        tester.add_digits_with_continue("1b2");
+        assert_eq!(tester.value, 3 + 140);
+    }
+}
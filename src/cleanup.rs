@@ -0,0 +1,269 @@
+/// Either get the value from an Option type or run a cleanup block and return from the current
+/// function. A default return value can be provided, which is evaluated after the cleanup. The
+/// cleanup only runs on the escape path, never when there is a value.
+/// ```
+/// use early_returns::some_or_return_cleanup;
+///
+/// fn first_word(input: &str, log: &mut Vec<String>) -> usize {
+///     log.push(format!("reading {input:?}"));
+///     let word = some_or_return_cleanup!(input.split_whitespace().next(), 0, {
+///         log.push("nothing to read".to_string());
+///     });
+///     word.len()
+/// }
+///
+/// let mut log = Vec::new();
+/// assert_eq!(first_word("hello world", &mut log), 5);
+/// assert_eq!(first_word("  ", &mut log), 0);
+/// assert_eq!(log, ["reading \"hello world\"", "reading \"  \"", "nothing to read"]);
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! some_or_return_cleanup {
+    ($from:expr, $cleanup:block) => {
+        $crate::__extract_or!(Some, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return;
+        })
+    };
+
+    ($from:expr, $default_result:expr, $cleanup:block) => {
+        $crate::__extract_or!(Some, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        })
+    };
+}
+
+/// Either get the value from an Option type or run a cleanup block and break out of a loop. If a
+/// loop lifetime is specified, that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::some_or_break_cleanup;
+///
+/// let mut flushed = Vec::new();
+/// let mut buffer = Vec::new();
+/// for value in [Some(1), Some(2), None, Some(4)] {
+///     let value = some_or_break_cleanup!(value, {
+///         flushed.append(&mut buffer);
+///     });
+///     buffer.push(value);
+/// }
+/// assert_eq!(flushed, [1, 2]);
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! some_or_break_cleanup {
+    ($from:expr, $cleanup:block) => {
+        $crate::__extract_or!(Some, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_break();
+            break
+        })
+    };
+
+    ($from:expr, $lt:lifetime, $cleanup:block) => {
+        $crate::__extract_or!(Some, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_break();
+            break $lt
+        })
+    };
+}
+
+/// Either get the value from an Option type or run a cleanup block and continue in a loop. If a
+/// loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::some_or_continue_cleanup;
+///
+/// let mut skipped = 0;
+/// let mut sum = 0;
+/// for value in [Some(1), None, Some(3)] {
+///     sum += some_or_continue_cleanup!(value, { skipped += 1 });
+/// }
+/// assert_eq!((sum, skipped), (4, 1));
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! some_or_continue_cleanup {
+    ($from:expr, $cleanup:block) => {
+        $crate::__extract_or!(Some, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_continue();
+            continue
+        })
+    };
+
+    ($from:expr, $lt:lifetime, $cleanup:block) => {
+        $crate::__extract_or!(Some, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_continue();
+            continue $lt
+        })
+    };
+}
+
+/// Either get the Ok value from a Result type or run a cleanup block and return from the current
+/// function. A default return value can be provided, which is evaluated after the cleanup. The
+/// cleanup only runs on the escape path, never when there is a value.
+/// ```
+/// use std::io::Write;
+///
+/// use early_returns::ok_or_return_cleanup;
+///
+/// fn write_all(out: &mut impl Write, rows: &[&str]) -> bool {
+///     for row in rows {
+///         let number = ok_or_return_cleanup!(row.parse::<u32>(), false, {
+///             // Whatever was written before the bad row is still flushed.
+///             let _ = out.flush();
+///         });
+///         let _ = writeln!(out, "{number}");
+///     }
+///     out.flush().is_ok()
+/// }
+///
+/// let mut out = Vec::new();
+/// assert!(!write_all(&mut out, &["1", "x", "3"]));
+/// assert_eq!(out, b"1\n");
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_return_cleanup {
+    ($from:expr, $cleanup:block) => {
+        $crate::__extract_or!(Ok, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return;
+        })
+    };
+
+    ($from:expr, $default_result:expr, $cleanup:block) => {
+        $crate::__extract_or!(Ok, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        })
+    };
+}
+
+/// Either get the Ok value from a Result type or run a cleanup block and break out of a loop. If a
+/// loop lifetime is specified, that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::ok_or_break_cleanup;
+///
+/// let mut open = 0;
+/// for row in ["1", "x", "3"] {
+///     open += 1;
+///     let _value: u32 = ok_or_break_cleanup!(row.parse(), { open -= 1 });
+///     open -= 1;
+/// }
+/// assert_eq!(open, 0);
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_break_cleanup {
+    ($from:expr, $cleanup:block) => {
+        $crate::__extract_or!(Ok, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_break();
+            break
+        })
+    };
+
+    ($from:expr, $lt:lifetime, $cleanup:block) => {
+        $crate::__extract_or!(Ok, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_break();
+            break $lt
+        })
+    };
+}
+
+/// Either get the Ok value from a Result type or run a cleanup block and continue in a loop. If a
+/// loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::ok_or_continue_cleanup;
+///
+/// let mut bad = Vec::new();
+/// let mut sum = 0;
+/// for row in ["1", "x", "3"] {
+///     sum += ok_or_continue_cleanup!(row.parse::<u32>(), { bad.push(row) });
+/// }
+/// assert_eq!((sum, bad), (4, vec!["x"]));
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_continue_cleanup {
+    ($from:expr, $cleanup:block) => {
+        $crate::__extract_or!(Ok, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_continue();
+            continue
+        })
+    };
+
+    ($from:expr, $lt:lifetime, $cleanup:block) => {
+        $crate::__extract_or!(Ok, $from, {
+            let () = $cleanup;
+            $crate::__private::silent_continue();
+            continue $lt
+        })
+    };
+}
+
+#[cfg(all(test, feature = "option", feature = "result"))]
+mod test {
+    use core::cell::Cell;
+
+    fn checked_len(value: Option<&str>, cleanups: &Cell<u32>) -> usize {
+        let value = some_or_return_cleanup!(value, 0, { cleanups.set(cleanups.get() + 1) });
+        value.len()
+    }
+
+    fn record(value: Result<i32, ()>, sum: &mut i32, cleanups: &Cell<u32>) {
+        *sum += ok_or_return_cleanup!(value, { cleanups.set(cleanups.get() + 1) });
+    }
+
+    #[test]
+    fn should_only_clean_up_when_returning() {
+        let cleanups = Cell::new(0);
+        assert_eq!(checked_len(Some("abc"), &cleanups), 3);
+        assert_eq!(cleanups.get(), 0);
+        assert_eq!(checked_len(None, &cleanups), 0);
+        assert_eq!(cleanups.get(), 1);
+        let mut sum = 0;
+        record(Ok(2), &mut sum, &cleanups);
+        record(Err(()), &mut sum, &cleanups);
+        assert_eq!((sum, cleanups.get()), (2, 2));
+    }
+
+    #[test]
+    fn should_clean_up_before_escaping_labeled_loops() {
+        let mut cleanups = 0;
+        let mut sum = 0;
+        'l: for value in [Some(1), None, Some(3)] {
+            for _i in 0..2 {
+                sum += some_or_continue_cleanup!(value, 'l, { cleanups += 1 });
+            }
+        }
+        assert_eq!((sum, cleanups), (8, 1));
+        'l: for value in [Ok(1), Err(()), Ok(3)] {
+            for _i in 0..2 {
+                sum += ok_or_break_cleanup!(value, 'l, { cleanups += 10 });
+            }
+        }
+        assert_eq!((sum, cleanups), (10, 11));
+        for value in [Ok::<_, ()>(1), Ok(2)] {
+            sum += ok_or_continue_cleanup!(value, { cleanups += 100 });
+            sum += some_or_break_cleanup!(Some(1), { cleanups += 100 });
+        }
+        assert_eq!((sum, cleanups), (15, 11));
+    }
+}
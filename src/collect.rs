@@ -0,0 +1,173 @@
+/// Either get the Ok value from a Result type or push the error into a collection and continue in
+/// a loop. The collection can be anything implementing `Extend` for the error type. If a loop
+/// lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::ok_or_continue_collect;
+/// fn parse_all(lines: &[&str]) -> (Vec<i32>, Vec<std::num::ParseIntError>) {
+///     let mut values = Vec::new();
+///     let mut errors = Vec::new();
+///     for line in lines {
+///         let value = ok_or_continue_collect!(line.parse::<i32>(), &mut errors);
+///         values.push(value);
+///     }
+///     (values, errors)
+/// }
+///
+/// let (values, errors) = parse_all(&["1", "x", "3"]);
+/// assert_eq!(values, vec![1, 3]);
+/// assert_eq!(errors.len(), 1);
+/// ```
+#[macro_export]
+macro_rules! ok_or_continue_collect {
+    ($from:expr, $errors:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                ::core::iter::Extend::extend($errors, ::core::iter::once(e));
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $errors:expr, $lt:lifetime) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                ::core::iter::Extend::extend($errors, ::core::iter::once(e));
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or push a tag into a collection and continue in a
+/// loop. The collection can be anything implementing `Extend` for the tag type, and the tag is
+/// only evaluated when the Option is `None`. If a loop lifetime is specified, that loop will be
+/// "continued", otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::some_or_continue_collect;
+/// use std::collections::HashMap;
+///
+/// fn lookup_all(ids: &[u32], names: &HashMap<u32, &'static str>) -> (Vec<&'static str>, Vec<u32>) {
+///     let mut found = Vec::new();
+///     let mut missing = Vec::new();
+///     for id in ids {
+///         let name = some_or_continue_collect!(names.get(id), &mut missing, *id);
+///         found.push(*name);
+///     }
+///     (found, missing)
+/// }
+///
+/// let names = HashMap::from([(1, "one"), (3, "three")]);
+/// assert_eq!(lookup_all(&[1, 2, 3], &names), (vec!["one", "three"], vec![2]));
+/// ```
+#[macro_export]
+macro_rules! some_or_continue_collect {
+    ($from:expr, $tags:expr, $tag:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            ::core::iter::Extend::extend($tags, ::core::iter::once($tag));
+            continue;
+        })
+    };
+
+    ($from:expr, $tags:expr, $tag:expr, $lt:lifetime) => {
+        $crate::__extract_or!(Some, $from, {
+            ::core::iter::Extend::extend($tags, ::core::iter::once($tag));
+            continue $lt;
+        })
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn should_collect_errors_and_continue() {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+        for value in [Ok(1), Err("a"), Ok(2), Err("b")] {
+            let value = ok_or_continue_collect!(value, &mut errors);
+            values.push(value);
+        }
+        assert_eq!(values, vec![1, 2]);
+        assert_eq!(errors, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn should_collect_errors_and_continue_with_lifetime() {
+        let mut value = 0;
+        let mut errors = Vec::new();
+        'l: for result in [Ok(1), Err("a"), Ok(2)] {
+            value += 1;
+            for _i in 0..1 {
+                value += ok_or_continue_collect!(result, &mut errors, 'l);
+            }
+        }
+        assert_eq!(value, 6);
+        assert_eq!(errors, vec!["a"]);
+    }
+
+    #[test]
+    fn should_collect_errors_into_any_extend() {
+        let mut errors = BTreeSet::new();
+        for value in [Err::<i32, _>("b"), Err("a"), Err("b")] {
+            ok_or_continue_collect!(value, &mut errors);
+        }
+        assert_eq!(errors.into_iter().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn should_collect_tags_and_continue() {
+        let mut values = Vec::new();
+        let mut missing = Vec::new();
+        for (i, value) in [Some(1), None, Some(3), None].into_iter().enumerate() {
+            let value = some_or_continue_collect!(value, &mut missing, i);
+            values.push(value);
+        }
+        assert_eq!(values, vec![1, 3]);
+        assert_eq!(missing, vec![1, 3]);
+    }
+
+    #[test]
+    fn should_collect_tags_and_continue_with_lifetime() {
+        let mut value = 0;
+        let mut missing = Vec::new();
+        'l: for (i, option) in [Some(1), None, Some(2)].into_iter().enumerate() {
+            value += 1;
+            for _i in 0..1 {
+                value += some_or_continue_collect!(option, &mut missing, i, 'l);
+            }
+        }
+        assert_eq!(value, 6);
+        assert_eq!(missing, vec![1]);
+    }
+
+    #[test]
+    fn should_not_evaluate_tag_when_engaged() {
+        let mut evaluated = 0;
+        let mut tag = || {
+            evaluated += 1;
+            "missing"
+        };
+        let mut missing = Vec::new();
+        for value in [Some(1), Some(2)] {
+            some_or_continue_collect!(value, &mut missing, tag());
+        }
+        assert_eq!(evaluated, 0);
+        assert!(missing.is_empty());
+    }
+
+    #[cfg(feature = "let-else")]
+    #[test]
+    fn should_release_lock_before_collecting() {
+        let queue = std::sync::Mutex::new(vec![1]);
+        let mut unlocked = Vec::new();
+        for _ in 0..2 {
+            let unlocked_now = || queue.try_lock().is_ok();
+            some_or_continue_collect!(queue.lock().unwrap().pop(), &mut unlocked, unlocked_now());
+        }
+        assert_eq!(unlocked, vec![true]);
+    }
+}
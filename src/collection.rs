@@ -0,0 +1,734 @@
+/// Either look up a value in a collection with `get` or return from the current function if there
+/// is none. A default return value can be provided.
+///
+/// Works with everything that has a `get` method returning an `Option`, such as slices (with an
+/// index or a range), `Vec`, `VecDeque`, `HashMap` and `BTreeMap`. With the `mut` marker (e.g.
+/// `get_or_return!(mut map, &key)`), `get_mut` is used instead.
+/// ```
+/// use early_returns::get_or_return;
+/// use std::collections::HashMap;
+///
+/// fn greet(users: &HashMap<u32, String>, id: u32) -> Option<String> {
+///     let user = get_or_return!(users, &id, None);
+///     Some(format!("hello {user}"))
+/// }
+///
+/// fn rename(users: &mut HashMap<u32, String>, id: u32, name: &str) -> bool {
+///     let user = get_or_return!(mut users, &id, false);
+///     *user = name.to_string();
+///     true
+/// }
+///
+/// let mut users = HashMap::from([(1, "ada".to_string())]);
+/// assert_eq!(greet(&users, 1), Some("hello ada".to_string()));
+/// assert_eq!(greet(&users, 2), None);
+/// assert!(rename(&mut users, 1, "grace"));
+/// assert!(!rename(&mut users, 2, "alan"));
+/// assert_eq!(users[&1], "grace");
+/// ```
+#[macro_export]
+macro_rules! get_or_return {
+    (mut $collection:expr, $key:expr) => {
+        $crate::some_or_return!($collection.get_mut($key))
+    };
+
+    (mut $collection:expr, $key:expr, $default_result:expr) => {
+        $crate::some_or_return!($collection.get_mut($key), $default_result)
+    };
+
+    ($collection:expr, $key:expr) => {
+        $crate::some_or_return!($collection.get($key))
+    };
+
+    ($collection:expr, $key:expr, $default_result:expr) => {
+        $crate::some_or_return!($collection.get($key), $default_result)
+    };
+}
+
+/// Either look up a value in a collection with `get` or break out of a loop if there is none. If
+/// a loop lifetime is specified, that loop will be exited, otherwise the immediate loop is exited.
+///
+/// Works with everything that has a `get` method returning an `Option`, such as slices (with an
+/// index or a range), `Vec`, `VecDeque`, `HashMap` and `BTreeMap`. With the `mut` marker (e.g.
+/// `get_or_break!(mut map, &key)`), `get_mut` is used instead.
+/// ```
+/// use early_returns::get_or_break;
+///
+/// let values = vec![1, 2, 3];
+/// let mut sum = 0;
+/// for i in [0, 2, 5, 1] {
+///     sum += get_or_break!(values, i);
+/// }
+/// assert_eq!(sum, 4);
+/// ```
+#[macro_export]
+macro_rules! get_or_break {
+    (mut $collection:expr, $key:expr) => {
+        $crate::some_or_break!($collection.get_mut($key))
+    };
+
+    (mut $collection:expr, $key:expr, $lt:lifetime) => {
+        $crate::some_or_break!($collection.get_mut($key), $lt)
+    };
+
+    ($collection:expr, $key:expr) => {
+        $crate::some_or_break!($collection.get($key))
+    };
+
+    ($collection:expr, $key:expr, $lt:lifetime) => {
+        $crate::some_or_break!($collection.get($key), $lt)
+    };
+}
+
+/// Either look up a value in a collection with `get` or continue in a loop if there is none. If a
+/// loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+///
+/// Works with everything that has a `get` method returning an `Option`, such as slices (with an
+/// index or a range), `Vec`, `VecDeque`, `HashMap` and `BTreeMap`. With the `mut` marker (e.g.
+/// `get_or_continue!(mut map, &key)`), `get_mut` is used instead.
+/// ```
+/// use early_returns::get_or_continue;
+/// use std::collections::BTreeMap;
+///
+/// let mut scores = BTreeMap::from([("ada", 1), ("grace", 2)]);
+/// for name in ["ada", "alan", "grace"] {
+///     *get_or_continue!(mut scores, name) += 10;
+/// }
+/// assert_eq!(scores, BTreeMap::from([("ada", 11), ("grace", 12)]));
+/// ```
+#[macro_export]
+macro_rules! get_or_continue {
+    (mut $collection:expr, $key:expr) => {
+        $crate::some_or_continue!($collection.get_mut($key))
+    };
+
+    (mut $collection:expr, $key:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($collection.get_mut($key), $lt)
+    };
+
+    ($collection:expr, $key:expr) => {
+        $crate::some_or_continue!($collection.get($key))
+    };
+
+    ($collection:expr, $key:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($collection.get($key), $lt)
+    };
+}
+
+/// Either get the first element of a slice with `first` or return from the current function if the
+/// slice is empty. A default return value can be provided.
+/// ```
+/// use early_returns::first_or_return;
+///
+/// fn header(packet: &[u8]) -> Option<u8> {
+///     let header = first_or_return!(packet, None);
+///     Some(*header)
+/// }
+///
+/// assert_eq!(header(&[7, 1, 2]), Some(7));
+/// assert_eq!(header(&[]), None);
+/// ```
+#[macro_export]
+macro_rules! first_or_return {
+    ($slice:expr) => {
+        $crate::some_or_return!($slice.first())
+    };
+
+    ($slice:expr, $default_result:expr) => {
+        $crate::some_or_return!($slice.first(), $default_result)
+    };
+}
+
+/// Either get the first element of a slice with `first` or break out of a loop if the slice is
+/// empty. If a loop lifetime is specified, that loop will be exited, otherwise the immediate loop
+/// is exited.
+/// ```
+/// use early_returns::first_or_break;
+///
+/// let batches: Vec<&[i32]> = vec![&[1, 2], &[3], &[], &[4]];
+/// let mut heads = Vec::new();
+/// for batch in batches {
+///     heads.push(*first_or_break!(batch));
+/// }
+/// assert_eq!(heads, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! first_or_break {
+    ($slice:expr) => {
+        $crate::some_or_break!($slice.first())
+    };
+
+    ($slice:expr, $lt:lifetime) => {
+        $crate::some_or_break!($slice.first(), $lt)
+    };
+}
+
+/// Either get the first element of a slice with `first` or continue in a loop if the slice is
+/// empty. If a loop lifetime is specified, that loop will be "continued", otherwise the immediate
+/// loop is "continued".
+/// ```
+/// use early_returns::first_or_continue;
+///
+/// let words = vec!["apple", "", "cherry"];
+/// let mut initials = String::new();
+/// for word in words {
+///     initials.push(*first_or_continue!(word.as_bytes()) as char);
+/// }
+/// assert_eq!(initials, "ac");
+/// ```
+#[macro_export]
+macro_rules! first_or_continue {
+    ($slice:expr) => {
+        $crate::some_or_continue!($slice.first())
+    };
+
+    ($slice:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($slice.first(), $lt)
+    };
+}
+
+/// Either get the last element of a slice with `last` or return from the current function if the
+/// slice is empty. A default return value can be provided.
+/// ```
+/// use early_returns::last_or_return;
+///
+/// fn latest(readings: &[f64]) -> f64 {
+///     *last_or_return!(readings, f64::NAN)
+/// }
+///
+/// assert_eq!(latest(&[1.0, 2.5]), 2.5);
+/// assert!(latest(&[]).is_nan());
+/// ```
+#[macro_export]
+macro_rules! last_or_return {
+    ($slice:expr) => {
+        $crate::some_or_return!($slice.last())
+    };
+
+    ($slice:expr, $default_result:expr) => {
+        $crate::some_or_return!($slice.last(), $default_result)
+    };
+}
+
+/// Either get the last element of a slice with `last` or break out of a loop if the slice is empty.
+/// If a loop lifetime is specified, that loop will be exited, otherwise the immediate loop is
+/// exited.
+/// ```
+/// use early_returns::last_or_break;
+///
+/// let batches: Vec<Vec<i32>> = vec![vec![1, 2], vec![], vec![3]];
+/// let mut tails = Vec::new();
+/// for batch in &batches {
+///     tails.push(*last_or_break!(batch));
+/// }
+/// assert_eq!(tails, vec![2]);
+/// ```
+#[macro_export]
+macro_rules! last_or_break {
+    ($slice:expr) => {
+        $crate::some_or_break!($slice.last())
+    };
+
+    ($slice:expr, $lt:lifetime) => {
+        $crate::some_or_break!($slice.last(), $lt)
+    };
+}
+
+/// Either get the last element of a slice with `last` or continue in a loop if the slice is empty.
+/// If a loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::last_or_continue;
+///
+/// let paths = vec![vec!["usr", "bin"], vec![], vec!["etc"]];
+/// let mut names = Vec::new();
+/// for path in &paths {
+///     names.push(*last_or_continue!(path));
+/// }
+/// assert_eq!(names, vec!["bin", "etc"]);
+/// ```
+#[macro_export]
+macro_rules! last_or_continue {
+    ($slice:expr) => {
+        $crate::some_or_continue!($slice.last())
+    };
+
+    ($slice:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($slice.last(), $lt)
+    };
+}
+
+/// Either get the first element of a slice and the rest of it with `split_first` or return from the
+/// current function if the slice is empty. A default return value can be provided.
+/// The element and the rest are "extracted" as a tuple, so both can be bound at once.
+/// ```
+/// use early_returns::split_first_or_return;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct UnexpectedEof;
+///
+/// fn parse_message(bytes: &[u8]) -> Result<(u8, &[u8]), UnexpectedEof> {
+///     let (kind, payload) = split_first_or_return!(bytes, Err(UnexpectedEof));
+///     Ok((*kind, payload))
+/// }
+///
+/// assert_eq!(parse_message(&[1, 2, 3]), Ok((1, &[2, 3][..])));
+/// assert_eq!(parse_message(&[]), Err(UnexpectedEof));
+/// ```
+#[macro_export]
+macro_rules! split_first_or_return {
+    ($slice:expr) => {
+        $crate::some_or_return!($slice.split_first())
+    };
+
+    ($slice:expr, $default_result:expr) => {
+        $crate::some_or_return!($slice.split_first(), $default_result)
+    };
+}
+
+/// Either get the first element of a slice and the rest of it with `split_first` or break out of a
+/// loop if the slice is empty. If a loop lifetime is specified, that loop will be exited, otherwise
+/// the immediate loop is exited.
+/// The element and the rest are "extracted" as a tuple, so both can be bound at once.
+/// ```
+/// use early_returns::split_first_or_break;
+///
+/// let mut input: &[u8] = &[2, 10, 20, 1, 30, 3, 40];
+/// let mut records = Vec::new();
+/// loop {
+///     let (len, rest) = split_first_or_break!(input);
+///     let len = usize::from(*len).min(rest.len());
+///     records.push(&rest[..len]);
+///     input = &rest[len..];
+/// }
+/// assert_eq!(records, vec![&[10, 20][..], &[30], &[40]]);
+/// ```
+#[macro_export]
+macro_rules! split_first_or_break {
+    ($slice:expr) => {
+        $crate::some_or_break!($slice.split_first())
+    };
+
+    ($slice:expr, $lt:lifetime) => {
+        $crate::some_or_break!($slice.split_first(), $lt)
+    };
+}
+
+/// Either get the first element of a slice and the rest of it with `split_first` or continue in a
+/// loop if the slice is empty. If a loop lifetime is specified, that loop will be "continued",
+/// otherwise the immediate loop is "continued".
+/// The element and the rest are "extracted" as a tuple, so both can be bound at once.
+/// ```
+/// use early_returns::split_first_or_continue;
+///
+/// let lines: Vec<&[&str]> = vec![&["add", "1", "2"], &[], &["neg", "3"]];
+/// let mut commands = Vec::new();
+/// for line in lines {
+///     let (command, args) = split_first_or_continue!(line);
+///     commands.push((*command, args.len()));
+/// }
+/// assert_eq!(commands, vec![("add", 2), ("neg", 1)]);
+/// ```
+#[macro_export]
+macro_rules! split_first_or_continue {
+    ($slice:expr) => {
+        $crate::some_or_continue!($slice.split_first())
+    };
+
+    ($slice:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($slice.split_first(), $lt)
+    };
+}
+
+/// Either get the last element of a slice and the rest of it with `split_last` or return from the
+/// current function if the slice is empty. A default return value can be provided.
+/// The element and the rest are "extracted" as a tuple, so both can be bound at once.
+/// ```
+/// use early_returns::split_last_or_return;
+///
+/// fn strip_checksum(frame: &[u8]) -> Option<&[u8]> {
+///     let (checksum, body) = split_last_or_return!(frame, None);
+///     let sum = body.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+///     if sum == *checksum {
+///         Some(body)
+///     } else {
+///         None
+///     }
+/// }
+///
+/// assert_eq!(strip_checksum(&[1, 2, 3]), Some(&[1, 2][..]));
+/// assert_eq!(strip_checksum(&[]), None);
+/// ```
+#[macro_export]
+macro_rules! split_last_or_return {
+    ($slice:expr) => {
+        $crate::some_or_return!($slice.split_last())
+    };
+
+    ($slice:expr, $default_result:expr) => {
+        $crate::some_or_return!($slice.split_last(), $default_result)
+    };
+}
+
+/// Either get the last element of a slice and the rest of it with `split_last` or break out of a
+/// loop if the slice is empty. If a loop lifetime is specified, that loop will be exited, otherwise
+/// the immediate loop is exited.
+/// The element and the rest are "extracted" as a tuple, so both can be bound at once.
+/// ```
+/// use early_returns::split_last_or_break;
+///
+/// let mut stack: &[i32] = &[1, 2, 3];
+/// let mut popped = Vec::new();
+/// loop {
+///     let (top, rest) = split_last_or_break!(stack);
+///     popped.push(*top);
+///     stack = rest;
+/// }
+/// assert_eq!(popped, vec![3, 2, 1]);
+/// ```
+#[macro_export]
+macro_rules! split_last_or_break {
+    ($slice:expr) => {
+        $crate::some_or_break!($slice.split_last())
+    };
+
+    ($slice:expr, $lt:lifetime) => {
+        $crate::some_or_break!($slice.split_last(), $lt)
+    };
+}
+
+/// Either get the last element of a slice and the rest of it with `split_last` or continue in a
+/// loop if the slice is empty. If a loop lifetime is specified, that loop will be "continued",
+/// otherwise the immediate loop is "continued".
+/// The element and the rest are "extracted" as a tuple, so both can be bound at once.
+/// ```
+/// use early_returns::split_last_or_continue;
+///
+/// let paths: Vec<&[&str]> = vec![&["usr", "bin"], &[], &["etc"]];
+/// let mut depths = Vec::new();
+/// for path in paths {
+///     let (name, parents) = split_last_or_continue!(path);
+///     depths.push((*name, parents.len()));
+/// }
+/// assert_eq!(depths, vec![("bin", 1), ("etc", 0)]);
+/// ```
+#[macro_export]
+macro_rules! split_last_or_continue {
+    ($slice:expr) => {
+        $crate::some_or_continue!($slice.split_last())
+    };
+
+    ($slice:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($slice.split_last(), $lt)
+    };
+}
+
+/// Either pop a value off a work queue with `pop` or break out of a loop once the queue is empty.
+/// If a loop lifetime is specified, that loop will be exited, otherwise the immediate loop is
+/// exited.
+///
+/// Works with everything that has a `pop` method returning an `Option`, such as `Vec` and
+/// `BinaryHeap`.
+/// ```
+/// use early_returns::pop_or_break;
+///
+/// let mut stack = vec![1, 2, 3];
+/// let mut visited = Vec::new();
+/// loop {
+///     let node = pop_or_break!(stack);
+///     if node == 3 {
+///         stack.push(4);
+///     }
+///     visited.push(node);
+/// }
+/// assert_eq!(visited, vec![3, 4, 2, 1]);
+/// ```
+#[macro_export]
+macro_rules! pop_or_break {
+    ($queue:expr) => {
+        $crate::some_or_break!($queue.pop())
+    };
+
+    ($queue:expr, $lt:lifetime) => {
+        $crate::some_or_break!($queue.pop(), $lt)
+    };
+}
+
+/// Either pop a value off the front of a work queue with `pop_front` or break out of a loop once
+/// the queue is empty. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+///
+/// Works with everything that has a `pop_front` method returning an `Option`, such as `VecDeque`
+/// and `LinkedList`.
+/// ```
+/// use early_returns::pop_front_or_break;
+/// use std::collections::VecDeque;
+///
+/// let mut jobs = VecDeque::from(["build", "test"]);
+/// let mut done = Vec::new();
+/// loop {
+///     let job = pop_front_or_break!(jobs);
+///     if job == "build" {
+///         jobs.push_back("package");
+///     }
+///     done.push(job);
+/// }
+/// assert_eq!(done, vec!["build", "test", "package"]);
+/// ```
+#[macro_export]
+macro_rules! pop_front_or_break {
+    ($queue:expr) => {
+        $crate::some_or_break!($queue.pop_front())
+    };
+
+    ($queue:expr, $lt:lifetime) => {
+        $crate::some_or_break!($queue.pop_front(), $lt)
+    };
+}
+
+/// Either pop a value off the back of a work queue with `pop_back` or break out of a loop once
+/// the queue is empty. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+///
+/// Works with everything that has a `pop_back` method returning an `Option`, such as `VecDeque`
+/// and `LinkedList`.
+/// ```
+/// use early_returns::pop_back_or_break;
+/// use std::collections::VecDeque;
+///
+/// let mut undo = VecDeque::from([1, 2, 3]);
+/// let mut undone = Vec::new();
+/// loop {
+///     undone.push(pop_back_or_break!(undo));
+/// }
+/// assert_eq!(undone, vec![3, 2, 1]);
+/// ```
+#[macro_export]
+macro_rules! pop_back_or_break {
+    ($queue:expr) => {
+        $crate::some_or_break!($queue.pop_back())
+    };
+
+    ($queue:expr, $lt:lifetime) => {
+        $crate::some_or_break!($queue.pop_back(), $lt)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
+
+    struct Tester {
+        values: Vec<i32>,
+        names: HashMap<&'static str, i32>,
+        value: i32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester {
+                values: vec![1, 2, 3],
+                names: HashMap::from([("one", 1), ("two", 2)]),
+                value: 0,
+            }
+        }
+
+        fn add_at(&mut self, i: usize) {
+            self.value += get_or_return!(self.values, i);
+        }
+
+        fn add_named(&mut self, name: &str) {
+            self.value += get_or_return!(self.names, name);
+        }
+
+        fn increment_at(&mut self, i: usize) {
+            *get_or_return!(mut self.values, i) += 1;
+        }
+
+        fn add_all_with_break(&mut self, indices: &[usize]) {
+            for i in indices {
+                self.value += get_or_break!(self.values, *i);
+            }
+            'l: for name in ["two", "three", "one"] {
+                for _i in 0..1 {
+                    *get_or_break!(mut self.names, name, 'l) += 1;
+                }
+            }
+        }
+
+        fn add_all_with_continue(&mut self, indices: &[usize]) {
+            for i in indices {
+                *get_or_continue!(mut self.values, *i) *= 2;
+            }
+            'l: for name in ["two", "three", "one"] {
+                for _i in 0..1 {
+                    self.value += get_or_continue!(self.names, name, 'l);
+                }
+            }
+        }
+    }
+
+    fn sum_or_default(values: &[i32], from: usize, to: usize) -> i32 {
+        get_or_return!(values, from..to, -1).iter().sum()
+    }
+
+    fn lookup_or_default(map: &mut BTreeMap<i32, i32>, key: i32) -> i32 {
+        *get_or_return!(mut map, &key, -1)
+    }
+
+    #[test]
+    fn should_return_early_when_missing() {
+        let mut tester = Tester::new();
+        tester.add_at(1);
+        tester.add_at(3);
+        tester.add_named("two");
+        tester.add_named("three");
+        tester.increment_at(0);
+        tester.increment_at(3);
+        assert_eq!(tester.value, 4);
+        assert_eq!(tester.values, vec![2, 2, 3]);
+        assert_eq!(sum_or_default(&[1, 2, 3], 1, 3), 5);
+        assert_eq!(sum_or_default(&[1, 2, 3], 1, 4), -1);
+        let mut map = BTreeMap::from([(1, 10)]);
+        assert_eq!(lookup_or_default(&mut map, 1), 10);
+        assert_eq!(lookup_or_default(&mut map, 2), -1);
+    }
+
+    #[test]
+    fn should_break_when_missing() {
+        let mut tester = Tester::new();
+        tester.add_all_with_break(&[0, 3, 1]);
+        assert_eq!(tester.value, 1);
+        assert_eq!(tester.names["two"], 3);
+        assert_eq!(tester.names["one"], 1);
+    }
+
+    #[test]
+    fn should_continue_when_missing() {
+        let mut tester = Tester::new();
+        tester.add_all_with_continue(&[0, 3, 1]);
+        assert_eq!(tester.values, vec![2, 4, 3]);
+        assert_eq!(tester.value, 3);
+    }
+
+    fn ends_or_default(values: &[i32]) -> (i32, i32) {
+        let first = first_or_return!(values, (-1, -1));
+        let last = last_or_return!(values, (-1, -1));
+        (*first, *last)
+    }
+
+    fn split_sums(values: &[i32]) -> (i32, i32) {
+        let (first, rest) = split_first_or_return!(values, (0, 0));
+        let (last, _) = split_last_or_return!(rest, (*first, 0));
+        (*first, *last)
+    }
+
+    fn push_ends(values: &[i32], ends: &mut Vec<i32>) {
+        ends.push(*first_or_return!(values));
+        ends.push(*last_or_return!(values));
+        let (_, rest) = split_first_or_return!(values);
+        let (last, _) = split_last_or_return!(rest);
+        ends.push(*last);
+    }
+
+    fn heads_with_break(batches: &[Vec<i32>]) -> Vec<i32> {
+        let mut heads = Vec::new();
+        for batch in batches {
+            heads.push(*first_or_break!(batch));
+        }
+        'l: for batch in batches {
+            for _i in 0..1 {
+                heads.push(*last_or_break!(batch, 'l));
+            }
+        }
+        for batch in batches {
+            let (first, _) = split_first_or_break!(batch);
+            heads.push(*first);
+        }
+        'l: for batch in batches {
+            for _i in 0..1 {
+                let (last, _) = split_last_or_break!(batch, 'l);
+                heads.push(*last);
+            }
+        }
+        heads
+    }
+
+    fn heads_with_continue(batches: &[Vec<i32>]) -> Vec<i32> {
+        let mut heads = Vec::new();
+        for batch in batches {
+            heads.push(*first_or_continue!(batch));
+        }
+        'l: for batch in batches {
+            for _i in 0..1 {
+                heads.push(*last_or_continue!(batch, 'l));
+            }
+        }
+        for batch in batches {
+            let (first, _) = split_first_or_continue!(batch);
+            heads.push(*first);
+        }
+        'l: for batch in batches {
+            for _i in 0..1 {
+                let (last, _) = split_last_or_continue!(batch, 'l);
+                heads.push(*last);
+            }
+        }
+        heads
+    }
+
+    #[test]
+    fn should_return_early_when_slice_is_empty() {
+        assert_eq!(ends_or_default(&[1, 2, 3]), (1, 3));
+        assert_eq!(ends_or_default(&[]), (-1, -1));
+        assert_eq!(split_sums(&[1, 2, 3]), (1, 3));
+        assert_eq!(split_sums(&[1]), (1, 0));
+        assert_eq!(split_sums(&[]), (0, 0));
+        let mut ends = Vec::new();
+        push_ends(&[1, 2, 3], &mut ends);
+        push_ends(&[4], &mut ends);
+        push_ends(&[], &mut ends);
+        assert_eq!(ends, vec![1, 3, 3, 4, 4]);
+    }
+
+    #[test]
+    fn should_break_when_slice_is_empty() {
+        let batches = vec![vec![1, 2], vec![], vec![3]];
+        assert_eq!(heads_with_break(&batches), vec![1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn should_continue_when_slice_is_empty() {
+        let batches = vec![vec![1, 2], vec![], vec![3]];
+        assert_eq!(heads_with_continue(&batches), vec![1, 3, 2, 3, 1, 3, 2, 3]);
+    }
+
+    #[test]
+    fn should_break_when_queue_is_empty() {
+        let mut stack = vec![1, 2];
+        let mut heap = BinaryHeap::from(vec![1, 3, 2]);
+        let mut queue = VecDeque::from(vec![1, 2, 3]);
+        let mut drained = Vec::new();
+        loop {
+            drained.push(pop_or_break!(stack));
+        }
+        'l: loop {
+            for _i in 0..1 {
+                drained.push(pop_or_break!(heap, 'l));
+            }
+        }
+        loop {
+            drained.push(pop_front_or_break!(queue));
+            drained.push(pop_back_or_break!(queue));
+        }
+        queue.extend([1, 2, 3]);
+        'l: loop {
+            for _i in 0..1 {
+                drained.push(pop_back_or_break!(queue, 'l));
+                drained.push(pop_front_or_break!(queue, 'l));
+            }
+        }
+        assert_eq!(drained, vec![2, 1, 3, 2, 1, 1, 3, 2, 3, 1, 2]);
+    }
+}
@@ -0,0 +1,133 @@
+//! Extension traits turning Options and Results into a [`ControlFlow`], for closures passed to
+//! `try_fold` and `try_for_each`, where the macros cannot `return`, `break` or `continue`.
+//!
+//! A `Break` stops the iteration and becomes its result, while the `?` operator unwraps a
+//! `Continue` in a closure returning a `ControlFlow`.
+//! ```
+//! use std::ops::ControlFlow;
+//!
+//! use early_returns::control_flow::{OptionEarlyExt, ResultEarlyExt};
+//!
+//! let rows = ["1", "2", "x", "4"];
+//! let result = rows.iter().try_fold(0u32, |sum, row| {
+//!     let value = row.parse::<u32>().or_break_cf_with(|_| format!("bad row {row:?}"))?;
+//!     let sum = sum.checked_add(value).or_break_cf("overflow".to_string())?;
+//!     ControlFlow::Continue(sum)
+//! });
+//! assert_eq!(result, ControlFlow::Break("bad row \"x\"".to_string()));
+//! ```
+
+use core::ops::ControlFlow;
+
+use crate::early_exit::EarlyExit;
+
+/// Early-exit methods for Options, producing a [`ControlFlow`].
+pub trait OptionEarlyExt<T> {
+    /// Continue with the value if there is one, or break with the given reason.
+    fn or_break_cf<B>(self, reason: B) -> ControlFlow<B, T>;
+
+    /// Continue with the value if there is one, or break with the reason computed by `reason`.
+    fn or_break_cf_with<B, F>(self, reason: F) -> ControlFlow<B, T>
+    where
+        F: FnOnce() -> B;
+
+    /// The value if there is one, or an [`EarlyExit::Default`] to propagate with `?`.
+    fn or_exit<D>(self) -> Result<T, EarlyExit<D>>;
+}
+
+impl<T> OptionEarlyExt<T> for Option<T> {
+    #[inline]
+    fn or_break_cf<B>(self, reason: B) -> ControlFlow<B, T> {
+        match self {
+            Some(value) => ControlFlow::Continue(value),
+            None => ControlFlow::Break(reason),
+        }
+    }
+
+    #[inline]
+    fn or_break_cf_with<B, F>(self, reason: F) -> ControlFlow<B, T>
+    where
+        F: FnOnce() -> B,
+    {
+        match self {
+            Some(value) => ControlFlow::Continue(value),
+            None => ControlFlow::Break(reason()),
+        }
+    }
+
+    #[inline]
+    fn or_exit<D>(self) -> Result<T, EarlyExit<D>> {
+        self.ok_or(EarlyExit::Default)
+    }
+}
+
+/// Early-exit methods for Results, producing a [`ControlFlow`].
+pub trait ResultEarlyExt<T, E> {
+    /// Continue with the Ok value, or break with the error.
+    fn or_break_cf(self) -> ControlFlow<E, T>;
+
+    /// Continue with the Ok value, or break with the reason `reason` computes from the error.
+    fn or_break_cf_with<B, F>(self, reason: F) -> ControlFlow<B, T>
+    where
+        F: FnOnce(E) -> B;
+
+    /// The Ok value, or an [`EarlyExit::Default`] to propagate with `?`, discarding the error.
+    fn or_exit<D>(self) -> Result<T, EarlyExit<D>>;
+}
+
+impl<T, E> ResultEarlyExt<T, E> for Result<T, E> {
+    #[inline]
+    fn or_break_cf(self) -> ControlFlow<E, T> {
+        match self {
+            Ok(value) => ControlFlow::Continue(value),
+            Err(error) => ControlFlow::Break(error),
+        }
+    }
+
+    #[inline]
+    fn or_break_cf_with<B, F>(self, reason: F) -> ControlFlow<B, T>
+    where
+        F: FnOnce(E) -> B,
+    {
+        match self {
+            Ok(value) => ControlFlow::Continue(value),
+            Err(error) => ControlFlow::Break(reason(error)),
+        }
+    }
+
+    #[inline]
+    fn or_exit<D>(self) -> Result<T, EarlyExit<D>> {
+        self.map_err(|_| EarlyExit::Default)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{OptionEarlyExt, ResultEarlyExt};
+    use core::ops::ControlFlow;
+
+    #[test]
+    fn should_break_on_none() {
+        assert_eq!(Some(1).or_break_cf("none"), ControlFlow::Continue(1));
+        assert_eq!(None::<i32>.or_break_cf("none"), ControlFlow::Break("none"));
+        assert_eq!(None::<i32>.or_break_cf_with(|| 2), ControlFlow::Break(2));
+        let values = [Some(1), Some(2), None, Some(4)];
+        let first_gap = values.into_iter().enumerate().try_for_each(|(i, value)| {
+            value.or_break_cf(i)?;
+            ControlFlow::Continue(())
+        });
+        assert_eq!(first_gap, ControlFlow::Break(2));
+    }
+
+    #[test]
+    fn should_break_on_err() {
+        assert_eq!(Ok::<_, &str>(1).or_break_cf(), ControlFlow::Continue(1));
+        assert_eq!(Err::<i32, _>("bad").or_break_cf(), ControlFlow::Break("bad"));
+        let values = [Ok(1), Ok(2), Err("bad"), Ok(4)];
+        let sum = values.into_iter().try_fold(0, |sum, value| {
+            let value = value.or_break_cf_with(|e| format!("{e} after {sum}"))?;
+            ControlFlow::Continue(sum + value)
+        });
+        assert_eq!(sum, ControlFlow::Break("bad after 3".to_string()));
+    }
+}
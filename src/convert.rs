@@ -0,0 +1,613 @@
+/// Either convert a value with `TryInto` or return from the current function if the conversion
+/// fails. The target type is inferred, e.g. from the binding. A default return value can be
+/// provided, or the conversion error can be bound and used to produce the return value.
+/// ```
+/// use early_returns::try_into_or_return;
+///
+/// fn byte_at(data: &[u8], offset: i64) -> Option<u8> {
+///     let idx: usize = try_into_or_return!(offset, None);
+///     data.get(idx).copied()
+/// }
+///
+/// fn narrow(value: u32) -> Result<u8, String> {
+///     let byte: u8 = try_into_or_return!(value, e => Err(e.to_string()));
+///     Ok(byte)
+/// }
+///
+/// assert_eq!(byte_at(b"abc", 1), Some(b'b'));
+/// assert_eq!(byte_at(b"abc", -1), None);
+/// assert_eq!(narrow(7), Ok(7));
+/// assert!(narrow(300).is_err());
+/// ```
+#[macro_export]
+macro_rules! try_into_or_return {
+    ($from:expr, $e:ident => $result:expr) => {{
+        match ::core::convert::TryInto::try_into($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_return!(::core::convert::TryInto::try_into($from))
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::ok_or_return!(::core::convert::TryInto::try_into($from), $default_result)
+    };
+}
+
+/// Either convert a value with `TryInto` or break out of a loop if the conversion fails. If a
+/// loop lifetime is specified, that loop will be exited, otherwise the immediate loop is exited.
+/// The conversion error can be bound and handled before breaking.
+/// ```
+/// use early_returns::try_into_or_break;
+///
+/// let mut bytes = Vec::new();
+/// let mut error = None;
+/// for value in [1u32, 2, 300, 4] {
+///     let byte: u8 = try_into_or_break!(value, e => error = Some(e));
+///     bytes.push(byte);
+/// }
+/// assert_eq!(bytes, vec![1, 2]);
+/// assert!(error.is_some());
+/// ```
+#[macro_export]
+macro_rules! try_into_or_break {
+    ($from:expr, $e:ident => $handler:expr) => {{
+        match ::core::convert::TryInto::try_into($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $e:ident => $handler:expr) => {{
+        match ::core::convert::TryInto::try_into($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                break $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_break!(::core::convert::TryInto::try_into($from))
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::ok_or_break!(::core::convert::TryInto::try_into($from), $lt)
+    };
+}
+
+/// Either convert a value with `TryInto` or continue in a loop if the conversion fails. If a loop
+/// lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued". The conversion error can be bound and handled before continuing.
+/// ```
+/// use early_returns::try_into_or_continue;
+///
+/// let mut indices = Vec::new();
+/// let mut skipped = 0;
+/// for offset in [3i64, -1, 5] {
+///     let idx: usize = try_into_or_continue!(offset, _e => skipped += 1);
+///     indices.push(idx);
+/// }
+/// assert_eq!(indices, vec![3, 5]);
+/// assert_eq!(skipped, 1);
+/// ```
+#[macro_export]
+macro_rules! try_into_or_continue {
+    ($from:expr, $e:ident => $handler:expr) => {{
+        match ::core::convert::TryInto::try_into($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $e:ident => $handler:expr) => {{
+        match ::core::convert::TryInto::try_into($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_continue!(::core::convert::TryInto::try_into($from))
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!(::core::convert::TryInto::try_into($from), $lt)
+    };
+}
+
+/// Either parse a string with `str::parse` or return from the current function if parsing fails.
+/// The target type is inferred, e.g. from the binding, or can be given as a turbofish after the
+/// string (e.g. `parse_or_return!(line, ::<u32>)`). A default return value can be provided, or
+/// the parse error can be bound and used to produce the return value.
+/// ```
+/// use early_returns::parse_or_return;
+///
+/// fn port(value: &str) -> Option<u16> {
+///     let port: u16 = parse_or_return!(value, None);
+///     Some(port)
+/// }
+///
+/// fn doubled(value: &str) -> Result<u32, String> {
+///     let value = parse_or_return!(value.trim(), ::<u32>, e => Err(e.to_string()));
+///     Ok(value * 2)
+/// }
+///
+/// assert_eq!(port("8080"), Some(8080));
+/// assert_eq!(port("eighty"), None);
+/// assert_eq!(doubled(" 21 "), Ok(42));
+/// assert!(doubled("-1").is_err());
+/// ```
+#[macro_export]
+macro_rules! parse_or_return {
+    ($from:expr, ::<$to:ty>, $e:ident => $result:expr) => {{
+        match $from.parse::<$to>() {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr, ::<$to:ty>) => {
+        $crate::ok_or_return!($from.parse::<$to>())
+    };
+
+    ($from:expr, ::<$to:ty>, $default_result:expr) => {
+        $crate::ok_or_return!($from.parse::<$to>(), $default_result)
+    };
+
+    ($from:expr, $e:ident => $result:expr) => {{
+        match $from.parse() {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_return!($from.parse())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::ok_or_return!($from.parse(), $default_result)
+    };
+}
+
+/// Either parse a string with `str::parse` or continue in a loop if parsing fails. If a loop
+/// lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued". The target type is inferred, e.g. from the binding, or can be given as a
+/// turbofish after the string (e.g. `parse_or_continue!(line, ::<u32>)`). The parse error can be
+/// bound and handled before continuing.
+/// ```
+/// use early_returns::parse_or_continue;
+///
+/// let input = "1\ntwo\n3\n";
+/// let mut sum = 0;
+/// let mut rejected = Vec::new();
+/// for line in input.lines() {
+///     sum += parse_or_continue!(line, ::<i32>, _e => rejected.push(line));
+/// }
+/// assert_eq!(sum, 4);
+/// assert_eq!(rejected, vec!["two"]);
+/// ```
+#[macro_export]
+macro_rules! parse_or_continue {
+    ($from:expr, ::<$to:ty>, $e:ident => $handler:expr) => {{
+        match $from.parse::<$to>() {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, ::<$to:ty>, $lt:lifetime, $e:ident => $handler:expr) => {{
+        match $from.parse::<$to>() {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue $lt;
+            }
+        }
+    }};
+
+    ($from:expr, ::<$to:ty>) => {
+        $crate::ok_or_continue!($from.parse::<$to>())
+    };
+
+    ($from:expr, ::<$to:ty>, $lt:lifetime) => {
+        $crate::ok_or_continue!($from.parse::<$to>(), $lt)
+    };
+
+    ($from:expr, $e:ident => $handler:expr) => {{
+        match $from.parse() {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $e:ident => $handler:expr) => {{
+        match $from.parse() {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_continue!($from.parse())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!($from.parse(), $lt)
+    };
+}
+
+/// Either decode a byte slice as UTF-8 with `str::from_utf8` or return from the current function
+/// if it is not valid UTF-8. A default return value can be provided, or the `Utf8Error` can be
+/// bound and used to produce the return value.
+///
+/// With the `owned` marker, a `Vec<u8>` is decoded into a `String` with `String::from_utf8`
+/// instead, in which case the bound error is a `FromUtf8Error` that still holds the bytes.
+#[cfg_attr(feature = "alloc", doc = "```")]
+#[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+/// use early_returns::utf8_or_return;
+///
+/// fn greeting(bytes: &[u8]) -> Result<&str, String> {
+///     let text = utf8_or_return!(bytes, e => Err(format!("invalid at {}", e.valid_up_to())));
+///     Ok(text)
+/// }
+///
+/// fn into_text(bytes: Vec<u8>) -> Option<String> {
+///     let text = utf8_or_return!(owned bytes, None);
+///     Some(text)
+/// }
+///
+/// assert_eq!(greeting(b"hello"), Ok("hello"));
+/// assert_eq!(greeting(b"he\xffllo"), Err("invalid at 2".to_string()));
+/// assert_eq!(into_text(b"hi".to_vec()), Some("hi".to_string()));
+/// assert_eq!(into_text(vec![0xff]), None);
+/// ```
+#[macro_export]
+macro_rules! utf8_or_return {
+    (owned $from:expr, $e:ident => $result:expr) => {{
+        match $crate::__private::String::from_utf8($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    (owned $from:expr) => {
+        $crate::ok_or_return!($crate::__private::String::from_utf8($from))
+    };
+
+    (owned $from:expr, $default_result:expr) => {
+        $crate::ok_or_return!($crate::__private::String::from_utf8($from), $default_result)
+    };
+
+    ($from:expr, $e:ident => $result:expr) => {{
+        match ::core::str::from_utf8($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_return!(::core::str::from_utf8($from))
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::ok_or_return!(::core::str::from_utf8($from), $default_result)
+    };
+}
+
+/// Either decode a byte slice as UTF-8 with `str::from_utf8` or continue in a loop if it is not
+/// valid UTF-8. If a loop lifetime is specified, that loop will be "continued", otherwise the
+/// immediate loop is "continued". The `Utf8Error` can be bound and handled before continuing.
+///
+/// With the `owned` marker, a `Vec<u8>` is decoded into a `String` with `String::from_utf8`
+/// instead, in which case the bound error is a `FromUtf8Error` that still holds the bytes.
+/// ```
+/// use early_returns::utf8_or_continue;
+///
+/// let frames: Vec<&[u8]> = vec![b"ping", b"\xc3\x28", b"pong"];
+/// let mut messages = Vec::new();
+/// let mut invalid = 0;
+/// for frame in frames {
+///     messages.push(utf8_or_continue!(frame, _e => invalid += 1));
+/// }
+/// assert_eq!(messages, vec!["ping", "pong"]);
+/// assert_eq!(invalid, 1);
+/// ```
+#[macro_export]
+macro_rules! utf8_or_continue {
+    (owned $from:expr, $e:ident => $handler:expr) => {{
+        match $crate::__private::String::from_utf8($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue;
+            }
+        }
+    }};
+
+    (owned $from:expr, $lt:lifetime, $e:ident => $handler:expr) => {{
+        match $crate::__private::String::from_utf8($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue $lt;
+            }
+        }
+    }};
+
+    (owned $from:expr) => {
+        $crate::ok_or_continue!($crate::__private::String::from_utf8($from))
+    };
+
+    (owned $from:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!($crate::__private::String::from_utf8($from), $lt)
+    };
+
+    ($from:expr, $e:ident => $handler:expr) => {{
+        match ::core::str::from_utf8($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $e:ident => $handler:expr) => {{
+        match ::core::str::from_utf8($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                continue $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_continue!(::core::str::from_utf8($from))
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!(::core::str::from_utf8($from), $lt)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::{ParseIntError, TryFromIntError};
+
+    struct Tester {
+        value: u8,
+        errors: Vec<TryFromIntError>,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester {
+                value: 0,
+                errors: Vec::new(),
+            }
+        }
+
+        fn add(&mut self, value: i32) {
+            let value: u8 = try_into_or_return!(value);
+            self.value += value;
+        }
+
+        fn add_with_break(&mut self, values: Vec<i32>) {
+            for value in values {
+                let value: u8 = try_into_or_break!(value);
+                self.value += value;
+            }
+        }
+
+        fn add_with_break_with_lifetime(&mut self, values: Vec<i32>) {
+            'l: for value in values {
+                self.value += 1;
+                for _i in 0..1 {
+                    let value: u8 = try_into_or_break!(value, 'l, e => self.errors.push(e));
+                    self.value += value;
+                }
+            }
+        }
+
+        fn add_with_continue(&mut self, values: Vec<i32>) {
+            for value in values {
+                let value: u8 = try_into_or_continue!(value, e => self.errors.push(e));
+                self.value += value;
+            }
+        }
+
+        fn add_with_continue_with_lifetime(&mut self, values: Vec<i32>) {
+            'l: for value in values {
+                self.value += 1;
+                for _i in 0..1 {
+                    let value: u8 = try_into_or_continue!(value, 'l);
+                    self.value += value;
+                }
+            }
+        }
+    }
+
+    fn converted_or_default(value: i64) -> u16 {
+        try_into_or_return!(value, 0)
+    }
+
+    fn converted_or_error(value: i64) -> Result<u16, TryFromIntError> {
+        Ok(try_into_or_return!(value, e => Err(e)))
+    }
+
+    #[test]
+    fn should_return_early_when_conversion_fails() {
+        let mut tester = Tester::new();
+        tester.add(-1);
+        tester.add(2);
+        assert_eq!(tester.value, 2);
+        assert_eq!(converted_or_default(7), 7);
+        assert_eq!(converted_or_default(-7), 0);
+        assert_eq!(converted_or_error(7), Ok(7));
+        assert!(converted_or_error(70_000).is_err());
+    }
+
+    #[test]
+    fn should_break_when_conversion_fails() {
+        let mut tester = Tester::new();
+        tester.add_with_break(vec![1, 256, 2]);
+        assert_eq!(tester.value, 1);
+        tester.add_with_break_with_lifetime(vec![-1, 2]);
+        assert_eq!(tester.value, 2);
+        assert_eq!(tester.errors.len(), 1);
+    }
+
+    #[test]
+    fn should_continue_when_conversion_fails() {
+        let mut tester = Tester::new();
+        tester.add_with_continue(vec![1, 256, 2]);
+        assert_eq!(tester.value, 3);
+        assert_eq!(tester.errors.len(), 1);
+        tester.add_with_continue_with_lifetime(vec![-1, 2]);
+        assert_eq!(tester.value, 7);
+    }
+
+    fn parsed_sum(lines: &[&str]) -> i32 {
+        let mut sum = 0;
+        for line in lines {
+            sum += parse_or_continue!(line, ::<i32>);
+        }
+        'l: for line in lines {
+            for _i in 0..1 {
+                let value: i32 = parse_or_continue!(line, 'l);
+                sum += value * 10;
+            }
+        }
+        sum
+    }
+
+    fn parsed_errors(lines: &[&str]) -> Vec<ParseIntError> {
+        let mut errors = Vec::new();
+        'l: for line in lines {
+            for _i in 0..1 {
+                let _value = parse_or_continue!(line, ::<u8>, 'l, e => errors.push(e));
+            }
+        }
+        errors
+    }
+
+    fn parsed_or_default(line: &str) -> i32 {
+        parse_or_return!(line, -1)
+    }
+
+    fn typed_parsed_or_default(line: &str) -> i32 {
+        parse_or_return!(line, ::<u8>, -1).into()
+    }
+
+    fn parsed_or_error(line: &str) -> Result<i32, ParseIntError> {
+        Ok(parse_or_return!(line, e => Err(e)))
+    }
+
+    #[test]
+    fn should_return_early_when_parsing_fails() {
+        assert_eq!(parsed_or_default("12"), 12);
+        assert_eq!(parsed_or_default("twelve"), -1);
+        assert_eq!(typed_parsed_or_default("12"), 12);
+        assert_eq!(typed_parsed_or_default("-12"), -1);
+        assert_eq!(parsed_or_error("12"), Ok(12));
+        assert!(parsed_or_error("").is_err());
+    }
+
+    #[test]
+    fn should_continue_when_parsing_fails() {
+        assert_eq!(parsed_sum(&["1", "x", "2"]), 33);
+        assert_eq!(parsed_errors(&["1", "x", "300"]).len(), 2);
+    }
+
+    fn push_decoded(bytes: &[u8], lines: &mut Vec<String>) {
+        lines.push(utf8_or_return!(bytes).to_string());
+    }
+
+    fn decoded_or_default(bytes: &[u8]) -> &str {
+        utf8_or_return!(bytes, "")
+    }
+
+    #[cfg(feature = "alloc")]
+    fn decoded_owned_or_bytes(bytes: Vec<u8>) -> Result<String, Vec<u8>> {
+        Ok(utf8_or_return!(owned bytes, e => Err(e.into_bytes())))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn decoded_lines(frames: Vec<Vec<u8>>) -> (Vec<String>, usize) {
+        let mut lines = Vec::new();
+        let mut invalid = 0;
+        for frame in &frames {
+            lines.push(utf8_or_continue!(frame).to_string());
+        }
+        'l: for frame in frames {
+            for _i in 0..1 {
+                let line = utf8_or_continue!(owned frame.clone(), 'l, _e => invalid += 1);
+                lines.push(line);
+            }
+        }
+        (lines, invalid)
+    }
+
+    #[test]
+    fn should_return_early_on_invalid_utf8() {
+        let mut lines = Vec::new();
+        push_decoded(b"abc", &mut lines);
+        push_decoded(b"\xff", &mut lines);
+        assert_eq!(lines, vec!["abc"]);
+        assert_eq!(decoded_or_default(b"abc"), "abc");
+        assert_eq!(decoded_or_default(b"a\xffc"), "");
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(decoded_owned_or_bytes(b"abc".to_vec()), Ok("abc".to_string()));
+            assert_eq!(decoded_owned_or_bytes(vec![0xff]), Err(vec![0xff]));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn should_continue_on_invalid_utf8() {
+        let (lines, invalid) = decoded_lines(vec![b"a".to_vec(), vec![0xff], b"b".to_vec()]);
+        assert_eq!(lines, vec!["a", "b", "a", "b"]);
+        assert_eq!(invalid, 1);
+    }
+}
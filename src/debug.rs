@@ -0,0 +1,323 @@
+/// Either get the value from an Option type or panic in debug builds if there is none. In release
+/// builds (without `debug_assertions`) the usual early exit is taken instead: return from the
+/// current function. A default return value can be provided.
+/// ```
+/// use early_returns::debug_some_or_return;
+///
+/// fn frobnicate(frobnicator: Option<&str>) -> Option<usize> {
+///     // Every caller is expected to pass a frobnicator: surface a missing one loudly in tests,
+///     // but degrade gracefully in production.
+///     let frobnicator = debug_some_or_return!(frobnicator, None);
+///     Some(frobnicator.len())
+/// }
+///
+/// assert_eq!(frobnicate(Some("abc")), Some(3));
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! debug_some_or_return {
+    ($from:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `None` in `debug_some_or_return!`");
+                }
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `None` in `debug_some_or_return!`");
+                }
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or panic in debug builds if there is none. In release
+/// builds (without `debug_assertions`) the usual early exit is taken instead: break out of a loop.
+/// If a loop lifetime is specified, that loop will be exited, otherwise the immediate loop is
+/// exited.
+/// ```
+/// use early_returns::debug_some_or_break;
+///
+/// let mut sum = 0;
+/// for value in [Some(1), Some(2)] {
+///     sum += debug_some_or_break!(value);
+/// }
+/// assert_eq!(sum, 3);
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! debug_some_or_break {
+    ($from:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `None` in `debug_some_or_break!`");
+                }
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `None` in `debug_some_or_break!`");
+                }
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or panic in debug builds if there is none. In release
+/// builds (without `debug_assertions`) the usual early exit is taken instead: continue in a loop.
+/// If a loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::debug_some_or_continue;
+///
+/// let mut sum = 0;
+/// for value in [Some(1), Some(2)] {
+///     sum += debug_some_or_continue!(value);
+/// }
+/// assert_eq!(sum, 3);
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! debug_some_or_continue {
+    ($from:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `None` in `debug_some_or_continue!`");
+                }
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `None` in `debug_some_or_continue!`");
+                }
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or panic in debug builds (including the `Debug` of
+/// the error) if it is an `Err`. In release builds (without `debug_assertions`) the usual early
+/// exit is taken instead: return from the current function. A default return value can be provided.
+/// ```
+/// use early_returns::debug_ok_or_return;
+///
+/// fn port(value: &str) -> u16 {
+///     debug_ok_or_return!(value.parse(), 80)
+/// }
+///
+/// assert_eq!(port("8080"), 8080);
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! debug_ok_or_return {
+    ($from:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `Err` in `debug_ok_or_return!`: {:?}", e);
+                }
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `Err` in `debug_ok_or_return!`: {:?}", e);
+                }
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or panic in debug builds (including the `Debug` of
+/// the error) if it is an `Err`. In release builds (without `debug_assertions`) the usual early
+/// exit is taken instead: break out of a loop. If a loop lifetime is specified, that loop will be
+/// exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::debug_ok_or_break;
+///
+/// let mut sum = 0;
+/// for value in [Ok::<_, ()>(1), Ok(2)] {
+///     sum += debug_ok_or_break!(value);
+/// }
+/// assert_eq!(sum, 3);
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! debug_ok_or_break {
+    ($from:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `Err` in `debug_ok_or_break!`: {:?}", e);
+                }
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `Err` in `debug_ok_or_break!`: {:?}", e);
+                }
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or panic in debug builds (including the `Debug` of
+/// the error) if it is an `Err`. In release builds (without `debug_assertions`) the usual early
+/// exit is taken instead: continue in a loop. If a loop lifetime is specified, that loop will be
+/// "continued", otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::debug_ok_or_continue;
+///
+/// let mut sum = 0;
+/// for value in [Ok::<_, ()>(1), Ok(2)] {
+///     sum += debug_ok_or_continue!(value);
+/// }
+/// assert_eq!(sum, 3);
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! debug_ok_or_continue {
+    ($from:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `Err` in `debug_ok_or_continue!`: {:?}", e);
+                }
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                if ::core::cfg!(debug_assertions) {
+                    ::core::panic!("unexpected `Err` in `debug_ok_or_continue!`: {:?}", e);
+                }
+                continue $lt;
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "option")]
+    fn value_or_default(value: Option<i32>) -> i32 {
+        debug_some_or_return!(value, -1)
+    }
+
+    #[cfg(feature = "result")]
+    fn add(value: Result<i32, &str>, sum: &mut i32) {
+        *sum += debug_ok_or_return!(value);
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    #[test]
+    fn should_get_value_when_present() {
+        assert_eq!(value_or_default(Some(1)), 1);
+        let mut sum = 0;
+        add(Ok(2), &mut sum);
+        for value in [Some(1), Some(2)] {
+            sum += debug_some_or_break!(value);
+        }
+        'l: for value in [Ok::<_, ()>(1), Ok(2)] {
+            for _i in 0..2 {
+                sum += debug_ok_or_continue!(value, 'l);
+            }
+        }
+        assert_eq!(sum, 11);
+    }
+
+    #[cfg(feature = "option")]
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "unexpected `None`"))]
+    fn should_panic_in_debug_and_return_in_release_when_none() {
+        assert_eq!(value_or_default(None), -1);
+    }
+
+    #[test]
+    #[cfg_attr(
+        debug_assertions,
+        should_panic(expected = "`debug_ok_or_return!`: \"bad\"")
+    )]
+    #[cfg(feature = "result")]
+    fn should_panic_in_debug_and_return_in_release_when_err() {
+        let mut sum = 0;
+        add(Err("bad"), &mut sum);
+        assert_eq!(sum, 0);
+    }
+
+    #[cfg(feature = "result")]
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "`debug_ok_or_break!`"))]
+    fn should_panic_in_debug_and_break_in_release() {
+        let mut sum = 0;
+        for value in [Ok(1), Err(()), Ok(2)] {
+            sum += debug_ok_or_break!(value);
+        }
+        assert_eq!(sum, 1);
+    }
+
+    #[cfg(feature = "option")]
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "`debug_some_or_continue!`"))]
+    fn should_panic_in_debug_and_continue_in_release() {
+        let mut sum = 0;
+        'l: for value in [Some(1), None, Some(2)] {
+            for _i in 0..2 {
+                sum += debug_some_or_continue!(value, 'l);
+            }
+        }
+        assert_eq!(sum, 6);
+    }
+}
@@ -0,0 +1,336 @@
+//! Scope guards running cleanup code when a scope is left.
+//!
+//! [`defer!`](crate::defer!) registers cleanup that runs however the scope is left: through one of
+//! the crate's early exits, a `?`, a `return`, its end or a panic.
+//!
+#![cfg_attr(feature = "option", doc = "```")]
+#![cfg_attr(not(feature = "option"), doc = "```ignore")]
+//! use early_returns::{defer, some_or_return};
+//!
+//! fn first_char(input: &str, done: &mut Vec<&'static str>) -> Option<char> {
+//!     defer! { done.push("first_char") }
+//!     let c = some_or_return!(input.chars().next(), None);
+//!     Some(c)
+//! }
+//!
+//! let mut done = Vec::new();
+//! assert_eq!(first_char("", &mut done), None);
+//! assert_eq!(first_char("a", &mut done), Some('a'));
+//! assert_eq!(done, ["first_char", "first_char"]);
+//! ```
+//!
+//! With the `defer` feature, [`defer_on_early_exit!`](crate::defer_on_early_exit!) registers
+//! cleanup that only runs when the function is left through one of the crate's `*_or_return`
+//! escapes, and not when it returns normally, panics or only escapes from a closure.
+//!
+//! The closure of a guard borrows what it uses until the end of the scope, so values that are
+//! still used afterwards are best shared through a `Cell` or a `RefCell`.
+
+#[cfg(feature = "defer")]
+use core::marker::PhantomData;
+#[cfg(feature = "defer")]
+use std::cell::{Cell, RefCell};
+
+/// Runs a cleanup closure when it is dropped, unless it was dismissed.
+///
+/// Usually created by [`defer!`](crate::defer!).
+#[must_use = "the cleanup runs right away if the guard is not bound to a variable"]
+pub struct ScopeGuard<F: FnOnce()> {
+    cleanup: Option<F>,
+}
+
+impl<F: FnOnce()> ScopeGuard<F> {
+    /// A guard running `cleanup` when it is dropped.
+    pub fn new(cleanup: F) -> Self {
+        ScopeGuard {
+            cleanup: Some(cleanup),
+        }
+    }
+
+    /// Drop the guard without running its cleanup.
+    pub fn dismiss(mut self) {
+        self.cleanup = None;
+    }
+}
+
+impl<F: FnOnce()> Drop for ScopeGuard<F> {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            cleanup();
+        }
+    }
+}
+
+/// Runs a cleanup closure when it is dropped, if one of the crate's early returns was taken in the
+/// function it was created in since, unless it was dismissed.
+///
+/// Created by [`defer_on_early_exit!`](crate::defer_on_early_exit!). It is registered with the
+/// thread it is created on, so it cannot be sent to another one (or held across an `.await` in a
+/// future that can be).
+#[cfg(feature = "defer")]
+#[must_use = "the guard is unregistered right away if it is not bound to a variable"]
+pub struct EarlyExitGuard<F: FnOnce()> {
+    cleanup: Option<F>,
+    id: u64,
+    _thread: PhantomData<*const ()>,
+}
+
+#[cfg(feature = "defer")]
+impl<F: FnOnce()> EarlyExitGuard<F> {
+    /// A guard running `cleanup` when it is dropped after one of the crate's early returns was
+    /// taken in `function`. The `site` tells the guards registered by the same function apart.
+    #[doc(hidden)]
+    pub fn new(function: &'static str, site: &'static str, cleanup: F) -> Self {
+        let id = NEXT_GUARD.with(|next| next.replace(next.get() + 1));
+        LIVE_GUARDS.with(|live| {
+            live.borrow_mut().push(LiveGuard {
+                id,
+                function,
+                site,
+                taken: false,
+            })
+        });
+        EarlyExitGuard {
+            cleanup: Some(cleanup),
+            id,
+            _thread: PhantomData,
+        }
+    }
+
+    /// Drop the guard without running its cleanup.
+    pub fn dismiss(mut self) {
+        self.cleanup = None;
+    }
+}
+
+#[cfg(feature = "defer")]
+impl<F: FnOnce()> Drop for EarlyExitGuard<F> {
+    fn drop(&mut self) {
+        let taken = LIVE_GUARDS.try_with(|live| {
+            let mut live = live.borrow_mut();
+            let index = live.iter().rposition(|guard| guard.id == self.id)?;
+            Some(live.remove(index).taken)
+        });
+        // The registry is released before the cleanup runs, which may register guards itself.
+        if let (Ok(Some(true)), Some(cleanup)) = (taken, self.cleanup.take()) {
+            cleanup();
+        }
+    }
+}
+
+#[cfg(feature = "defer")]
+struct LiveGuard {
+    id: u64,
+    function: &'static str,
+    site: &'static str,
+    taken: bool,
+}
+
+#[cfg(feature = "defer")]
+thread_local! {
+    static NEXT_GUARD: Cell<u64> = const { Cell::new(0) };
+    // The guards of `defer_on_early_exit!` alive on this thread, innermost last.
+    static LIVE_GUARDS: RefCell<Vec<LiveGuard>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The name of the function a marker function item was declared in, with the marker's name.
+#[cfg(feature = "defer")]
+#[doc(hidden)]
+pub fn function_of<M>(_marker: M) -> &'static str {
+    core::any::type_name::<M>()
+}
+
+/// Record that an early return is being taken in the function `marker` was declared in.
+///
+/// A call of a function has at most one live guard per site, and those of a recursive call are
+/// registered after those of its caller, so the innermost guard of every site of the function
+/// belongs to the call that is returning.
+#[cfg(feature = "defer")]
+#[doc(hidden)]
+#[cold]
+pub fn mark_early_exit<M>(marker: M) {
+    let function = function_of(marker);
+    let _ = LIVE_GUARDS.try_with(|live| {
+        let mut live = live.borrow_mut();
+        for index in (0..live.len()).rev() {
+            let (guard, inner) = (&live[index], &live[index + 1..]);
+            let innermost = guard.function == function
+                && !inner
+                    .iter()
+                    .any(|other| other.function == function && other.site == guard.site);
+            if innermost {
+                live[index].taken = true;
+            }
+        }
+    });
+}
+
+/// Run the given statements when the current scope is left, however it is left (including by
+/// unwinding from a panic).
+#[cfg_attr(feature = "result", doc = "```")]
+#[cfg_attr(not(feature = "result"), doc = "```ignore")]
+/// use std::cell::RefCell;
+///
+/// use early_returns::{defer, ok_or_continue};
+///
+/// let log = RefCell::new(Vec::new());
+/// for row in ["1", "x"] {
+///     defer! { log.borrow_mut().push(format!("done with {row:?}")) }
+///     let value: u32 = ok_or_continue!(row.parse());
+///     log.borrow_mut().push(format!("parsed {value}"));
+/// }
+/// assert_eq!(log.into_inner(), ["parsed 1", "done with \"1\"", "done with \"x\""]);
+/// ```
+#[macro_export]
+macro_rules! defer {
+    ($($cleanup:tt)*) => {
+        let _guard = $crate::defer::ScopeGuard::new(|| {
+            $($cleanup)*
+        });
+    };
+}
+
+/// Run the given statements when the current function is left through one of the crate's early
+/// returns (`some_or_return!`, `ok_or_return!` and the other `*_or_return` macros), but not when it
+/// returns normally, panics, or only breaks out of or continues a loop. Requires the `defer`
+/// feature.
+///
+/// Only the escapes taken in the function itself count: an early return in a function it calls,
+/// or in a closure it defines, does not. In a recursive function, only the guards of the call that
+/// returns early run, as long as that call registered them before returning: an early return
+/// taken before the guard is registered is attributed to the guard of the calling call instead.
+/// ```
+/// use std::cell::Cell;
+///
+/// use early_returns::{defer_on_early_exit, ok_or_return};
+///
+/// fn parse_all(rows: &[&str], rolled_back: &Cell<bool>) -> Vec<u32> {
+///     defer_on_early_exit! { rolled_back.set(true) }
+///     let mut values = Vec::new();
+///     for row in rows {
+///         values.push(ok_or_return!(row.parse(), Vec::new()));
+///     }
+///     values
+/// }
+///
+/// let rolled_back = Cell::new(false);
+/// assert_eq!(parse_all(&["1", "2"], &rolled_back), [1, 2]);
+/// assert!(!rolled_back.get());
+/// assert_eq!(parse_all(&["1", "x"], &rolled_back), []);
+/// assert!(rolled_back.get());
+/// ```
+#[cfg(feature = "defer")]
+#[macro_export]
+macro_rules! defer_on_early_exit {
+    ($($cleanup:tt)*) => {
+        let _guard = $crate::defer::EarlyExitGuard::new(
+            {
+                fn __early_exit() {}
+                $crate::__private::function_of(__early_exit)
+            },
+            ::core::concat!(::core::file!(), ":", ::core::line!(), ":", ::core::column!()),
+            || {
+                $($cleanup)*
+            },
+        );
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::ScopeGuard;
+    use core::cell::Cell;
+
+    #[test]
+    fn should_run_unless_dismissed() {
+        let runs = Cell::new(0);
+        {
+            let _guard = ScopeGuard::new(|| runs.set(runs.get() + 1));
+            ScopeGuard::new(|| runs.set(runs.get() + 10)).dismiss();
+        }
+        assert_eq!(runs.get(), 1);
+        for _ in 0..2 {
+            defer! { runs.set(runs.get() + 100) }
+            defer! { runs.set(runs.get() * 2) }
+        }
+        // Guards run in the reverse order of their registration.
+        assert_eq!(runs.get(), 304);
+    }
+
+    #[cfg(feature = "defer")]
+    mod on_early_exit {
+        use core::cell::{Cell, RefCell};
+
+        type Steps = RefCell<Vec<&'static str>>;
+
+        fn helper(value: Option<u32>) -> u32 {
+            crate::some_or_return!(value, 0)
+        }
+
+        fn guarded(value: Option<u32>, inner: Option<u32>, runs: &Cell<u32>) -> u32 {
+            defer_on_early_exit! { runs.set(runs.get() + 1) }
+            // Neither escapes in callees nor in closures leave this function.
+            let helped = helper(inner);
+            let closure = (|| Some(crate::some_or_return!(inner, None)))();
+            for value in [None, Some(1)] {
+                crate::some_or_continue!(value);
+            }
+            helped + closure.unwrap_or(0) + crate::some_or_return!(value, 0)
+        }
+
+        #[test]
+        fn should_only_run_when_returning_early() {
+            let runs = Cell::new(0);
+            assert_eq!(guarded(Some(1), None, &runs), 1);
+            assert_eq!(runs.get(), 0);
+            assert_eq!(guarded(Some(1), Some(2), &runs), 5);
+            assert_eq!(runs.get(), 0);
+            assert_eq!(guarded(None, Some(2), &runs), 0);
+            assert_eq!(runs.get(), 1);
+            assert_eq!(guarded(None, None, &runs), 0);
+            assert_eq!(runs.get(), 2);
+        }
+
+        fn countdown(depth: u32, fail_at: u32, runs: &Cell<u32>) -> u32 {
+            defer_on_early_exit! { runs.set(runs.get() + 1) }
+            if depth == 0 {
+                return 0;
+            }
+            let inner = countdown(depth - 1, fail_at, runs);
+            let value = crate::some_or_return!(Some(depth).filter(|depth| *depth != fail_at), 0);
+            inner + value
+        }
+
+        #[test]
+        fn should_only_run_the_guard_of_the_recursive_call_returning_early() {
+            let runs = Cell::new(0);
+            assert_eq!(countdown(3, 4, &runs), 6);
+            assert_eq!(runs.get(), 0);
+            assert_eq!(countdown(3, 1, &runs), 5);
+            assert_eq!(runs.get(), 1);
+            assert_eq!(countdown(3, 3, &runs), 0);
+            assert_eq!(runs.get(), 2);
+        }
+
+        fn rolled_back(value: Result<u32, ()>, nested: bool, steps: &Steps) -> u32 {
+            defer_on_early_exit! { steps.borrow_mut().push("first") }
+            defer_on_early_exit! { steps.borrow_mut().push("second") }
+            if nested {
+                let value = crate::ok_or_return!(value, 1);
+                return value;
+            }
+            crate::ok_or_return!(value, 1)
+        }
+
+        #[test]
+        fn should_run_every_guard_of_the_function_in_reverse_order() {
+            let steps = RefCell::new(Vec::new());
+            assert_eq!(rolled_back(Ok(2), false, &steps), 2);
+            assert_eq!(rolled_back(Ok(2), true, &steps), 2);
+            assert!(steps.borrow().is_empty());
+            assert_eq!(rolled_back(Err(()), false, &steps), 1);
+            assert_eq!(rolled_back(Err(()), true, &steps), 1);
+            assert_eq!(steps.borrow().as_slice(), ["second", "first", "second", "first"]);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! A residual type for composing early returns across helper functions with the `?` operator.
+//!
+//! Helpers return `Result<T, EarlyExit<D>>`, where `D` is what the outermost function returns.
+//! The `?` operator propagates an [`EarlyExit`] through any number of helpers, and the outermost
+//! function turns it into its return value with [`ResolveEarlyExit::resolve`].
+//! ```
+//! use early_returns::control_flow::OptionEarlyExt;
+//! use early_returns::early_exit::{EarlyExit, ResolveEarlyExit};
+//!
+//! fn port(config: &[(&str, &str)]) -> Result<u16, EarlyExit<String>> {
+//!     let (_, value) = config.iter().find(|(key, _)| *key == "port").or_exit()?;
+//!     // Any `Result<_, String>` propagates its error as the value to return.
+//!     let port = value.parse().map_err(|_| format!("bad port {value:?}"))?;
+//!     Ok(port)
+//! }
+//!
+//! fn describe(config: &[(&str, &str)]) -> String {
+//!     let describe = || Ok(format!("listening on {}", port(config)?));
+//!     describe().resolve("not listening".to_string())
+//! }
+//!
+//! assert_eq!(describe(&[("port", "80")]), "listening on 80");
+//! assert_eq!(describe(&[("port", "http")]), "bad port \"http\"");
+//! assert_eq!(describe(&[]), "not listening");
+//! ```
+
+/// Why a function is returning early: either with the default value of the function it is
+/// eventually resolved in, or with a value of its own.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EarlyExit<D> {
+    /// Return the default value given to [`resolve`](EarlyExit::resolve), like
+    /// `some_or_return!(value)` without a default.
+    Default,
+    /// Return the given value, like `some_or_return!(value, returned)`.
+    Return(D),
+}
+
+impl<D> EarlyExit<D> {
+    /// The value to return: the carried one, or `default` for [`EarlyExit::Default`].
+    pub fn resolve(self, default: D) -> D {
+        match self {
+            EarlyExit::Default => default,
+            EarlyExit::Return(returned) => returned,
+        }
+    }
+
+    /// Like [`resolve`](EarlyExit::resolve), but only computes the default when it is needed.
+    pub fn resolve_with<F>(self, default: F) -> D
+    where
+        F: FnOnce() -> D,
+    {
+        match self {
+            EarlyExit::Default => default(),
+            EarlyExit::Return(returned) => returned,
+        }
+    }
+}
+
+/// Lets the `?` operator propagate the error of a `Result<_, D>` as the value to return.
+impl<D> From<D> for EarlyExit<D> {
+    fn from(returned: D) -> Self {
+        EarlyExit::Return(returned)
+    }
+}
+
+/// The carried value, or `None` for [`EarlyExit::Default`].
+impl<D> From<EarlyExit<D>> for Option<D> {
+    fn from(exit: EarlyExit<D>) -> Self {
+        match exit {
+            EarlyExit::Default => None,
+            EarlyExit::Return(returned) => Some(returned),
+        }
+    }
+}
+
+/// Finishes a composition of helpers returning `Result<T, EarlyExit<T>>`.
+pub trait ResolveEarlyExit<T> {
+    /// The Ok value, or the value the [`EarlyExit`] resolves to with the given default.
+    fn resolve(self, default: T) -> T;
+
+    /// Like [`resolve`](ResolveEarlyExit::resolve), but only computes the default when it is
+    /// needed.
+    fn resolve_with<F>(self, default: F) -> T
+    where
+        F: FnOnce() -> T;
+}
+
+impl<T> ResolveEarlyExit<T> for Result<T, EarlyExit<T>> {
+    fn resolve(self, default: T) -> T {
+        self.unwrap_or_else(|exit| exit.resolve(default))
+    }
+
+    fn resolve_with<F>(self, default: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        self.unwrap_or_else(|exit| exit.resolve_with(default))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EarlyExit, ResolveEarlyExit};
+    use crate::control_flow::{OptionEarlyExt, ResultEarlyExt};
+
+    fn first_even(values: &[i32]) -> Result<i32, EarlyExit<i32>> {
+        let first = values.first().or_exit()?;
+        let even = values.iter().find(|v| *v % 2 == 0).ok_or(-*first)?;
+        Ok(*even)
+    }
+
+    fn doubled(values: &[i32]) -> Result<i32, EarlyExit<i32>> {
+        Ok(first_even(values)? * 2)
+    }
+
+    #[test]
+    fn should_propagate_through_helpers() {
+        assert_eq!(doubled(&[1, 2]).resolve(0), 4);
+        assert_eq!(doubled(&[3, 5]).resolve(0), -3);
+        assert_eq!(doubled(&[]).resolve(0), 0);
+        assert_eq!(doubled(&[]).resolve_with(|| 7), 7);
+        assert_eq!(Err::<i32, _>(EarlyExit::Return(1)).resolve_with(|| unreachable!()), 1);
+    }
+
+    #[test]
+    fn should_convert() {
+        assert_eq!(EarlyExit::from("stop"), EarlyExit::Return("stop"));
+        assert_eq!(Option::from(EarlyExit::Return(1)), Some(1));
+        assert_eq!(Option::<i32>::from(EarlyExit::Default), None);
+        assert_eq!("x".parse::<i32>().or_exit::<()>(), Err(EarlyExit::Default));
+        assert_eq!("1".parse::<i32>().or_exit::<()>(), Ok(1));
+    }
+}
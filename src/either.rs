@@ -0,0 +1,410 @@
+/// Either get the `Left` value from an `Either` or return from the current function if it is
+/// `Right`. A default return value can be provided, or the `Right` value can be bound and used to
+/// compute the return value.
+/// ```
+/// use early_returns::left_or_return;
+/// use either::Either;
+///
+/// fn left_len(value: Either<String, i32>) -> usize {
+///     let s = left_or_return!(value, 0);
+///     s.len()
+/// }
+///
+/// fn describe(value: Either<String, i32>) -> String {
+///     let s = left_or_return!(value, n => format!("number {n}"));
+///     format!("string {s}")
+/// }
+///
+/// assert_eq!(left_len(Either::Left("abc".to_string())), 3);
+/// assert_eq!(left_len(Either::Right(7)), 0);
+/// assert_eq!(describe(Either::Right(7)), "number 7");
+/// ```
+#[macro_export]
+macro_rules! left_or_return {
+    ($from:expr, $r:ident => $result:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            $crate::__private::either::Either::Right($r) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            _ => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            _ => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the `Left` value from an `Either` or break out of a loop if it is `Right`. If a loop
+/// lifetime is specified, that loop will be exited, otherwise the immediate loop is exited. The
+/// `Right` value can be bound and handled before breaking.
+/// ```
+/// use early_returns::left_or_break;
+/// use either::Either;
+///
+/// let values = vec![Either::Left(1), Either::Left(2), Either::Right("stop"), Either::Left(3)];
+/// let mut taken = Vec::new();
+/// let mut stopped_by = None;
+/// for value in values {
+///     taken.push(left_or_break!(value, reason => stopped_by = Some(reason)));
+/// }
+/// assert_eq!(taken, vec![1, 2]);
+/// assert_eq!(stopped_by, Some("stop"));
+/// ```
+#[macro_export]
+macro_rules! left_or_break {
+    ($from:expr, $r:ident => $handler:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            $crate::__private::either::Either::Right($r) => {
+                $handler;
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $r:ident => $handler:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            $crate::__private::either::Either::Right($r) => {
+                $handler;
+                break $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            _ => {
+                $crate::__private::silent_break();
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            _ => {
+                $crate::__private::silent_break();
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the `Left` value from an `Either` or continue in a loop if it is `Right`. If a loop
+/// lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued". The `Right` value can be bound and handled before continuing.
+/// ```
+/// use early_returns::left_or_continue;
+/// use either::Either;
+///
+/// let values = vec![Either::Left(1), Either::Right("skip"), Either::Left(3)];
+/// let mut taken = Vec::new();
+/// for value in values {
+///     taken.push(left_or_continue!(value));
+/// }
+/// assert_eq!(taken, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! left_or_continue {
+    ($from:expr, $r:ident => $handler:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            $crate::__private::either::Either::Right($r) => {
+                $handler;
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $r:ident => $handler:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            $crate::__private::either::Either::Right($r) => {
+                $handler;
+                continue $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            _ => {
+                $crate::__private::silent_continue();
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            $crate::__private::either::Either::Left(f) => f,
+            _ => {
+                $crate::__private::silent_continue();
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the `Right` value from an `Either` or return from the current function if it is
+/// `Left`. A default return value can be provided, or the `Left` value can be bound and used to
+/// compute the return value.
+/// ```
+/// use early_returns::right_or_return;
+/// use either::Either;
+///
+/// fn right_len(value: Either<i32, String>) -> usize {
+///     let s = right_or_return!(value, 0);
+///     s.len()
+/// }
+///
+/// fn describe(value: Either<i32, String>) -> String {
+///     let s = right_or_return!(value, n => format!("number {n}"));
+///     format!("string {s}")
+/// }
+///
+/// assert_eq!(right_len(Either::Right("abc".to_string())), 3);
+/// assert_eq!(right_len(Either::Left(7)), 0);
+/// assert_eq!(describe(Either::Left(7)), "number 7");
+/// ```
+#[macro_export]
+macro_rules! right_or_return {
+    ($from:expr, $l:ident => $result:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            $crate::__private::either::Either::Left($l) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            _ => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            _ => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the `Right` value from an `Either` or break out of a loop if it is `Left`. If a loop
+/// lifetime is specified, that loop will be exited, otherwise the immediate loop is exited. The
+/// `Left` value can be bound and handled before breaking.
+/// ```
+/// use early_returns::right_or_break;
+/// use either::Either;
+///
+/// let values = vec![Either::Right(1), Either::Right(2), Either::Left("stop"), Either::Right(3)];
+/// let mut taken = Vec::new();
+/// let mut stopped_by = None;
+/// for value in values {
+///     taken.push(right_or_break!(value, reason => stopped_by = Some(reason)));
+/// }
+/// assert_eq!(taken, vec![1, 2]);
+/// assert_eq!(stopped_by, Some("stop"));
+/// ```
+#[macro_export]
+macro_rules! right_or_break {
+    ($from:expr, $l:ident => $handler:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            $crate::__private::either::Either::Left($l) => {
+                $handler;
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $l:ident => $handler:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            $crate::__private::either::Either::Left($l) => {
+                $handler;
+                break $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            _ => {
+                $crate::__private::silent_break();
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            _ => {
+                $crate::__private::silent_break();
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the `Right` value from an `Either` or continue in a loop if it is `Left`. If a loop
+/// lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued". The `Left` value can be bound and handled before continuing.
+/// ```
+/// use early_returns::right_or_continue;
+/// use either::Either;
+///
+/// let values = vec![Either::Right(1), Either::Left("skip"), Either::Right(3)];
+/// let mut taken = Vec::new();
+/// for value in values {
+///     taken.push(right_or_continue!(value));
+/// }
+/// assert_eq!(taken, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! right_or_continue {
+    ($from:expr, $l:ident => $handler:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            $crate::__private::either::Either::Left($l) => {
+                $handler;
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $l:ident => $handler:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            $crate::__private::either::Either::Left($l) => {
+                $handler;
+                continue $lt;
+            }
+        }
+    }};
+
+    ($from:expr) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            _ => {
+                $crate::__private::silent_continue();
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            $crate::__private::either::Either::Right(f) => f,
+            _ => {
+                $crate::__private::silent_continue();
+                continue $lt;
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use either::Either;
+
+    fn left_or_default(value: Either<i32, &str>) -> i32 {
+        left_or_return!(value, -1)
+    }
+
+    fn right_or_describe(value: Either<i32, &str>) -> String {
+        let s = right_or_return!(value, n => format!("left {n}"));
+        s.to_string()
+    }
+
+    fn push_left(value: &Either<i32, &str>, values: &mut Vec<i32>) {
+        values.push(*left_or_return!(value));
+    }
+
+    #[test]
+    fn should_return_early_when_other_side() {
+        assert_eq!(left_or_default(Either::Left(1)), 1);
+        assert_eq!(left_or_default(Either::Right("x")), -1);
+        assert_eq!(right_or_describe(Either::Right("x")), "x");
+        assert_eq!(right_or_describe(Either::Left(2)), "left 2");
+        let mut values = Vec::new();
+        push_left(&Either::Left(1), &mut values);
+        push_left(&Either::Right("x"), &mut values);
+        assert_eq!(values, vec![1]);
+    }
+
+    #[test]
+    fn should_break_when_other_side() {
+        let values = vec![Either::Left(1), Either::Right("a"), Either::Left(2)];
+        let mut sum = 0;
+        for value in &values {
+            sum += left_or_break!(value);
+        }
+        assert_eq!(sum, 1);
+        let mut seen = Vec::new();
+        'l: for value in &values {
+            for _i in 0..2 {
+                seen.push(*right_or_break!(value, 'l, l => sum += l));
+            }
+        }
+        assert_eq!(sum, 2);
+        assert!(seen.is_empty());
+    }
+
+    #[test]
+    fn should_continue_when_other_side() {
+        let values = vec![Either::Left(1), Either::Right("a"), Either::Left(2)];
+        let mut sum = 0;
+        let mut skipped = Vec::new();
+        for value in &values {
+            sum += left_or_continue!(value, r => skipped.push(*r));
+        }
+        assert_eq!(sum, 3);
+        assert_eq!(skipped, vec!["a"]);
+        let mut seen = Vec::new();
+        'l: for value in &values {
+            for _i in 0..2 {
+                seen.push(*right_or_continue!(value, 'l));
+            }
+        }
+        assert_eq!(seen, vec!["a", "a"]);
+    }
+}
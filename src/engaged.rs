@@ -0,0 +1,75 @@
+/// Implemented for the types the guard macros know how to "extract" a value from, so a single
+/// macro can accept both Options and Results.
+///
+/// The residual is what is left over when there is no value: `()` for Options and the error for
+/// Results.
+pub trait Engaged {
+    type Value;
+    type Residual;
+
+    fn into_engaged(self) -> Result<Self::Value, Self::Residual>;
+}
+
+impl<T> Engaged for Option<T> {
+    type Value = T;
+    type Residual = ();
+
+    #[inline]
+    fn into_engaged(self) -> Result<T, ()> {
+        self.ok_or(())
+    }
+}
+
+impl<'a, T> Engaged for &'a Option<T> {
+    type Value = &'a T;
+    type Residual = ();
+
+    #[inline]
+    fn into_engaged(self) -> Result<&'a T, ()> {
+        self.as_ref().ok_or(())
+    }
+}
+
+impl<'a, T> Engaged for &'a mut Option<T> {
+    type Value = &'a mut T;
+    type Residual = ();
+
+    #[inline]
+    fn into_engaged(self) -> Result<&'a mut T, ()> {
+        self.as_mut().ok_or(())
+    }
+}
+
+impl<T, E> Engaged for Result<T, E> {
+    type Value = T;
+    type Residual = E;
+
+    #[inline]
+    fn into_engaged(self) -> Result<T, E> {
+        self
+    }
+}
+
+impl<'a, T, E> Engaged for &'a Result<T, E> {
+    type Value = &'a T;
+    type Residual = &'a E;
+
+    #[inline]
+    fn into_engaged(self) -> Result<&'a T, &'a E> {
+        self.as_ref()
+    }
+}
+
+impl<'a, T, E> Engaged for &'a mut Result<T, E> {
+    type Value = &'a mut T;
+    type Residual = &'a mut E;
+
+    #[inline]
+    fn into_engaged(self) -> Result<&'a mut T, &'a mut E> {
+        self.as_mut()
+    }
+}
+
+/// The bindings of a `guard!` pattern, carried out of its `match`.
+#[doc(hidden)]
+pub struct GuardBindings<T>(pub T);
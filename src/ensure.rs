@@ -0,0 +1,101 @@
+/// Break out of a loop unless a condition holds. If a loop lifetime is specified, that loop will be
+/// exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::ensure_or_break;
+///
+/// let costs = [3, 4, 2, 5];
+/// let mut budget = 8;
+/// let mut done = Vec::new();
+/// 'outer: for (task, cost) in costs.iter().enumerate() {
+///     for _step in 0..*cost {
+///         ensure_or_break!(budget > 0, 'outer);
+///         budget -= 1;
+///     }
+///     done.push(task);
+/// }
+/// assert_eq!(done, [0, 1]);
+/// ```
+#[macro_export]
+macro_rules! ensure_or_break {
+    ($condition:expr) => {
+        if !$condition {
+            $crate::__private::silent_break();
+            break;
+        }
+    };
+
+    ($condition:expr, $lt:lifetime) => {
+        if !$condition {
+            $crate::__private::silent_break();
+            break $lt;
+        }
+    };
+}
+
+/// Continue in a loop unless a condition holds. If a loop lifetime is specified, that loop will be
+/// "continued", otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::ensure_or_continue;
+///
+/// let mut names = Vec::new();
+/// for name in ["ada", "", "grace", "x"] {
+///     ensure_or_continue!(name.len() > 1);
+///     names.push(name);
+/// }
+/// assert_eq!(names, ["ada", "grace"]);
+/// ```
+#[macro_export]
+macro_rules! ensure_or_continue {
+    ($condition:expr) => {
+        if !$condition {
+            $crate::__private::silent_continue();
+            continue;
+        }
+    };
+
+    ($condition:expr, $lt:lifetime) => {
+        if !$condition {
+            $crate::__private::silent_continue();
+            continue $lt;
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn should_break_unless_the_condition_holds() {
+        let mut seen = 0;
+        for value in [1, 2, -3, 4] {
+            ensure_or_break!(value > 0);
+            seen += value;
+        }
+        assert_eq!(seen, 3);
+        let mut seen = 0;
+        'l: for value in [1, 2, -3, 4] {
+            for _i in 0..2 {
+                ensure_or_break!(value > 0, 'l);
+                seen += value;
+            }
+        }
+        assert_eq!(seen, 6);
+    }
+
+    #[test]
+    fn should_continue_unless_the_condition_holds() {
+        let mut seen = 0;
+        for value in [1, 2, -3, 4] {
+            ensure_or_continue!(value > 0);
+            seen += value;
+        }
+        assert_eq!(seen, 7);
+        let mut seen = 0;
+        'l: for value in [1, 2, -3, 4] {
+            for _i in 0..2 {
+                ensure_or_continue!(value > 0, 'l);
+                seen += value;
+            }
+        }
+        assert_eq!(seen, 14);
+    }
+}
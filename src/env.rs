@@ -0,0 +1,172 @@
+/// Either get the value of an environment variable with `std::env::var` or return from the
+/// current function if it is not set (or not valid unicode). A default return value can be
+/// provided, or the `VarError` can be bound and used to produce the return value.
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::env_or_return;
+///
+/// fn home() -> Option<String> {
+///     let home = env_or_return!("EARLY_RETURNS_DOC_HOME", None);
+///     Some(home)
+/// }
+///
+/// fn user() -> Result<String, String> {
+///     let user = env_or_return!("EARLY_RETURNS_DOC_USER", e => Err(e.to_string()));
+///     Ok(user)
+/// }
+///
+/// std::env::set_var("EARLY_RETURNS_DOC_HOME", "/home/ada");
+/// assert_eq!(home(), Some("/home/ada".to_string()));
+/// assert!(user().is_err());
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! env_or_return {
+    ($name:expr, $e:ident => $result:expr) => {{
+        match $crate::__private::env::var($name) {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($name:expr) => {
+        $crate::ok_or_return!($crate::__private::env::var($name))
+    };
+
+    ($name:expr, $default_result:expr) => {
+        $crate::ok_or_return!($crate::__private::env::var($name), $default_result)
+    };
+}
+
+/// Either get and parse the value of an environment variable or return from the current function
+/// if it is not set (or not valid unicode) or cannot be parsed. The target type is inferred, e.g.
+/// from the binding, or can be given as a turbofish after the name (e.g.
+/// `env_parse_or_return!("PORT", ::<u16>)`). A default return value can be provided.
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::env_parse_or_return;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct BadConfig;
+///
+/// fn workers() -> Result<usize, BadConfig> {
+///     let workers = env_parse_or_return!("EARLY_RETURNS_DOC_WORKERS", ::<usize>, Err(BadConfig));
+///     Ok(workers)
+/// }
+///
+/// assert_eq!(workers(), Err(BadConfig));
+/// std::env::set_var("EARLY_RETURNS_DOC_WORKERS", "many");
+/// assert_eq!(workers(), Err(BadConfig));
+/// std::env::set_var("EARLY_RETURNS_DOC_WORKERS", "4");
+/// assert_eq!(workers(), Ok(4));
+/// ```
+#[cfg(feature = "std")]
+#[cfg(feature = "conversions")]
+#[macro_export]
+macro_rules! env_parse_or_return {
+    ($name:expr, ::<$to:ty>) => {{
+        let value = $crate::env_or_return!($name);
+        $crate::parse_or_return!(value, ::<$to>)
+    }};
+
+    ($name:expr, ::<$to:ty>, $default_result:expr) => {{
+        let value = $crate::env_or_return!($name, $default_result);
+        $crate::parse_or_return!(value, ::<$to>, $default_result)
+    }};
+
+    ($name:expr) => {{
+        let value = $crate::env_or_return!($name);
+        $crate::parse_or_return!(value)
+    }};
+
+    ($name:expr, $default_result:expr) => {{
+        let value = $crate::env_or_return!($name, $default_result);
+        $crate::parse_or_return!(value, $default_result)
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    struct Tester {
+        values: Vec<String>,
+        #[cfg(feature = "conversions")]
+        value: u32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester {
+                values: Vec::new(),
+                #[cfg(feature = "conversions")]
+                value: 0,
+            }
+        }
+
+        fn push_var(&mut self, name: &str) {
+            self.values.push(env_or_return!(name));
+        }
+
+        #[cfg(feature = "conversions")]
+        fn add_parsed(&mut self, name: &str) {
+            let value: u32 = env_parse_or_return!(name);
+            self.value += value;
+        }
+    }
+
+    fn var_or_default(name: &str) -> String {
+        env_or_return!(name, "default".to_string())
+    }
+
+    fn var_or_error(name: &str) -> Result<String, env::VarError> {
+        Ok(env_or_return!(name, e => Err(e)))
+    }
+
+    #[cfg(feature = "conversions")]
+    fn parsed_or_default(name: &str) -> i64 {
+        env_parse_or_return!(name, -1)
+    }
+
+    #[cfg(feature = "conversions")]
+    fn typed_parsed_or_default(name: &str) -> i64 {
+        env_parse_or_return!(name, ::<u8>, -1).into()
+    }
+
+    #[test]
+    fn should_return_early_when_var_is_missing() {
+        env::set_var("EARLY_RETURNS_TEST_PRESENT", "12");
+        let mut tester = Tester::new();
+        tester.push_var("EARLY_RETURNS_TEST_PRESENT");
+        tester.push_var("EARLY_RETURNS_TEST_MISSING");
+        assert_eq!(tester.values, vec!["12"]);
+        assert_eq!(var_or_default("EARLY_RETURNS_TEST_PRESENT"), "12");
+        assert_eq!(var_or_default("EARLY_RETURNS_TEST_MISSING"), "default");
+        assert_eq!(var_or_error("EARLY_RETURNS_TEST_PRESENT"), Ok("12".to_string()));
+        assert_eq!(
+            var_or_error("EARLY_RETURNS_TEST_MISSING"),
+            Err(env::VarError::NotPresent)
+        );
+    }
+
+    #[cfg(feature = "conversions")]
+    #[test]
+    fn should_return_early_when_var_is_unparsable() {
+        env::set_var("EARLY_RETURNS_TEST_NUMBER", "300");
+        env::set_var("EARLY_RETURNS_TEST_WORD", "three");
+        let mut tester = Tester::new();
+        tester.add_parsed("EARLY_RETURNS_TEST_NUMBER");
+        tester.add_parsed("EARLY_RETURNS_TEST_WORD");
+        tester.add_parsed("EARLY_RETURNS_TEST_NOTHING");
+        assert_eq!(tester.value, 300);
+        assert_eq!(parsed_or_default("EARLY_RETURNS_TEST_NUMBER"), 300);
+        assert_eq!(parsed_or_default("EARLY_RETURNS_TEST_WORD"), -1);
+        assert_eq!(parsed_or_default("EARLY_RETURNS_TEST_NOTHING"), -1);
+        assert_eq!(typed_parsed_or_default("EARLY_RETURNS_TEST_NUMBER"), -1);
+    }
+}
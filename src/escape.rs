@@ -0,0 +1,239 @@
+/// Return from the current function unconditionally, optionally with a value.
+///
+/// Like the escape of `early!`, the return can be instrumented with `log`, `trace`, `count` and
+/// `hook` (requiring the `log`, `tracing` and `metrics` features, and `std` for the hook),
+/// separated by commas and written after the return value, if there is one
+/// (`return_early!(None, log warn "no port", count "config.port")`). A `log` record or `tracing`
+/// event carries the location of the invocation, as does a `metrics` counter as a label. Without
+/// instrumentation, the return is silent like that of `some_or_return!`.
+/// ```
+/// use early_returns::return_early;
+///
+/// fn port(scheme: &str) -> u16 {
+///     if scheme == "https" {
+///         return_early!(443);
+///     }
+///     80
+/// }
+///
+/// assert_eq!(port("https"), 443);
+/// assert_eq!(port("http"), 80);
+/// ```
+///
+/// `#[instrument_early_returns]` (with the `proc-macro` feature) reports it like the conditional
+/// `*_or_return!` macros.
+#[macro_export]
+macro_rules! return_early {
+    () => {{
+        $crate::__private::silent_return();
+        $crate::__mark_early_exit!();
+        return;
+    }};
+
+    (log $level:ident $message:literal $($instruments:tt)*) => {{
+        $crate::__instrument_exit!("return" Return; log $level $message $($instruments)*);
+        $crate::__mark_early_exit!();
+        return;
+    }};
+
+    (trace $level:ident $message:literal $($instruments:tt)*) => {{
+        $crate::__instrument_exit!("return" Return; trace $level $message $($instruments)*);
+        $crate::__mark_early_exit!();
+        return;
+    }};
+
+    (count $metric:literal $($instruments:tt)*) => {{
+        $crate::__instrument_exit!("return" Return; count $metric $($instruments)*);
+        $crate::__mark_early_exit!();
+        return;
+    }};
+
+    (hook $reason:literal $($instruments:tt)*) => {{
+        $crate::__instrument_exit!("return" Return; hook $reason $($instruments)*);
+        $crate::__mark_early_exit!();
+        return;
+    }};
+
+    ($result:expr) => {{
+        $crate::__private::silent_return();
+        $crate::__mark_early_exit!();
+        return $result;
+    }};
+
+    ($result:expr, $($instruments:tt)+) => {{
+        $crate::__instrument_exit!("return" Return; $($instruments)+);
+        $crate::__mark_early_exit!();
+        return $result;
+    }};
+}
+
+/// Break out of a loop unconditionally. If a loop lifetime is specified, that loop will be exited,
+/// otherwise the immediate loop is exited.
+///
+/// The break can be instrumented like the return of `return_early!`, after the loop lifetime if
+/// there is one (`break_early!('rows, hook "blank line")`).
+/// ```
+/// use early_returns::break_early;
+///
+/// let mut read = Vec::new();
+/// for line in ["a", "b", "", "c"] {
+///     if line.is_empty() {
+///         break_early!();
+///     }
+///     read.push(line);
+/// }
+/// assert_eq!(read, ["a", "b"]);
+/// ```
+#[macro_export]
+macro_rules! break_early {
+    () => {{
+        $crate::__private::silent_break();
+        break;
+    }};
+
+    ($lt:lifetime) => {{
+        $crate::__private::silent_break();
+        break $lt;
+    }};
+
+    ($lt:lifetime, $($instruments:tt)+) => {{
+        $crate::__instrument_exit!("break" Break; $($instruments)+);
+        break $lt;
+    }};
+
+    ($($instruments:tt)+) => {{
+        $crate::__instrument_exit!("break" Break; $($instruments)+);
+        break;
+    }};
+}
+
+/// Continue in a loop unconditionally. If a loop lifetime is specified, that loop will be
+/// "continued", otherwise the immediate loop is "continued".
+///
+/// The continue can be instrumented like the return of `return_early!`, after the loop lifetime
+/// if there is one (`continue_early!(log debug "skipping comment")`).
+/// ```
+/// use early_returns::continue_early;
+///
+/// let mut kept = Vec::new();
+/// for line in ["a", "# comment", "b"] {
+///     if line.starts_with('#') {
+///         continue_early!();
+///     }
+///     kept.push(line);
+/// }
+/// assert_eq!(kept, ["a", "b"]);
+/// ```
+#[macro_export]
+macro_rules! continue_early {
+    () => {{
+        $crate::__private::silent_continue();
+        continue;
+    }};
+
+    ($lt:lifetime) => {{
+        $crate::__private::silent_continue();
+        continue $lt;
+    }};
+
+    ($lt:lifetime, $($instruments:tt)+) => {{
+        $crate::__instrument_exit!("continue" Continue; $($instruments)+);
+        continue $lt;
+    }};
+
+    ($($instruments:tt)+) => {{
+        $crate::__instrument_exit!("continue" Continue; $($instruments)+);
+        continue;
+    }};
+}
+
+// The instrumentation of the unconditional escapes, in the vocabulary of `early!`, which expands
+// to the same calls.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __instrument_exit {
+    ($kind:literal $variant:ident;) => {};
+
+    ($kind:literal $variant:ident; log $level:ident $message:literal $(, $($rest:tt)*)?) => {
+        $crate::__private::log::$level!(
+            "{} [early {} at {}]",
+            ::core::format_args!($message),
+            $kind,
+            $crate::__private::caller_location(),
+        );
+        $crate::__instrument_exit!($kind $variant; $($($rest)*)?);
+    };
+
+    ($kind:literal $variant:ident; trace $level:ident $message:literal $(, $($rest:tt)*)?) => {
+        $crate::__private::tracing::$level!(
+            early_exit = $kind,
+            location = %$crate::__private::caller_location(),
+            $message
+        );
+        $crate::__instrument_exit!($kind $variant; $($($rest)*)?);
+    };
+
+    ($kind:literal $variant:ident; count $metric:expr $(, $($rest:tt)*)?) => {
+        $crate::__private::metrics::counter!(
+            $metric,
+            "early_exit" => $kind,
+            "location" => $crate::__private::caller_location().to_string(),
+        )
+        .increment(1);
+        $crate::__instrument_exit!($kind $variant; $($($rest)*)?);
+    };
+
+    ($kind:literal $variant:ident; hook $reason:expr $(, $($rest:tt)*)?) => {
+        $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::$variant, $reason);
+        $crate::__instrument_exit!($kind $variant; $($($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+mod test {
+    fn sign(value: i32) -> i32 {
+        if value < 0 {
+            return_early!(-1);
+        }
+        1
+    }
+
+    fn push_positive(value: i32, values: &mut Vec<i32>) {
+        if value <= 0 {
+            return_early!();
+        }
+        values.push(value);
+    }
+
+    #[test]
+    fn should_return() {
+        assert_eq!((sign(-5), sign(5)), (-1, 1));
+        let mut values = Vec::new();
+        push_positive(0, &mut values);
+        push_positive(2, &mut values);
+        assert_eq!(values, [2]);
+    }
+
+    #[test]
+    fn should_break_and_continue() {
+        let mut seen = Vec::new();
+        'l: for i in 0..3 {
+            for j in 0..3 {
+                if j == 1 {
+                    continue_early!();
+                }
+                if i == 1 {
+                    continue_early!('l);
+                }
+                if i == 2 && j == 2 {
+                    break_early!('l);
+                }
+                seen.push((i, j));
+                if j == 2 {
+                    break_early!();
+                }
+            }
+        }
+        assert_eq!(seen, [(0, 0), (0, 2), (2, 0)]);
+    }
+}
@@ -0,0 +1,1195 @@
+//! Tests for the single-evaluation guarantee documented in the README: every exported macro
+//! evaluates its scrutinee exactly once, on the value path as well as on the escape path.
+//!
+//! Each case hands the macro a closure call (`from()`) that counts its invocations and can only
+//! produce its value once. `every_exported_macro_is_covered` fails for a new macro until a case
+//! for it is added here (or it is listed in `REEVALUATING` or `WITHOUT_SCRUTINEE`).
+
+// Each loop only needs to run until the macro under test has either escaped or not.
+#![allow(clippy::never_loop)]
+
+// The cases of every family, and the `async-stream` one, which counts without `once!`.
+#[cfg(any(
+    feature = "option",
+    feature = "result",
+    feature = "bool",
+    feature = "poll",
+    feature = "instrumented",
+    feature = "either",
+    feature = "heapless",
+    feature = "nb",
+    feature = "windows",
+    feature = "async-stream"
+))]
+use crate::*;
+#[cfg(any(
+    feature = "option",
+    feature = "result",
+    feature = "bool",
+    feature = "poll",
+    feature = "instrumented",
+    feature = "either",
+    feature = "heapless",
+    feature = "nb",
+    feature = "windows",
+    feature = "async-stream"
+))]
+use std::cell::Cell;
+
+/// Macros that re-evaluate an expression by design, once per attempt or iteration.
+const REEVALUATING: &[&str] = &[
+    "loop_until_some",
+    "retry",
+    "retry_async",
+    "retry_backoff",
+    "retry_on_interrupted",
+    "while_ok",
+];
+
+/// Macros that take statements to run later, or nothing at all, rather than a value to extract.
+const WITHOUT_SCRUTINEE: &[&str] =
+    &["break_early", "continue_early", "defer", "defer_on_early_exit"];
+
+#[cfg(any(
+    feature = "option",
+    feature = "result",
+    feature = "bool",
+    feature = "poll",
+    feature = "instrumented",
+    feature = "either",
+    feature = "heapless",
+    feature = "nb",
+    feature = "windows"
+))]
+fn run<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+/// Runs `$body` in a closure (or an async block) once for every one of `$values`, with `$from()`
+/// producing that value, and asserts that the body evaluated `$from()` exactly once.
+#[cfg(any(
+    feature = "option",
+    feature = "result",
+    feature = "bool",
+    feature = "poll",
+    feature = "instrumented",
+    feature = "either",
+    feature = "heapless",
+    feature = "nb",
+    feature = "windows"
+))]
+macro_rules! once {
+    (async $values:expr, |$from:ident| $body:expr) => {
+        once!(@each $values, $from, async { $body }.await)
+    };
+
+    ($values:expr, |$from:ident| $body:expr) => {
+        once!(@each $values, $from, run(|| $body))
+    };
+
+    (@each $values:expr, $from:ident, $run:expr) => {
+        for value in $values {
+            let count = Cell::new(0);
+            let mut value = Some(value);
+            let mut $from = || {
+                count.set(count.get() + 1);
+                value.take().expect("scrutinee evaluated more than once")
+            };
+            let _ = $run;
+            assert_eq!(count.get(), 1, "`{}` skipped its scrutinee", stringify!($run));
+        }
+    };
+}
+
+#[test]
+fn every_exported_macro_is_covered() {
+    let this = include_str!("evaluation.rs");
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let source = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        for exported in source.split("#[macro_export]\nmacro_rules! ").skip(1) {
+            let name = exported.split_whitespace().next().unwrap();
+            let hidden = name.starts_with("__");
+            let exempt = REEVALUATING.contains(&name) || WITHOUT_SCRUTINEE.contains(&name);
+            assert!(
+                hidden || exempt || this.contains(&format!("{name}!(")),
+                "`{name}!` has no single-evaluation test"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "option")]
+#[test]
+fn core_some() {
+    once!([Some(1), None], |from| some_or_return!(from(), 0));
+    once!([Some(1), None], |from| loop {
+        some_or_break!(from());
+        break;
+    });
+    once!([Some(1), None], |from| 'l: loop {
+        loop {
+            some_or_break!(from(), 'l);
+            break 'l;
+        }
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue!(from());
+    });
+}
+
+#[cfg(feature = "result")]
+#[test]
+fn core_ok() {
+    once!([Ok(1), Err(())], |from| ok_or_return!(from(), 0));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break!(from());
+        break;
+    });
+    once!([Ok(1), Err(())], |from| 'l: for _ in 0..1 {
+        for _ in 0..1 {
+            ok_or_continue!(from(), 'l);
+        }
+    });
+}
+
+#[cfg(feature = "conversions")]
+#[test]
+fn any() {
+    use std::any::Any;
+
+    let (one, text): (Box<dyn Any>, Box<dyn Any>) = (Box::new(1), Box::new(""));
+    for boxed in [one, text] {
+        once!([&*boxed], |from| *downcast_or_return!(from(), i32, 0));
+        once!([boxed], |from| *downcast_or_return!(owned from(), i32, 0));
+    }
+    let mut values: [Box<dyn Any>; 2] = [Box::new(1), Box::new("")];
+    for value in &mut values {
+        once!([&mut **value], |from| for _ in 0..1 {
+            *downcast_or_continue!(mut from(), i32) += 1;
+        });
+    }
+}
+
+#[cfg(feature = "conversions")]
+#[test]
+fn arith() {
+    once!([1u8.checked_add(1), 255u8.checked_add(1)], |from| checked_or_return!(from(), 0));
+    once!([Some(1), None], |from| loop {
+        checked_or_break!(from());
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        checked_or_continue!(from());
+    });
+    once!([1u32, 0], |from| div_or_return!(6u32, from(), 0));
+    once!([6u32, 0], |from| div_or_return!(from(), 0, 0));
+    once!([1usize, 0], |from| nonzero_or_return!(core::num::NonZeroUsize, from(), 0).get());
+}
+
+#[cfg(feature = "result")]
+#[test]
+fn boxed() {
+    type BoxedResult = Result<i32, Box<dyn std::error::Error>>;
+
+    let values = || [Ok(1), Err("missing")];
+    once!(values(), |from| BoxedResult::Ok(ok_or_return_boxed!(from())));
+    once!(values(), |from| BoxedResult::Ok(ok_or_return_boxed!(from(), e => e.to_string())));
+}
+
+#[cfg(feature = "collections")]
+#[test]
+fn cache() {
+    once!([Some(1), None], |from| {
+        cached_or_compute_or_return!(from(), || Ok::<_, ()>(2), |_: &i32| {}, 0)
+    });
+}
+
+#[cfg(feature = "conversions")]
+#[test]
+fn chars() {
+    once!([0x61, 0xD800], |from| char_or_return!(from(), '?'));
+    once!([0x61, 0xD800], |from| loop {
+        char_or_break!(from());
+        break;
+    });
+    once!([1, 16], |from| for _ in 0..1 {
+        char_or_continue!(digit from(), 10);
+    });
+    once!(['7', 'x'], |from| digit_or_return!(from(), 10, 0));
+    once!(['7', 'x'], |from| loop {
+        digit_or_break!(from(), 10);
+        break;
+    });
+    once!(['7', 'x'], |from| for _ in 0..1 {
+        digit_or_continue!(from(), 10);
+    });
+}
+
+#[cfg(feature = "option")]
+#[test]
+fn cleanup_some() {
+    once!([Some(1), None], |from| some_or_return_cleanup!(from(), 0, {}));
+    once!([Some(1), None], |from| loop {
+        some_or_break_cleanup!(from(), {});
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue_cleanup!(from(), {});
+    });
+}
+
+#[cfg(feature = "result")]
+#[test]
+fn cleanup_ok() {
+    once!([Ok(1), Err(())], |from| ok_or_return_cleanup!(from(), 0, {}));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break_cleanup!(from(), {});
+        break;
+    });
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_cleanup!(from(), {});
+    });
+}
+
+#[cfg(feature = "option")]
+#[test]
+fn inspect_some() {
+    once!([Some(1), None], |from| some_or_return_inspect!(from(), 0, || ()));
+}
+
+#[cfg(feature = "result")]
+#[test]
+fn inspect_ok() {
+    once!([Ok(1), Err(())], |from| ok_or_return_inspect_err!(from(), 0, |_e| ()));
+}
+
+#[cfg(feature = "result")]
+#[test]
+fn mapped() {
+    once!([Ok(1), Err(())], |from| Result::<i32, i32>::Ok(ok_or_return_mapped!(from(), |_e| 0)));
+    once!([Ok(1), Err(())], |from| loop {
+        break Result::<i32, i32>::Ok(ok_or_break_mapped!(from(), |_e| 0));
+    });
+}
+
+#[cfg(feature = "result")]
+#[test]
+fn store() {
+    let mut last_err = None;
+    once!([Ok(1), Err(())], |from| ok_or_return_store!(from(), &mut last_err, 0));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break_store!(from(), &mut last_err);
+        break;
+    });
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_store!(from(), &mut last_err);
+    });
+}
+
+#[cfg(all(feature = "alloc", feature = "result"))]
+#[test]
+fn fmt() {
+    once!([Ok(1), Err(2)], |from| Result::<i32, String>::Ok(ok_or_return_fmt!(from(), "{e}")));
+}
+
+#[cfg(feature = "collections")]
+#[test]
+fn collect() {
+    let (mut errors, mut tags) = (Vec::new(), Vec::new());
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_collect!(from(), &mut errors);
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue_collect!(from(), &mut tags, 0);
+    });
+    assert_eq!((errors.len(), tags.len()), (1, 1));
+}
+
+#[cfg(feature = "collections")]
+#[test]
+fn collection() {
+    let (full, empty) = ([1, 2, 3], [0; 0]);
+    let slices = [&full[..], &empty[..]];
+    once!(slices, |from| *get_or_return!(from(), 1, 0));
+    once!(slices, |from| loop {
+        get_or_break!(from(), 1);
+        break;
+    });
+    once!(slices, |from| for _ in 0..1 {
+        get_or_continue!(from(), 1);
+    });
+    once!([1, 5], |from| *get_or_return!(full, from(), 0));
+    once!(slices, |from| *first_or_return!(from(), 0));
+    once!(slices, |from| loop {
+        first_or_break!(from());
+        break;
+    });
+    once!(slices, |from| for _ in 0..1 {
+        first_or_continue!(from());
+    });
+    once!(slices, |from| *last_or_return!(from(), 0));
+    once!(slices, |from| loop {
+        last_or_break!(from());
+        break;
+    });
+    once!(slices, |from| for _ in 0..1 {
+        last_or_continue!(from());
+    });
+    once!(slices, |from| *split_first_or_return!(from(), 0).0);
+    once!(slices, |from| loop {
+        split_first_or_break!(from());
+        break;
+    });
+    once!(slices, |from| for _ in 0..1 {
+        split_first_or_continue!(from());
+    });
+    once!(slices, |from| *split_last_or_return!(from(), 0).0);
+    once!(slices, |from| loop {
+        split_last_or_break!(from());
+        break;
+    });
+    once!(slices, |from| for _ in 0..1 {
+        split_last_or_continue!(from());
+    });
+    once!([&mut vec![1], &mut Vec::new()], |from| loop {
+        pop_or_break!(from());
+        break;
+    });
+    let mut deque = std::collections::VecDeque::from([1, 2]);
+    once!([&mut deque, &mut Default::default()], |from| loop {
+        pop_front_or_break!(from());
+        break;
+    });
+    once!([&mut deque, &mut Default::default()], |from| loop {
+        pop_back_or_break!(from());
+        break;
+    });
+}
+
+#[cfg(feature = "conversions")]
+#[test]
+fn convert() {
+    once!([1i64, -1], |from| u8::to_le(try_into_or_return!(from(), 0)));
+    once!([1i64, -1], |from| loop {
+        let _: u8 = try_into_or_break!(from());
+        break;
+    });
+    once!([1i64, -1], |from| for _ in 0..1 {
+        let _: u8 = try_into_or_continue!(from());
+    });
+    once!(["1", "x"], |from| parse_or_return!(from(), ::<i32>, 0));
+    once!(["1", "x"], |from| for _ in 0..1 {
+        parse_or_continue!(from(), ::<i32>);
+    });
+    once!([&b"a"[..], &[0xFF][..]], |from| utf8_or_return!(from(), 0).len());
+    #[cfg(feature = "alloc")]
+    once!([vec![b'a'], vec![0xFF]], |from| utf8_or_return!(owned from(), 0).len());
+    once!([&b"a"[..], &[0xFF][..]], |from| for _ in 0..1 {
+        utf8_or_continue!(from());
+    });
+}
+
+#[cfg(feature = "option")]
+#[test]
+fn debug_some() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    // The escape paths panic in debug builds, which must not stop the count from being checked.
+    once!([Some(1), None], |from| catch_unwind(AssertUnwindSafe(|| {
+        debug_some_or_return!(from(), 0)
+    })));
+    once!([Some(1), None], |from| catch_unwind(AssertUnwindSafe(|| loop {
+        debug_some_or_break!(from());
+        break;
+    })));
+    once!([Some(1), None], |from| catch_unwind(AssertUnwindSafe(|| for _ in 0..1 {
+        debug_some_or_continue!(from());
+    })));
+}
+
+#[cfg(feature = "result")]
+#[test]
+fn debug_ok() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    once!([Ok(1), Err(())], |from| catch_unwind(AssertUnwindSafe(|| {
+        debug_ok_or_return!(from(), 0)
+    })));
+    once!([Ok(1), Err(())], |from| catch_unwind(AssertUnwindSafe(|| loop {
+        debug_ok_or_break!(from());
+        break;
+    })));
+    once!([Ok(1), Err(())], |from| catch_unwind(AssertUnwindSafe(|| for _ in 0..1 {
+        debug_ok_or_continue!(from());
+    })));
+}
+
+#[cfg(feature = "iterators")]
+#[test]
+fn iter() {
+    let mut iters = [vec![1].into_iter(), Vec::new().into_iter()];
+    once!(&mut iters, |from| next_or_return!(from(), 0));
+    let mut iters = [vec![1].into_iter(), Vec::new().into_iter()];
+    once!(&mut iters, |from| loop {
+        next_or_break!(from());
+        break;
+    });
+    let mut iters = [vec![1].into_iter().peekable(), Vec::new().into_iter().peekable()];
+    once!(&mut iters, |from| *peek_or_return!(from(), 0));
+    once!(&mut iters, |from| loop {
+        peek_or_break!(from());
+        break;
+    });
+}
+
+#[cfg(feature = "bool")]
+#[test]
+fn ensure() {
+    once!([true, false], |from| loop {
+        ensure_or_break!(from());
+        break;
+    });
+    once!([true, false], |from| for _ in 0..1 {
+        ensure_or_continue!(from());
+    });
+}
+
+#[cfg(feature = "poll")]
+#[test]
+fn poll() {
+    use core::task::Poll;
+
+    once!([Poll::Ready(1), Poll::Pending], |from| Poll::Ready(ready_or_return!(from())));
+    once!([Poll::Ready(1), Poll::Pending], |from| ready_or_return!(from(), 0));
+    once!([Poll::Ready(1), Poll::Pending], |from| loop {
+        ready_or_break!(from());
+        break;
+    });
+    once!([Poll::Ready(1), Poll::Pending], |from| for _ in 0..1 {
+        ready_or_continue!(from());
+    });
+}
+
+#[cfg(feature = "instrumented")]
+#[test]
+fn escape() {
+    once!([1, 2], |from| return_early!(from()));
+}
+
+#[cfg(feature = "loops")]
+#[test]
+fn loops() {
+    once!([[1, 2].into_iter().map(Some)], |from| for_some!(value in from() => {
+        let _ = value;
+    }));
+    once!([Some(1), None], |from| {
+        'l: loop {
+            branch_or_break!(from(), 'l);
+            break;
+        }
+    });
+}
+
+#[cfg(feature = "patterns")]
+#[test]
+fn multi() {
+    once!([Some(1), None], |from| all_some_or_return!((from(), Some(2)), 0).0);
+    once!([Some(1), None], |from| loop {
+        all_some_or_break!((from(), Some(2)));
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        all_some_or_continue!((from(), Some(2)));
+    });
+    once!([Ok(1), Err(())], |from| Ok::<_, ()>(all_ok_or_return!((from(), Ok(2))).0));
+    #[cfg(feature = "alloc")]
+    once!([Ok(1), Err(())], |from| {
+        Ok::<_, Vec<()>>(all_ok_or_return!((from(), Ok(2)), all).0)
+    });
+    once!([Some(1), None], |from| first_some_or_return!(None, from(); 0));
+    once!([Some(1), None], |from| {
+        bind_all_or_return!(a = from(), b = Some(2); 0);
+        a + b
+    });
+}
+
+#[cfg(feature = "patterns")]
+#[test]
+fn nested() {
+    let values = [Ok(Some(1)), Ok(None), Err(())];
+    once!(values, |from| some_ok_or_return!(from(), 0));
+    once!(values, |from| some_ok_or_return!(from(), err(_e) => 0, none => 0));
+    once!(values, |from| loop {
+        some_ok_or_break!(from());
+        break;
+    });
+    once!(values, |from| for _ in 0..1 {
+        some_ok_or_continue!(from());
+    });
+    let values = [Some(Ok(1)), Some(Err(())), None];
+    once!(values, |from| ok_some_or_return!(from(), 0));
+    once!(values, |from| ok_some_or_return!(from(), none => 0, err(_e) => 0));
+    once!(values, |from| loop {
+        ok_some_or_break!(from());
+        break;
+    });
+    once!(values, |from| for _ in 0..1 {
+        ok_some_or_continue!(from());
+    });
+}
+
+#[cfg(feature = "patterns")]
+#[test]
+fn pattern() {
+    #[derive(Clone, Copy)]
+    enum Msg {
+        Data(i32),
+        Quit,
+    }
+
+    once!([Some(1), None], |from| {
+        let_or_return!(Some(value) = from(), 0);
+        value
+    });
+    once!([Some(1), None], |from| loop {
+        let_or_break!(Some(_value) = from());
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        let_or_continue!(Some(_value) = from());
+    });
+    let msgs = [Msg::Data(1), Msg::Quit];
+    once!(msgs, |from| variant_or_return!(from(), Msg::Data, 0));
+    once!(msgs, |from| loop {
+        variant_or_break!(from(), Msg::Data);
+        break;
+    });
+    once!(msgs, |from| for _ in 0..1 {
+        variant_or_continue!(from(), Msg::Data);
+    });
+}
+
+#[cfg(feature = "patterns")]
+#[test]
+fn projection() {
+    once!([Some(&1), None], |from| some_copied_or_return!(from(), 0));
+    once!([Some(&1), None], |from| loop {
+        some_copied_or_break!(from());
+        break;
+    });
+    once!([Some(&1), None], |from| for _ in 0..1 {
+        some_copied_or_continue!(from());
+    });
+    once!([Some(&1), None], |from| some_cloned_or_return!(from(), 0));
+    once!([Some(&1), None], |from| loop {
+        some_cloned_or_break!(from());
+        break;
+    });
+    once!([Some(&1), None], |from| for _ in 0..1 {
+        some_cloned_or_continue!(from());
+    });
+    let strings = [Some(String::from("a")), None];
+    once!(&strings, |from| some_deref_or_return!(from(), 0).len());
+    once!(&strings, |from| loop {
+        some_deref_or_break!(from());
+        break;
+    });
+    once!(&strings, |from| for _ in 0..1 {
+        some_deref_or_continue!(from());
+    });
+    let mut options = [Some(1), None];
+    once!(&mut options, |from| *some_mut_or_return!(from(), 0));
+    once!(&mut options, |from| loop {
+        some_mut_or_break!(from());
+        break;
+    });
+    once!(&mut options, |from| for _ in 0..1 {
+        some_mut_or_continue!(from());
+    });
+    let mut results = [Ok(1), Err(())];
+    once!(&mut results, |from| *ok_mut_or_return!(from(), 0));
+    once!(&mut results, |from| loop {
+        ok_mut_or_break!(from());
+        break;
+    });
+    once!(&mut results, |from| for _ in 0..1 {
+        ok_mut_or_continue!(from());
+    });
+    once!(&mut [Some(1), None], |from| take_or_return!(from(), 0));
+    once!(&mut [Some(1), None], |from| loop {
+        take_or_break!(from());
+        break;
+    });
+    once!(&mut [Some(1), None], |from| for _ in 0..1 {
+        take_or_continue!(from());
+    });
+}
+
+#[cfg(feature = "conversions")]
+#[test]
+fn ptr() {
+    let mut value = 1;
+    once!([&mut value as *mut i32, std::ptr::null_mut()], |from| {
+        nonnull_or_return!(from(), 0);
+        1
+    });
+}
+
+#[cfg(feature = "conversions")]
+#[test]
+fn weak() {
+    use std::rc::{Rc, Weak};
+
+    let strong = Rc::new(1);
+    let weaks = [Rc::downgrade(&strong), Weak::new()];
+    once!(&weaks, |from| *upgrade_or_return!(from(), 0));
+    once!(&weaks, |from| loop {
+        upgrade_or_break!(from());
+        break;
+    });
+    once!(&weaks, |from| for _ in 0..1 {
+        upgrade_or_continue!(from());
+    });
+}
+
+// `stream!` rejects yields inside closures and nested async blocks, so the evaluations are counted
+// across the whole stream instead of with `once!`.
+#[cfg(feature = "async-stream")]
+#[tokio::test]
+async fn async_stream() {
+    use ::futures_util::StreamExt;
+
+    fn counted<T>(count: &Cell<usize>, value: T) -> T {
+        count.set(count.get() + 1);
+        value
+    }
+
+    let count = &Cell::new(0);
+    let items = ::async_stream::stream! {
+        for value in [Ok(1), Err(())] {
+            yield ok_or_yield!(counted(count, value), _e => yield 0);
+        }
+        for value in [Some(1), None] {
+            yield some_or_yield!(counted(count, value), yield 0);
+        }
+    };
+    assert_eq!(Box::pin(items).count().await, 4);
+    assert_eq!(count.get(), 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn channel() {
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel();
+    let (closed_tx, closed) = mpsc::channel();
+    for value in 0..4 {
+        tx.send(value).unwrap();
+    }
+    drop(closed_tx);
+    once!([&rx, &closed], |from| recv_or_return!(from(), 0));
+    once!([&rx, &closed], |from| loop {
+        recv_or_break!(from());
+        break;
+    });
+    once!([&rx, &closed], |from| for _ in 0..1 {
+        try_recv_or_continue!(from());
+    });
+    once!([&rx, &closed], |from| for _ in 0..1 {
+        recv_timeout_or_continue!(from(), std::time::Duration::ZERO);
+    });
+    drop(closed);
+    once!([tx.send(1), tx.send(2)], |from| send_or_return!(from()));
+    once!([tx.send(3)], |from| loop {
+        send_or_break!(from());
+        break;
+    });
+    drop(rx);
+    once!([tx.send(4)], |from| send_or_return!(from()));
+}
+
+#[cfg(feature = "std")]
+#[tokio::test]
+async fn channel_async() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel(2);
+    tx.send(1).await.unwrap();
+    tx.send(2).await.unwrap();
+    drop(tx);
+    // Two values are received, then the closed channel is the escape.
+    for _ in 0..3 {
+        once!(async [&mut rx], |from| recv_or_return!(async from(), 0));
+    }
+    once!(async [&mut rx], |from| loop {
+        recv_or_break!(async from());
+        break;
+    });
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn stream_async() {
+    use ::futures_util::stream;
+
+    // One item is taken, then the end of the stream is the escape.
+    let mut numbers = stream::iter([1]);
+    for _ in 0..2 {
+        once!(async [&mut numbers], |from| next_or_return!(async from(), 0));
+    }
+    let mut numbers = stream::iter([1]);
+    for _ in 0..2 {
+        once!(async [&mut numbers], |from| loop {
+            next_or_break!(async from());
+            break;
+        });
+    }
+    // An item, an error, then the end of the stream.
+    let mut rows = stream::iter([Ok(1), Err(())]);
+    for _ in 0..3 {
+        once!(async [&mut rows], |from| Ok::<_, ()>(try_next_or_return!(from(), Ok(0))));
+    }
+    let mut rows = stream::iter([Ok(1), Err(())]);
+    for _ in 0..3 {
+        once!(async [&mut rows], |from| {
+            loop {
+                try_next_or_break!(from(), _e => 1);
+                break;
+            }
+            0
+        });
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn env() {
+    const UNSET: &str = "EARLY_RETURNS_EVALUATION_UNSET";
+    once!(["CARGO_MANIFEST_DIR", UNSET], |from| env_or_return!(from(), String::new()));
+    #[cfg(feature = "conversions")]
+    once!(["CARGO_PKG_VERSION_MAJOR", UNSET], |from| env_parse_or_return!(from(), ::<u32>, 0));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn ffi() {
+    use std::ffi::{CStr, OsStr};
+    use std::path::Path;
+
+    once!([&b"a\0"[..], &b"a"[..]], |from| cstr_or_return!(from(), 0).to_bytes().len());
+    let name = CStr::from_bytes_with_nul(b"a\0").unwrap();
+    once!([name], |from| cstr_or_return!(to_str from(), 0).len());
+    once!([vec![b'a'], vec![0]], |from| cstring_or_return!(from(), 0).as_bytes().len());
+    once!([0, -1], |from| ffi_ok_or_return!(from(), 1));
+    once!([OsStr::new("a")], |from| osstr_or_return!(from(), 0).len());
+    once!([OsStr::new("a")], |from| for _ in 0..1 {
+        osstr_or_continue!(from());
+    });
+    let paths = [Path::new("/a"), Path::new("/")];
+    once!(paths, |from| path_str_or_return!(file_name from(), 0).len());
+    once!(paths, |from| for _ in 0..1 {
+        path_str_or_continue!(file_name from());
+    });
+}
+
+#[cfg(all(feature = "std", feature = "instrumented"))]
+#[test]
+fn hooks() {
+    once!([Some(1), None], |from| some_or_return_hooked!(from(), 0, reason: "once"));
+    once!([Some(1), None], |from| loop {
+        some_or_break_hooked!(from(), reason: "once");
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue_hooked!(from(), reason: "once");
+    });
+    once!([Ok(1), Err(())], |from| ok_or_return_hooked!(from(), 0, reason: "once"));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break_hooked!(from(), reason: "once");
+        break;
+    });
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_hooked!(from(), reason: "once");
+    });
+}
+
+#[cfg(all(feature = "instrumented", feature = "option"))]
+#[test]
+fn reason_some() {
+    use crate::reason::EarlyExitReason;
+
+    let mut last = None;
+    once!([Some(1), None], |from| Result::<i32, EarlyExitReason>::Ok(some_or_return_reason!(
+        from(),
+        "once"
+    )));
+    once!([Some(1), None], |from| loop {
+        some_or_break_reason!(from(), "once", store: &mut last);
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue_reason!(from(), "once", store: &mut last);
+    });
+}
+
+#[cfg(all(feature = "instrumented", feature = "result"))]
+#[test]
+fn reason_ok() {
+    let mut last = None;
+    once!([Ok(1), Err(())], |from| ok_or_return_reason!(from(), "once", store: &mut last, 0));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break_reason!(from(), "once", store: &mut last);
+        break;
+    });
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_reason!(from(), "once", store: &mut last);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io() {
+    use std::io::{Error, ErrorKind};
+
+    let values = || [Ok(1), Err(Error::from(ErrorKind::WouldBlock)), Err(ErrorKind::Other.into())];
+    once!(values(), |from| {
+        for _ in 0..1 {
+            ok_or_continue_if!(from(), WouldBlock, _e => 1);
+        }
+        0
+    });
+    once!(values(), |from| {
+        for _ in 0..1 {
+            ok_or_return_if!(from(), WouldBlock);
+        }
+        Ok::<_, Error>(())
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn join() {
+    let finished = std::thread::spawn(|| 1);
+    let panicked = std::thread::Builder::new()
+        .name(String::from("evaluation"))
+        .spawn(|| -> i32 { panic!("joined once") })
+        .unwrap();
+    once!([finished, panicked], |from| join_or_return!(from(), 0));
+}
+
+#[cfg(feature = "std")]
+#[tokio::test]
+async fn join_async() {
+    let finished = tokio::spawn(async { 1 });
+    let cancelled = tokio::spawn(std::future::pending::<i32>());
+    cancelled.abort();
+    once!(async [finished, cancelled], |from| join_or_return!(async from(), 0));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn lock() {
+    use std::sync::{Mutex, RwLock};
+
+    let (mutex, rwlock) = (Mutex::new(1), RwLock::new(1));
+    once!([&mutex], |from| *lock_or_return!(from(), 0));
+    once!([&mutex], |from| *lock_or_return!(from(), recover));
+    once!([&rwlock], |from| *read_or_return!(from(), 0));
+    once!([&rwlock], |from| *write_or_return!(from(), 0));
+    once!([&mutex], |from| *try_lock_or_return!(from(), 0));
+    once!([&rwlock], |from| *try_read_or_return!(from(), 0));
+    once!([&rwlock], |from| *try_write_or_return!(from(), 0));
+    // The same macros again, while the locks are held.
+    let guard = mutex.lock().unwrap();
+    once!([&mutex], |from| *try_lock_or_return!(from(), 0));
+    once!([&mutex], |from| for _ in 0..1 {
+        let _guard = try_lock_or_continue!(from());
+    });
+    drop(guard);
+    let guard = rwlock.write().unwrap();
+    once!([&rwlock], |from| *try_read_or_return!(from(), 0));
+    once!([&rwlock], |from| *try_write_or_return!(from(), 0));
+    once!([&rwlock], |from| for _ in 0..1 {
+        let _guard = try_read_or_continue!(from());
+    });
+    once!([&rwlock], |from| for _ in 0..1 {
+        let _guard = try_write_or_continue!(from());
+    });
+    drop(guard);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn oneshot() {
+    use tokio::sync::oneshot;
+
+    let (sent_tx, sent) = oneshot::channel();
+    let (dropped_tx, dropped) = oneshot::channel();
+    sent_tx.send(1).unwrap();
+    drop(dropped_tx);
+    once!(async [sent, dropped], |from| oneshot_or_return!(from(), 0));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn timeout() {
+    use std::future::ready;
+    use std::time::Duration;
+
+    let futures = [ready(Ok(1)), ready(Err(()))];
+    once!(async futures, |from| timeout_or_return!(Duration::from_secs(5), from(), 0));
+    let durations = [Duration::from_millis(1), Duration::ZERO];
+    once!(async durations, |from| {
+        timeout_or_return!(from(), std::future::pending::<Result<i32, ()>>(), 0)
+    });
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn semaphore() {
+    use tokio::sync::Semaphore;
+
+    let (open, closed) = (Semaphore::new(2), Semaphore::new(2));
+    closed.close();
+    once!(async [&open, &closed], |from| {
+        let _permit = acquire_or_return!(from(), 0);
+        1
+    });
+    once!([&open, &closed], |from| loop {
+        let _permit = try_acquire_or_continue!(from());
+        break;
+    });
+}
+
+#[cfg(feature = "tokio-util")]
+#[test]
+fn cancel() {
+    use tokio_util::sync::CancellationToken;
+
+    let tokens = || {
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        [CancellationToken::new(), cancelled]
+    };
+    once!(tokens(), |from| {
+        not_cancelled_or_return!(from(), 0);
+        1
+    });
+    once!(tokens(), |from| loop {
+        not_cancelled_or_break!(from());
+        break;
+    });
+}
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn anyhow() {
+    let values = || [Ok(1), Err(std::fmt::Error)];
+    once!(values(), |from| ::anyhow::Ok(ok_or_return_context!(from(), "once")));
+    once!([Some(1), None], |from| ::anyhow::Ok(some_or_return_context!(from(), "once")));
+}
+
+#[cfg(feature = "either")]
+#[test]
+fn either() {
+    use ::either::Either::{self, Left, Right};
+
+    let values: [Either<i32, ()>; 2] = [Left(1), Right(())];
+    once!(values, |from| left_or_return!(from(), 0));
+    once!(values, |from| loop {
+        left_or_break!(from());
+        break;
+    });
+    once!(values, |from| for _ in 0..1 {
+        left_or_continue!(from());
+    });
+    let values: [Either<(), i32>; 2] = [Right(1), Left(())];
+    once!(values, |from| right_or_return!(from(), 0));
+    once!(values, |from| loop {
+        right_or_break!(from());
+        break;
+    });
+    once!(values, |from| for _ in 0..1 {
+        right_or_continue!(from());
+    });
+}
+
+#[cfg(feature = "eyre")]
+#[test]
+fn eyre() {
+    let values = || [Ok(1), Err(std::fmt::Error)];
+    once!(values(), |from| ::eyre::Ok(ok_or_return_eyre!(from(), "once")));
+    once!([Some(1), None], |from| ::eyre::Ok(some_or_return_eyre!(from(), "once")));
+}
+
+#[cfg(feature = "windows")]
+#[test]
+fn hresult() {
+    use windows_result::HRESULT;
+
+    once!([HRESULT(0), HRESULT(-1)], |from| hr_ok_or_return!(from(), HRESULT(1)));
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn log() {
+    once!([Some(1), None], |from| some_or_return_logged!(from(), 0, level: info, "once"));
+    once!([Some(1), None], |from| loop {
+        some_or_break_logged!(from(), level: info, "once");
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue_logged!(from(), level: info, "once");
+    });
+    once!([Ok(1), Err(())], |from| ok_or_return_logged!(from(), 0, level: info, "once"));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break_logged!(from(), level: info, "once");
+        break;
+    });
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_logged!(from(), level: info, "once");
+    });
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn metrics() {
+    once!([Some(1), None], |from| some_or_return_counted!(from(), 0, metric: "once"));
+    once!([Some(1), None], |from| loop {
+        some_or_break_counted!(from(), metric: "once");
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue_counted!(from(), metric: "once");
+    });
+    once!([Ok(1), Err(())], |from| ok_or_return_counted!(from(), 0, metric: "once"));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break_counted!(from(), metric: "once");
+        break;
+    });
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_counted!(from(), metric: "once");
+    });
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn heapless() {
+    let mut full = ::heapless::Vec::<i32, 1>::new();
+    once!([1, 2], |from| push_or_return!(full, from()));
+    once!([1, 2], |from| {
+        push_or_return!(full, from(), r => r);
+        0
+    });
+    once!([1, 2], |from| loop {
+        push_or_break!(full, from());
+        break;
+    });
+    once!([1, 2], |from| for _ in 0..1 {
+        push_or_continue!(full, from());
+    });
+}
+
+#[cfg(feature = "miette")]
+#[test]
+fn miette() {
+    let values = || [Ok(1), Err(std::fmt::Error)];
+    once!(values(), |from| Ok::<_, ::miette::Report>(ok_or_return_diag!(from(), code = "a", "once")));
+    once!([Some(1), None], |from| {
+        Ok::<_, ::miette::Report>(some_or_return_diag!(from(), help = "b", "once"))
+    });
+}
+
+#[cfg(feature = "nb")]
+#[test]
+fn nb() {
+    let values = || [Ok(1), Err(::nb::Error::WouldBlock), Err(::nb::Error::Other(()))];
+    once!(values(), |from| Ok::<_, ::nb::Error<()>>(nb_ok_or_return!(from())));
+    once!(values(), |from| nb_ok_or_return!(from(), would_block => 0, _e => 0));
+    once!(values(), |from| nb_ok_or_return!(from(), 0));
+    once!(values(), |from| {
+        for _ in 0..1 {
+            nb_ok_or_continue!(from(), _e => 1);
+        }
+        0
+    });
+}
+
+#[cfg(feature = "nom")]
+#[test]
+fn nom() {
+    use ::nom::bytes::complete::tag;
+    use ::nom::IResult;
+
+    fn once(input: &str) -> IResult<&str, &str> {
+        tag("once")(input)
+    }
+
+    once!([once("once"), once("twice")], |from| nom_or_return!(from(), ("", "")));
+    once!([once("once"), once("twice")], |from| for _ in 0..1 {
+        nom_or_continue!(from());
+    });
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn rayon() {
+    let skipped = Cell::new(false);
+    once!([Some(1), None], |from| {
+        some_or_continue_par!(from());
+        Ok::<_, ()>(())
+    });
+    once!([Some(1), None], |from| {
+        some_or_break_par!(from(), ());
+        Ok(())
+    });
+    once!([Ok(1), Err(())], |from| {
+        ok_or_continue_par!(from());
+        Ok::<_, ()>(())
+    });
+    once!([Ok(1), Err(())], |from| {
+        ok_or_continue_par!(from(), _e => skipped.set(true));
+        Ok::<_, ()>(())
+    });
+    once!([Ok(1), Err(())], |from| {
+        ok_or_break_par!(from());
+        Ok::<_, ()>(())
+    });
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing() {
+    once!([Some(1), None], |from| some_or_return_traced!(from(), 0, level: info, "once"));
+    once!([Some(1), None], |from| loop {
+        some_or_break_traced!(from(), level: info, "once");
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue_traced!(from(), level: info, "once");
+    });
+    once!([Ok(1), Err(())], |from| ok_or_return_traced!(from(), 0, level: info, "once"));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break_traced!(from(), level: info, "once");
+        break;
+    });
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_traced!(from(), level: info, "once");
+    });
+    once!([Some(1), None], |from| some_or_return_recorded!(from(), 0, reason: "once"));
+    once!([Some(1), None], |from| loop {
+        some_or_break_recorded!(from(), reason: "once");
+        break;
+    });
+    once!([Some(1), None], |from| for _ in 0..1 {
+        some_or_continue_recorded!(from(), reason: "once");
+    });
+    once!([Ok(1), Err(())], |from| ok_or_return_recorded!(from(), 0, reason: "once"));
+    once!([Ok(1), Err(())], |from| loop {
+        ok_or_break_recorded!(from(), reason: "once");
+        break;
+    });
+    once!([Ok(1), Err(())], |from| for _ in 0..1 {
+        ok_or_continue_recorded!(from(), reason: "once");
+    });
+}
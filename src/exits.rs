@@ -0,0 +1,91 @@
+use core::cell::Cell;
+use core::fmt;
+use core::panic::Location;
+
+/// Keeps track of the exit path a function instrumented with `#[instrument_early_returns]` is
+/// taking, and reports it when the function is left.
+///
+/// Every instrumented exit records itself before it may be taken and restores the previous record
+/// once it has been passed, so whatever is recorded when the tracker is dropped is the exit that
+/// was taken. Nothing recorded means the function reached its end.
+#[doc(hidden)]
+pub struct ExitTracker {
+    function: &'static str,
+    exit: Cell<Option<Exit>>,
+    report: fn(&ExitReport),
+}
+
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub struct Exit {
+    through: &'static str,
+    location: &'static Location<'static>,
+}
+
+/// What `#[instrument_early_returns]` reports when a function is left.
+#[doc(hidden)]
+pub struct ExitReport {
+    pub function: &'static str,
+    exit: Option<Exit>,
+}
+
+impl ExitTracker {
+    pub fn new(function: &'static str, report: fn(&ExitReport)) -> Self {
+        ExitTracker {
+            function,
+            exit: Cell::new(None),
+            report,
+        }
+    }
+
+    /// Record an exit that may be taken next, returning the previous record.
+    #[track_caller]
+    #[inline]
+    pub fn enter(&self, through: &'static str) -> Option<Exit> {
+        let location = Location::caller();
+        self.exit.replace(Some(Exit { through, location }))
+    }
+
+    /// Record an exit that is always taken, returning the (then current) record.
+    #[track_caller]
+    #[inline]
+    pub fn taken(&self, through: &'static str) -> Option<Exit> {
+        self.enter(through);
+        self.exit.get()
+    }
+
+    /// Restore the record from before an exit that was not taken, passing its value on.
+    #[inline]
+    pub fn leave<T>(&self, previous: Option<Exit>, value: T) -> T {
+        self.exit.set(previous);
+        value
+    }
+}
+
+impl Drop for ExitTracker {
+    fn drop(&mut self) {
+        // Unwinding is not one of the exit paths of the function.
+        if std::thread::panicking() {
+            return;
+        }
+        (self.report)(&ExitReport {
+            function: self.function,
+            exit: self.exit.get(),
+        });
+    }
+}
+
+impl fmt::Display for ExitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.exit {
+            Some(Exit { through, location }) => {
+                write!(
+                    f,
+                    "`{}` returned through `{}` at {}",
+                    self.function, through, location
+                )
+            }
+            None => write!(f, "`{}` returned at its end", self.function),
+        }
+    }
+}
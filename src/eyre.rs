@@ -0,0 +1,119 @@
+/// Either get the Ok value from a Result type or return the error from the current function, with
+/// a context message attached. The message accepts format arguments and is only formatted on the
+/// escape path.
+///
+/// The error is wrapped into an `eyre::Report` with `eyre::WrapErr` and then converted into the
+/// error type of the function with `From`, so this works in functions returning `eyre::Result`
+/// (including with a `color-eyre` handler installed). This is the `eyre` counterpart of
+/// `ok_or_return_context!`.
+/// ```
+/// use early_returns::ok_or_return_eyre;
+///
+/// fn port(value: &str) -> eyre::Result<u16> {
+///     let port: u16 = ok_or_return_eyre!(value.parse(), "invalid port {value:?}");
+///     Ok(port)
+/// }
+///
+/// assert_eq!(port("8080").unwrap(), 8080);
+/// let e = port("http").unwrap_err();
+/// assert_eq!(e.to_string(), "invalid port \"http\"");
+/// assert_eq!(e.root_cause().to_string(), "invalid digit found in string");
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_eyre {
+    ($from:expr, $($arg:tt)+) => {{
+        match $crate::__private::eyre::WrapErr::wrap_err_with($from, || {
+            ::std::format!($($arg)+)
+        }) {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from(e));
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or return an error built with `eyre!` from the given
+/// message from the current function. The message accepts format arguments and is only formatted
+/// on the escape path. This is the `eyre` counterpart of `some_or_return_context!`.
+/// ```
+/// use early_returns::some_or_return_eyre;
+/// use std::collections::HashMap;
+///
+/// fn lookup(users: &HashMap<u32, String>, id: u32) -> eyre::Result<&str> {
+///     let user = some_or_return_eyre!(users.get(&id), "no user with id {id}");
+///     Ok(user)
+/// }
+///
+/// let users = HashMap::from([(1, "ada".to_string())]);
+/// assert_eq!(lookup(&users, 1).unwrap(), "ada");
+/// assert_eq!(lookup(&users, 2).unwrap_err().to_string(), "no user with id 2");
+/// ```
+#[macro_export]
+macro_rules! some_or_return_eyre {
+    ($from:expr, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from(
+                    $crate::__private::eyre::eyre!($($arg)+),
+                ))
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use eyre::Result;
+    use std::cell::Cell;
+
+    fn parse_all(values: &[&str]) -> Result<Vec<i32>> {
+        let mut parsed = Vec::new();
+        for (i, value) in values.iter().enumerate() {
+            parsed.push(ok_or_return_eyre!(value.parse(), "value {} is invalid", i));
+        }
+        Ok(parsed)
+    }
+
+    fn first_even(values: &[i32]) -> Result<i32> {
+        let even = some_or_return_eyre!(values.iter().find(|v| *v % 2 == 0), "no even value");
+        Ok(*even)
+    }
+
+    #[test]
+    fn should_return_report_with_context() {
+        assert_eq!(parse_all(&["1", "2"]).unwrap(), vec![1, 2]);
+        let e = parse_all(&["1", "x"]).unwrap_err();
+        assert_eq!(e.to_string(), "value 1 is invalid");
+        assert!(e.root_cause().is::<std::num::ParseIntError>());
+        assert_eq!(first_even(&[1, 4]).unwrap(), 4);
+        assert_eq!(first_even(&[1, 3]).unwrap_err().to_string(), "no even value");
+    }
+
+    #[test]
+    fn should_only_format_message_on_escape_path() {
+        struct Counted<'a>(&'a Cell<i32>);
+
+        impl std::fmt::Display for Counted<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.set(self.0.get() + 1);
+                write!(f, "formatted")
+            }
+        }
+
+        fn check(value: Option<i32>, formatted: &Cell<i32>) -> Result<i32> {
+            let value = some_or_return_eyre!(value, "{}", Counted(formatted));
+            Ok(ok_or_return_eyre!(u8::try_from(value), "{}", Counted(formatted)).into())
+        }
+
+        let formatted = Cell::new(0);
+        assert_eq!(check(Some(1), &formatted).unwrap(), 1);
+        assert_eq!(formatted.get(), 0);
+        assert!(check(None, &formatted).is_err());
+        assert!(check(Some(300), &formatted).is_err());
+        assert_eq!(formatted.get(), 2);
+    }
+}
@@ -0,0 +1,513 @@
+/// Either borrow a nul-terminated byte slice as a `CStr` with `CStr::from_bytes_with_nul` or
+/// return from the current function if it is not nul-terminated or contains an interior nul. A
+/// default return value can be provided, or the `FromBytesWithNulError` can be bound and used to
+/// produce the return value.
+///
+/// With the `to_str` marker (e.g. `cstr_or_return!(to_str name)`), a `CStr` is converted into a
+/// `&str` with `CStr::to_str` instead, returning if it is not valid UTF-8, in which case the bound
+/// error is a `Utf8Error`.
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::cstr_or_return;
+/// use std::ffi::CStr;
+///
+/// fn name_len(bytes: &[u8]) -> Option<usize> {
+///     let name: &CStr = cstr_or_return!(bytes, None);
+///     let name = cstr_or_return!(to_str name, None);
+///     Some(name.len())
+/// }
+///
+/// assert_eq!(name_len(b"ada\0"), Some(3));
+/// assert_eq!(name_len(b"ada"), None);
+/// assert_eq!(name_len(b"\xff\0"), None);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! cstr_or_return {
+    (to_str $from:expr, $e:ident => $result:expr) => {{
+        match $from.to_str() {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    (to_str $from:expr) => {
+        $crate::ok_or_return!($from.to_str())
+    };
+
+    (to_str $from:expr, $default_result:expr) => {
+        $crate::ok_or_return!($from.to_str(), $default_result)
+    };
+
+    ($from:expr, $e:ident => $result:expr) => {{
+        match $crate::__private::ffi::CStr::from_bytes_with_nul($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_return!($crate::__private::ffi::CStr::from_bytes_with_nul($from))
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::ok_or_return!(
+            $crate::__private::ffi::CStr::from_bytes_with_nul($from),
+            $default_result
+        )
+    };
+}
+
+/// Either create an owned `CString` with `CString::new` or return from the current function if
+/// the data contains an interior nul byte. A default return value can be provided, or the
+/// `NulError` (which still holds the data) can be bound and used to produce the return value.
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::cstring_or_return;
+/// use std::ffi::CString;
+///
+/// fn to_c(path: &str) -> Result<CString, String> {
+///     let path = cstring_or_return!(path, e => Err(format!("nul at {}", e.nul_position())));
+///     Ok(path)
+/// }
+///
+/// assert_eq!(to_c("/tmp").unwrap().as_bytes(), b"/tmp");
+/// assert_eq!(to_c("/t\0mp"), Err("nul at 2".to_string()));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! cstring_or_return {
+    ($from:expr, $e:ident => $result:expr) => {{
+        match $crate::__private::ffi::CString::new($from) {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_return!($crate::__private::ffi::CString::new($from))
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::ok_or_return!($crate::__private::ffi::CString::new($from), $default_result)
+    };
+}
+
+/// Either get the return code of an FFI call if it signals success or return from the current
+/// function if it signals failure. By default, a return code of `0` signals success, but a
+/// success predicate can be provided with `ok_if` (e.g. `ok_if |fd| fd >= 0`). A default return
+/// value can be provided, or `std::io::Error::last_os_error()` (i.e. errno or `GetLastError`) can
+/// be bound and used to produce the return value.
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::ffi_ok_or_return;
+/// use std::io;
+///
+/// # unsafe extern "C" fn close(fd: i32) -> i32 { if fd < 0 { -1 } else { 0 } }
+/// # unsafe extern "C" fn dup(fd: i32) -> i32 { if fd < 0 { -1 } else { fd + 1 } }
+/// fn close_fd(fd: i32) -> io::Result<()> {
+///     ffi_ok_or_return!(unsafe { close(fd) }, e => Err(e));
+///     Ok(())
+/// }
+///
+/// fn duplicate(fd: i32) -> Option<i32> {
+///     let fd = ffi_ok_or_return!(unsafe { dup(fd) }, ok_if |fd| fd >= 0, None);
+///     Some(fd)
+/// }
+///
+/// assert!(close_fd(3).is_ok());
+/// assert!(close_fd(-1).is_err());
+/// assert_eq!(duplicate(3), Some(4));
+/// assert_eq!(duplicate(-1), None);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! ffi_ok_or_return {
+    ($rc:expr, ok_if $is_ok:expr, $e:ident => $result:expr) => {{
+        let rc = $rc;
+        if !($is_ok)(rc) {
+            let $e = $crate::__private::io::Error::last_os_error();
+            $crate::__mark_early_exit!();
+            return $result;
+        }
+        rc
+    }};
+
+    ($rc:expr, ok_if $is_ok:expr) => {{
+        let rc = $rc;
+        if !($is_ok)(rc) {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return;
+        }
+        rc
+    }};
+
+    ($rc:expr, ok_if $is_ok:expr, $default_result:expr) => {{
+        let rc = $rc;
+        if !($is_ok)(rc) {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        }
+        rc
+    }};
+
+    ($rc:expr, $e:ident => $result:expr) => {
+        $crate::ffi_ok_or_return!($rc, ok_if |rc| rc == 0, $e => $result)
+    };
+
+    ($rc:expr) => {
+        $crate::ffi_ok_or_return!($rc, ok_if |rc| rc == 0)
+    };
+
+    ($rc:expr, $default_result:expr) => {
+        $crate::ffi_ok_or_return!($rc, ok_if |rc| rc == 0, $default_result)
+    };
+}
+
+/// Either convert an `OsStr` into a `&str` with `OsStr::to_str` or return from the current
+/// function if it is not valid unicode. A default return value can be provided.
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::osstr_or_return;
+/// use std::ffi::OsStr;
+///
+/// fn shout(arg: &OsStr) -> Option<String> {
+///     let arg = osstr_or_return!(arg, None);
+///     Some(arg.to_uppercase())
+/// }
+///
+/// assert_eq!(shout(OsStr::new("hi")), Some("HI".to_string()));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! osstr_or_return {
+    ($from:expr) => {
+        $crate::some_or_return!($from.to_str())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::some_or_return!($from.to_str(), $default_result)
+    };
+}
+
+/// Either convert an `OsStr` into a `&str` with `OsStr::to_str` or continue in a loop if it is
+/// not valid unicode. If a loop lifetime is specified, that loop will be "continued", otherwise
+/// the immediate loop is "continued".
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::osstr_or_continue;
+/// use std::ffi::OsString;
+///
+/// let args = vec![OsString::from("-v"), OsString::from("input.txt")];
+/// let mut flags = Vec::new();
+/// for arg in &args {
+///     let arg = osstr_or_continue!(arg);
+///     if arg.starts_with('-') {
+///         flags.push(arg);
+///     }
+/// }
+/// assert_eq!(flags, vec!["-v"]);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! osstr_or_continue {
+    ($from:expr) => {
+        $crate::some_or_continue!($from.to_str())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($from.to_str(), $lt)
+    };
+}
+
+/// Either convert a `Path` into a `&str` with `Path::to_str` or return from the current function
+/// if it is not valid unicode. A default return value can be provided.
+///
+/// With the `file_name` or `extension` marker (e.g. `path_str_or_return!(extension path)`), the
+/// file name or extension of the path is converted instead, returning if the path has none.
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::path_str_or_return;
+/// use std::path::Path;
+///
+/// fn is_markdown(path: &Path) -> bool {
+///     let extension = path_str_or_return!(extension path, false);
+///     extension == "md"
+/// }
+///
+/// assert!(is_markdown(Path::new("docs/README.md")));
+/// assert!(!is_markdown(Path::new("docs/README")));
+/// assert!(!is_markdown(Path::new("src/lib.rs")));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! path_str_or_return {
+    (file_name $from:expr) => {
+        $crate::some_or_return!($crate::path_str_or_return!(@str $from.file_name()))
+    };
+
+    (file_name $from:expr, $default_result:expr) => {
+        $crate::some_or_return!(
+            $crate::path_str_or_return!(@str $from.file_name()),
+            $default_result
+        )
+    };
+
+    (extension $from:expr) => {
+        $crate::some_or_return!($crate::path_str_or_return!(@str $from.extension()))
+    };
+
+    (extension $from:expr, $default_result:expr) => {
+        $crate::some_or_return!(
+            $crate::path_str_or_return!(@str $from.extension()),
+            $default_result
+        )
+    };
+
+    (@str $from:expr) => {
+        $from.and_then($crate::__private::ffi::OsStr::to_str)
+    };
+
+    ($from:expr) => {
+        $crate::some_or_return!($from.to_str())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::some_or_return!($from.to_str(), $default_result)
+    };
+}
+
+/// Either convert a `Path` into a `&str` with `Path::to_str` or continue in a loop if it is not
+/// valid unicode. If a loop lifetime is specified, that loop will be "continued", otherwise the
+/// immediate loop is "continued".
+///
+/// With the `file_name` or `extension` marker (e.g. `path_str_or_continue!(extension path)`), the
+/// file name or extension of the path is converted instead, continuing if the path has none.
+///
+/// Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::path_str_or_continue;
+/// use std::path::PathBuf;
+///
+/// let entries = vec![
+///     PathBuf::from("a/Makefile"),
+///     PathBuf::from("a/main.rs"),
+///     PathBuf::from("b/lib.rs"),
+/// ];
+/// let mut sources = Vec::new();
+/// for entry in &entries {
+///     if path_str_or_continue!(extension entry) != "rs" {
+///         continue;
+///     }
+///     sources.push(path_str_or_continue!(file_name entry));
+/// }
+/// assert_eq!(sources, vec!["main.rs", "lib.rs"]);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! path_str_or_continue {
+    (file_name $from:expr) => {
+        $crate::some_or_continue!($crate::path_str_or_return!(@str $from.file_name()))
+    };
+
+    (file_name $from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($crate::path_str_or_return!(@str $from.file_name()), $lt)
+    };
+
+    (extension $from:expr) => {
+        $crate::some_or_continue!($crate::path_str_or_return!(@str $from.extension()))
+    };
+
+    (extension $from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($crate::path_str_or_return!(@str $from.extension()), $lt)
+    };
+
+    ($from:expr) => {
+        $crate::some_or_continue!($from.to_str())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($from.to_str(), $lt)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::{CStr, CString, OsStr};
+    use std::io;
+    use std::path::Path;
+
+    struct Tester {
+        names: Vec<String>,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester { names: Vec::new() }
+        }
+
+        fn push_name(&mut self, bytes: &[u8]) {
+            let name = cstr_or_return!(bytes);
+            self.names.push(cstr_or_return!(to_str name).to_string());
+        }
+
+        fn push_owned(&mut self, name: &str) {
+            let name = cstring_or_return!(name);
+            self.names.push(name.into_string().unwrap());
+        }
+    }
+
+    fn cstr_or_default(bytes: &[u8]) -> &CStr {
+        cstr_or_return!(bytes, Default::default())
+    }
+
+    fn cstr_error(bytes: &[u8]) -> Result<&str, String> {
+        let name = cstr_or_return!(bytes, e => Err(e.to_string()));
+        Ok(cstr_or_return!(to_str name, e => Err(e.to_string())))
+    }
+
+    fn cstring_or_default(name: &str) -> CString {
+        cstring_or_return!(name, CString::default())
+    }
+
+    fn cstring_or_bytes(name: &str) -> Result<CString, Vec<u8>> {
+        Ok(cstring_or_return!(name, e => Err(e.into_vec())))
+    }
+
+    fn str_or_default(name: &CStr) -> &str {
+        cstr_or_return!(to_str name, "invalid")
+    }
+
+    #[test]
+    fn should_return_early_on_invalid_c_strings() {
+        let mut tester = Tester::new();
+        tester.push_name(b"ada\0");
+        tester.push_name(b"ada");
+        tester.push_name(b"a\0da\0");
+        tester.push_name(b"\xff\0");
+        tester.push_owned("grace");
+        tester.push_owned("gr\0ace");
+        assert_eq!(tester.names, vec!["ada", "grace"]);
+        assert_eq!(cstr_or_default(b"ada\0").to_bytes(), b"ada");
+        assert_eq!(cstr_or_default(b"ada").to_bytes(), b"");
+        assert_eq!(cstr_error(b"ada\0"), Ok("ada"));
+        assert!(cstr_error(b"ada").is_err());
+        assert!(cstr_error(b"\xff\0").is_err());
+        assert_eq!(cstring_or_default("ada").as_bytes(), b"ada");
+        assert_eq!(cstring_or_default("a\0da").as_bytes(), b"");
+        assert_eq!(cstring_or_bytes("a\0da"), Err(b"a\0da".to_vec()));
+        assert_eq!(str_or_default(&CString::new(vec![0xff]).unwrap()), "invalid");
+    }
+
+    fn arg_or_default(arg: &OsStr) -> &str {
+        osstr_or_return!(arg, "")
+    }
+
+    fn push_path_parts(path: &Path, parts: &mut Vec<String>) {
+        parts.push(path_str_or_return!(path).to_string());
+        parts.push(osstr_or_return!(path.as_os_str()).to_string());
+        parts.push(path_str_or_return!(file_name path).to_string());
+        parts.push(path_str_or_return!(extension path).to_string());
+    }
+
+    fn path_parts_or_default(path: &Path) -> (&str, &str) {
+        let name = path_str_or_return!(file_name path, ("", ""));
+        let extension = path_str_or_return!(extension path, (name, ""));
+        (name, extension)
+    }
+
+    fn path_parts_with_continue(paths: &[&Path]) -> Vec<String> {
+        let mut parts = Vec::new();
+        for path in paths {
+            parts.push(path_str_or_continue!(extension path).to_string());
+        }
+        'l: for path in paths {
+            for _i in 0..1 {
+                parts.push(path_str_or_continue!(file_name path, 'l).to_string());
+                parts.push(path_str_or_continue!(extension path, 'l).to_string());
+            }
+        }
+        for path in paths {
+            parts.push(path_str_or_continue!(file_name path).to_string());
+            parts.push(osstr_or_continue!(path.as_os_str()).to_string());
+        }
+        'l: for path in paths {
+            for _i in 0..1 {
+                parts.push(path_str_or_continue!(path, 'l).to_string());
+                parts.push(osstr_or_continue!(path.as_os_str(), 'l).to_string());
+            }
+        }
+        parts
+    }
+
+    #[test]
+    fn should_return_early_on_missing_path_parts() {
+        assert_eq!(arg_or_default(OsStr::new("-v")), "-v");
+        let mut parts = Vec::new();
+        push_path_parts(Path::new("a/b.rs"), &mut parts);
+        push_path_parts(Path::new("a/b"), &mut parts);
+        push_path_parts(Path::new(".."), &mut parts);
+        assert_eq!(parts, vec!["a/b.rs", "a/b.rs", "b.rs", "rs", "a/b", "a/b", "b", "..", ".."]);
+        assert_eq!(path_parts_or_default(Path::new("a/b.rs")), ("b.rs", "rs"));
+        assert_eq!(path_parts_or_default(Path::new("a/b")), ("b", ""));
+        assert_eq!(path_parts_or_default(Path::new("/")), ("", ""));
+    }
+
+    #[test]
+    fn should_continue_on_missing_path_parts() {
+        let paths = [Path::new("a.rs"), Path::new("/"), Path::new("b")];
+        assert_eq!(
+            path_parts_with_continue(&paths),
+            vec![
+                "rs", "a.rs", "rs", "b", "a.rs", "a.rs", "b", "b", "a.rs", "a.rs", "/", "/", "b",
+                "b"
+            ]
+        );
+    }
+
+    fn checked_calls(rcs: &[i32], calls: &mut i32) {
+        for rc in rcs {
+            ffi_ok_or_return!(*rc);
+            let fd = ffi_ok_or_return!(*rc + 3, ok_if |fd| fd >= 3);
+            *calls += fd;
+        }
+    }
+
+    fn rc_or_default(rc: i32) -> i32 {
+        ffi_ok_or_return!(rc, -1);
+        ffi_ok_or_return!(rc + 1, ok_if |rc: i32| rc.is_positive(), -2)
+    }
+
+    fn rc_or_error(rc: i32) -> io::Result<i32> {
+        ffi_ok_or_return!(rc, e => Err(e));
+        Ok(ffi_ok_or_return!(rc + 4, ok_if |fd| fd > 0, e => Err(e)))
+    }
+
+    #[test]
+    fn should_return_early_on_failure_codes() {
+        let mut calls = 0;
+        checked_calls(&[0, 0, -1, 0], &mut calls);
+        assert_eq!(calls, 6);
+        assert_eq!(rc_or_default(0), 1);
+        assert_eq!(rc_or_default(1), -1);
+        assert_eq!(rc_or_error(0).unwrap(), 4);
+        assert!(rc_or_error(-1).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+/// Either get the Ok value from a Result type or return a formatted message from the current
+/// function as the error, converted with `From` (so the function can return `Result<T, String>`,
+/// `Box<dyn Error>` or any error type constructible from a `String`).
+///
+/// The message is written like the arguments of `format!`, with the error available as `{e}`
+/// (or `{e:?}`) next to the captured variables and any further arguments. The message must use
+/// the error, otherwise the `e` argument is reported as never used: `ok_or_return_mapped!` or
+/// `ok_or_return_boxed!` can return a message without it. Requires the `alloc` feature.
+/// ```
+/// use early_returns::ok_or_return_fmt;
+///
+/// fn port(name: &str, value: &str) -> Result<u16, String> {
+///     let port = ok_or_return_fmt!(value.parse(), "parsing {name} ({value:?}): {e}");
+///     Ok(port)
+/// }
+///
+/// assert_eq!(port("http", "80"), Ok(80));
+/// assert_eq!(
+///     port("http", "eighty"),
+///     Err("parsing http (\"eighty\"): invalid digit found in string".to_string())
+/// );
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_fmt {
+    ($from:expr, $message:literal $(, $($args:tt)+)?) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from($crate::__private::format!(
+                    $message,
+                    $($($args)+,)?
+                    e = e
+                )));
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    fn load(path: &str, contents: Result<&str, i32>) -> Result<usize, String> {
+        let contents = ok_or_return_fmt!(contents, "loading {path}: error {e}");
+        Ok(contents.len())
+    }
+
+    fn parse(line: usize, value: &str) -> Result<i32, Box<dyn Error>> {
+        let value: i32 = ok_or_return_fmt!(value.parse(), "line {}: {e:?}", line + 1);
+        Ok(value)
+    }
+
+    #[test]
+    fn should_return_formatted_errors() {
+        assert_eq!(load("a.txt", Ok("abc")), Ok(3));
+        assert_eq!(load("b.txt", Err(2)), Err("loading b.txt: error 2".to_string()));
+        assert_eq!(parse(0, "7").unwrap(), 7);
+        assert_eq!(
+            parse(1, "x").unwrap_err().to_string(),
+            "line 2: ParseIntError { kind: InvalidDigit }"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+/// Either push a value into a fixed-capacity collection, such as a `heapless::Vec` or a
+/// `heapless::String`, or return from the current function if it is full. A default return value
+/// can be provided, or the rejected value can be bound and used to compute the return value.
+/// ```
+/// use early_returns::push_or_return;
+/// use heapless::Vec;
+///
+/// fn fill(buffer: &mut Vec<u8, 2>, bytes: &[u8]) -> Result<(), u8> {
+///     for &byte in bytes {
+///         push_or_return!(buffer, byte, rejected => Err(rejected));
+///     }
+///     Ok(())
+/// }
+///
+/// let mut buffer = Vec::new();
+/// assert_eq!(fill(&mut buffer, &[1, 2, 3]), Err(3));
+/// assert_eq!(buffer, [1, 2]);
+/// ```
+#[macro_export]
+macro_rules! push_or_return {
+    ($collection:expr, $value:expr, $r:ident => $result:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err($r) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($collection:expr, $value:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err(_) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($collection:expr, $value:expr, $default_result:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err(_) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either push a value into a fixed-capacity collection, such as a `heapless::Vec` or a
+/// `heapless::String`, or break out of a loop if it is full. If a loop lifetime is specified, that
+/// loop will be exited, otherwise the immediate loop is exited. The rejected value can be bound
+/// and handled before breaking.
+/// ```
+/// use early_returns::push_or_break;
+/// use heapless::Vec;
+///
+/// let mut frame: Vec<u8, 3> = Vec::new();
+/// let mut overflow = None;
+/// for byte in [1, 2, 3, 4, 5] {
+///     push_or_break!(frame, byte, rejected => overflow = Some(rejected));
+/// }
+/// assert_eq!(frame, [1, 2, 3]);
+/// assert_eq!(overflow, Some(4));
+/// ```
+#[macro_export]
+macro_rules! push_or_break {
+    ($collection:expr, $value:expr, $r:ident => $handler:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err($r) => {
+                $handler;
+                break
+            }
+        }
+    }};
+
+    ($collection:expr, $value:expr, $lt:lifetime, $r:ident => $handler:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err($r) => {
+                $handler;
+                break $lt
+            }
+        }
+    }};
+
+    ($collection:expr, $value:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err(_) => {
+                $crate::__private::silent_break();
+                break
+            }
+        }
+    }};
+
+    ($collection:expr, $value:expr, $lt:lifetime) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err(_) => {
+                $crate::__private::silent_break();
+                break $lt
+            }
+        }
+    }};
+}
+
+/// Either push a value into a fixed-capacity collection, such as a `heapless::Vec` or a
+/// `heapless::String`, or continue in a loop if it is full. If a loop lifetime is specified, that
+/// loop will be "continued", otherwise the immediate loop is "continued". The rejected value can
+/// be bound and handled before continuing.
+/// ```
+/// use early_returns::push_or_continue;
+/// use heapless::String;
+///
+/// let mut short: String<4> = String::new();
+/// let mut dropped = 0;
+/// for c in "early returns".chars() {
+///     push_or_continue!(short, c, _rejected => dropped += 1);
+/// }
+/// assert_eq!(short, "earl");
+/// assert_eq!(dropped, 9);
+/// ```
+#[macro_export]
+macro_rules! push_or_continue {
+    ($collection:expr, $value:expr, $r:ident => $handler:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err($r) => {
+                $handler;
+                continue
+            }
+        }
+    }};
+
+    ($collection:expr, $value:expr, $lt:lifetime, $r:ident => $handler:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err($r) => {
+                $handler;
+                continue $lt
+            }
+        }
+    }};
+
+    ($collection:expr, $value:expr) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err(_) => {
+                $crate::__private::silent_continue();
+                continue
+            }
+        }
+    }};
+
+    ($collection:expr, $value:expr, $lt:lifetime) => {{
+        match $collection.push($value) {
+            Ok(()) => (),
+            Err(_) => {
+                $crate::__private::silent_continue();
+                continue $lt
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use heapless::Vec;
+
+    fn push_all(buffer: &mut Vec<i32, 2>, values: &[i32]) -> usize {
+        for &value in values {
+            push_or_return!(buffer, value, 0);
+        }
+        buffer.len()
+    }
+
+    fn push_silently(buffer: &mut Vec<i32, 1>, value: i32) {
+        push_or_return!(buffer, value);
+        buffer[0] += 10;
+    }
+
+    fn push_or_reject(buffer: &mut Vec<i32, 1>, value: i32) -> Result<(), i32> {
+        push_or_return!(buffer, value, rejected => Err(rejected * 2));
+        Ok(())
+    }
+
+    #[test]
+    fn should_return_when_full() {
+        assert_eq!(push_all(&mut Vec::new(), &[1, 2]), 2);
+        assert_eq!(push_all(&mut Vec::new(), &[1, 2, 3]), 0);
+        let mut buffer = Vec::new();
+        push_silently(&mut buffer, 1);
+        push_silently(&mut buffer, 2);
+        assert_eq!(buffer, [11]);
+        let mut buffer = Vec::new();
+        assert_eq!(push_or_reject(&mut buffer, 1), Ok(()));
+        assert_eq!(push_or_reject(&mut buffer, 2), Err(4));
+    }
+
+    #[test]
+    fn should_break_when_full() {
+        let mut buffer: Vec<i32, 2> = Vec::new();
+        for value in 0..5 {
+            push_or_break!(buffer, value);
+        }
+        assert_eq!(buffer, [0, 1]);
+        let mut rejected = Vec::<i32, 4>::new();
+        let mut buffer: Vec<i32, 1> = Vec::new();
+        'l: for value in 0..5 {
+            for _i in 0..2 {
+                push_or_break!(buffer, value, 'l, r => rejected.push(r).unwrap());
+            }
+        }
+        assert_eq!((buffer.as_slice(), rejected.as_slice()), ([0].as_slice(), [0].as_slice()));
+    }
+
+    #[test]
+    fn should_continue_when_full() {
+        let mut buffer: Vec<i32, 2> = Vec::new();
+        let mut skipped = 0;
+        for value in 0..5 {
+            push_or_continue!(buffer, value, _r => skipped += 1);
+        }
+        assert_eq!((buffer.as_slice(), skipped), ([0, 1].as_slice(), 3));
+        let mut buffer: Vec<i32, 3> = Vec::new();
+        let mut attempts = 0;
+        'l: for value in 0..3 {
+            for _i in 0..2 {
+                attempts += 1;
+                push_or_continue!(buffer, value, 'l);
+            }
+        }
+        assert_eq!((buffer.as_slice(), attempts), ([0, 0, 1].as_slice(), 5));
+    }
+}
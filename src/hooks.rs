@@ -0,0 +1,470 @@
+//! An opt-in, process-wide hook that the `*_hooked` macros call before taking their escape path.
+//!
+//! Install a hook with [`set_hook`] to assert on unexpected early exits in tests or to forward
+//! them to telemetry in production. Without an installed hook the `*_hooked` macros behave like
+//! their plain counterparts.
+//! ```
+//! use early_returns::hooks::{self, EarlyReturnKind};
+//! use early_returns::some_or_return_hooked;
+//!
+//! hooks::set_hook(|info| {
+//!     assert_eq!(info.kind, EarlyReturnKind::Return);
+//!     eprintln!("{}:{}:{} returned early: {}", info.file, info.line, info.column, info.reason);
+//! });
+//!
+//! fn frobnicate(frobnicator: Option<&str>) -> Option<usize> {
+//!     let frobnicator = some_or_return_hooked!(frobnicator, None, reason: "missing frobnicator");
+//!     Some(frobnicator.len())
+//! }
+//!
+//! assert_eq!(frobnicate(None), None);
+//! let _ = hooks::take_hook();
+//! ```
+
+use core::panic::Location;
+use std::sync::{Arc, RwLock};
+
+pub use crate::reason::EarlyReturnKind;
+
+/// An installed hook.
+pub type Hook = Arc<dyn Fn(&EarlyReturnInfo<'_>) + Send + Sync>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// What the installed hook is told about an early exit.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct EarlyReturnInfo<'a> {
+    /// The file of the macro invocation.
+    pub file: &'static str,
+    /// The line of the macro invocation.
+    pub line: u32,
+    /// The column of the macro invocation.
+    pub column: u32,
+    /// The escape path that is about to be taken.
+    pub kind: EarlyReturnKind,
+    /// The reason given at the macro invocation.
+    pub reason: &'a str,
+}
+
+/// Installs the hook called by the `*_hooked` macros, replacing any previously installed hook.
+pub fn set_hook<F>(hook: F)
+where
+    F: Fn(&EarlyReturnInfo<'_>) + Send + Sync + 'static,
+{
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Removes the installed hook, returning it if there was one.
+pub fn take_hook() -> Option<Hook> {
+    HOOK.write().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Calls the installed hook, if any, with the details of an early exit at the location of the
+/// macro invocation.
+#[doc(hidden)]
+#[track_caller]
+pub fn call_hook(kind: EarlyReturnKind, reason: &str) {
+    call_hook_at(Location::caller(), kind, reason);
+}
+
+pub(crate) fn call_hook_at(
+    location: &'static Location<'static>,
+    kind: EarlyReturnKind,
+    reason: &str,
+) {
+    // The lock is released before the hook runs, so the hook can replace or remove itself.
+    let hook = HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(hook) = hook {
+        hook(&EarlyReturnInfo {
+            file: location.file(),
+            line: location.line(),
+            column: location.column(),
+            kind,
+            reason,
+        });
+    }
+}
+
+/// Either get the value from an Option type or return from the current function, calling the hook
+/// installed with `early_returns::hooks::set_hook` first if there is none. A default return value
+/// can be provided before the reason.
+/// ```
+/// use early_returns::some_or_return_hooked;
+///
+/// fn frobnicate(frobnicator: Option<&str>) -> Option<usize> {
+///     let frobnicator = some_or_return_hooked!(frobnicator, None, reason: "missing frobnicator");
+///     Some(frobnicator.len())
+/// }
+///
+/// assert_eq!(frobnicate(Some("abc")), Some(3));
+/// assert_eq!(frobnicate(None), None);
+/// ```
+#[macro_export]
+macro_rules! some_or_return_hooked {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Return, $reason);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Return, $reason);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or break out of a loop, calling the hook installed with
+/// `early_returns::hooks::set_hook` first if there is none. If a loop lifetime is specified (before
+/// the reason), that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::some_or_break_hooked;
+///
+/// let mut read = Vec::new();
+/// for value in [Some(1), Some(2), None, Some(4)] {
+///     read.push(some_or_break_hooked!(value, reason: "end of input"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! some_or_break_hooked {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Break, $reason);
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Break, $reason);
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or continue in a loop, calling the hook installed with
+/// `early_returns::hooks::set_hook` first if there is none. If a loop lifetime is specified (before
+/// the reason), that loop will be "continued", otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::some_or_continue_hooked;
+///
+/// let mut read = Vec::new();
+/// for value in [Some(1), None, Some(3)] {
+///     read.push(some_or_continue_hooked!(value, reason: "skipped row"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! some_or_continue_hooked {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Continue, $reason);
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Continue, $reason);
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or return from the current function, calling the hook
+/// installed with `early_returns::hooks::set_hook` first if it is an `Err`. A default return value
+/// can be provided before the reason.
+/// ```
+/// use early_returns::ok_or_return_hooked;
+///
+/// fn port(value: &str) {
+///     let port: u16 = ok_or_return_hooked!(value.parse(), reason: "bad port");
+///     println!("listening on {port}");
+/// }
+///
+/// port("8080");
+/// port("http");
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_hooked {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Return, $reason);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Return, $reason);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or break out of a loop, calling the hook installed
+/// with `early_returns::hooks::set_hook` first if it is an `Err`. If a loop lifetime is specified
+/// (before the reason), that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::ok_or_break_hooked;
+///
+/// let mut read = Vec::new();
+/// for value in [Ok(1), Ok(2), Err("eof"), Ok(4)] {
+///     read.push(ok_or_break_hooked!(value, reason: "end of input"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_break_hooked {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Break, $reason);
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Break, $reason);
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or continue in a loop, calling the hook installed
+/// with `early_returns::hooks::set_hook` first if it is an `Err`. If a loop lifetime is specified
+/// (before the reason), that loop will be "continued", otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::ok_or_continue_hooked;
+///
+/// let mut read = Vec::new();
+/// for value in [Ok(1), Err("bad row"), Ok(3)] {
+///     read.push(ok_or_continue_hooked!(value, reason: "skipped row"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_continue_hooked {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Continue, $reason);
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::hooks::call_hook($crate::hooks::EarlyReturnKind::Continue, $reason);
+                continue $lt;
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::{set_hook, take_hook, EarlyReturnKind};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Mutex, MutexGuard};
+
+    static SEEN: Mutex<Vec<(EarlyReturnKind, String, u32)>> = Mutex::new(Vec::new());
+    // Held by every test, as some of them install a hook of their own.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    fn init() -> MutexGuard<'static, ()> {
+        let serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        set_hook(|info| {
+            // The hook is process-wide, so it also sees the early returns of other tests.
+            if info.file != "src/hooks.rs" {
+                return;
+            }
+            SEEN.lock()
+                .unwrap()
+                .push((info.kind, info.reason.to_string(), info.line));
+        });
+        serial
+    }
+
+    fn seen(reason: &str) -> Vec<EarlyReturnKind> {
+        let seen = SEEN.lock().unwrap();
+        seen.iter()
+            .filter(|(_, r, _)| r == reason)
+            .map(|(kind, _, _)| *kind)
+            .collect()
+    }
+
+    fn seen_at(reason: &str) -> Vec<u32> {
+        let seen = SEEN.lock().unwrap();
+        seen.iter()
+            .filter(|(_, r, _)| r == reason)
+            .map(|(_, _, line)| *line)
+            .collect()
+    }
+
+    const VALUE_MISSING_LINE: u32 = line!() + 2;
+    fn value_or_default(value: Option<i32>) -> i32 {
+        some_or_return_hooked!(value, -1, reason: "value missing")
+    }
+
+    fn push_ok(value: Result<i32, ()>, values: &mut Vec<i32>) {
+        let reason = format!("push {}", "failed");
+        values.push(ok_or_return_hooked!(value, reason: &reason));
+    }
+
+    #[test]
+    fn should_call_hook_and_return_early() {
+        let _serial = init();
+        assert_eq!(value_or_default(Some(1)), 1);
+        assert_eq!(value_or_default(None), -1);
+        let mut values = Vec::new();
+        push_ok(Ok(1), &mut values);
+        push_ok(Err(()), &mut values);
+        assert_eq!(values, vec![1]);
+        assert_eq!(seen("value missing"), vec![EarlyReturnKind::Return]);
+        assert_eq!(seen_at("value missing"), vec![VALUE_MISSING_LINE]);
+        assert_eq!(seen("push failed"), vec![EarlyReturnKind::Return]);
+    }
+
+    #[test]
+    fn should_call_hook_and_break_or_continue() {
+        let _serial = init();
+        let mut sum = 0;
+        for value in [Some(1), None, Some(3)] {
+            sum += some_or_continue_hooked!(value, reason: "skip some");
+        }
+        'l: for value in [Ok(4), Err(5), Ok(6)] {
+            for _i in 0..2 {
+                sum += ok_or_break_hooked!(value, 'l, reason: "stop ok");
+            }
+        }
+        'l: for value in [Err(()), Ok(1)] {
+            for _i in 0..2 {
+                sum += ok_or_continue_hooked!(value, 'l, reason: "skip ok");
+            }
+        }
+        for value in [None, Some(1)] {
+            sum += some_or_break_hooked!(value, reason: "stop some");
+        }
+        assert_eq!(sum, 14);
+        assert_eq!(seen("skip some"), vec![EarlyReturnKind::Continue]);
+        assert_eq!(seen("stop ok"), vec![EarlyReturnKind::Break]);
+        assert_eq!(seen("skip ok"), vec![EarlyReturnKind::Continue]);
+        assert_eq!(seen("stop some"), vec![EarlyReturnKind::Break]);
+    }
+
+    fn record_positive(value: i32, values: &mut Vec<i32>) {
+        if value < 0 {
+            crate::return_early!(hook "negative");
+        }
+        values.push(value);
+    }
+
+    #[test]
+    fn should_call_hook_on_unconditional_escapes() {
+        let _serial = init();
+        let mut values = Vec::new();
+        record_positive(-1, &mut values);
+        record_positive(1, &mut values);
+        for value in [1, 2, 3] {
+            if value == 1 {
+                crate::continue_early!(hook "odd");
+            }
+            if value == 2 {
+                crate::break_early!(hook "even", hook "stopped");
+            }
+        }
+        assert_eq!(values, [1]);
+        assert_eq!(seen("negative"), vec![EarlyReturnKind::Return]);
+        assert_eq!(seen("odd"), vec![EarlyReturnKind::Continue]);
+        assert_eq!(seen("even"), vec![EarlyReturnKind::Break]);
+        assert_eq!(seen("stopped"), vec![EarlyReturnKind::Break]);
+    }
+
+    const REASON_MISSING_LINE: u32 = line!() + 2;
+    fn reason_or_default(value: Option<i32>) -> i32 {
+        crate::some_or_return_reason!(value, "reason missing", hook, -1)
+    }
+
+    #[test]
+    fn should_call_hook_with_reasons() {
+        let _serial = init();
+        assert_eq!((reason_or_default(Some(1)), reason_or_default(None)), (1, -1));
+        let mut sum = 0;
+        'l: for value in [Ok(1), Err(()), Ok(3)] {
+            for _i in 0..2 {
+                sum += crate::ok_or_continue_reason!(value, "reason skip", hook, 'l);
+            }
+        }
+        for value in [Some(4), None, Some(5)] {
+            sum += crate::some_or_break_reason!(value, "reason stop", hook);
+        }
+        assert_eq!(sum, 12);
+        assert_eq!(seen("reason missing"), vec![EarlyReturnKind::Return]);
+        assert_eq!(seen_at("reason missing"), vec![REASON_MISSING_LINE]);
+        assert_eq!(seen("reason skip"), vec![EarlyReturnKind::Continue]);
+        assert_eq!(seen("reason stop"), vec![EarlyReturnKind::Break]);
+    }
+
+    static TAKEN: AtomicU32 = AtomicU32::new(0);
+
+    fn taken_or_default(value: Option<i32>) -> i32 {
+        some_or_return_hooked!(value, -1, reason: "take hook")
+    }
+
+    #[test]
+    fn should_let_the_hook_remove_itself() {
+        let _serial = init();
+        set_hook(|info| {
+            if info.reason == "take hook" {
+                TAKEN.fetch_add(1, Ordering::SeqCst);
+                assert!(take_hook().is_some());
+            }
+        });
+        assert_eq!((taken_or_default(None), taken_or_default(None)), (-1, -1));
+        assert_eq!(TAKEN.load(Ordering::SeqCst), 1);
+        assert!(take_hook().is_none());
+    }
+}
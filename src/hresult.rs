@@ -0,0 +1,119 @@
+/// Either get an `HRESULT` if it is a success code or return from the current function if it is a
+/// failure code. A default return value can be provided, or the failing `HRESULT` can be bound and
+/// used to produce the return value. With `Err` (e.g. `hr_ok_or_return!(hr, Err)`), the failure
+/// is converted into a `windows::core::Error` and returned as `Err(error.into())`, like `?` does.
+///
+/// Works with the `HRESULT` of the `windows`, `windows-core` and `windows-result` crates and
+/// requires the `windows` feature.
+/// ```
+/// use early_returns::hr_ok_or_return;
+/// use windows_result::{Error, HRESULT};
+///
+/// const S_OK: HRESULT = HRESULT(0);
+/// const E_FAIL: HRESULT = HRESULT(0x80004005_u32 as i32);
+///
+/// fn initialize(hr: HRESULT) -> Result<(), Error> {
+///     hr_ok_or_return!(hr, Err);
+///     Ok(())
+/// }
+///
+/// fn failing_code(hr: HRESULT) -> Option<i32> {
+///     hr_ok_or_return!(hr, failed => Some(failed.0));
+///     None
+/// }
+///
+/// assert!(initialize(S_OK).is_ok());
+/// assert_eq!(initialize(E_FAIL).unwrap_err().code(), E_FAIL);
+/// assert_eq!(failing_code(S_OK), None);
+/// assert_eq!(failing_code(E_FAIL), Some(E_FAIL.0));
+/// ```
+#[macro_export]
+macro_rules! hr_ok_or_return {
+    ($hr:expr, Err) => {{
+        let hr = $hr;
+        if let Err(e) = hr.ok() {
+            $crate::__mark_early_exit!();
+            return Err(::core::convert::From::from(e));
+        }
+        hr
+    }};
+
+    ($hr:expr, $failed:ident => $result:expr) => {{
+        let hr = $hr;
+        if hr.is_err() {
+            let $failed = hr;
+            $crate::__mark_early_exit!();
+            return $result;
+        }
+        hr
+    }};
+
+    ($hr:expr) => {{
+        let hr = $hr;
+        if hr.is_err() {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return;
+        }
+        hr
+    }};
+
+    ($hr:expr, $default_result:expr) => {{
+        let hr = $hr;
+        if hr.is_err() {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        }
+        hr
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use windows_result::{Error, HRESULT};
+
+    const S_OK: HRESULT = HRESULT(0);
+    const S_FALSE: HRESULT = HRESULT(1);
+    const E_FAIL: HRESULT = HRESULT(0x80004005_u32 as i32);
+
+    #[derive(Debug)]
+    struct AppError(HRESULT);
+
+    impl From<Error> for AppError {
+        fn from(error: Error) -> AppError {
+            AppError(error.code())
+        }
+    }
+
+    fn push_ok(hr: HRESULT, codes: &mut Vec<HRESULT>) {
+        codes.push(hr_ok_or_return!(hr));
+    }
+
+    fn ok_or_default(hr: HRESULT) -> i32 {
+        hr_ok_or_return!(hr, -1).0
+    }
+
+    fn ok_or_failed(hr: HRESULT) -> Result<HRESULT, HRESULT> {
+        Ok(hr_ok_or_return!(hr, failed => Err(failed)))
+    }
+
+    fn ok_or_error(hr: HRESULT) -> Result<HRESULT, AppError> {
+        Ok(hr_ok_or_return!(hr, Err))
+    }
+
+    #[test]
+    fn should_return_early_on_failure_codes() {
+        let mut codes = Vec::new();
+        push_ok(S_OK, &mut codes);
+        push_ok(E_FAIL, &mut codes);
+        push_ok(S_FALSE, &mut codes);
+        assert_eq!(codes, vec![S_OK, S_FALSE]);
+        assert_eq!(ok_or_default(S_FALSE), 1);
+        assert_eq!(ok_or_default(E_FAIL), -1);
+        assert_eq!(ok_or_failed(S_OK), Ok(S_OK));
+        assert_eq!(ok_or_failed(E_FAIL), Err(E_FAIL));
+        assert_eq!(ok_or_error(S_FALSE).unwrap(), S_FALSE);
+        assert_eq!(ok_or_error(E_FAIL).unwrap_err().0, E_FAIL);
+    }
+}
@@ -0,0 +1,137 @@
+/// Either get the value from an Option type or call a closure and return from the current
+/// function. A default return value can be provided before the closure, and is returned as is:
+/// the closure only observes the failure, so it can log or count it without deciding the fallback.
+/// ```
+/// use early_returns::some_or_return_inspect;
+///
+/// fn port(config: &[(&str, u16)], misses: &mut u32) -> u16 {
+///     let (_, port) = some_or_return_inspect!(
+///         config.iter().find(|(key, _)| *key == "port"),
+///         8080,
+///         || *misses += 1
+///     );
+///     *port
+/// }
+///
+/// let mut misses = 0;
+/// assert_eq!(port(&[("port", 80)], &mut misses), 80);
+/// assert_eq!(port(&[], &mut misses), 8080);
+/// assert_eq!(misses, 1);
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! some_or_return_inspect {
+    ($from:expr, $inspect:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            ($inspect)();
+            $crate::__mark_early_exit!();
+            return;
+        })
+    };
+
+    ($from:expr, $default_result:expr, $inspect:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            ($inspect)();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        })
+    };
+}
+
+/// Either get the Ok value from a Result type or call a closure with a reference to the error and
+/// return from the current function, like `Result::inspect_err` followed by `ok_or_return!`. A
+/// default return value can be provided before the closure, and is returned as is: the closure
+/// only observes the error, so it can log or count it without deciding the fallback.
+/// ```
+/// use early_returns::ok_or_return_inspect_err;
+///
+/// fn port(value: &str, errors: &mut Vec<String>) -> u16 {
+///     ok_or_return_inspect_err!(value.parse(), 8080, |e| errors.push(format!("{value:?}: {e}")))
+/// }
+///
+/// let mut errors = Vec::new();
+/// assert_eq!(port("80", &mut errors), 80);
+/// assert_eq!(port("eighty", &mut errors), 8080);
+/// assert_eq!(errors, ["\"eighty\": invalid digit found in string"]);
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_return_inspect_err {
+    ($from:expr, $inspect:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::inspect_err(&e, $inspect);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, $inspect:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::inspect_err(&e, $inspect);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+// Fixes the parameter type of the closure given to `ok_or_return_inspect_err!`, which could not
+// be inferred from a direct call.
+#[cfg(feature = "result")]
+#[inline]
+pub fn inspect_err<E>(e: &E, inspect: impl FnOnce(&E)) {
+    inspect(e)
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    #[cfg(feature = "option")]
+    fn push_some(value: Option<i32>, values: &mut Vec<i32>, misses: &Cell<u32>) {
+        values.push(some_or_return_inspect!(value, || misses.set(misses.get() + 1)));
+    }
+
+    #[cfg(feature = "result")]
+    fn double(value: Result<i32, &str>, errors: &mut Vec<String>) -> Result<i32, ()> {
+        let value = ok_or_return_inspect_err!(value, Err(()), |e| errors.push(e.to_string()));
+        Ok(value * 2)
+    }
+
+    #[cfg(feature = "option")]
+    #[test]
+    fn should_inspect_none_before_returning() {
+        let (mut values, misses) = (Vec::new(), Cell::new(0));
+        push_some(Some(1), &mut values, &misses);
+        push_some(None, &mut values, &misses);
+        assert_eq!((values, misses.get()), (vec![1], 1));
+        let fallback = |value: Option<i32>| -> i32 {
+            some_or_return_inspect!(value, -1, || misses.set(10))
+        };
+        assert_eq!((fallback(Some(2)), misses.get()), (2, 1));
+        assert_eq!((fallback(None), misses.get()), (-1, 10));
+    }
+
+    #[cfg(feature = "result")]
+    #[test]
+    fn should_inspect_errors_before_returning() {
+        let mut errors = Vec::new();
+        assert_eq!(double(Ok(2), &mut errors), Ok(4));
+        assert_eq!(double(Err("bad"), &mut errors), Err(()));
+        assert_eq!(errors, ["bad"]);
+        let seen = Cell::new(0);
+        let record = |value: Result<i32, i32>| {
+            let value = ok_or_return_inspect_err!(value, |e| seen.set(*e));
+            seen.set(value);
+        };
+        record(Err(3));
+        assert_eq!(seen.get(), 3);
+        record(Ok(5));
+        assert_eq!(seen.get(), 5);
+    }
+}
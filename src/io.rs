@@ -0,0 +1,306 @@
+/// Either get the Ok value of an io Result, or continue in a loop if the error is of one of the
+/// given `std::io::ErrorKind`s, or return from the current function for any other error. If a
+/// loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+///
+/// Other errors are returned as `Err(e.into())`, like `?` does, or the error can be bound and used
+/// to produce the return value. Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::ok_or_continue_if;
+/// use std::io::{self, ErrorKind};
+///
+/// fn poll(results: Vec<io::Result<u8>>) -> io::Result<Vec<u8>> {
+///     let mut received = Vec::new();
+///     for result in results {
+///         received.push(ok_or_continue_if!(result, WouldBlock | TimedOut));
+///     }
+///     Ok(received)
+/// }
+///
+/// let would_block = || Err(io::Error::from(ErrorKind::WouldBlock));
+/// assert_eq!(poll(vec![Ok(1), would_block(), Ok(2)]).unwrap(), vec![1, 2]);
+/// let reset = Err(io::Error::from(ErrorKind::ConnectionReset));
+/// assert_eq!(poll(vec![Ok(1), reset]).unwrap_err().kind(), ErrorKind::ConnectionReset);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! ok_or_continue_if {
+    ($from:expr, $($kind:ident)|+, $e:ident => $result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) if matches!(e.kind(), $($crate::__private::io::ErrorKind::$kind)|+) => {
+                $crate::__private::silent_continue();
+                continue;
+            }
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr, $($kind:ident)|+, $lt:lifetime, $e:ident => $result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) if matches!(e.kind(), $($crate::__private::io::ErrorKind::$kind)|+) => {
+                $crate::__private::silent_continue();
+                continue $lt;
+            }
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr, $($kind:ident)|+) => {
+        $crate::ok_or_continue_if!($from, $($kind)|+, e => Err(::core::convert::From::from(e)))
+    };
+
+    ($from:expr, $($kind:ident)|+, $lt:lifetime) => {
+        $crate::ok_or_continue_if!(
+            $from, $($kind)|+, $lt, e => Err(::core::convert::From::from(e))
+        )
+    };
+}
+
+/// Either get the Ok value of an io Result, or return from the current function if the error is
+/// of one of the given `std::io::ErrorKind`s, or continue in a loop for any other error. If a loop
+/// lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+///
+/// Errors of the given kinds are returned as `Err(e.into())`, like `?` does, or the error can be
+/// bound and used to produce the return value. Requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::ok_or_return_if;
+/// use std::io::{self, ErrorKind};
+///
+/// fn serve(requests: Vec<io::Result<u8>>) -> &'static str {
+///     let mut served = 0;
+///     for request in requests {
+///         ok_or_return_if!(request, BrokenPipe | ConnectionReset, _e => "disconnected");
+///         served += 1;
+///     }
+///     if served > 0 { "served" } else { "idle" }
+/// }
+///
+/// let invalid = || Err(io::Error::from(ErrorKind::InvalidData));
+/// assert_eq!(serve(vec![Ok(1), invalid()]), "served");
+/// assert_eq!(serve(vec![invalid()]), "idle");
+/// let reset = Err(io::Error::from(ErrorKind::ConnectionReset));
+/// assert_eq!(serve(vec![Ok(1), reset, Ok(2)]), "disconnected");
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! ok_or_return_if {
+    ($from:expr, $($kind:ident)|+, $e:ident => $result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) if matches!($e.kind(), $($crate::__private::io::ErrorKind::$kind)|+) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+            Err(_) => {
+                $crate::__private::silent_continue();
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $($kind:ident)|+, $lt:lifetime, $e:ident => $result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) if matches!($e.kind(), $($crate::__private::io::ErrorKind::$kind)|+) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+            Err(_) => {
+                $crate::__private::silent_continue();
+                continue $lt;
+            }
+        }
+    }};
+
+    ($from:expr, $($kind:ident)|+) => {
+        $crate::ok_or_return_if!($from, $($kind)|+, e => Err(::core::convert::From::from(e)))
+    };
+
+    ($from:expr, $($kind:ident)|+, $lt:lifetime) => {
+        $crate::ok_or_return_if!(
+            $from, $($kind)|+, $lt, e => Err(::core::convert::From::from(e))
+        )
+    };
+}
+
+/// Either get the Ok value of an io expression, evaluating it again for as long as it fails with
+/// `std::io::ErrorKind::Interrupted`, or return from the current function for any other error.
+///
+/// Other errors are returned as `Err(e.into())`, like `?` does. A default return value can be
+/// provided instead, or the error can be bound and used to produce the return value. Requires the
+/// `std` feature (enabled by default).
+/// ```
+/// use early_returns::retry_on_interrupted;
+/// use std::io::{self, ErrorKind, Read};
+///
+/// struct Flaky(Vec<io::Result<u8>>);
+///
+/// impl Read for Flaky {
+///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+///         let byte = self.0.remove(0)?;
+///         buf[0] = byte;
+///         Ok(1)
+///     }
+/// }
+///
+/// fn read_byte(reader: &mut impl Read) -> io::Result<u8> {
+///     let mut buf = [0];
+///     retry_on_interrupted!(reader.read(&mut buf));
+///     Ok(buf[0])
+/// }
+///
+/// let interrupted = || Err(io::Error::from(ErrorKind::Interrupted));
+/// let mut reader = Flaky(vec![interrupted(), interrupted(), Ok(7)]);
+/// assert_eq!(read_byte(&mut reader).unwrap(), 7);
+/// let mut reader = Flaky(vec![Err(io::Error::from(ErrorKind::UnexpectedEof))]);
+/// assert_eq!(read_byte(&mut reader).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! retry_on_interrupted {
+    ($from:expr, $e:ident => $result:expr) => {
+        loop {
+            match $from {
+                Ok(f) => break f,
+                Err(e) if e.kind() == $crate::__private::io::ErrorKind::Interrupted => {}
+                Err($e) => {
+                    $crate::__mark_early_exit!();
+                    return $result;
+                }
+            }
+        }
+    };
+
+    ($from:expr) => {
+        $crate::retry_on_interrupted!($from, e => Err(::core::convert::From::from(e)))
+    };
+
+    ($from:expr, $default_result:expr) => {
+        loop {
+            match $from {
+                Ok(f) => break f,
+                Err(e) if e.kind() == $crate::__private::io::ErrorKind::Interrupted => {}
+                Err(_) => {
+                    $crate::__private::silent_return();
+                    $crate::__mark_early_exit!();
+                    return $default_result;
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, ErrorKind};
+
+    fn error(kind: ErrorKind) -> io::Result<i32> {
+        Err(io::Error::from(kind))
+    }
+
+    fn sum_continuing(results: Vec<io::Result<i32>>) -> io::Result<i32> {
+        let mut sum = 0;
+        for result in results {
+            sum += ok_or_continue_if!(result, WouldBlock);
+        }
+        Ok(sum)
+    }
+
+    fn sum_continuing_with_lifetime(results: Vec<io::Result<i32>>) -> Result<i32, ErrorKind> {
+        let mut sum = 0;
+        'l: for result in results {
+            for _i in 0..1 {
+                sum += ok_or_continue_if!(result, WouldBlock | TimedOut, 'l, e => Err(e.kind()));
+            }
+        }
+        Ok(sum)
+    }
+
+    fn sum_returning(results: Vec<io::Result<i32>>) -> io::Result<i32> {
+        let mut sum = 0;
+        'l: for result in results {
+            for _i in 0..1 {
+                sum += ok_or_return_if!(result, UnexpectedEof | BrokenPipe, 'l);
+            }
+        }
+        Ok(sum)
+    }
+
+    fn sum_returning_kind(results: Vec<io::Result<i32>>) -> Result<i32, ErrorKind> {
+        let mut sum = 0;
+        for result in results {
+            sum += ok_or_return_if!(result, UnexpectedEof, e => Err(e.kind()));
+        }
+        Ok(sum)
+    }
+
+    #[test]
+    fn should_continue_on_listed_kinds() {
+        let results = vec![Ok(1), error(ErrorKind::WouldBlock), Ok(2)];
+        assert_eq!(sum_continuing(results).unwrap(), 3);
+        let results = vec![Ok(1), error(ErrorKind::TimedOut), Ok(2)];
+        assert_eq!(sum_continuing_with_lifetime(results), Ok(3));
+        let results = vec![Ok(1), error(ErrorKind::InvalidData), Ok(2)];
+        assert_eq!(sum_returning(results).unwrap(), 3);
+        let results = vec![Ok(1), error(ErrorKind::WouldBlock), Ok(2)];
+        assert_eq!(sum_returning_kind(results), Ok(3));
+    }
+
+    #[test]
+    fn should_return_on_other_kinds() {
+        let results = vec![Ok(1), error(ErrorKind::TimedOut), Ok(2)];
+        assert_eq!(sum_continuing(results).unwrap_err().kind(), ErrorKind::TimedOut);
+        let results = vec![Ok(1), error(ErrorKind::NotFound), Ok(2)];
+        assert_eq!(sum_continuing_with_lifetime(results), Err(ErrorKind::NotFound));
+        let results = vec![Ok(1), error(ErrorKind::BrokenPipe), Ok(2)];
+        assert_eq!(sum_returning(results).unwrap_err().kind(), ErrorKind::BrokenPipe);
+        let results = vec![Ok(1), error(ErrorKind::UnexpectedEof), Ok(2)];
+        assert_eq!(sum_returning_kind(results), Err(ErrorKind::UnexpectedEof));
+    }
+
+    fn next_result(results: &mut Vec<io::Result<i32>>) -> io::Result<i32> {
+        results.remove(0)
+    }
+
+    fn retried(results: &mut Vec<io::Result<i32>>) -> io::Result<i32> {
+        Ok(retry_on_interrupted!(next_result(results)))
+    }
+
+    fn retried_or_default(results: &mut Vec<io::Result<i32>>) -> i32 {
+        retry_on_interrupted!(next_result(results), -1)
+    }
+
+    fn retried_or_kind(results: &mut Vec<io::Result<i32>>) -> Result<i32, ErrorKind> {
+        Ok(retry_on_interrupted!(next_result(results), e => Err(e.kind())))
+    }
+
+    #[test]
+    fn should_retry_while_interrupted() {
+        let mut results = vec![error(ErrorKind::Interrupted), error(ErrorKind::Interrupted), Ok(1)];
+        assert_eq!(retried(&mut results).unwrap(), 1);
+        let mut results = vec![error(ErrorKind::Interrupted), Ok(2)];
+        assert_eq!(retried_or_default(&mut results), 2);
+        let mut results = vec![error(ErrorKind::Interrupted), Ok(3)];
+        assert_eq!(retried_or_kind(&mut results), Ok(3));
+    }
+
+    #[test]
+    fn should_return_on_other_errors() {
+        let mut results = vec![error(ErrorKind::Interrupted), error(ErrorKind::NotFound), Ok(1)];
+        assert_eq!(retried(&mut results).unwrap_err().kind(), ErrorKind::NotFound);
+        assert_eq!(results.len(), 1);
+        let mut results = vec![error(ErrorKind::Other), Ok(2)];
+        assert_eq!(retried_or_default(&mut results), -1);
+        let mut results = vec![error(ErrorKind::Interrupted), error(ErrorKind::TimedOut)];
+        assert_eq!(retried_or_kind(&mut results), Err(ErrorKind::TimedOut));
+    }
+}
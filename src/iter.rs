@@ -0,0 +1,694 @@
+//! Iterator adapters that take the escape paths of the macros inside iterator pipelines, where
+//! `break` and `continue` cannot reach.
+//!
+//! The adapters are provided by [`EarlyIteratorExt`], which is implemented for every iterator.
+
+#[cfg(feature = "log")]
+use core::fmt;
+use core::iter::FusedIterator;
+#[cfg(feature = "log")]
+use core::panic::Location;
+
+/// Either get the next item of an iterator or return from the current function once the iterator
+/// is exhausted. A default return value can be provided.
+///
+/// With the `async` marker and the `futures` feature, the next item of a `Stream` is awaited
+/// instead (`next_or_return!(async stream)`), with `futures::StreamExt::next`.
+/// ```
+/// use early_returns::next_or_return;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct UnexpectedEof;
+///
+/// fn parse_pair<'a, I>(tokens: &mut I) -> Result<(&'a str, &'a str), UnexpectedEof>
+/// where
+///     I: Iterator<Item = &'a str>,
+/// {
+///     let key = next_or_return!(tokens, Err(UnexpectedEof));
+///     let value = next_or_return!(tokens, Err(UnexpectedEof));
+///     Ok((key, value))
+/// }
+///
+/// let mut tokens = "name ada age".split(' ');
+/// assert_eq!(parse_pair(&mut tokens), Ok(("name", "ada")));
+/// assert_eq!(parse_pair(&mut tokens), Err(UnexpectedEof));
+/// ```
+#[cfg(feature = "iterators")]
+#[macro_export]
+macro_rules! next_or_return {
+    (async $stream:expr) => {
+        $crate::some_or_return!($crate::__stream_next!($stream))
+    };
+
+    (async $stream:expr, $default_result:expr) => {
+        $crate::some_or_return!($crate::__stream_next!($stream), $default_result)
+    };
+
+    ($iter:expr) => {
+        $crate::some_or_return!($iter.next())
+    };
+
+    ($iter:expr, $default_result:expr) => {
+        $crate::some_or_return!($iter.next(), $default_result)
+    };
+}
+
+/// Either get the next item of an iterator or break out of a loop once the iterator is exhausted.
+/// If a loop lifetime is specified, that loop will be exited, otherwise the immediate loop is
+/// exited.
+/// ```
+/// use early_returns::next_or_break;
+///
+/// let mut chars = "a=1;b=2".chars();
+/// let mut keys = String::new();
+/// loop {
+///     keys.push(next_or_break!(chars));
+///     loop {
+///         if next_or_break!(chars) == ';' {
+///             break;
+///         }
+///     }
+/// }
+/// assert_eq!(keys, "ab");
+/// ```
+///
+/// With the `async` marker and the `futures` feature, the next item of a `Stream` is awaited
+/// instead, so async consumption loops read like their sync counterparts:
+#[cfg_attr(feature = "futures", doc = "```")]
+#[cfg_attr(not(feature = "futures"), doc = "```ignore")]
+/// use early_returns::next_or_break;
+/// use futures_util::stream::{self, Stream};
+///
+/// async fn sum(mut numbers: impl Stream<Item = i32> + Unpin) -> i32 {
+///     let mut sum = 0;
+///     loop {
+///         sum += next_or_break!(async numbers);
+///     }
+///     sum
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// assert_eq!(sum(stream::iter([1, 2, 3])).await, 6);
+/// # });
+/// ```
+#[cfg(feature = "iterators")]
+#[macro_export]
+macro_rules! next_or_break {
+    (async $stream:expr) => {
+        $crate::some_or_break!($crate::__stream_next!($stream))
+    };
+
+    (async $stream:expr, $lt:lifetime) => {
+        $crate::some_or_break!($crate::__stream_next!($stream), $lt)
+    };
+
+    ($iter:expr) => {
+        $crate::some_or_break!($iter.next())
+    };
+
+    ($iter:expr, $lt:lifetime) => {
+        $crate::some_or_break!($iter.next(), $lt)
+    };
+}
+
+/// Either peek at the next item of a `Peekable` iterator or return from the current function once
+/// the iterator is exhausted. A default return value can be provided. The item is not consumed,
+/// so a reference to it is "extracted".
+/// ```
+/// use early_returns::peek_or_return;
+///
+/// fn starts_with_digit(chars: &mut std::iter::Peekable<std::str::Chars>) -> bool {
+///     let c = peek_or_return!(chars, false);
+///     c.is_ascii_digit()
+/// }
+///
+/// assert!(starts_with_digit(&mut "1a".chars().peekable()));
+/// assert!(!starts_with_digit(&mut "".chars().peekable()));
+/// ```
+#[cfg(feature = "iterators")]
+#[macro_export]
+macro_rules! peek_or_return {
+    ($iter:expr) => {
+        $crate::some_or_return!($iter.peek())
+    };
+
+    ($iter:expr, $default_result:expr) => {
+        $crate::some_or_return!($iter.peek(), $default_result)
+    };
+}
+
+/// Either peek at the next item of a `Peekable` iterator or break out of a loop once the iterator
+/// is exhausted. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited. The item is not consumed, so a reference to it is "extracted".
+/// ```
+/// use early_returns::peek_or_break;
+///
+/// let mut chars = "123abc".chars().peekable();
+/// let mut number = 0;
+/// loop {
+///     let digit = match peek_or_break!(chars).to_digit(10) {
+///         Some(digit) => digit,
+///         None => break,
+///     };
+///     number = number * 10 + digit;
+///     chars.next();
+/// }
+/// assert_eq!(number, 123);
+/// assert_eq!(chars.collect::<String>(), "abc");
+/// ```
+#[cfg(feature = "iterators")]
+#[macro_export]
+macro_rules! peek_or_break {
+    ($iter:expr) => {
+        $crate::some_or_break!($iter.peek())
+    };
+
+    ($iter:expr, $lt:lifetime) => {
+        $crate::some_or_break!($iter.peek(), $lt)
+    };
+}
+
+/// Adapters for iterators over Options and Results, each the pipeline equivalent of one of the
+/// loop macros.
+pub trait EarlyIteratorExt: Iterator + Sized {
+    /// Yield the values of the `Some` items and end at the first `None`, like `some_or_break!` does
+    /// in a loop. The iterator stays exhausted afterwards, even if more items follow.
+    /// ```
+    /// use early_returns::iter::EarlyIteratorExt;
+    ///
+    /// let lengths = ["ab", "c", "", "def"].iter().map(|s| s.chars().next().map(|_| s.len()));
+    /// assert_eq!(lengths.stop_on_none().collect::<Vec<_>>(), [2, 1]);
+    /// ```
+    fn stop_on_none<T>(self) -> StopOnNone<Self>
+    where
+        Self: Iterator<Item = Option<T>>,
+    {
+        StopOnNone { iter: Some(self) }
+    }
+
+    /// Yield the values of the `Ok` items and end at the first `Err`, keeping that error, like
+    /// `while_ok!` does in a loop. The error can be inspected with [`TakeWhileOk::error`] or taken
+    /// with [`TakeWhileOk::finish`], so the adapter is usually consumed with `by_ref()`.
+    /// ```
+    /// use early_returns::iter::EarlyIteratorExt;
+    ///
+    /// fn sum(rows: &[&str]) -> Result<u32, std::num::ParseIntError> {
+    ///     let mut values = rows.iter().map(|row| row.parse::<u32>()).take_while_ok();
+    ///     let sum = values.by_ref().sum();
+    ///     values.finish()?;
+    ///     Ok(sum)
+    /// }
+    ///
+    /// assert_eq!(sum(&["1", "2"]), Ok(3));
+    /// assert!(sum(&["1", "x", "2"]).is_err());
+    /// ```
+    fn take_while_ok<T, E>(self) -> TakeWhileOk<Self, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+    {
+        TakeWhileOk { iter: Some(self), error: None }
+    }
+
+    /// Collect the values of the `Ok` items up to the first `Err`, returning that error alongside
+    /// the partial collection instead of discarding it like collecting into a `Result` does.
+    /// Nothing after the first error is consumed.
+    /// ```
+    /// use early_returns::iter::EarlyIteratorExt;
+    ///
+    /// let rows = ["1", "2", "x", "4"].iter().map(|row| row.parse::<u32>());
+    /// let (parsed, error): (Vec<_>, _) = rows.collect_until_err();
+    /// assert_eq!(parsed, [1, 2]);
+    /// assert!(error.is_some());
+    /// ```
+    fn collect_until_err<C, T, E>(self) -> (C, Option<E>)
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        C: FromIterator<T>,
+    {
+        let mut values = self.take_while_ok();
+        let collection = values.by_ref().collect();
+        (collection, values.finish().err())
+    }
+
+    /// Yield the values of the `Some` items and skip the `None` items, calling `on_skip` with the
+    /// (0-based) position of every skipped item first, like `some_or_continue!` with a side effect
+    /// does in a loop.
+    /// ```
+    /// use early_returns::iter::EarlyIteratorExt;
+    ///
+    /// let mut skipped = Vec::new();
+    /// let values = [Some(1), None, Some(3), None].into_iter().skip_none_with(|i| skipped.push(i));
+    /// assert_eq!(values.collect::<Vec<_>>(), [1, 3]);
+    /// assert_eq!(skipped, [1, 3]);
+    /// ```
+    fn skip_none_with<T, F>(self, on_skip: F) -> SkipNoneWith<Self, F>
+    where
+        Self: Iterator<Item = Option<T>>,
+        F: FnMut(usize),
+    {
+        SkipNoneWith { iter: self, position: 0, on_skip }
+    }
+
+    /// Yield the values of the `Ok` items and skip the `Err` items, calling `on_skip` with every
+    /// error first, like `ok_or_continue!` with a side effect does in a loop.
+    /// ```
+    /// use early_returns::iter::EarlyIteratorExt;
+    ///
+    /// let mut errors = Vec::new();
+    /// let ports = ["80", "http", "443"].iter().map(|p| p.parse::<u16>());
+    /// let ports = ports.skip_err_with(|e| errors.push(e.to_string())).collect::<Vec<_>>();
+    /// assert_eq!(ports, [80, 443]);
+    /// assert_eq!(errors, ["invalid digit found in string"]);
+    /// ```
+    fn skip_err_with<T, E, F>(self, on_skip: F) -> SkipErrWith<Self, F>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        F: FnMut(E),
+    {
+        SkipErrWith { iter: self, on_skip }
+    }
+
+    /// Like [`skip_none_with`](EarlyIteratorExt::skip_none_with), but emit a `log` record at the
+    /// given level for every skipped item. The record contains the message, the position of the
+    /// item and the location of the call to `skip_none_logged`.
+    /// ```
+    /// use early_returns::iter::EarlyIteratorExt;
+    ///
+    /// let rows = [Some("a"), None, Some("c")].into_iter();
+    /// let rows = rows.skip_none_logged(log::Level::Warn, "missing row").collect::<Vec<_>>();
+    /// assert_eq!(rows, ["a", "c"]);
+    /// ```
+    #[cfg(feature = "log")]
+    #[track_caller]
+    fn skip_none_logged<T, M>(self, level: ::log::Level, message: M) -> SkipNoneLogged<Self, M>
+    where
+        Self: Iterator<Item = Option<T>>,
+        M: fmt::Display,
+    {
+        let location = Location::caller();
+        SkipNoneLogged { iter: self, position: 0, level, message, location }
+    }
+
+    /// Like [`skip_err_with`](EarlyIteratorExt::skip_err_with), but emit a `log` record at the
+    /// given level for every skipped item. The record contains the message, the `Debug` of the
+    /// error, the position of the item and the location of the call to `skip_err_logged`.
+    /// ```
+    /// use early_returns::iter::EarlyIteratorExt;
+    ///
+    /// let ports = ["80", "http", "443"].iter().map(|p| p.parse::<u16>());
+    /// let ports = ports.skip_err_logged(log::Level::Warn, "bad port").collect::<Vec<_>>();
+    /// assert_eq!(ports, [80, 443]);
+    /// ```
+    #[cfg(feature = "log")]
+    #[track_caller]
+    fn skip_err_logged<T, E, M>(self, level: ::log::Level, message: M) -> SkipErrLogged<Self, M>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        E: fmt::Debug,
+        M: fmt::Display,
+    {
+        let location = Location::caller();
+        SkipErrLogged { iter: self, position: 0, level, message, location }
+    }
+}
+
+impl<I: Iterator> EarlyIteratorExt for I {}
+
+/// The iterator returned by [`EarlyIteratorExt::stop_on_none`].
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct StopOnNone<I> {
+    // Dropped at the first `None`, so it is not polled again.
+    iter: Option<I>,
+}
+
+impl<I, T> Iterator for StopOnNone<I>
+where
+    I: Iterator<Item = Option<T>>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let value = self.iter.as_mut()?.next().flatten();
+        if value.is_none() {
+            self.iter = None;
+        }
+        value
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            Some(iter) => (0, iter.size_hint().1),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<I, T> FusedIterator for StopOnNone<I> where I: Iterator<Item = Option<T>> {}
+
+/// The iterator returned by [`EarlyIteratorExt::take_while_ok`].
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct TakeWhileOk<I, E> {
+    // Dropped at the first `Err`, so it is not polled again.
+    iter: Option<I>,
+    error: Option<E>,
+}
+
+impl<I, E> TakeWhileOk<I, E> {
+    /// The error the iterator ended at, if it has ended at one yet.
+    pub fn error(&self) -> Option<&E> {
+        self.error.as_ref()
+    }
+
+    /// Take the error the iterator ended at, if any. Items that have not been yielded yet are not
+    /// inspected, so an iterator that has not been run to its end is `Ok` unless it has already
+    /// ended at an error.
+    pub fn finish(self) -> Result<(), E> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
+
+impl<I, T, E> Iterator for TakeWhileOk<I, E>
+where
+    I: Iterator<Item = Result<T, E>>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self.iter.as_mut()?.next() {
+            Some(Ok(value)) => Some(value),
+            Some(Err(error)) => {
+                self.error = Some(error);
+                self.iter = None;
+                None
+            }
+            None => {
+                self.iter = None;
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            Some(iter) => (0, iter.size_hint().1),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<I, T, E> FusedIterator for TakeWhileOk<I, E> where I: Iterator<Item = Result<T, E>> {}
+
+/// The iterator returned by [`EarlyIteratorExt::skip_none_with`].
+#[derive(Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SkipNoneWith<I, F> {
+    iter: I,
+    position: usize,
+    on_skip: F,
+}
+
+impl<I, T, F> Iterator for SkipNoneWith<I, F>
+where
+    I: Iterator<Item = Option<T>>,
+    F: FnMut(usize),
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        for item in self.iter.by_ref() {
+            let position = self.position;
+            self.position += 1;
+            match item {
+                Some(value) => return Some(value),
+                None => (self.on_skip)(position),
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// The iterator returned by [`EarlyIteratorExt::skip_err_with`].
+#[derive(Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SkipErrWith<I, F> {
+    iter: I,
+    on_skip: F,
+}
+
+impl<I, T, E, F> Iterator for SkipErrWith<I, F>
+where
+    I: Iterator<Item = Result<T, E>>,
+    F: FnMut(E),
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        for item in self.iter.by_ref() {
+            match item {
+                Ok(value) => return Some(value),
+                Err(error) => (self.on_skip)(error),
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// The iterator returned by [`EarlyIteratorExt::skip_none_logged`].
+#[cfg(feature = "log")]
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SkipNoneLogged<I, M> {
+    iter: I,
+    position: usize,
+    level: ::log::Level,
+    message: M,
+    location: &'static Location<'static>,
+}
+
+#[cfg(feature = "log")]
+impl<I, T, M> Iterator for SkipNoneLogged<I, M>
+where
+    I: Iterator<Item = Option<T>>,
+    M: fmt::Display,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        for item in self.iter.by_ref() {
+            let position = self.position;
+            self.position += 1;
+            match item {
+                Some(value) => return Some(value),
+                None => ::log::log!(
+                    self.level,
+                    "{} [skipped item {} at {}]",
+                    self.message,
+                    position,
+                    self.location,
+                ),
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// The iterator returned by [`EarlyIteratorExt::skip_err_logged`].
+#[cfg(feature = "log")]
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SkipErrLogged<I, M> {
+    iter: I,
+    position: usize,
+    level: ::log::Level,
+    message: M,
+    location: &'static Location<'static>,
+}
+
+#[cfg(feature = "log")]
+impl<I, T, E, M> Iterator for SkipErrLogged<I, M>
+where
+    I: Iterator<Item = Result<T, E>>,
+    E: fmt::Debug,
+    M: fmt::Display,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        for item in self.iter.by_ref() {
+            let position = self.position;
+            self.position += 1;
+            match item {
+                Ok(value) => return Some(value),
+                Err(error) => ::log::log!(
+                    self.level,
+                    "{}: {:?} [skipped item {} at {}]",
+                    self.message,
+                    error,
+                    position,
+                    self.location,
+                ),
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EarlyIteratorExt;
+    #[cfg(feature = "iterators")]
+    use std::iter::Peekable;
+    #[cfg(feature = "iterators")]
+    use std::vec::IntoIter;
+
+    #[cfg(feature = "iterators")]
+    struct Tester {
+        tokens: Peekable<IntoIter<i32>>,
+        value: i32,
+    }
+
+    #[cfg(feature = "iterators")]
+    impl Tester {
+        fn new(tokens: Vec<i32>) -> Tester {
+            Tester {
+                tokens: tokens.into_iter().peekable(),
+                value: 0,
+            }
+        }
+
+        fn add_next(&mut self) {
+            self.value += next_or_return!(self.tokens);
+        }
+
+        fn add_peeked(&mut self) {
+            self.value += *peek_or_return!(self.tokens);
+        }
+
+        fn add_all_with_break(&mut self) {
+            loop {
+                if *peek_or_break!(self.tokens) < 0 {
+                    break;
+                }
+                self.value += next_or_break!(self.tokens);
+            }
+        }
+
+        fn add_all_with_break_with_lifetime(&mut self) {
+            'l: loop {
+                for _i in 0..1 {
+                    let peeked = *peek_or_break!(self.tokens, 'l);
+                    self.value += next_or_break!(self.tokens, 'l) * peeked;
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "iterators")]
+    fn next_or_default(tokens: &mut impl Iterator<Item = i32>) -> i32 {
+        next_or_return!(tokens, -1)
+    }
+
+    #[cfg(feature = "iterators")]
+    fn peeked_or_default(tokens: &mut Peekable<IntoIter<i32>>) -> i32 {
+        *peek_or_return!(tokens, -1)
+    }
+
+    #[cfg(feature = "iterators")]
+    #[test]
+    fn should_return_early_when_exhausted() {
+        let mut tester = Tester::new(vec![1]);
+        tester.add_peeked();
+        tester.add_next();
+        tester.add_peeked();
+        tester.add_next();
+        assert_eq!(tester.value, 2);
+        let mut tokens = vec![3].into_iter().peekable();
+        assert_eq!(peeked_or_default(&mut tokens), 3);
+        assert_eq!(next_or_default(&mut tokens), 3);
+        assert_eq!(peeked_or_default(&mut tokens), -1);
+        assert_eq!(next_or_default(&mut tokens), -1);
+    }
+
+    #[cfg(feature = "iterators")]
+    #[test]
+    fn should_break_when_exhausted() {
+        let mut tester = Tester::new(vec![1, 2, -1, 3]);
+        tester.add_all_with_break();
+        assert_eq!(tester.value, 3);
+        tester.add_all_with_break_with_lifetime();
+        assert_eq!(tester.value, 3 + 1 + 9);
+    }
+
+    #[test]
+    fn should_stop_on_first_none() {
+        let items = vec![Some(1), Some(2), None, Some(4)];
+        let mut polled = 0;
+        let mut values = items.into_iter().inspect(|_| polled += 1).stop_on_none();
+        assert_eq!(values.size_hint(), (0, Some(4)));
+        assert_eq!(values.by_ref().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(values.next(), None);
+        assert_eq!(values.size_hint(), (0, Some(0)));
+        drop(values);
+        assert_eq!(polled, 3);
+    }
+
+    #[test]
+    fn should_keep_first_err() {
+        let items: Vec<Result<i32, &str>> = vec![Ok(1), Ok(2), Err("bad"), Ok(4), Err("worse")];
+        let mut values = items.into_iter().take_while_ok();
+        assert_eq!(values.by_ref().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(values.next(), None);
+        assert_eq!(values.error(), Some(&"bad"));
+        assert_eq!(values.finish(), Err("bad"));
+
+        let mut values = vec![Ok::<_, &str>(1)].into_iter().take_while_ok();
+        assert_eq!(values.by_ref().sum::<i32>(), 1);
+        assert_eq!(values.error(), None);
+        assert_eq!(values.finish(), Ok(()));
+    }
+
+    #[test]
+    fn should_report_skipped_items() {
+        let mut skipped = Vec::new();
+        let values = vec![None, Some(1), None, Some(3)].into_iter();
+        let values = values.skip_none_with(|i| skipped.push(i)).collect::<Vec<_>>();
+        assert_eq!(values, [1, 3]);
+        assert_eq!(skipped, [0, 2]);
+
+        let mut errors = Vec::new();
+        let values = vec![Ok(1), Err("bad"), Ok(3), Err("worse")].into_iter();
+        let values = values.skip_err_with(|e| errors.push(e)).collect::<Vec<_>>();
+        assert_eq!(values, [1, 3]);
+        assert_eq!(errors, ["bad", "worse"]);
+    }
+
+    #[test]
+    fn should_collect_until_first_err() {
+        let mut items = vec![Ok(1), Ok(2), Err("bad"), Ok(4)].into_iter();
+        let (values, error): (Vec<_>, _) = items.by_ref().collect_until_err();
+        assert_eq!((values, error), (vec![1, 2], Some("bad")));
+        assert_eq!(items.next(), Some(Ok(4)));
+
+        let items = vec![Ok::<_, &str>('a'), Ok('b')].into_iter();
+        let (values, error): (String, _) = items.collect_until_err();
+        assert_eq!((values.as_str(), error), ("ab", None));
+    }
+}
@@ -0,0 +1,155 @@
+/// Either get the value a thread produced by joining its handle, or return from the current
+/// function if the thread panicked. A default return value can be provided.
+///
+/// Works with `std::thread::JoinHandle` and requires the `std` feature (enabled by default). With
+/// the `async` marker, a `tokio::task::JoinHandle` is awaited instead, in which case the task
+/// failing covers both it panicking and it being cancelled. Separate return values can be provided
+/// for those two cases.
+/// ```
+/// use early_returns::join_or_return;
+/// use std::thread;
+///
+/// fn sum_in_thread(values: Vec<i32>) -> Option<i32> {
+///     let handle = thread::spawn(move || {
+///         assert!(!values.is_empty(), "nothing to sum");
+///         values.into_iter().sum()
+///     });
+///     let sum = join_or_return!(handle, None);
+///     Some(sum)
+/// }
+///
+/// assert_eq!(sum_in_thread(vec![1, 2, 3]), Some(6));
+/// assert_eq!(sum_in_thread(vec![]), None);
+/// ```
+///
+/// Telling panicked and cancelled tasks apart:
+/// ```
+/// use early_returns::join_or_return;
+///
+/// async fn outcome(handle: tokio::task::JoinHandle<i32>) -> String {
+///     let value = join_or_return!(
+///         async handle,
+///         panicked => "panicked".to_string(),
+///         cancelled => "cancelled".to_string()
+///     );
+///     value.to_string()
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// assert_eq!(outcome(tokio::spawn(async { 42 })).await, "42");
+///
+/// let never = tokio::spawn(std::future::pending::<i32>());
+/// never.abort();
+/// assert_eq!(outcome(never).await, "cancelled");
+/// # });
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! join_or_return {
+    (async $handle:expr, panicked => $panicked_result:expr, cancelled => $cancelled_result:expr) => {{
+        match $handle.await {
+            Ok(f) => f,
+            Err(e) if e.is_panic() => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $panicked_result;
+            }
+            Err(_) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $cancelled_result;
+            }
+        }
+    }};
+
+    (async $handle:expr) => {
+        $crate::ok_or_return!($handle.await)
+    };
+
+    (async $handle:expr, $default_result:expr) => {
+        $crate::ok_or_return!($handle.await, $default_result)
+    };
+
+    ($handle:expr) => {
+        $crate::ok_or_return!($handle.join())
+    };
+
+    ($handle:expr, $default_result:expr) => {
+        $crate::ok_or_return!($handle.join(), $default_result)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    fn add_joined(handle: thread::JoinHandle<i32>, value: &mut i32) {
+        *value += join_or_return!(handle);
+    }
+
+    fn joined_or_default(handle: thread::JoinHandle<i32>) -> i32 {
+        join_or_return!(handle, -1)
+    }
+
+    #[test]
+    fn should_get_value_from_joined_thread() {
+        let mut value = 0;
+        add_joined(thread::spawn(|| 2), &mut value);
+        assert_eq!(value, 2);
+        assert_eq!(joined_or_default(thread::spawn(|| 3)), 3);
+    }
+
+    #[test]
+    fn should_return_early_when_thread_panicked() {
+        let mut value = 0;
+        add_joined(thread::spawn(|| panic!("expected panic")), &mut value);
+        assert_eq!(value, 0);
+        assert_eq!(
+            joined_or_default(thread::spawn(|| panic!("expected panic"))),
+            -1
+        );
+    }
+
+    async fn add_awaited(handle: tokio::task::JoinHandle<i32>, value: &mut i32) {
+        *value += join_or_return!(async handle);
+    }
+
+    async fn awaited_or_default(handle: tokio::task::JoinHandle<i32>) -> i32 {
+        join_or_return!(async handle, -1)
+    }
+
+    async fn classify(handle: tokio::task::JoinHandle<i32>) -> &'static str {
+        join_or_return!(async handle, panicked => "panicked", cancelled => "cancelled");
+        "ok"
+    }
+
+    #[tokio::test]
+    async fn should_get_value_from_awaited_task() {
+        let mut value = 0;
+        add_awaited(tokio::spawn(async { 2 }), &mut value).await;
+        assert_eq!(value, 2);
+        assert_eq!(awaited_or_default(tokio::spawn(async { 3 })).await, 3);
+    }
+
+    #[tokio::test]
+    async fn should_return_early_when_task_failed() {
+        let mut value = 0;
+        add_awaited(tokio::spawn(async { panic!("expected panic") }), &mut value).await;
+        assert_eq!(value, 0);
+        let cancelled = tokio::spawn(std::future::pending());
+        cancelled.abort();
+        assert_eq!(awaited_or_default(cancelled).await, -1);
+    }
+
+    #[tokio::test]
+    async fn should_distinguish_panicked_from_cancelled_tasks() {
+        assert_eq!(classify(tokio::spawn(async { 1 })).await, "ok");
+        assert_eq!(
+            classify(tokio::spawn(async { panic!("expected panic") })).await,
+            "panicked"
+        );
+        let cancelled = tokio::spawn(std::future::pending());
+        cancelled.abort();
+        assert_eq!(classify(cancelled).await, "cancelled");
+    }
+}
@@ -1,4 +1,231 @@
-#![doc = include_str!("../README.md")]
+// The examples of the README combine the `option` and `result` families.
+#![cfg_attr(all(feature = "option", feature = "result"), doc = include_str!("../README.md"))]
+#![cfg_attr(all(test, feature = "lint-silent-exits"), allow(deprecated))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "conversions")]
+mod any;
+#[cfg(feature = "anyhow")]
+mod anyhow;
+#[cfg(feature = "conversions")]
+mod arith;
+#[cfg(feature = "async-stream")]
+mod async_stream;
+#[cfg(feature = "result")]
+mod boxed;
+#[cfg(feature = "collections")]
+mod cache;
+#[cfg(feature = "tokio-util")]
+mod cancel;
+#[cfg(feature = "std")]
+mod channel;
+#[cfg(feature = "conversions")]
+mod chars;
+mod cleanup;
+#[cfg(feature = "collections")]
+mod collect;
+#[cfg(feature = "collections")]
+mod collection;
+pub mod control_flow;
+#[cfg(feature = "conversions")]
+mod convert;
+mod debug;
+pub mod defer;
+pub mod early_exit;
+#[cfg(feature = "bool")]
+mod ensure;
+#[cfg(feature = "instrumented")]
+mod escape;
+#[cfg(feature = "either")]
+mod either;
+mod engaged;
+#[cfg(feature = "std")]
+mod env;
+#[cfg(feature = "std")]
+mod exits;
+#[cfg(feature = "eyre")]
+mod eyre;
+#[cfg(feature = "std")]
+mod ffi;
+#[cfg(all(feature = "alloc", feature = "result"))]
+mod fmt;
+#[cfg(feature = "heapless")]
+mod heapless;
+#[cfg(all(feature = "std", feature = "instrumented"))]
+pub mod hooks;
+#[cfg(feature = "windows")]
+mod hresult;
+#[cfg(any(feature = "option", feature = "result"))]
+mod inspect;
+#[cfg(feature = "std")]
+mod io;
+pub mod iter;
+#[cfg(feature = "std")]
+mod join;
+mod location;
+#[cfg(feature = "std")]
+mod lock;
+#[cfg(feature = "log")]
+mod log;
+#[cfg(feature = "loops")]
+mod loops;
+#[cfg(feature = "result")]
+mod mapped;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "miette")]
+mod miette;
+#[cfg(feature = "nb")]
+mod nb;
+#[cfg(feature = "nom")]
+mod nom;
+#[cfg(feature = "patterns")]
+mod multi;
+#[cfg(feature = "patterns")]
+mod nested;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "patterns")]
+mod pattern;
+#[cfg(feature = "poll")]
+mod poll;
+pub mod prelude;
+#[cfg(feature = "patterns")]
+mod projection;
+#[cfg(feature = "conversions")]
+mod ptr;
+#[cfg(feature = "instrumented")]
+pub mod reason;
+#[cfg(feature = "loops")]
+mod retry;
+#[cfg(feature = "tokio")]
+mod semaphore;
+mod silent;
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(any(feature = "result", feature = "instrumented"))]
+mod store;
+#[cfg(feature = "tokio")]
+mod timeout;
+#[cfg(feature = "tracing")]
+mod tracing;
+#[cfg(feature = "conversions")]
+mod weak;
+
+#[cfg(feature = "derive")]
+pub use early_returns_macros::EarlyExtract;
+#[cfg(feature = "proc-macro")]
+pub use early_returns_macros::{
+    check_loop_exits, early, early_return, guard, instrument_early_returns,
+};
+
+// Short names for the core macros, for code that uses them on nearly every line. The attribute
+// macros that look for the escapes by name (`#[check_loop_exits]` and
+// `#[instrument_early_returns]`) only recognise the full names.
+#[cfg(feature = "aliases")]
+pub use crate::{
+    ok_or_break as oob, ok_or_continue as ooc, ok_or_return as oor, some_or_break as sob,
+    some_or_continue as soc, some_or_return as sor,
+};
+
+// The shared expansion of the macros that get a value out of a variant. The temporaries of an
+// `if let` scrutinee live until the end of the whole expression, so they are still alive when the
+// escape path runs. `let ... else` drops them before it, but rejects values that borrow from them.
+// With `or`, the block is a fallback evaluating to the value instead of an escape.
+#[cfg(not(feature = "let-else"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __extract_or {
+    ($variant:path, $from:expr, or $fallback:block) => {{
+        if let $variant(f) = $from {
+            f
+        } else $fallback
+    }};
+    ($variant:path, $from:expr, $escape:block) => {{
+        if let $variant(f) = $from {
+            f
+        } else $escape
+    }};
+}
+
+#[cfg(feature = "let-else")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __extract_or {
+    ($variant:path, $from:expr, or $fallback:block) => {
+        'extracted: {
+            let $variant(f) = $from else {
+                let f = $fallback;
+                break 'extracted f;
+            };
+            f
+        }
+    };
+    ($variant:path, $from:expr, $escape:block) => {{
+        let $variant(f) = $from else $escape;
+        f
+    }};
+}
+
+// Called on the escape path of every early return, so `defer_on_early_exit!` can tell it apart
+// from a normal return. The marker function is named after the function the escape is taken in.
+#[cfg(not(feature = "defer"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mark_early_exit {
+    () => {};
+}
+
+#[cfg(feature = "defer")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mark_early_exit {
+    () => {{
+        fn __early_exit() {}
+        $crate::__private::mark_early_exit(__early_exit);
+    }};
+}
+
+// Awaits the next item of a stream, for the `async` forms of `next_or_return!` and
+// `next_or_break!`.
+#[cfg(feature = "futures")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __stream_next {
+    ($stream:expr) => {
+        $crate::__private::futures::StreamExt::next(&mut $stream).await
+    };
+}
+
+#[cfg(not(feature = "futures"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __stream_next {
+    ($stream:expr) => {
+        ::core::compile_error!("awaiting the next item of a stream requires the `futures` feature")
+    };
+}
+
+// The statement form of the core macros. Everything up to the first `:` or `=` is the pattern,
+// which a `pat` fragment cannot express since it may not be followed by a `:`. An optional type
+// follows as a `ty` fragment, so an `=` inside it (as in `Iterator<Item = u8>`) does not end it.
+// The rest is passed on to the expression form.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __let_with {
+    ($escape:ident [$($binding:tt)+] : $ty:ty = $($args:tt)+) => {
+        let $($binding)+: $ty = $crate::$escape!($($args)+);
+    };
+    ($escape:ident [$($binding:tt)+] = $($args:tt)+) => {
+        let $($binding)+ = $crate::$escape!($($args)+);
+    };
+    ($escape:ident [$($binding:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__let_with!($escape [$($binding)* $next] $($rest)*)
+    };
+}
 
 /// Either get the value from an Option type or return from the current function.
 /// A default return value can be provided.
@@ -9,26 +236,54 @@
 ///     println!("{i}");
 /// }
 /// ```
+///
+/// In statement position the macro can also own the whole `let`, so the value can be bound with
+/// `mut`, destructured or given a type. The `Some(..)` around the pattern is optional; a type
+/// after it is that of the Option, as with `let ... else`.
+/// ```
+/// use early_returns::some_or_return;
+/// fn sum_pair(pair: Option<(u32, u32)>, limit: Option<&str>) -> u32 {
+///     some_or_return!(let Some((a, mut b)) = pair, 0);
+///     some_or_return!(let limit: u32 = limit.and_then(|l| l.parse().ok()), 0);
+///     b += a;
+///     b.min(limit)
+/// }
+///
+/// assert_eq!(sum_pair(Some((1, 2)), Some("10")), 3);
+/// assert_eq!(sum_pair(None, Some("10")), 0);
+/// ```
+#[cfg(feature = "option")]
 #[macro_export]
 macro_rules! some_or_return {
-    ($from:expr) => {{
-        if let Some(f) = $from {
-            f
-        } else {
+    (let Some($($binding:tt)+) = $($rest:tt)+) => {
+        $crate::__let_with!(some_or_return [] $($binding)+ = $($rest)+)
+    };
+    (let Some($($binding:tt)+): $ty:ty = $from:expr $(, $($args:tt)+)?) => {
+        $crate::__let_with!(some_or_return [] $($binding)+ = ::core::convert::identity::<$ty>($from) $(, $($args)+)?)
+    };
+    (let $($rest:tt)+) => {
+        $crate::__let_with!(some_or_return [] $($rest)+)
+    };
+    ($from:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
             return;
-        }
-    }};
-    ($from:expr, $default_result:expr) => {{
-        if let Some(f) = $from {
-            f
-        } else {
+        })
+    };
+    ($from:expr, $default_result:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
             return $default_result;
-        }
-    }};
+        })
+    };
 }
 
 /// Either get the value from an Option type or break out of a loop. If a loop lifetime is
 /// specified, that loop will be exited, otherwise the immediate loop is exited.
+/// It also has a statement form, like [`some_or_return!`]
+/// (`some_or_break!(let Some(mut val) = val)`).
 /// ```
 /// use early_returns::some_or_break;
 /// fn do_something_with_option(vals: &Vec<Option<i32>>) {
@@ -45,27 +300,37 @@ macro_rules! some_or_return {
 ///     }
 /// }
 /// ```
+#[cfg(feature = "option")]
 #[macro_export]
 macro_rules! some_or_break {
-    ($from:expr) => {{
-        if let Some(f) = $from {
-            f
-        } else {
+    (let Some($($binding:tt)+) = $($rest:tt)+) => {
+        $crate::__let_with!(some_or_break [] $($binding)+ = $($rest)+)
+    };
+    (let Some($($binding:tt)+): $ty:ty = $from:expr $(, $($args:tt)+)?) => {
+        $crate::__let_with!(some_or_break [] $($binding)+ = ::core::convert::identity::<$ty>($from) $(, $($args)+)?)
+    };
+    (let $($rest:tt)+) => {
+        $crate::__let_with!(some_or_break [] $($rest)+)
+    };
+    ($from:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            $crate::__private::silent_break();
             break;
-        }
-    }};
+        })
+    };
 
-    ($from:expr, $lt:lifetime) => {{
-        if let Some(f) = $from {
-            f
-        } else {
+    ($from:expr, $lt:lifetime) => {
+        $crate::__extract_or!(Some, $from, {
+            $crate::__private::silent_break();
             break $lt;
-        }
-    }};
+        })
+    };
 }
 
 /// Either get the value from an Option type or continue in a loop. If a loop lifetime is specified,
 /// that loop will be "continued", otherwise the immediate loop is "continued".
+/// It also has a statement form, like [`some_or_return!`]
+/// (`some_or_continue!(let Some(mut val) = val)`).
 /// ```
 /// use early_returns::some_or_continue;
 /// fn do_something_with_option(vals: &Vec<Option<i32>>) {
@@ -82,54 +347,75 @@ macro_rules! some_or_break {
 ///     }
 /// }
 /// ```
+#[cfg(feature = "option")]
 #[macro_export]
 macro_rules! some_or_continue {
-    ($from:expr) => {{
-        if let Some(f) = $from {
-            f
-        } else {
+    (let Some($($binding:tt)+) = $($rest:tt)+) => {
+        $crate::__let_with!(some_or_continue [] $($binding)+ = $($rest)+)
+    };
+    (let Some($($binding:tt)+): $ty:ty = $from:expr $(, $($args:tt)+)?) => {
+        $crate::__let_with!(some_or_continue [] $($binding)+ = ::core::convert::identity::<$ty>($from) $(, $($args)+)?)
+    };
+    (let $($rest:tt)+) => {
+        $crate::__let_with!(some_or_continue [] $($rest)+)
+    };
+    ($from:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            $crate::__private::silent_continue();
             continue;
-        }
-    }};
+        })
+    };
 
-    ($from:expr, $lt:lifetime) => {{
-        if let Some(f) = $from {
-            f
-        } else {
+    ($from:expr, $lt:lifetime) => {
+        $crate::__extract_or!(Some, $from, {
+            $crate::__private::silent_continue();
             continue $lt;
-        }
-    }};
+        })
+    };
 }
 
 /// Either get the value from a Result type or return from the current function.
-/// A default return value can be provided.
+/// A default return value can be provided. Like [`some_or_return!`], it also has a statement form
+/// (`ok_or_return!(let mut port: u16 = value.parse())`).
 /// ```
-/// use early_returns::{ok_or_return, some_or_return};
+/// use early_returns::ok_or_return;
 /// fn do_something_with_result(i: Result<i32, ()>) {
 ///     let i = ok_or_return!(i);
 /// }
 /// ```
+#[cfg(feature = "result")]
 #[macro_export]
 macro_rules! ok_or_return {
-    ($from:expr) => {{
-        if let Ok(f) = $from {
-            f
-        } else {
+    (let Ok($($binding:tt)+) = $($rest:tt)+) => {
+        $crate::__let_with!(ok_or_return [] $($binding)+ = $($rest)+)
+    };
+    (let Ok($($binding:tt)+): $ty:ty = $from:expr $(, $($args:tt)+)?) => {
+        $crate::__let_with!(ok_or_return [] $($binding)+ = ::core::convert::identity::<$ty>($from) $(, $($args)+)?)
+    };
+    (let $($rest:tt)+) => {
+        $crate::__let_with!(ok_or_return [] $($rest)+)
+    };
+    ($from:expr) => {
+        $crate::__extract_or!(Ok, $from, {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
             return;
-        }
-    }};
+        })
+    };
 
-    ($from:expr, $default_result:expr) => {{
-        if let Ok(f) = $from {
-            f
-        } else {
+    ($from:expr, $default_result:expr) => {
+        $crate::__extract_or!(Ok, $from, {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
             return $default_result;
-        }
-    }};
+        })
+    };
 }
 
 /// Either get the Ok value from a Result type or break out of a loop. If a loop lifetime is
 /// specified, that loop will be exited, otherwise the immediate loop is exited.
+/// It also has a statement form, like [`some_or_return!`]
+/// (`ok_or_break!(let Ok(mut val) = val)`).
 /// ```
 /// use early_returns::ok_or_break;
 /// fn do_something_with_option(vals: &Vec<Result<i32, ()>>) {
@@ -146,26 +432,36 @@ macro_rules! ok_or_return {
 ///     }
 /// }
 /// ```
+#[cfg(feature = "result")]
 #[macro_export]
 macro_rules! ok_or_break {
-    ($from:expr) => {{
-        if let Ok(f) = $from {
-            f
-        } else {
+    (let Ok($($binding:tt)+) = $($rest:tt)+) => {
+        $crate::__let_with!(ok_or_break [] $($binding)+ = $($rest)+)
+    };
+    (let Ok($($binding:tt)+): $ty:ty = $from:expr $(, $($args:tt)+)?) => {
+        $crate::__let_with!(ok_or_break [] $($binding)+ = ::core::convert::identity::<$ty>($from) $(, $($args)+)?)
+    };
+    (let $($rest:tt)+) => {
+        $crate::__let_with!(ok_or_break [] $($rest)+)
+    };
+    ($from:expr) => {
+        $crate::__extract_or!(Ok, $from, {
+            $crate::__private::silent_break();
             break;
-        }
-    }};
-    ($from:expr, $lt:lifetime) => {{
-        if let Ok(f) = $from {
-            f
-        } else {
+        })
+    };
+    ($from:expr, $lt:lifetime) => {
+        $crate::__extract_or!(Ok, $from, {
+            $crate::__private::silent_break();
             break $lt;
-        }
-    }};
+        })
+    };
 }
 
 /// Either get the value from a Result type or continue in a loop. If a loop lifetime is specified,
 /// that loop will be "continued", otherwise the immediate loop is "continued".
+/// It also has a statement form, like [`some_or_return!`]
+/// (`ok_or_continue!(let Ok(mut val) = val)`).
 /// ```
 /// use early_returns::ok_or_continue;
 /// fn do_something_with_option(vals: &Vec<Result<i32, ()>>) {
@@ -182,25 +478,108 @@ macro_rules! ok_or_break {
 ///     }
 /// }
 /// ```
+#[cfg(feature = "result")]
 #[macro_export]
 macro_rules! ok_or_continue {
-    ($from:expr) => {{
-        if let Ok(f) = $from {
-            f
-        } else {
+    (let Ok($($binding:tt)+) = $($rest:tt)+) => {
+        $crate::__let_with!(ok_or_continue [] $($binding)+ = $($rest)+)
+    };
+    (let Ok($($binding:tt)+): $ty:ty = $from:expr $(, $($args:tt)+)?) => {
+        $crate::__let_with!(ok_or_continue [] $($binding)+ = ::core::convert::identity::<$ty>($from) $(, $($args)+)?)
+    };
+    (let $($rest:tt)+) => {
+        $crate::__let_with!(ok_or_continue [] $($rest)+)
+    };
+    ($from:expr) => {
+        $crate::__extract_or!(Ok, $from, {
+            $crate::__private::silent_continue();
             continue;
-        }
-    }};
-    ($from:expr, $lt:lifetime) => {{
-        if let Ok(f) = $from {
-            f
-        } else {
+        })
+    };
+    ($from:expr, $lt:lifetime) => {
+        $crate::__extract_or!(Ok, $from, {
+            $crate::__private::silent_continue();
             continue $lt;
-        }
-    }};
+        })
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "anyhow")]
+    pub use ::anyhow;
+    #[cfg(feature = "either")]
+    pub use ::either;
+    #[cfg(feature = "eyre")]
+    pub use ::eyre;
+    #[cfg(feature = "futures")]
+    pub use ::futures_util as futures;
+    #[cfg(feature = "log")]
+    pub use ::log;
+    #[cfg(feature = "metrics")]
+    pub use ::metrics;
+    #[cfg(feature = "miette")]
+    pub use ::miette;
+    #[cfg(feature = "nb")]
+    pub use ::nb;
+    #[cfg(feature = "nom")]
+    pub use ::nom;
+
+    #[cfg(feature = "std")]
+    pub use ::std::env;
+    #[cfg(feature = "std")]
+    pub use ::std::ffi;
+    #[cfg(feature = "std")]
+    pub use ::std::io;
+    #[cfg(feature = "std")]
+    pub use ::std::sync::mpsc;
+    #[cfg(feature = "std")]
+    pub use ::std::thread;
+    #[cfg(feature = "tokio")]
+    pub use ::tokio;
+    #[cfg(feature = "tokio-util")]
+    pub use ::tokio_util;
+    #[cfg(feature = "tracing")]
+    pub use ::tracing;
+
+    pub use ::core::time::Duration;
+    #[cfg(feature = "alloc")]
+    pub use ::alloc::format;
+    #[cfg(feature = "alloc")]
+    pub use ::alloc::string::String;
+    #[cfg(feature = "alloc")]
+    pub use ::alloc::vec::Vec;
+
+    #[cfg(feature = "std")]
+    pub use crate::channel::ChannelError;
+    #[cfg(feature = "defer")]
+    pub use crate::defer::{function_of, mark_early_exit};
+    pub use crate::engaged::Engaged;
+    #[cfg(feature = "std")]
+    pub use crate::exits::{ExitReport, ExitTracker};
+    pub use crate::location::caller_location;
+    #[cfg(feature = "miette")]
+    pub use crate::miette::EscapeDiagnostic;
+    pub use crate::engaged::GuardBindings;
+    #[cfg(feature = "result")]
+    pub use crate::inspect::inspect_err;
+    #[cfg(feature = "result")]
+    pub use crate::mapped::map_err;
+    #[cfg(feature = "loops")]
+    pub use crate::retry::backoff_delay;
+    #[cfg(any(feature = "result", feature = "instrumented"))]
+    pub use crate::store::{Slot, StoreExtend, StoreOption};
+    #[cfg(feature = "tracing")]
+    pub use crate::tracing::record_early_return;
+
+    #[allow(deprecated)]
+    pub use crate::silent::{silent_break, silent_continue, silent_return};
 }
 
 #[cfg(test)]
+mod evaluation;
+
+#[cfg(all(test, any(feature = "option", feature = "result")))]
 mod test {
     struct Tester {
         value: i32,
@@ -211,36 +590,43 @@ mod test {
             Tester { value: 0 }
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_optional(&mut self, value: Option<i32>) {
             let value = some_or_return!(value);
             self.value += value;
         }
 
+        #[cfg(feature = "result")]
         fn increment_with_result(&mut self, value: Result<i32, ()>) {
             let value = ok_or_return!(value);
             self.value += value;
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_optional_with_ref(&mut self, value: Option<&i32>) {
             let value = some_or_return!(value);
             self.value += value;
         }
 
+        #[cfg(feature = "result")]
         fn increment_with_result_with_ref(&mut self, value: Result<&i32, ()>) {
             let value = ok_or_return!(value);
             self.value += value;
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_ref_to_optional(&mut self, value: &Option<i32>) {
             let value = some_or_return!(value);
             self.value += value;
         }
 
+        #[cfg(feature = "result")]
         fn increment_with_ref_to_result(&mut self, value: &Result<i32, ()>) {
             let value = ok_or_return!(value);
             self.value += value;
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_optional_with_break(&mut self, values: Vec<Option<i32>>) {
             for value in values {
                 let value = some_or_break!(value);
@@ -248,6 +634,7 @@ mod test {
             }
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_optional_with_break_with_lifetime(&mut self, values: Vec<Option<i32>>) {
             'l: for value in values {
                 self.value += 1;
@@ -258,6 +645,7 @@ mod test {
             }
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_optional_with_continue(&mut self, values: Vec<Option<i32>>) {
             for value in values {
                 let value = some_or_continue!(value);
@@ -265,6 +653,7 @@ mod test {
             }
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_optional_with_continue_with_lifetime(
             &mut self,
             values: Vec<Option<i32>>,
@@ -278,6 +667,7 @@ mod test {
             }
         }
 
+        #[cfg(feature = "result")]
         fn increment_with_result_with_break(&mut self, values: Vec<Result<i32, ()>>) {
             for value in values {
                 let value = ok_or_break!(value);
@@ -285,6 +675,7 @@ mod test {
             }
         }
 
+        #[cfg(feature = "result")]
         fn increment_with_result_with_break_with_lifetime(&mut self, values: Vec<Result<i32, ()>>) {
             'l: for value in values {
                 self.value += 1;
@@ -295,6 +686,7 @@ mod test {
             }
         }
 
+        #[cfg(feature = "result")]
         fn increment_with_result_with_continue(&mut self, values: Vec<Result<i32, ()>>) {
             for value in values {
                 let value = ok_or_continue!(value);
@@ -302,6 +694,7 @@ mod test {
             }
         }
 
+        #[cfg(feature = "result")]
         fn increment_with_result_with_continue_with_lifetime(
             &mut self,
             values: Vec<Result<i32, ()>>,
@@ -315,6 +708,7 @@ mod test {
             }
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_optional_by_ref(
             &mut self,
             values: Option<i32>
@@ -324,6 +718,7 @@ mod test {
             self.value += i;
         }
 
+        #[cfg(feature = "option")]
         fn increment_with_optional_from_fn_result<F: Fn() -> Option<i32>>(
             &mut self,
             value_getter: F
@@ -334,6 +729,7 @@ mod test {
         }
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_return_early_with_unengaged_optional() {
         let mut tester = Tester::new();
@@ -341,6 +737,7 @@ mod test {
         assert_eq!(tester.value, 0);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_not_return_early_with_engaged_optional() {
         let mut tester = Tester::new();
@@ -348,6 +745,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_return_early_with_err() {
         let mut tester = Tester::new();
@@ -355,6 +753,7 @@ mod test {
         assert_eq!(tester.value, 0);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_not_return_early_with_ok() {
         let mut tester = Tester::new();
@@ -362,6 +761,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_return_early_with_unengaged_optional_with_ref() {
         let mut tester = Tester::new();
@@ -369,6 +769,7 @@ mod test {
         assert_eq!(tester.value, 0);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_not_return_early_with_engaged_optional_with_ref() {
         let mut tester = Tester::new();
@@ -376,6 +777,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_return_early_with_err_with_ref() {
         let mut tester = Tester::new();
@@ -383,6 +785,7 @@ mod test {
         assert_eq!(tester.value, 0);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_not_return_early_with_ok_with_ref() {
         let mut tester = Tester::new();
@@ -390,6 +793,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_return_early_with_ref_to_unengaged_optional() {
         let mut tester = Tester::new();
@@ -397,6 +801,7 @@ mod test {
         assert_eq!(tester.value, 0);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_not_return_early_with_ref_to_engaged_optional() {
         let mut tester = Tester::new();
@@ -404,6 +809,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_return_early_with_ref_to_err() {
         let mut tester = Tester::new();
@@ -411,6 +817,7 @@ mod test {
         assert_eq!(tester.value, 0);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_not_return_early_with_ref_to_ok() {
         let mut tester = Tester::new();
@@ -418,6 +825,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_break_with_unengaged_optional() {
         let mut tester = Tester::new();
@@ -425,6 +833,7 @@ mod test {
         assert_eq!(tester.value, 0);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_break_with_unengaged_optional_with_lifetime() {
         let mut tester = Tester::new();
@@ -432,6 +841,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_continue_with_unengaged_optional() {
         let mut tester = Tester::new();
@@ -439,6 +849,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_continue_with_unengaged_optional_with_lifetime() {
         let mut tester = Tester::new();
@@ -446,6 +857,7 @@ mod test {
         assert_eq!(tester.value, 3);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_break_with_err_result() {
         let mut tester = Tester::new();
@@ -453,6 +865,7 @@ mod test {
         assert_eq!(tester.value, 0);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_break_with_err_result_with_lifetime() {
         let mut tester = Tester::new();
@@ -460,6 +873,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_continue_with_err_result() {
         let mut tester = Tester::new();
@@ -467,6 +881,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_continue_with_err_result_with_lifetime() {
         let mut tester = Tester::new();
@@ -474,6 +889,7 @@ mod test {
         assert_eq!(tester.value, 3);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_get_optional_from_reference() {
         let mut tester = Tester::new();
@@ -483,6 +899,7 @@ mod test {
         assert_eq!(tester.value, 1);
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_get_optional_from_function_result() {
         let mut tester = Tester::new();
@@ -500,6 +917,7 @@ mod test {
         value: i32
     }
 
+    #[cfg(feature = "option")]
     fn try_some_or_return_with_default(val: Option<i32>) -> MeaningOfLifeAnd {
         let val = some_or_return!(val, MeaningOfLifeAnd { value: 42 });
         MeaningOfLifeAnd {
@@ -507,12 +925,14 @@ mod test {
         }
     }
 
+    #[cfg(feature = "option")]
     #[test]
     fn should_return_default_when_none() {
         assert_eq!(try_some_or_return_with_default(Some(1)), MeaningOfLifeAnd { value: 43 });
         assert_eq!(try_some_or_return_with_default(None), MeaningOfLifeAnd { value: 42 });
     }
 
+    #[cfg(feature = "result")]
     fn try_ok_or_return_with_default(val: Result<i32, ()>) -> MeaningOfLifeAnd {
         let val = ok_or_return!(val, MeaningOfLifeAnd { value: 42 });
         MeaningOfLifeAnd {
@@ -520,9 +940,205 @@ mod test {
         }
     }
 
+    #[cfg(feature = "result")]
     #[test]
     fn should_return_default_when_err() {
         assert_eq!(try_ok_or_return_with_default(Ok(1)), MeaningOfLifeAnd { value: 43 });
         assert_eq!(try_ok_or_return_with_default(Err(())), MeaningOfLifeAnd { value: 42 });
     }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    fn sum_pair(pair: Option<(i32, i32)>, scale: Result<&str, ()>) -> i32 {
+        some_or_return!(let Some((a, mut b)) = pair, -1);
+        ok_or_return!(let scale: i32 = scale.map(|s| s.parse().unwrap_or(1)), -2);
+        b += a;
+        b * scale
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    #[test]
+    fn should_bind_patterns_with_statement_form() {
+        assert_eq!(sum_pair(Some((1, 2)), Ok("2")), 6);
+        assert_eq!(sum_pair(None, Ok("2")), -1);
+        assert_eq!(sum_pair(Some((1, 2)), Err(())), -2);
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    fn sum_boxed(values: Option<Vec<u8>>, extra: Result<Vec<u8>, ()>) -> u8 {
+        some_or_return!(
+            let Some(values): Option<Box<dyn Iterator<Item = u8>>> =
+                values.map(|v| Box::new(v.into_iter()) as _),
+            0
+        );
+        ok_or_return!(
+            let extra: Box<dyn Iterator<Item = u8>> = extra.map(|v| Box::new(v.into_iter()) as _),
+            1
+        );
+        values.chain(extra).sum()
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    #[test]
+    fn should_bind_with_types_containing_equals_signs() {
+        assert_eq!(sum_boxed(Some(vec![1, 2]), Ok(vec![3])), 6);
+        assert_eq!(sum_boxed(None, Ok(vec![3])), 0);
+        assert_eq!(sum_boxed(Some(vec![1, 2]), Err(())), 1);
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    #[test]
+    fn should_break_and_continue_with_statement_form() {
+        let mut sum = 0;
+        'l: for val in [Some(1), None, Some(3)] {
+            for _i in 0..2 {
+                some_or_continue!(let mut val = val, 'l);
+                val *= 10;
+                sum += val;
+            }
+        }
+        for val in [Ok(1), Err(()), Ok(3)] {
+            ok_or_break!(let Ok(mut val) = val);
+            val += 1;
+            sum += val;
+        }
+        for val in [Err(()), Ok(5)] {
+            ok_or_continue!(let val: i32 = val);
+            sum += val;
+        }
+        for val in [Some(7), None, Some(9)] {
+            some_or_break!(let val: i32 = val);
+            sum += val;
+        }
+        assert_eq!(sum, 20 + 60 + 2 + 5 + 7);
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    struct Noisy<'a>(&'a std::cell::RefCell<Vec<&'static str>>);
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    impl Noisy<'_> {
+        fn value(&self, value: Option<i32>) -> Option<i32> {
+            value
+        }
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    impl Drop for Noisy<'_> {
+        fn drop(&mut self) {
+            self.0.borrow_mut().push("dropped");
+        }
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    fn escape(events: &std::cell::RefCell<Vec<&'static str>>) -> i32 {
+        events.borrow_mut().push("escaped");
+        0
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    fn value_or_escape(value: Option<i32>, events: &std::cell::RefCell<Vec<&'static str>>) -> i32 {
+        some_or_return!(Noisy(events).value(value), escape(events))
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    fn ok_or_escape(value: Option<i32>, events: &std::cell::RefCell<Vec<&'static str>>) -> i32 {
+        ok_or_return!(Noisy(events).value(value).ok_or(()), escape(events))
+    }
+
+    #[cfg(all(feature = "option", feature = "result", not(feature = "let-else")))]
+    #[test]
+    fn should_keep_temporaries_alive_while_escaping() {
+        let events = std::cell::RefCell::new(Vec::new());
+        assert_eq!(value_or_escape(None, &events), 0);
+        assert_eq!(ok_or_escape(None, &events), 0);
+        assert_eq!(*events.borrow(), ["escaped", "dropped", "escaped", "dropped"]);
+    }
+
+    #[cfg(all(feature = "option", feature = "result", feature = "let-else"))]
+    #[test]
+    fn should_drop_temporaries_before_escaping() {
+        let events = std::cell::RefCell::new(Vec::new());
+        assert_eq!(value_or_escape(None, &events), 0);
+        assert_eq!(ok_or_escape(None, &events), 0);
+        assert_eq!(*events.borrow(), ["dropped", "escaped", "dropped", "escaped"]);
+    }
+
+    #[cfg(all(feature = "option", feature = "let-else"))]
+    #[test]
+    fn should_release_lock_before_escaping() {
+        let queue = std::sync::Mutex::new(Vec::<i32>::new());
+        // The guard is a temporary of the scrutinee, so the lock is only free again once it is
+        // dropped.
+        let first_or_unlocked = || -> Result<i32, bool> {
+            let unlocked = || queue.try_lock().is_ok();
+            Ok(some_or_return!(queue.lock().unwrap().first().copied(), Err(unlocked())))
+        };
+        assert_eq!(first_or_unlocked(), Err(true));
+    }
+
+    // With the `defer` feature the return escapes record themselves at runtime, and with the
+    // `strict` feature they panic, which fails the evaluation of the constants below.
+    #[cfg(all(
+        feature = "option",
+        feature = "result",
+        not(any(feature = "defer", feature = "strict"))
+    ))]
+    #[test]
+    fn should_work_in_const_fns() {
+        const fn digit(byte: u8) -> Result<u32, u8> {
+            if byte.is_ascii_digit() {
+                Ok((byte - b'0') as u32)
+            } else {
+                Err(byte)
+            }
+        }
+
+        // Parses a port, or 0 if it is missing or malformed.
+        const fn parse_port(input: &[u8]) -> u32 {
+            some_or_return!(let Some(&first) = input.first(), 0);
+            let mut port = ok_or_return!(digit(first), 0);
+            let mut i = 1;
+            while i < input.len() {
+                ok_or_return!(let d = digit(input[i]), 0);
+                port = port * 10 + d;
+                i += 1;
+            }
+            port
+        }
+
+        // Sums the digits of the first word, skipping the other characters.
+        const fn sum_first_word(input: &[u8]) -> u32 {
+            let mut sum = 0;
+            let mut i = 0;
+            'bytes: while i < input.len() {
+                i += 1;
+                let byte = if input[i - 1] == b' ' { None } else { Some(input[i - 1]) };
+                let byte = some_or_break!(byte);
+                loop {
+                    ok_or_continue!(let Ok(d) = digit(byte), 'bytes);
+                    sum += d;
+                    some_or_continue!(None::<u8>, 'bytes);
+                }
+            }
+            sum
+        }
+
+        // Counts the digits up to the first character that is not one.
+        const fn count_digits(input: &[u8]) -> usize {
+            let mut i = 0;
+            while i < input.len() {
+                ok_or_break!(digit(input[i]));
+                i += 1;
+            }
+            i
+        }
+
+        const PORT: u32 = parse_port(b"8080");
+        const BAD_PORT: u32 = parse_port(b"80x");
+        const NO_PORT: u32 = parse_port(b"");
+        const SUM: u32 = sum_first_word(b"1a2 3");
+        const DIGITS: usize = count_digits(b"12x4");
+        assert_eq!((PORT, BAD_PORT, NO_PORT), (8080, 0, 0));
+        assert_eq!((SUM, DIGITS), (3, 2));
+    }
 }
@@ -0,0 +1,33 @@
+use core::panic::Location;
+
+/// The location of the macro invocation that expanded to the call of this function.
+///
+/// Calls made from macro-expanded code are attributed to the invocation of the outermost macro,
+/// so the instrumented macros can report where they were used without having to pass
+/// `file!()`/`line!()`/`column!()` around.
+#[doc(hidden)]
+#[track_caller]
+#[inline(always)]
+pub fn caller_location() -> &'static Location<'static> {
+    Location::caller()
+}
+
+#[cfg(test)]
+mod test {
+    use super::caller_location;
+
+    macro_rules! located {
+        () => {
+            $crate::__private::caller_location()
+        };
+    }
+
+    #[test]
+    fn should_capture_location_of_macro_invocation() {
+        let (location, line) = (located!(), line!());
+        assert_eq!(location.file(), "src/location.rs");
+        assert_eq!(location.line(), line);
+        assert_eq!(location.column(), 33);
+        assert_eq!(caller_location().line(), line!());
+    }
+}
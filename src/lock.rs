@@ -0,0 +1,474 @@
+/// Either get the guard of a locked mutex or return from the current function if the mutex is
+/// poisoned. A default return value can be provided, or with `recover` the guard is recovered
+/// from the poison error with `into_inner` instead of returning.
+///
+/// Works with `std::sync::Mutex` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::lock_or_return;
+/// use std::sync::Mutex;
+///
+/// fn next_id(counter: &Mutex<u32>) -> Option<u32> {
+///     let mut guard = lock_or_return!(counter, None);
+///     *guard += 1;
+///     Some(*guard)
+/// }
+///
+/// fn next_id_even_if_poisoned(counter: &Mutex<u32>) -> u32 {
+///     let mut guard = lock_or_return!(counter, recover);
+///     *guard += 1;
+///     *guard
+/// }
+///
+/// let counter = Mutex::new(0);
+/// assert_eq!(next_id(&counter), Some(1));
+///
+/// let _ = std::panic::catch_unwind(|| {
+///     let _guard = counter.lock().unwrap();
+///     panic!("poisoning the mutex");
+/// });
+/// assert_eq!(next_id(&counter), None);
+/// assert_eq!(next_id_even_if_poisoned(&counter), 2);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! lock_or_return {
+    ($lock:expr, recover) => {
+        $crate::lock_or_return!(@recover $lock.lock())
+    };
+
+    (@recover $from:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => e.into_inner(),
+        }
+    }};
+
+    ($lock:expr) => {
+        $crate::ok_or_return!($lock.lock())
+    };
+
+    ($lock:expr, $default_result:expr) => {
+        $crate::ok_or_return!($lock.lock(), $default_result)
+    };
+}
+
+/// Either get the read guard of a locked `RwLock` or return from the current function if the lock
+/// is poisoned. A default return value can be provided, or with `recover` the guard is recovered
+/// from the poison error with `into_inner` instead of returning.
+///
+/// Works with `std::sync::RwLock` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::read_or_return;
+/// use std::sync::RwLock;
+///
+/// fn first_name(names: &RwLock<Vec<String>>) -> Option<String> {
+///     let guard = read_or_return!(names, None);
+///     guard.first().cloned()
+/// }
+///
+/// let names = RwLock::new(vec!["ada".to_string()]);
+/// assert_eq!(first_name(&names), Some("ada".to_string()));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! read_or_return {
+    ($lock:expr, recover) => {
+        $crate::lock_or_return!(@recover $lock.read())
+    };
+
+    ($lock:expr) => {
+        $crate::ok_or_return!($lock.read())
+    };
+
+    ($lock:expr, $default_result:expr) => {
+        $crate::ok_or_return!($lock.read(), $default_result)
+    };
+}
+
+/// Either get the write guard of a locked `RwLock` or return from the current function if the lock
+/// is poisoned. A default return value can be provided, or with `recover` the guard is recovered
+/// from the poison error with `into_inner` instead of returning.
+///
+/// Works with `std::sync::RwLock` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::write_or_return;
+/// use std::sync::RwLock;
+///
+/// fn add_name(names: &RwLock<Vec<String>>, name: &str) -> bool {
+///     let mut guard = write_or_return!(names, false);
+///     guard.push(name.to_string());
+///     true
+/// }
+///
+/// let names = RwLock::new(Vec::new());
+/// assert!(add_name(&names, "ada"));
+/// assert_eq!(names.read().unwrap().len(), 1);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! write_or_return {
+    ($lock:expr, recover) => {
+        $crate::lock_or_return!(@recover $lock.write())
+    };
+
+    ($lock:expr) => {
+        $crate::ok_or_return!($lock.write())
+    };
+
+    ($lock:expr, $default_result:expr) => {
+        $crate::ok_or_return!($lock.write(), $default_result)
+    };
+}
+
+/// Either get the guard of a mutex without blocking or continue in a loop if the mutex is
+/// currently held (or poisoned). If a loop lifetime is specified, that loop will be "continued",
+/// otherwise the immediate loop is "continued".
+///
+/// Works with `std::sync::Mutex` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::try_lock_or_continue;
+/// use std::sync::Mutex;
+///
+/// let queues = vec![Mutex::new(vec![1]), Mutex::new(vec![2])];
+/// let _busy = queues[0].lock().unwrap();
+///
+/// let mut drained = Vec::new();
+/// for queue in &queues {
+///     let mut guard = try_lock_or_continue!(queue);
+///     drained.append(&mut guard);
+/// }
+/// assert_eq!(drained, vec![2]);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! try_lock_or_continue {
+    ($lock:expr) => {
+        $crate::ok_or_continue!($lock.try_lock())
+    };
+
+    ($lock:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!($lock.try_lock(), $lt)
+    };
+}
+
+/// Either get the read guard of an `RwLock` without blocking or continue in a loop if the lock is
+/// currently held for writing (or poisoned). If a loop lifetime is specified, that loop will be
+/// "continued", otherwise the immediate loop is "continued".
+///
+/// Works with `std::sync::RwLock` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::try_read_or_continue;
+/// use std::sync::RwLock;
+///
+/// let settings = vec![RwLock::new(1), RwLock::new(2)];
+/// let _busy = settings[1].write().unwrap();
+///
+/// let mut seen = Vec::new();
+/// for setting in &settings {
+///     seen.push(*try_read_or_continue!(setting));
+/// }
+/// assert_eq!(seen, vec![1]);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! try_read_or_continue {
+    ($lock:expr) => {
+        $crate::ok_or_continue!($lock.try_read())
+    };
+
+    ($lock:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!($lock.try_read(), $lt)
+    };
+}
+
+/// Either get the write guard of an `RwLock` without blocking or continue in a loop if the lock
+/// is currently held (or poisoned). If a loop lifetime is specified, that loop will be
+/// "continued", otherwise the immediate loop is "continued".
+///
+/// Works with `std::sync::RwLock` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::try_write_or_continue;
+/// use std::sync::RwLock;
+///
+/// let counters = vec![RwLock::new(0), RwLock::new(0)];
+/// let _busy = counters[0].read().unwrap();
+///
+/// for counter in &counters {
+///     *try_write_or_continue!(counter) += 1;
+/// }
+/// assert_eq!(*counters[1].read().unwrap(), 1);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! try_write_or_continue {
+    ($lock:expr) => {
+        $crate::ok_or_continue!($lock.try_write())
+    };
+
+    ($lock:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!($lock.try_write(), $lt)
+    };
+}
+
+/// Either get the guard of a mutex without blocking or return from the current function if the
+/// mutex is currently held (or poisoned). A default return value can be provided.
+///
+/// Works with `std::sync::Mutex` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::try_lock_or_return;
+/// use std::sync::Mutex;
+///
+/// fn flush(buffer: &Mutex<Vec<u8>>) -> usize {
+///     let mut guard = try_lock_or_return!(buffer, 0);
+///     guard.drain(..).count()
+/// }
+///
+/// let buffer = Mutex::new(vec![1, 2, 3]);
+/// {
+///     let _busy = buffer.lock().unwrap();
+///     assert_eq!(flush(&buffer), 0);
+/// }
+/// assert_eq!(flush(&buffer), 3);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! try_lock_or_return {
+    ($lock:expr) => {
+        $crate::ok_or_return!($lock.try_lock())
+    };
+
+    ($lock:expr, $default_result:expr) => {
+        $crate::ok_or_return!($lock.try_lock(), $default_result)
+    };
+}
+
+/// Either get the read guard of an `RwLock` without blocking or return from the current function
+/// if the lock is currently held for writing (or poisoned). A default return value can be
+/// provided.
+///
+/// Works with `std::sync::RwLock` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::try_read_or_return;
+/// use std::sync::RwLock;
+///
+/// fn snapshot(value: &RwLock<i32>) -> Option<i32> {
+///     let guard = try_read_or_return!(value, None);
+///     Some(*guard)
+/// }
+///
+/// let value = RwLock::new(7);
+/// assert_eq!(snapshot(&value), Some(7));
+/// let _busy = value.write().unwrap();
+/// assert_eq!(snapshot(&value), None);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! try_read_or_return {
+    ($lock:expr) => {
+        $crate::ok_or_return!($lock.try_read())
+    };
+
+    ($lock:expr, $default_result:expr) => {
+        $crate::ok_or_return!($lock.try_read(), $default_result)
+    };
+}
+
+/// Either get the write guard of an `RwLock` without blocking or return from the current function
+/// if the lock is currently held (or poisoned). A default return value can be provided.
+///
+/// Works with `std::sync::RwLock` and requires the `std` feature (enabled by default).
+/// ```
+/// use early_returns::try_write_or_return;
+/// use std::sync::RwLock;
+///
+/// fn reset(value: &RwLock<i32>) -> bool {
+///     *try_write_or_return!(value, false) = 0;
+///     true
+/// }
+///
+/// let value = RwLock::new(7);
+/// {
+///     let _busy = value.read().unwrap();
+///     assert!(!reset(&value));
+/// }
+/// assert!(reset(&value));
+/// assert_eq!(*value.read().unwrap(), 0);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! try_write_or_return {
+    ($lock:expr) => {
+        $crate::ok_or_return!($lock.try_write())
+    };
+
+    ($lock:expr, $default_result:expr) => {
+        $crate::ok_or_return!($lock.try_write(), $default_result)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic;
+    use std::sync::{Mutex, RwLock};
+
+    fn poison<T>(lock: impl FnOnce() + panic::UnwindSafe) -> Option<T> {
+        let _ = panic::catch_unwind(lock);
+        None
+    }
+
+    struct Tester {
+        mutex: Mutex<i32>,
+        rw_lock: RwLock<i32>,
+        value: i32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester {
+                mutex: Mutex::new(1),
+                rw_lock: RwLock::new(2),
+                value: 0,
+            }
+        }
+
+        fn poison_all(&self) {
+            poison::<()>(|| {
+                let _guard = self.mutex.lock().unwrap();
+                panic!("expected panic");
+            });
+            poison::<()>(|| {
+                let _guard = self.rw_lock.write().unwrap();
+                panic!("expected panic");
+            });
+        }
+
+        fn increment_with_locked(&mut self) {
+            let value = *lock_or_return!(self.mutex);
+            self.value += value;
+        }
+
+        fn increment_with_read(&mut self) {
+            let value = *read_or_return!(self.rw_lock);
+            self.value += value;
+        }
+
+        fn increment_written(&mut self) {
+            *write_or_return!(self.rw_lock) += 1;
+        }
+
+        fn locked_or_default(&self) -> i32 {
+            *lock_or_return!(self.mutex, -1)
+        }
+
+        fn read_or_default(&self) -> i32 {
+            *read_or_return!(self.rw_lock, -1)
+        }
+
+        fn written_or_default(&self) -> i32 {
+            let mut guard = write_or_return!(self.rw_lock, -1);
+            *guard += 1;
+            *guard
+        }
+
+        fn recovered(&self) -> i32 {
+            let locked = *lock_or_return!(self.mutex, recover);
+            let read = *read_or_return!(self.rw_lock, recover);
+            let written = *write_or_return!(self.rw_lock, recover);
+            locked + read + written
+        }
+    }
+
+    #[test]
+    fn should_get_guards_of_healthy_locks() {
+        let mut tester = Tester::new();
+        tester.increment_with_locked();
+        tester.increment_with_read();
+        tester.increment_written();
+        assert_eq!(tester.value, 3);
+        assert_eq!(*tester.rw_lock.read().unwrap(), 3);
+        assert_eq!(tester.locked_or_default(), 1);
+        assert_eq!(tester.read_or_default(), 3);
+        assert_eq!(tester.written_or_default(), 4);
+    }
+
+    #[test]
+    fn should_return_early_when_poisoned() {
+        let mut tester = Tester::new();
+        tester.poison_all();
+        tester.increment_with_locked();
+        tester.increment_with_read();
+        tester.increment_written();
+        assert_eq!(tester.value, 0);
+    }
+
+    #[test]
+    fn should_return_default_when_poisoned() {
+        let tester = Tester::new();
+        tester.poison_all();
+        assert_eq!(tester.locked_or_default(), -1);
+        assert_eq!(tester.read_or_default(), -1);
+        assert_eq!(tester.written_or_default(), -1);
+    }
+
+    fn count_unheld(mutexes: &[Mutex<i32>], rw_locks: &[RwLock<i32>]) -> i32 {
+        let mut count = 0;
+        for mutex in mutexes {
+            count += *try_lock_or_continue!(mutex);
+        }
+        for rw_lock in rw_locks {
+            count += *try_read_or_continue!(rw_lock);
+        }
+        'l: for rw_lock in rw_locks {
+            for _i in 0..1 {
+                *try_write_or_continue!(rw_lock, 'l) += 1;
+            }
+            count += 10;
+        }
+        count
+    }
+
+    fn try_locked_or_default(mutex: &Mutex<i32>) -> i32 {
+        *try_lock_or_return!(mutex, -1)
+    }
+
+    fn try_read_or_default(rw_lock: &RwLock<i32>) -> i32 {
+        *try_read_or_return!(rw_lock, -1)
+    }
+
+    fn try_written_or_default(rw_lock: &RwLock<i32>) -> i32 {
+        *try_write_or_return!(rw_lock, -1)
+    }
+
+    #[test]
+    fn should_skip_held_locks() {
+        let mutexes = [Mutex::new(1), Mutex::new(2)];
+        let rw_locks = [RwLock::new(3), RwLock::new(4)];
+        let _held = mutexes[0].lock().unwrap();
+        let write_held = rw_locks[0].write().unwrap();
+        assert_eq!(count_unheld(&mutexes, &rw_locks), 16);
+        drop(write_held);
+        let _read_held = rw_locks[0].read().unwrap();
+        assert_eq!(count_unheld(&mutexes, &rw_locks), 20);
+    }
+
+    #[test]
+    fn should_return_default_when_held() {
+        let mutex = Mutex::new(1);
+        let rw_lock = RwLock::new(2);
+        assert_eq!(try_locked_or_default(&mutex), 1);
+        assert_eq!(try_read_or_default(&rw_lock), 2);
+        assert_eq!(try_written_or_default(&rw_lock), 2);
+        let _held = mutex.lock().unwrap();
+        let _read_held = rw_lock.read().unwrap();
+        assert_eq!(try_locked_or_default(&mutex), -1);
+        assert_eq!(try_read_or_default(&rw_lock), 2);
+        assert_eq!(try_written_or_default(&rw_lock), -1);
+    }
+
+    #[test]
+    fn should_recover_guards_when_poisoned() {
+        let tester = Tester::new();
+        tester.poison_all();
+        assert_eq!(tester.recovered(), 5);
+    }
+}
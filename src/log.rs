@@ -0,0 +1,409 @@
+/// Either get the value from an Option type or return from the current function, emitting a `log`
+/// record at the given level first if there is none. A default return value can be provided before
+/// the level. The record contains the formatted message and the location (file, line and column) of
+/// the macro invocation.
+/// ```
+/// use early_returns::some_or_return_logged;
+///
+/// fn frobnicate(frobnicator: Option<&str>) -> Option<usize> {
+///     let frobnicator =
+///         some_or_return_logged!(frobnicator, None, level: warn, "missing frobnicator");
+///     Some(frobnicator.len())
+/// }
+///
+/// assert_eq!(frobnicate(Some("abc")), Some(3));
+/// assert_eq!(frobnicate(None), None);
+/// ```
+#[macro_export]
+macro_rules! some_or_return_logged {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::log::$level!(
+                    "{} [early return at {}]",
+                    ::core::format_args!($($arg)+),
+                    $crate::__private::caller_location(),
+                );
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::log::$level!(
+                    "{} [early return at {}]",
+                    ::core::format_args!($($arg)+),
+                    $crate::__private::caller_location(),
+                );
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or break out of a loop, emitting a `log` record at the
+/// given level first if there is none. If a loop lifetime is specified (before the level), that
+/// loop will be exited, otherwise the immediate loop is exited. The record contains the formatted
+/// message and the location (file, line and column) of the macro invocation.
+/// ```
+/// use early_returns::some_or_break_logged;
+///
+/// let mut read = Vec::new();
+/// for value in [Some(1), Some(2), None, Some(4)] {
+///     read.push(some_or_break_logged!(value, level: info, "stopped reading"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! some_or_break_logged {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::log::$level!(
+                    "{} [early break at {}]",
+                    ::core::format_args!($($arg)+),
+                    $crate::__private::caller_location(),
+                );
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::log::$level!(
+                    "{} [early break at {}]",
+                    ::core::format_args!($($arg)+),
+                    $crate::__private::caller_location(),
+                );
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or continue in a loop, emitting a `log` record at the
+/// given level first if there is none. If a loop lifetime is specified (before the level), that
+/// loop will be "continued", otherwise the immediate loop is "continued". The record contains the
+/// formatted message and the location (file, line and column) of the macro invocation.
+/// ```
+/// use early_returns::some_or_continue_logged;
+///
+/// let mut read = Vec::new();
+/// for (i, value) in [Some(1), None, Some(3)].into_iter().enumerate() {
+///     read.push(some_or_continue_logged!(value, level: debug, "skipping row {i}"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! some_or_continue_logged {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::log::$level!(
+                    "{} [early continue at {}]",
+                    ::core::format_args!($($arg)+),
+                    $crate::__private::caller_location(),
+                );
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::log::$level!(
+                    "{} [early continue at {}]",
+                    ::core::format_args!($($arg)+),
+                    $crate::__private::caller_location(),
+                );
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or return from the current function, emitting a `log`
+/// record at the given level first if it is an `Err`. A default return value can be provided before
+/// the level. The record contains the formatted message (including the `Debug` of the error) and
+/// the location of the macro invocation.
+/// ```
+/// use early_returns::ok_or_return_logged;
+///
+/// fn port(value: &str) {
+///     let port: u16 = ok_or_return_logged!(value.parse(), level: error, "bad port {value:?}");
+///     println!("listening on {port}");
+/// }
+///
+/// port("8080");
+/// port("http");
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_logged {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::log::$level!(
+                    "{}: {:?} [early return at {}]",
+                    ::core::format_args!($($arg)+),
+                    e,
+                    $crate::__private::caller_location(),
+                );
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::log::$level!(
+                    "{}: {:?} [early return at {}]",
+                    ::core::format_args!($($arg)+),
+                    e,
+                    $crate::__private::caller_location(),
+                );
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or break out of a loop, emitting a `log` record at
+/// the given level first if it is an `Err`. If a loop lifetime is specified (before the level),
+/// that loop will be exited, otherwise the immediate loop is exited. The record contains the
+/// formatted message (including the `Debug` of the error) and the location (file, line and column)
+/// of the macro invocation.
+/// ```
+/// use early_returns::ok_or_break_logged;
+///
+/// let mut read = Vec::new();
+/// for value in [Ok(1), Ok(2), Err("eof"), Ok(4)] {
+///     read.push(ok_or_break_logged!(value, level: info, "stopped reading"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_break_logged {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::log::$level!(
+                    "{}: {:?} [early break at {}]",
+                    ::core::format_args!($($arg)+),
+                    e,
+                    $crate::__private::caller_location(),
+                );
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::log::$level!(
+                    "{}: {:?} [early break at {}]",
+                    ::core::format_args!($($arg)+),
+                    e,
+                    $crate::__private::caller_location(),
+                );
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or continue in a loop, emitting a `log` record at the
+/// given level first if it is an `Err`. If a loop lifetime is specified (before the level), that
+/// loop will be "continued", otherwise the immediate loop is "continued". The record contains the
+/// formatted message (including the `Debug` of the error) and the location (file, line and column)
+/// of the macro invocation.
+/// ```
+/// use early_returns::ok_or_continue_logged;
+///
+/// let mut read = Vec::new();
+/// for (i, value) in [Ok(1), Err("bad row"), Ok(3)].into_iter().enumerate() {
+///     read.push(ok_or_continue_logged!(value, level: debug, "skipping row {i}"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_continue_logged {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::log::$level!(
+                    "{}: {:?} [early continue at {}]",
+                    ::core::format_args!($($arg)+),
+                    e,
+                    $crate::__private::caller_location(),
+                );
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::log::$level!(
+                    "{}: {:?} [early continue at {}]",
+                    ::core::format_args!($($arg)+),
+                    e,
+                    $crate::__private::caller_location(),
+                );
+                continue $lt;
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    struct Recorder;
+
+    static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    impl log::Log for Recorder {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let line = format!("{} {}", record.level(), record.args());
+            RECORDS.lock().unwrap().push(line);
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn init() {
+        let _ = log::set_logger(&Recorder);
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+
+    fn recorded(needle: &str) -> bool {
+        RECORDS.lock().unwrap().iter().any(|r| r.contains(needle))
+    }
+
+    const VALUE_MISSING_LINE: u32 = line!() + 2;
+    fn value_or_default(value: Option<i32>) -> i32 {
+        some_or_return_logged!(value, -1, level: warn, "missing value {}", 1)
+    }
+
+    fn push_ok(value: Result<i32, &str>, values: &mut Vec<i32>) {
+        values.push(ok_or_return_logged!(value, level: error, "push failed"));
+    }
+
+    #[test]
+    fn should_log_and_return_early() {
+        init();
+        assert_eq!(value_or_default(Some(2)), 2);
+        assert_eq!(value_or_default(None), -1);
+        assert!(recorded(&format!(
+            "WARN missing value 1 [early return at src/log.rs:{VALUE_MISSING_LINE}:9]"
+        )));
+        let mut values = Vec::new();
+        push_ok(Ok(1), &mut values);
+        push_ok(Err("disk full"), &mut values);
+        assert_eq!(values, vec![1]);
+        assert!(recorded("ERROR push failed: \"disk full\" [early return at src/log.rs:"));
+    }
+
+    #[test]
+    fn should_log_and_break() {
+        init();
+        let mut sum = 0;
+        'l: for value in [Some(1), None, Some(3)] {
+            for _i in 0..2 {
+                sum += some_or_break_logged!(value, 'l, level: info, "break some");
+            }
+        }
+        for value in [Ok(4), Err(5), Ok(6)] {
+            sum += ok_or_break_logged!(value, level: info, "break ok");
+        }
+        assert_eq!(sum, 6);
+        assert!(recorded("INFO break some [early break at"));
+        assert!(recorded("INFO break ok: 5 [early break at"));
+    }
+
+    #[test]
+    fn should_log_and_continue() {
+        init();
+        let mut sum = 0;
+        for value in [Some(1), None, Some(3)] {
+            sum += some_or_continue_logged!(value, level: debug, "continue some");
+        }
+        'l: for value in [Ok(4), Err(5), Ok(6)] {
+            for _i in 0..2 {
+                sum += ok_or_continue_logged!(value, 'l, level: trace, "continue ok");
+            }
+        }
+        assert_eq!(sum, 24);
+        assert!(recorded("DEBUG continue some [early continue at"));
+        assert!(recorded("TRACE continue ok: 5 [early continue at"));
+    }
+
+    #[test]
+    fn should_log_skipped_items() {
+        use crate::iter::EarlyIteratorExt;
+
+        init();
+        let rows = vec![Some(1), None, Some(3)].into_iter();
+        let (rows, line) = (rows.skip_none_logged(log::Level::Info, "missing row"), line!());
+        assert_eq!(rows.collect::<Vec<_>>(), [1, 3]);
+        assert!(recorded(&format!("INFO missing row [skipped item 1 at src/log.rs:{line}:34]")));
+        let rows = vec![Ok(1), Err("bad"), Err("worse")].into_iter();
+        let rows = rows.skip_err_logged(log::Level::Warn, "bad row");
+        assert_eq!(rows.collect::<Vec<_>>(), [1]);
+        assert!(recorded("WARN bad row: \"bad\" [skipped item 1 at src/log.rs:"));
+        assert!(recorded("WARN bad row: \"worse\" [skipped item 2 at src/log.rs:"));
+    }
+
+    const SIGN_LINE: u32 = line!() + 3;
+    fn sign(value: i32) -> i32 {
+        if value < 0 {
+            crate::return_early!(-1, log warn "negative {value}");
+        }
+        1
+    }
+
+    #[test]
+    fn should_log_unconditional_escapes() {
+        init();
+        assert_eq!((sign(-2), sign(2)), (-1, 1));
+        assert!(recorded(&format!("WARN negative -2 [early return at src/log.rs:{SIGN_LINE}:13]")));
+        'l: for value in [1, 2] {
+            for i in 0..2 {
+                if i == 1 {
+                    crate::continue_early!('l, log debug "skip {value}");
+                }
+            }
+        }
+        assert!(recorded("DEBUG skip 1 [early continue at src/log.rs:"));
+        assert!(recorded("DEBUG skip 2 [early continue at src/log.rs:"));
+    }
+}
@@ -0,0 +1,459 @@
+/// Loop for as long as an expression evaluates to `Ok`, binding the Ok value for the body of each
+/// iteration.
+///
+/// Once the expression evaluates to `Err`, the loop ends and evaluates to the error. Alternatively,
+/// an `else` arm can bind the error and handle it, in which case the loop evaluates to `()` and the
+/// body may also `break` out of it.
+/// ```
+/// use early_returns::while_ok;
+///
+/// fn read_all(mut lines: Vec<Result<String, &'static str>>) -> (Vec<String>, &'static str) {
+///     lines.reverse();
+///     let mut read = Vec::new();
+///     let err = while_ok!(let line = lines.pop().unwrap_or(Err("eof")) => {
+///         read.push(line);
+///     });
+///     (read, err)
+/// }
+///
+/// fn read_until_blank(mut lines: Vec<Result<String, &'static str>>) -> Vec<String> {
+///     lines.reverse();
+///     let mut read = Vec::new();
+///     while_ok!(let line = lines.pop().unwrap_or(Err("eof")) => {
+///         if line.is_empty() {
+///             break;
+///         }
+///         read.push(line);
+///     } else err => {
+///         eprintln!("stopped reading: {err}");
+///     });
+///     read
+/// }
+///
+/// let lines = vec![Ok("a".to_string()), Ok("b".to_string()), Err("broken pipe")];
+/// assert_eq!(read_all(lines), (vec!["a".to_string(), "b".to_string()], "broken pipe"));
+/// ```
+#[macro_export]
+macro_rules! while_ok {
+    (let $binding:pat = $from:expr => $body:block) => {
+        loop {
+            match $from {
+                Ok($binding) => $body,
+                Err(e) => break e,
+            }
+        }
+    };
+
+    (let $binding:pat = $from:expr => $body:block else $err:pat => $else_body:block) => {
+        loop {
+            match $from {
+                Ok($binding) => $body,
+                Err($err) => {
+                    $else_body;
+                    break;
+                }
+            }
+        }
+    };
+}
+
+/// Loop over an iterable of Options, binding the value of each `Some` item for the body.
+///
+/// By default (or with an explicit `skip_none:` mode) `None` items are skipped and the loop carries
+/// on with the next item. With the `stop_on_none:` mode the loop ends at the first `None` item.
+/// ```
+/// use early_returns::for_some;
+///
+/// let readings = vec![Some(1), None, Some(3), None, Some(5)];
+///
+/// let mut sum = 0;
+/// for_some!(reading in &readings => {
+///     sum += reading;
+/// });
+/// assert_eq!(sum, 9);
+///
+/// let mut sum = 0;
+/// for_some!(stop_on_none: reading in &readings => {
+///     sum += reading;
+/// });
+/// assert_eq!(sum, 1);
+/// ```
+#[macro_export]
+macro_rules! for_some {
+    (skip_none: $binding:pat in $iter:expr => $body:block) => {
+        for f in $iter {
+            match f {
+                Some($binding) => $body,
+                None => {}
+            }
+        }
+    };
+
+    (stop_on_none: $binding:pat in $iter:expr => $body:block) => {
+        for f in $iter {
+            match f {
+                Some($binding) => $body,
+                None => break,
+            }
+        }
+    };
+
+    ($binding:pat in $iter:expr => $body:block) => {
+        $crate::for_some!(skip_none: $binding in $iter => $body)
+    };
+}
+
+/// Repeatedly evaluate an Option-producing expression until it yields `Some`, and get that value.
+///
+/// A maximum number of attempts can be specified, after which the current function is returned
+/// from instead. A default return value can be provided as well. Running out of attempts is the
+/// escape the invocation asks for, so it does not panic with the `strict` feature enabled.
+/// ```
+/// use early_returns::loop_until_some;
+/// use std::collections::VecDeque;
+///
+/// fn next_message(queue: &mut VecDeque<Option<&'static str>>) -> Option<&'static str> {
+///     let message = loop_until_some!(queue.pop_front().flatten(), 3, None);
+///     Some(message)
+/// }
+///
+/// let mut queue = VecDeque::from([None, None, Some("hello")]);
+/// assert_eq!(next_message(&mut queue), Some("hello"));
+///
+/// let mut queue = VecDeque::from([None, None, None, Some("too late")]);
+/// assert_eq!(next_message(&mut queue), None);
+/// ```
+#[macro_export]
+macro_rules! loop_until_some {
+    ($from:expr) => {
+        loop {
+            if let Some(f) = $from {
+                break f;
+            }
+        }
+    };
+
+    ($from:expr, $max_attempts:expr) => {{
+        let mut found = None;
+        for _ in 0..$max_attempts {
+            found = $from;
+            if found.is_some() {
+                break;
+            }
+        }
+        match found {
+            Some(f) => f,
+            None => {
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $max_attempts:expr, $default_result:expr) => {{
+        let mut found = None;
+        for _ in 0..$max_attempts {
+            found = $from;
+            if found.is_some() {
+                break;
+            }
+        }
+        match found {
+            Some(f) => f,
+            None => {
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from the output of a `select!` arm (an Option or a Result, such as the
+/// result of `recv()`), or break out of the labeled loop around the `select!`. The residual (`()`
+/// for an Option, the error for a Result) can be bound and handled before breaking.
+///
+/// Matching the arm's output against `Some(msg)` in the arm itself would only disable that branch
+/// once the channel is closed, while the loop keeps running (or `select!` panics once every branch
+/// is disabled). Binding the whole output and extracting it in the arm's body shuts the loop down
+/// instead. The loop label is required, so the `break` cannot end up in an inner loop by accident.
+/// ```
+/// use early_returns::branch_or_break;
+/// use tokio::sync::mpsc::{self, Receiver};
+///
+/// async fn actor(mut commands: Receiver<i32>, mut shutdown: Receiver<()>) -> i32 {
+///     let mut total = 0;
+///     'actor: loop {
+///         tokio::select! {
+///             command = commands.recv() => {
+///                 total += branch_or_break!(command, 'actor);
+///             }
+///             signal = shutdown.recv() => {
+///                 branch_or_break!(signal, 'actor, _closed => total = -1);
+///                 break 'actor;
+///             }
+///         }
+///     }
+///     total
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (tx, commands) = mpsc::channel(4);
+/// let (_shutdown_tx, shutdown) = mpsc::channel(1);
+/// tx.send(1).await.unwrap();
+/// tx.send(2).await.unwrap();
+/// drop(tx);
+/// assert_eq!(actor(commands, shutdown).await, 3);
+/// # });
+/// ```
+#[macro_export]
+macro_rules! branch_or_break {
+    ($from:expr, $lt:lifetime) => {{
+        match $crate::__private::Engaged::into_engaged($from) {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::silent_break();
+                break $lt
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $r:ident => $handler:expr) => {{
+        match $crate::__private::Engaged::into_engaged($from) {
+            Ok(f) => f,
+            Err($r) => {
+                $handler;
+                break $lt
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    struct Reader {
+        results: Vec<Result<i32, &'static str>>,
+        reads: i32,
+    }
+
+    impl Reader {
+        fn new(mut results: Vec<Result<i32, &'static str>>) -> Reader {
+            results.reverse();
+            Reader { results, reads: 0 }
+        }
+
+        fn read(&mut self) -> Result<i32, &'static str> {
+            self.reads += 1;
+            self.results.pop().unwrap_or(Err("eof"))
+        }
+    }
+
+    #[test]
+    fn should_loop_while_ok_and_evaluate_to_error() {
+        let mut reader = Reader::new(vec![Ok(1), Ok(2), Err("broken")]);
+        let mut sum = 0;
+        let err = while_ok!(let value = reader.read() => {
+            sum += value;
+        });
+        assert_eq!(sum, 3);
+        assert_eq!(err, "broken");
+        assert_eq!(reader.reads, 3);
+    }
+
+    #[test]
+    fn should_not_run_body_when_first_evaluation_fails() {
+        let mut reader = Reader::new(vec![]);
+        let mut ran = false;
+        let err = while_ok!(let _value = reader.read() => {
+            ran = true;
+        });
+        assert!(!ran);
+        assert_eq!(err, "eof");
+    }
+
+    #[test]
+    fn should_allow_continue_in_body() {
+        let mut reader = Reader::new(vec![Ok(1), Ok(2), Ok(3)]);
+        let mut sum = 0;
+        while_ok!(let value = reader.read() => {
+            if value == 2 {
+                continue;
+            }
+            sum += value;
+        });
+        assert_eq!(sum, 4);
+    }
+
+    #[test]
+    fn should_run_else_arm_with_error() {
+        let mut reader = Reader::new(vec![Ok(1), Err("broken")]);
+        let mut sum = 0;
+        let mut errors = Vec::new();
+        while_ok!(let value = reader.read() => {
+            sum += value;
+        } else err => {
+            errors.push(err);
+        });
+        assert_eq!(sum, 1);
+        assert_eq!(errors, vec!["broken"]);
+    }
+
+    #[test]
+    fn should_allow_break_in_body_with_else_arm() {
+        let mut reader = Reader::new(vec![Ok(1), Ok(2), Ok(3)]);
+        let mut sum = 0;
+        let mut errors = Vec::new();
+        while_ok!(let value = reader.read() => {
+            if value == 2 {
+                break;
+            }
+            sum += value;
+        } else err => {
+            errors.push(err);
+        });
+        assert_eq!(sum, 1);
+        assert!(errors.is_empty());
+        assert_eq!(reader.reads, 2);
+    }
+
+    #[test]
+    fn should_skip_none_items_by_default() {
+        let mut values = Vec::new();
+        for_some!(value in [Some(1), None, Some(3)] => {
+            values.push(value);
+        });
+        assert_eq!(values, vec![1, 3]);
+    }
+
+    #[test]
+    fn should_skip_none_items_when_explicit() {
+        let mut values = Vec::new();
+        for_some!(skip_none: value in [None, Some(2), None, Some(4)] => {
+            values.push(value);
+        });
+        assert_eq!(values, vec![2, 4]);
+    }
+
+    #[test]
+    fn should_stop_on_first_none_item() {
+        let mut visited = 0;
+        let mut values = Vec::new();
+        for_some!(stop_on_none: value in [Some(1), Some(2), None, Some(4)].into_iter().inspect(|_| visited += 1) => {
+            values.push(value);
+        });
+        assert_eq!(values, vec![1, 2]);
+        assert_eq!(visited, 3);
+    }
+
+    #[test]
+    fn should_bind_patterns_from_items() {
+        let mut sum = 0;
+        for_some!((a, b) in [Some((1, 2)), None, Some((3, 4))] => {
+            if a == 3 {
+                continue;
+            }
+            sum += a + b;
+        });
+        assert_eq!(sum, 3);
+    }
+
+    struct Poller {
+        ready_after: i32,
+        polls: i32,
+    }
+
+    impl Poller {
+        fn new(ready_after: i32) -> Poller {
+            Poller {
+                ready_after,
+                polls: 0,
+            }
+        }
+
+        fn poll(&mut self) -> Option<i32> {
+            self.polls += 1;
+            if self.polls >= self.ready_after {
+                Some(self.polls * 10)
+            } else {
+                None
+            }
+        }
+
+        fn poll_at_most(&mut self, max_attempts: i32) -> i32 {
+            loop_until_some!(self.poll(), max_attempts, -1)
+        }
+
+        fn poll_at_most_or_return(&mut self, max_attempts: i32, value: &mut i32) {
+            *value = loop_until_some!(self.poll(), max_attempts);
+        }
+    }
+
+    #[test]
+    fn should_loop_until_some() {
+        let mut poller = Poller::new(4);
+        assert_eq!(loop_until_some!(poller.poll()), 40);
+        assert_eq!(poller.polls, 4);
+    }
+
+    #[test]
+    fn should_get_value_within_max_attempts() {
+        let mut poller = Poller::new(3);
+        assert_eq!(poller.poll_at_most(3), 30);
+        assert_eq!(poller.polls, 3);
+    }
+
+    #[test]
+    fn should_return_default_after_max_attempts() {
+        let mut poller = Poller::new(5);
+        assert_eq!(poller.poll_at_most(3), -1);
+        assert_eq!(poller.polls, 3);
+    }
+
+    #[test]
+    fn should_return_early_after_max_attempts() {
+        let mut value = 0;
+        let mut poller = Poller::new(2);
+        poller.poll_at_most_or_return(1, &mut value);
+        assert_eq!(value, 0);
+        poller.poll_at_most_or_return(1, &mut value);
+        assert_eq!(value, 20);
+    }
+
+    #[cfg(feature = "strict")]
+    #[test]
+    fn should_run_out_of_attempts_in_strict_mode() {
+        let mut value = 0;
+        let mut poller = Poller::new(3);
+        poller.poll_at_most_or_return(1, &mut value);
+        assert_eq!(value, 0);
+        assert_eq!(poller.poll_at_most(1), -1);
+    }
+
+    #[tokio::test]
+    async fn should_break_the_select_loop_when_a_branch_ends() {
+        let (tx, mut numbers) = tokio::sync::mpsc::channel(4);
+        let (_idle_tx, mut idle) = tokio::sync::mpsc::channel::<i32>(1);
+        tx.send(1).await.unwrap();
+        tx.send(2).await.unwrap();
+        drop(tx);
+        let mut sum = 0;
+        'select: loop {
+            for _i in 0..2 {
+                tokio::select! {
+                    number = numbers.recv() => sum += branch_or_break!(number, 'select),
+                    number = idle.recv() => sum += branch_or_break!(number, 'select),
+                }
+            }
+        }
+        assert_eq!(sum, 3);
+        let mut failures = Vec::new();
+        'select: loop {
+            tokio::select! {
+                result = async { "x".parse::<i32>() } => {
+                    sum += branch_or_break!(result, 'select, e => failures.push(e));
+                }
+            }
+        }
+        assert_eq!(sum, 3);
+        assert_eq!(failures.len(), 1);
+    }
+}
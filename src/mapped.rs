@@ -0,0 +1,132 @@
+/// Either get the Ok value from a Result type or return the error from the current function,
+/// mapped by a closure first, so the underlying error can be wrapped with context before bailing
+/// out. Unlike `ok_or_return_boxed!`, the mapped error is returned as is, without `From`.
+/// ```
+/// use early_returns::ok_or_return_mapped;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum ConfigError {
+///     Port(String),
+/// }
+///
+/// fn port(value: &str) -> Result<u16, ConfigError> {
+///     let port = ok_or_return_mapped!(value.parse(), |e| {
+///         ConfigError::Port(format!("{value:?}: {e}"))
+///     });
+///     Ok(port)
+/// }
+///
+/// assert_eq!(port("80"), Ok(80));
+/// assert_eq!(
+///     port("http"),
+///     Err(ConfigError::Port("\"http\": invalid digit found in string".to_string()))
+/// );
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_mapped {
+    ($from:expr, $map:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__mark_early_exit!();
+                return Err($crate::__private::map_err(e, $map));
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or break out of a loop with the error, mapped by a
+/// closure first, as the value of the loop (`Err(map(e))`). This only works in a `loop` or a
+/// labeled block, which can break with a value. If a lifetime is specified, that loop or block
+/// will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::ok_or_break_mapped;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct LineError {
+///     line: usize,
+///     message: String,
+/// }
+///
+/// fn sum(lines: &[&str]) -> Result<i32, LineError> {
+///     let mut lines = lines.iter().enumerate();
+///     let mut sum = 0;
+///     loop {
+///         let Some((line, value)) = lines.next() else {
+///             break Ok(sum);
+///         };
+///         sum += ok_or_break_mapped!(value.parse::<i32>(), |e| LineError {
+///             line: line + 1,
+///             message: e.to_string(),
+///         });
+///     }
+/// }
+///
+/// assert_eq!(sum(&["1", "2"]), Ok(3));
+/// assert_eq!(
+///     sum(&["1", "two"]),
+///     Err(LineError { line: 2, message: "invalid digit found in string".to_string() })
+/// );
+/// ```
+#[macro_export]
+macro_rules! ok_or_break_mapped {
+    ($from:expr, $lt:lifetime, $map:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => break $lt Err($crate::__private::map_err(e, $map)),
+        }
+    }};
+
+    ($from:expr, $map:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => break Err($crate::__private::map_err(e, $map)),
+        }
+    }};
+}
+
+// Fixes the parameter type of the closure given to the `*_mapped!` macros, which could not be
+// inferred from a direct call.
+#[inline]
+pub fn map_err<E, F>(e: E, map: impl FnOnce(E) -> F) -> F {
+    map(e)
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug, PartialEq)]
+    struct Context(&'static str, String);
+
+    fn double(value: Result<i32, &str>) -> Result<i32, Context> {
+        let value = ok_or_return_mapped!(value, |e| Context("double", e.to_uppercase()));
+        Ok(value * 2)
+    }
+
+    #[test]
+    fn should_return_mapped_errors() {
+        assert_eq!(double(Ok(2)), Ok(4));
+        assert_eq!(double(Err("bad")), Err(Context("double", "BAD".to_string())));
+    }
+
+    #[test]
+    fn should_break_with_mapped_errors() {
+        let values = [Ok(1), Ok(2), Err("bad"), Ok(4)];
+        let mut sum = 0;
+        let mut values_iter = values.iter();
+        let result = loop {
+            let Some(value) = values_iter.next() else {
+                break Ok(sum);
+            };
+            sum += ok_or_break_mapped!(value, |e| Context("sum", e.to_string()));
+        };
+        assert_eq!((result, sum), (Err(Context("sum", "bad".to_string())), 3));
+        let result: Result<i32, String> = 'sum: {
+            let mut sum = 0;
+            for value in [Ok(1), Err(2)] {
+                sum += ok_or_break_mapped!(value, 'sum, |e| format!("error {e}"));
+            }
+            Ok(sum)
+        };
+        assert_eq!(result, Err("error 2".to_string()));
+    }
+}
@@ -0,0 +1,454 @@
+/// Either get the value from an Option type or return from the current function, incrementing the
+/// `metrics` counter with the given name (labelled with `early_exit = "return"`) first if there is
+/// none. A default return value can be provided before the metric name.
+/// ```
+/// use early_returns::some_or_return_counted;
+///
+/// fn frobnicate(frobnicator: Option<&str>) -> Option<usize> {
+///     let frobnicator =
+///         some_or_return_counted!(frobnicator, None, metric: "frobnicator.missing");
+///     Some(frobnicator.len())
+/// }
+///
+/// assert_eq!(frobnicate(Some("abc")), Some(3));
+/// assert_eq!(frobnicate(None), None);
+/// ```
+#[macro_export]
+macro_rules! some_or_return_counted {
+    ($from:expr, metric: $metric:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "return",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, metric: $metric:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "return",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or break out of a loop, incrementing the `metrics`
+/// counter with the given name (labelled with `early_exit = "break"`) first if there is none. If a
+/// loop lifetime is specified (before the metric name), that loop will be exited, otherwise the
+/// immediate loop is exited.
+/// ```
+/// use early_returns::some_or_break_counted;
+///
+/// let mut read = Vec::new();
+/// for value in [Some(1), Some(2), None, Some(4)] {
+///     read.push(some_or_break_counted!(value, metric: "read.stopped"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! some_or_break_counted {
+    ($from:expr, metric: $metric:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "break",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, metric: $metric:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "break",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or continue in a loop, incrementing the `metrics`
+/// counter with the given name (labelled with `early_exit = "continue"`) first if there is none. If
+/// a loop lifetime is specified (before the metric name), that loop will be "continued", otherwise
+/// the immediate loop is "continued".
+/// ```
+/// use early_returns::some_or_continue_counted;
+///
+/// let mut read = Vec::new();
+/// for value in [Some(1), None, Some(3)] {
+///     read.push(some_or_continue_counted!(value, metric: "parse.skip"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! some_or_continue_counted {
+    ($from:expr, metric: $metric:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "continue",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, metric: $metric:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "continue",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or return from the current function, incrementing the
+/// `metrics` counter with the given name (labelled with `early_exit = "return"`) first if it is an
+/// `Err`. A default return value can be provided before the metric name.
+/// ```
+/// use early_returns::ok_or_return_counted;
+///
+/// fn port(value: &str) {
+///     let port: u16 = ok_or_return_counted!(value.parse(), metric: "config.bad_port");
+///     println!("listening on {port}");
+/// }
+///
+/// port("8080");
+/// port("http");
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_counted {
+    ($from:expr, metric: $metric:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "return",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, metric: $metric:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "return",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or break out of a loop, incrementing the `metrics`
+/// counter with the given name (labelled with `early_exit = "break"`) first if it is an `Err`. If a
+/// loop lifetime is specified (before the metric name), that loop will be exited, otherwise the
+/// immediate loop is exited.
+/// ```
+/// use early_returns::ok_or_break_counted;
+///
+/// let mut read = Vec::new();
+/// for value in [Ok(1), Ok(2), Err("eof"), Ok(4)] {
+///     read.push(ok_or_break_counted!(value, metric: "read.stopped"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_break_counted {
+    ($from:expr, metric: $metric:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "break",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, metric: $metric:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "break",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or continue in a loop, incrementing the `metrics`
+/// counter with the given name (labelled with `early_exit = "continue"`) first if it is an `Err`.
+/// If a loop lifetime is specified (before the metric name), that loop will be "continued",
+/// otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::ok_or_continue_counted;
+///
+/// let mut read = Vec::new();
+/// for value in [Ok(1), Err("bad row"), Ok(3)] {
+///     read.push(ok_or_continue_counted!(value, metric: "parse.skip"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_continue_counted {
+    ($from:expr, metric: $metric:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "continue",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, metric: $metric:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::metrics::counter!(
+                    $metric,
+                    "early_exit" => "continue",
+                    "location" => $crate::__private::caller_location().to_string(),
+                )
+                .increment(1);
+                continue $lt;
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use metrics::{Counter, CounterFn, Gauge, Histogram, Key, KeyName, Metadata, Recorder};
+    use metrics::{SharedString, Unit};
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Counts(Arc<Mutex<BTreeMap<String, u64>>>);
+
+    struct Count {
+        key: String,
+        counts: Arc<Mutex<BTreeMap<String, u64>>>,
+    }
+
+    impl CounterFn for Count {
+        fn increment(&self, value: u64) {
+            *self
+                .counts
+                .lock()
+                .unwrap()
+                .entry(self.key.clone())
+                .or_default() += value;
+        }
+
+        fn absolute(&self, value: u64) {
+            self.counts.lock().unwrap().insert(self.key.clone(), value);
+        }
+    }
+
+    impl Recorder for Counts {
+        fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _text: SharedString) {}
+
+        fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _text: SharedString) {}
+
+        fn describe_histogram(&self, _key: KeyName, _unit: Option<Unit>, _text: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|l| match l.key() {
+                    "location" => format!("location={}", l.value().split(':').next().unwrap()),
+                    _ => format!("{}={}", l.key(), l.value()),
+                })
+                .collect();
+            Counter::from_arc(Arc::new(Count {
+                key: format!("{} {}", key.name(), labels.join(",")),
+                counts: self.0.clone(),
+            }))
+        }
+
+        fn register_gauge(&self, _key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    fn count<F: FnOnce()>(f: F) -> Vec<(String, u64)> {
+        let counts = Counts::default();
+        metrics::with_local_recorder(&counts, f);
+        let counted = counts.0.lock().unwrap().clone();
+        counted.into_iter().collect()
+    }
+
+    fn value_or_default(value: Option<i32>) -> i32 {
+        some_or_return_counted!(value, -1, metric: "value.missing")
+    }
+
+    fn push_ok(value: Result<i32, ()>, values: &mut Vec<i32>) {
+        let name = String::from("push.failed");
+        values.push(ok_or_return_counted!(value, metric: name));
+    }
+
+    #[test]
+    fn should_count_and_return_early() {
+        let mut values = Vec::new();
+        let counted = count(|| {
+            assert_eq!(value_or_default(Some(1)), 1);
+            assert_eq!(value_or_default(None), -1);
+            assert_eq!(value_or_default(None), -1);
+            push_ok(Ok(1), &mut values);
+            push_ok(Err(()), &mut values);
+        });
+        assert_eq!(values, vec![1]);
+        assert_eq!(
+            counted,
+            vec![
+                (
+                    "push.failed early_exit=return,location=src/metrics.rs".to_string(),
+                    1
+                ),
+                (
+                    "value.missing early_exit=return,location=src/metrics.rs".to_string(),
+                    2
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_count_and_break_or_continue() {
+        let mut sum = 0;
+        let counted = count(|| {
+            for value in [Some(1), None, Some(3), None] {
+                sum += some_or_continue_counted!(value, metric: "skip");
+            }
+            'l: for value in [Ok(4), Err(5), Ok(6)] {
+                for _i in 0..2 {
+                    sum += ok_or_break_counted!(value, 'l, metric: "stop");
+                }
+            }
+            'l: for value in [Err(()), Ok(1)] {
+                for _i in 0..2 {
+                    sum += ok_or_continue_counted!(value, 'l, metric: "skip");
+                }
+            }
+            for value in [None, Some(1)] {
+                sum += some_or_break_counted!(value, metric: "stop");
+            }
+        });
+        assert_eq!(sum, 14);
+        assert_eq!(
+            counted,
+            vec![
+                (
+                    "skip early_exit=continue,location=src/metrics.rs".to_string(),
+                    3
+                ),
+                (
+                    "stop early_exit=break,location=src/metrics.rs".to_string(),
+                    2
+                ),
+            ]
+        );
+    }
+
+    fn sign(value: i32) -> i32 {
+        if value < 0 {
+            crate::return_early!(-1, count "negative");
+        }
+        1
+    }
+
+    #[test]
+    fn should_count_unconditional_escapes() {
+        let counted = count(|| {
+            assert_eq!((sign(-1), sign(2), sign(-3)), (-1, 1, -1));
+            for value in [1, 2] {
+                if value == 2 {
+                    crate::break_early!(count "stop");
+                }
+            }
+        });
+        assert_eq!(
+            counted,
+            vec![
+                (
+                    "negative early_exit=return,location=src/metrics.rs".to_string(),
+                    2
+                ),
+                (
+                    "stop early_exit=break,location=src/metrics.rs".to_string(),
+                    1
+                ),
+            ]
+        );
+    }
+}
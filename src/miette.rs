@@ -0,0 +1,246 @@
+use std::error::Error;
+use std::fmt;
+
+/// Either get the Ok value from a Result type or return the error from the current function,
+/// wrapped into a `miette::Report` with a message and, optionally, a diagnostic code and help
+/// text. The message accepts format arguments and, like the code and help text, is only evaluated
+/// on the escape path.
+///
+/// The original error is kept as the source of the diagnostic, so it is rendered as its cause. The
+/// report is converted into the error type of the function with `From`, so this works in functions
+/// returning `miette::Result`.
+/// ```
+/// use early_returns::ok_or_return_diag;
+///
+/// fn port(value: &str) -> miette::Result<u16> {
+///     let port: u16 = ok_or_return_diag!(
+///         value.parse(),
+///         code = "config::bad_port",
+///         help = "ports are numbers between 0 and 65535",
+///         "invalid port {value:?}",
+///     );
+///     Ok(port)
+/// }
+///
+/// assert_eq!(port("8080").unwrap(), 8080);
+/// let report = port("http").unwrap_err();
+/// assert_eq!(report.to_string(), "invalid port \"http\"");
+/// assert_eq!(report.code().unwrap().to_string(), "config::bad_port");
+/// assert_eq!(report.help().unwrap().to_string(), "ports are numbers between 0 and 65535");
+/// assert_eq!(report.root_cause().to_string(), "invalid digit found in string");
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_diag {
+    (@diag $from:expr, $code:expr, $help:expr, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from($crate::__private::miette::Report::new(
+                    $crate::__private::EscapeDiagnostic {
+                        message: ::std::format!($($arg)+),
+                        code: $code,
+                        help: $help,
+                        source: Some(::std::boxed::Box::from(e)),
+                    },
+                )))
+            }
+        }
+    }};
+
+    ($from:expr, code = $code:expr, help = $help:expr, $($arg:tt)+) => {
+        $crate::ok_or_return_diag!(
+            @diag $from,
+            Some(::std::string::ToString::to_string(&$code)),
+            Some(::std::string::ToString::to_string(&$help)),
+            $($arg)+
+        )
+    };
+
+    ($from:expr, code = $code:expr, $($arg:tt)+) => {
+        $crate::ok_or_return_diag!(
+            @diag $from,
+            Some(::std::string::ToString::to_string(&$code)),
+            None,
+            $($arg)+
+        )
+    };
+
+    ($from:expr, help = $help:expr, $($arg:tt)+) => {
+        $crate::ok_or_return_diag!(
+            @diag $from,
+            None,
+            Some(::std::string::ToString::to_string(&$help)),
+            $($arg)+
+        )
+    };
+
+    ($from:expr, $($arg:tt)+) => {
+        $crate::ok_or_return_diag!(@diag $from, None, None, $($arg)+)
+    };
+}
+
+/// Either get the value from an Option type or return a `miette::Report` with the given message
+/// and, optionally, a diagnostic code and help text from the current function. The message accepts
+/// format arguments and, like the code and help text, is only evaluated on the escape path.
+/// ```
+/// use early_returns::some_or_return_diag;
+/// use std::collections::HashMap;
+///
+/// fn lookup(users: &HashMap<u32, String>, id: u32) -> miette::Result<&str> {
+///     let user = some_or_return_diag!(users.get(&id), help = "run `users list`", "no user {id}");
+///     Ok(user)
+/// }
+///
+/// let users = HashMap::from([(1, "ada".to_string())]);
+/// assert_eq!(lookup(&users, 1).unwrap(), "ada");
+/// let report = lookup(&users, 2).unwrap_err();
+/// assert_eq!(report.to_string(), "no user 2");
+/// assert_eq!(report.help().unwrap().to_string(), "run `users list`");
+/// ```
+#[macro_export]
+macro_rules! some_or_return_diag {
+    (@diag $from:expr, $code:expr, $help:expr, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__mark_early_exit!();
+                return Err(::core::convert::From::from($crate::__private::miette::Report::new(
+                    $crate::__private::EscapeDiagnostic {
+                        message: ::std::format!($($arg)+),
+                        code: $code,
+                        help: $help,
+                        source: None,
+                    },
+                )))
+            }
+        }
+    }};
+
+    ($from:expr, code = $code:expr, help = $help:expr, $($arg:tt)+) => {
+        $crate::some_or_return_diag!(
+            @diag $from,
+            Some(::std::string::ToString::to_string(&$code)),
+            Some(::std::string::ToString::to_string(&$help)),
+            $($arg)+
+        )
+    };
+
+    ($from:expr, code = $code:expr, $($arg:tt)+) => {
+        $crate::some_or_return_diag!(
+            @diag $from,
+            Some(::std::string::ToString::to_string(&$code)),
+            None,
+            $($arg)+
+        )
+    };
+
+    ($from:expr, help = $help:expr, $($arg:tt)+) => {
+        $crate::some_or_return_diag!(
+            @diag $from,
+            None,
+            Some(::std::string::ToString::to_string(&$help)),
+            $($arg)+
+        )
+    };
+
+    ($from:expr, $($arg:tt)+) => {
+        $crate::some_or_return_diag!(@diag $from, None, None, $($arg)+)
+    };
+}
+
+/// The diagnostic built on the escape path of `ok_or_return_diag!` and `some_or_return_diag!`.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct EscapeDiagnostic {
+    pub message: String,
+    pub code: Option<String>,
+    pub help: Option<String>,
+    pub source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl fmt::Display for EscapeDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for EscapeDiagnostic {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
+}
+
+impl miette::Diagnostic for EscapeDiagnostic {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.code.as_ref().map(|c| Box::new(c) as Box<dyn fmt::Display>)
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.help.as_ref().map(|h| Box::new(h) as Box<dyn fmt::Display>)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use miette::Result;
+    use std::cell::Cell;
+
+    fn parse(value: &str) -> Result<i32> {
+        Ok(ok_or_return_diag!(value.parse(), "bad value {value}"))
+    }
+
+    fn parse_with_code(value: &str) -> Result<i32> {
+        Ok(ok_or_return_diag!(value.parse(), code = "app::parse", "bad value"))
+    }
+
+    fn first_even(values: &[i32]) -> Result<i32> {
+        let even = some_or_return_diag!(
+            values.iter().find(|v| *v % 2 == 0),
+            code = "app::no_even",
+            help = "add an even value",
+            "no even value in {} values",
+            values.len(),
+        );
+        Ok(*even)
+    }
+
+    #[test]
+    fn should_return_diagnostic_with_source() {
+        assert_eq!(parse("1").unwrap(), 1);
+        let report = parse("x").unwrap_err();
+        assert_eq!(report.to_string(), "bad value x");
+        assert!(report.code().is_none());
+        assert!(report.help().is_none());
+        assert!(report.root_cause().is::<std::num::ParseIntError>());
+        let report = parse_with_code("x").unwrap_err();
+        assert_eq!(report.code().unwrap().to_string(), "app::parse");
+        assert!(report.help().is_none());
+    }
+
+    #[test]
+    fn should_return_diagnostic_when_none() {
+        assert_eq!(first_even(&[1, 2]).unwrap(), 2);
+        let report = first_even(&[1, 3]).unwrap_err();
+        assert_eq!(report.to_string(), "no even value in 2 values");
+        assert_eq!(report.code().unwrap().to_string(), "app::no_even");
+        assert_eq!(report.help().unwrap().to_string(), "add an even value");
+        assert_eq!(report.chain().count(), 1);
+    }
+
+    #[test]
+    fn should_only_evaluate_diagnostic_on_escape_path() {
+        let evaluated = Cell::new(0);
+        let help = || {
+            evaluated.set(evaluated.get() + 1);
+            "help"
+        };
+        let check = |value: Option<i32>| -> Result<i32> {
+            Ok(some_or_return_diag!(value, help = help(), "missing"))
+        };
+        assert_eq!(check(Some(1)).unwrap(), 1);
+        assert_eq!(evaluated.get(), 0);
+        assert!(check(None).is_err());
+        assert_eq!(evaluated.get(), 1);
+    }
+}
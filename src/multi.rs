@@ -0,0 +1,583 @@
+/// Either get the values from a tuple of Option types or return from the current function.
+/// A default return value can be provided.
+///
+/// The Options are evaluated in order and evaluation stops at the first `None`.
+/// ```
+/// use early_returns::all_some_or_return;
+/// fn print_if_all_available(a: Option<i32>, b: Option<i32>, c: Option<i32>) {
+///     let (a, b, c) = all_some_or_return!((a, b, c));
+///     println!("{a} + {b} + {c} = {}", a + b + c);
+/// }
+///
+/// fn sum_if_all_available(a: Option<i32>, b: Option<i32>) -> i32 {
+///     let (a, b) = all_some_or_return!((a, b), 0);
+///     a + b
+/// }
+/// ```
+#[macro_export]
+macro_rules! all_some_or_return {
+    (($($from:expr),+ $(,)?)) => {
+        ($($crate::some_or_return!($from),)+)
+    };
+
+    (($($from:expr),+ $(,)?), $default_result:expr) => {
+        ($($crate::some_or_return!($from, $default_result),)+)
+    };
+}
+
+/// Either get the values from a tuple of Option types or break out of a loop. If a loop lifetime
+/// is specified, that loop will be exited, otherwise the immediate loop is exited.
+///
+/// The Options are evaluated in order and evaluation stops at the first `None`.
+/// ```
+/// use early_returns::all_some_or_break;
+/// fn sum_pairs(vals: &Vec<(Option<i32>, Option<i32>)>) -> i32 {
+///     let mut sum = 0;
+///     for (a, b) in vals {
+///         let (a, b) = all_some_or_break!((a, b));
+///         sum += a + b;
+///     }
+///
+///     'l: for (a, b) in vals {
+///         for i in 0..5 {
+///             let (a, b) = all_some_or_break!((a, b), 'l);
+///             sum += a + b + i;
+///         }
+///     }
+///     sum
+/// }
+/// ```
+#[macro_export]
+macro_rules! all_some_or_break {
+    (($($from:expr),+ $(,)?)) => {
+        ($($crate::some_or_break!($from),)+)
+    };
+
+    (($($from:expr),+ $(,)?), $lt:lifetime) => {
+        ($($crate::some_or_break!($from, $lt),)+)
+    };
+}
+
+/// Either get the values from a tuple of Option types or continue in a loop. If a loop lifetime
+/// is specified, that loop will be "continued", otherwise the immediate loop is "continued".
+///
+/// The Options are evaluated in order and evaluation stops at the first `None`.
+/// ```
+/// use early_returns::all_some_or_continue;
+/// fn sum_pairs(vals: &Vec<(Option<i32>, Option<i32>)>) -> i32 {
+///     let mut sum = 0;
+///     for (a, b) in vals {
+///         let (a, b) = all_some_or_continue!((a, b));
+///         sum += a + b;
+///     }
+///
+///     'l: for (a, b) in vals {
+///         for i in 0..5 {
+///             let (a, b) = all_some_or_continue!((a, b), 'l);
+///             sum += a + b + i;
+///         }
+///     }
+///     sum
+/// }
+/// ```
+#[macro_export]
+macro_rules! all_some_or_continue {
+    (($($from:expr),+ $(,)?)) => {
+        ($($crate::some_or_continue!($from),)+)
+    };
+
+    (($($from:expr),+ $(,)?), $lt:lifetime) => {
+        ($($crate::some_or_continue!($from, $lt),)+)
+    };
+}
+
+/// Either get the Ok values from a tuple of Result types or return an error from the current
+/// function.
+///
+/// By default (or with `first`), the Results are evaluated in order, evaluation stops at the
+/// first `Err` and that error is returned, converted with `From` like the `?` operator does.
+/// With `all`, every Result is evaluated and, if any of them failed, a `Vec` of all the errors
+/// is returned (also converted with `From`).
+#[cfg_attr(feature = "alloc", doc = "```")]
+#[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+/// use early_returns::all_ok_or_return;
+///
+/// fn parse_first(a: &str, b: &str) -> Result<i32, std::num::ParseIntError> {
+///     let (a, b) = all_ok_or_return!((a.parse::<i32>(), b.parse::<i32>()));
+///     Ok(a + b)
+/// }
+///
+/// fn parse_all(a: &str, b: &str) -> Result<i32, Vec<std::num::ParseIntError>> {
+///     let (a, b) = all_ok_or_return!((a.parse::<i32>(), b.parse::<i32>()), all);
+///     Ok(a + b)
+/// }
+///
+/// assert_eq!(parse_first("1", "2"), Ok(3));
+/// assert!(parse_first("x", "2").is_err());
+/// assert_eq!(parse_all("x", "y").unwrap_err().len(), 2);
+/// ```
+#[macro_export]
+macro_rules! all_ok_or_return {
+    (($($from:expr),+ $(,)?)) => {
+        $crate::all_ok_or_return!(($($from),+), first)
+    };
+
+    (($($from:expr),+ $(,)?), first) => {
+        ($(
+            match $from {
+                Ok(f) => f,
+                Err(e) => {
+                    $crate::__mark_early_exit!();
+                    return Err(::core::convert::From::from(e));
+                }
+            },
+        )+)
+    };
+
+    (($($from:expr),+ $(,)?), all) => {
+        $crate::all_ok_or_return!(@collect [] $($from,)+)
+    };
+
+    // Every step of the recursion introduces its own (hygienically distinct) `f` binding.
+    (@collect [$($done:tt)*] $from:expr, $($rest:expr,)*) => {
+        $crate::all_ok_or_return!(@collect [$($done)* (f $from)] $($rest,)*)
+    };
+
+    (@collect [$(($f:ident $from:expr))+]) => {{
+        let mut errors = $crate::__private::Vec::new();
+        $(
+            let $f = match $from {
+                Ok(f) => Some(f),
+                Err(e) => {
+                    errors.push(e);
+                    None
+                }
+            };
+        )+
+        if !errors.is_empty() {
+            $crate::__mark_early_exit!();
+            return Err(::core::convert::From::from(errors));
+        }
+        ($(
+            match $f {
+                Some(f) => f,
+                None => unreachable!(),
+            },
+        )+)
+    }};
+}
+
+/// Either get the value of the first engaged Option in a list of alternatives or return from the
+/// current function. A default return value can be provided after a `;`.
+///
+/// The alternatives are evaluated lazily and in order: once one of them is `Some`, the ones after
+/// it are never evaluated.
+/// ```
+/// use early_returns::first_some_or_return;
+///
+/// fn cli_arg() -> Option<u16> { None }
+/// fn env_var() -> Option<u16> { Some(8080) }
+/// fn config_file() -> Option<u16> { unreachable!() }
+///
+/// fn port() -> u16 {
+///     first_some_or_return!(cli_arg(), env_var(), config_file(); 80)
+/// }
+///
+/// fn print_port() {
+///     let port = first_some_or_return!(cli_arg(), env_var());
+///     println!("{port}");
+/// }
+///
+/// assert_eq!(port(), 8080);
+/// ```
+#[macro_export]
+macro_rules! first_some_or_return {
+    (@first [$from:expr] $escape:block) => {
+        $crate::__extract_or!(Some, $from, $escape)
+    };
+
+    (@first [$from:expr, $($rest:expr),+] $escape:block) => {
+        $crate::__extract_or!(Some, $from, or {
+            $crate::first_some_or_return!(@first [$($rest),+] $escape)
+        })
+    };
+
+    ($($from:expr),+ $(,)?) => {
+        $crate::first_some_or_return!(@first [$($from),+] {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return;
+        })
+    };
+
+    ($($from:expr),+ ; $default_result:expr) => {
+        $crate::first_some_or_return!(@first [$($from),+] {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        })
+    };
+}
+
+/// Either bind every name to the value of its Option or Result in the current scope or return
+/// from the current function. A default return value can be provided after a `;`.
+///
+/// The bindings are evaluated in order and evaluation stops at the first `None` or `Err`, so
+/// later expressions can use the names bound before them.
+/// ```
+/// use early_returns::bind_all_or_return;
+///
+/// fn describe(name: Option<&str>, age: &str) -> String {
+///     bind_all_or_return! {
+///         name = name,
+///         age = age.parse::<u32>(),
+///         mut greeting = Some(format!("{name} is {age}"));
+///         String::new()
+///     }
+///     greeting.push('!');
+///     greeting
+/// }
+///
+/// assert_eq!(describe(Some("Ferris"), "7"), "Ferris is 7!");
+/// assert_eq!(describe(Some("Ferris"), "seven"), "");
+/// assert_eq!(describe(None, "7"), "");
+/// ```
+#[macro_export]
+macro_rules! bind_all_or_return {
+    ($($binding:pat = $from:expr),+ $(,)?) => {
+        $(
+            let $binding = match $crate::__private::Engaged::into_engaged($from) {
+                Ok(f) => f,
+                Err(_) => {
+                    $crate::__private::silent_return();
+                    $crate::__mark_early_exit!();
+                    return;
+                }
+            };
+        )+
+    };
+
+    ($($binding:pat = $from:expr),+ ; $default_result:expr) => {
+        $(
+            let $binding = match $crate::__private::Engaged::into_engaged($from) {
+                Ok(f) => f,
+                Err(_) => {
+                    $crate::__private::silent_return();
+                    $crate::__mark_early_exit!();
+                    return $default_result;
+                }
+            };
+        )+
+    };
+}
+
+#[cfg(test)]
+mod test {
+    struct Tester {
+        value: i32,
+        evaluated: i32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester {
+                value: 0,
+                evaluated: 0,
+            }
+        }
+
+        fn evaluate(&mut self, value: Option<i32>) -> Option<i32> {
+            self.evaluated += 1;
+            value
+        }
+
+        fn increment_with_all(&mut self, a: Option<i32>, b: Option<i32>, c: Option<i32>) {
+            let (a, b, c) =
+                all_some_or_return!((self.evaluate(a), self.evaluate(b), self.evaluate(c)));
+            self.value += a + b + c;
+        }
+
+        fn increment_with_single(&mut self, a: Option<i32>) {
+            let (a,) = all_some_or_return!((a,));
+            self.value += a;
+        }
+
+        fn increment_with_bindings(&mut self, a: Option<i32>, b: Result<i32, ()>, c: &Option<i32>) {
+            bind_all_or_return! {
+                a = a,
+                b = b,
+                c = c,
+            }
+            self.value += a + b + c;
+        }
+    }
+
+    fn sum_or_default(a: Option<i32>, b: Option<&i32>) -> i32 {
+        let (a, b) = all_some_or_return!((a, b), -1);
+        a + b
+    }
+
+    #[test]
+    fn should_not_return_early_when_all_engaged() {
+        let mut tester = Tester::new();
+        tester.increment_with_all(Some(1), Some(2), Some(3));
+        assert_eq!(tester.value, 6);
+        assert_eq!(tester.evaluated, 3);
+    }
+
+    #[test]
+    fn should_return_early_and_stop_evaluating_at_first_unengaged() {
+        let mut tester = Tester::new();
+        tester.increment_with_all(Some(1), None, Some(3));
+        assert_eq!(tester.value, 0);
+        assert_eq!(tester.evaluated, 2);
+    }
+
+    #[test]
+    fn should_support_single_element_tuples() {
+        let mut tester = Tester::new();
+        tester.increment_with_single(Some(4));
+        tester.increment_with_single(None);
+        assert_eq!(tester.value, 4);
+    }
+
+    #[test]
+    fn should_return_default_when_any_unengaged() {
+        assert_eq!(sum_or_default(Some(1), Some(&2)), 3);
+        assert_eq!(sum_or_default(None, Some(&2)), -1);
+        assert_eq!(sum_or_default(Some(1), None), -1);
+    }
+
+    #[test]
+    fn should_break_when_any_unengaged() {
+        let mut value = 0;
+        for (a, b) in [(Some(1), Some(2)), (Some(1), None), (Some(3), Some(4))] {
+            let (a, b) = all_some_or_break!((a, b));
+            value += a + b;
+        }
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn should_break_with_lifetime_when_any_unengaged() {
+        let mut value = 0;
+        'l: for (a, b) in [(Some(1), Some(2)), (None, Some(1)), (Some(3), Some(4))] {
+            value += 1;
+            for _i in 0..1 {
+                let (a, b) = all_some_or_break!((a, b), 'l);
+                value += a + b;
+            }
+        }
+        assert_eq!(value, 5);
+    }
+
+    #[test]
+    fn should_continue_when_any_unengaged() {
+        let mut value = 0;
+        for (a, b) in [(Some(1), Some(2)), (Some(1), None), (Some(3), Some(4))] {
+            let (a, b) = all_some_or_continue!((a, b));
+            value += a + b;
+        }
+        assert_eq!(value, 10);
+    }
+
+    #[test]
+    fn should_continue_with_lifetime_when_any_unengaged() {
+        let mut value = 0;
+        'l: for (a, b) in [(Some(1), Some(2)), (None, Some(1)), (Some(3), Some(4))] {
+            value += 1;
+            for _i in 0..1 {
+                let (a, b) = all_some_or_continue!((a, b), 'l);
+                value += a + b;
+            }
+        }
+        assert_eq!(value, 13);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct ValidationError(&'static str);
+
+    fn non_empty(field: &'static str, value: &str) -> Result<usize, &'static str> {
+        if value.is_empty() {
+            Err(field)
+        } else {
+            Ok(value.len())
+        }
+    }
+
+    impl From<&'static str> for ValidationError {
+        fn from(field: &'static str) -> Self {
+            ValidationError(field)
+        }
+    }
+
+    fn validate_first(name: &str, email: &str) -> Result<usize, ValidationError> {
+        let (name, email) = all_ok_or_return!((non_empty("name", name), non_empty("email", email)));
+        Ok(name + email)
+    }
+
+    fn validate_first_explicitly(name: &str, email: &str) -> Result<usize, &'static str> {
+        let (name, email) =
+            all_ok_or_return!((non_empty("name", name), non_empty("email", email)), first);
+        Ok(name + email)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn validate_all(name: &str, email: &str, age: &str) -> Result<usize, Vec<&'static str>> {
+        let (name, email, age) = all_ok_or_return!(
+            (
+                non_empty("name", name),
+                non_empty("email", email),
+                non_empty("age", age),
+            ),
+            all
+        );
+        Ok(name + email + age)
+    }
+
+    #[test]
+    fn should_not_return_early_when_all_ok() {
+        assert_eq!(validate_first("a", "bc"), Ok(3));
+        assert_eq!(validate_first_explicitly("a", "bc"), Ok(3));
+        #[cfg(feature = "alloc")]
+        assert_eq!(validate_all("a", "bc", "def"), Ok(6));
+    }
+
+    #[test]
+    fn should_return_first_error_converted_with_from() {
+        assert_eq!(validate_first("", ""), Err(ValidationError("name")));
+        assert_eq!(validate_first("a", ""), Err(ValidationError("email")));
+        assert_eq!(validate_first_explicitly("", ""), Err("name"));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn should_return_all_errors() {
+        assert_eq!(validate_all("", "b", ""), Err(vec!["name", "age"]));
+        assert_eq!(validate_all("a", "", "c"), Err(vec!["email"]));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn should_evaluate_every_result_when_collecting_all_errors() {
+        let mut evaluated = 0;
+        let mut evaluate = |value: Result<i32, i32>| {
+            evaluated += 1;
+            value
+        };
+        let mut collect = || -> Result<i32, Vec<i32>> {
+            let (a, b, c) =
+                all_ok_or_return!((evaluate(Err(1)), evaluate(Ok(2)), evaluate(Err(3))), all);
+            Ok(a + b + c)
+        };
+        assert_eq!(collect(), Err(vec![1, 3]));
+        assert_eq!(evaluated, 3);
+    }
+
+    struct Alternatives {
+        evaluated: Vec<&'static str>,
+    }
+
+    impl Alternatives {
+        fn new() -> Alternatives {
+            Alternatives {
+                evaluated: Vec::new(),
+            }
+        }
+
+        fn get(&mut self, name: &'static str, value: Option<i32>) -> Option<i32> {
+            self.evaluated.push(name);
+            value
+        }
+
+        fn first(&mut self, a: Option<i32>, b: Option<i32>, c: Option<i32>) -> i32 {
+            first_some_or_return!(self.get("a", a), self.get("b", b), self.get("c", c); -1)
+        }
+    }
+
+    fn increment_with_first(value: &mut i32, a: Option<i32>, b: Option<&i32>) {
+        let first = first_some_or_return!(a, b.copied(),);
+        *value += first;
+    }
+
+    #[test]
+    fn should_return_first_engaged_alternative() {
+        let mut alternatives = Alternatives::new();
+        assert_eq!(alternatives.first(None, Some(2), Some(3)), 2);
+    }
+
+    #[test]
+    fn should_not_evaluate_alternatives_after_first_engaged() {
+        let mut alternatives = Alternatives::new();
+        alternatives.first(Some(1), Some(2), Some(3));
+        assert_eq!(alternatives.evaluated, vec!["a"]);
+
+        let mut alternatives = Alternatives::new();
+        alternatives.first(None, Some(2), Some(3));
+        assert_eq!(alternatives.evaluated, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn should_return_default_when_no_alternative_engaged() {
+        let mut alternatives = Alternatives::new();
+        assert_eq!(alternatives.first(None, None, None), -1);
+        assert_eq!(alternatives.evaluated, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn should_return_early_when_no_alternative_engaged() {
+        let mut value = 0;
+        increment_with_first(&mut value, None, None);
+        assert_eq!(value, 0);
+        increment_with_first(&mut value, None, Some(&2));
+        assert_eq!(value, 2);
+    }
+
+    fn sum_dependent(a: Option<i32>, b: Result<i32, String>) -> i32 {
+        bind_all_or_return! {
+            a = a,
+            (b, mut c) = b.map(|b| (b, a * 10));
+            -1
+        }
+        c += b;
+        c
+    }
+
+    #[test]
+    fn should_bind_all_names_when_engaged() {
+        let mut tester = Tester::new();
+        tester.increment_with_bindings(Some(1), Ok(2), &Some(3));
+        assert_eq!(tester.value, 6);
+    }
+
+    #[test]
+    fn should_return_early_when_any_binding_not_engaged() {
+        let mut tester = Tester::new();
+        tester.increment_with_bindings(None, Ok(2), &Some(3));
+        tester.increment_with_bindings(Some(1), Err(()), &Some(3));
+        tester.increment_with_bindings(Some(1), Ok(2), &None);
+        assert_eq!(tester.value, 0);
+    }
+
+    #[test]
+    fn should_allow_later_bindings_to_use_earlier_ones() {
+        assert_eq!(sum_dependent(Some(2), Ok(3)), 23);
+    }
+
+    #[test]
+    fn should_return_default_when_any_binding_not_engaged() {
+        assert_eq!(sum_dependent(None, Ok(3)), -1);
+        assert_eq!(sum_dependent(Some(2), Err("no".to_string())), -1);
+    }
+
+    #[cfg(feature = "let-else")]
+    #[test]
+    fn should_release_lock_before_trying_the_next_option() {
+        let queue = std::sync::Mutex::new(Vec::<i32>::new());
+        let first = || {
+            let unlocked = || queue.try_lock().ok().map(|_| 1);
+            first_some_or_return!(queue.lock().unwrap().pop(), unlocked(); -1)
+        };
+        assert_eq!(first(), 1);
+    }
+}
@@ -0,0 +1,230 @@
+/// Either get the Ok value of an `nb::Result`, or continue in a loop if the operation would block,
+/// or return from the current function for any other error. If a loop lifetime is specified, that
+/// loop will be "continued", otherwise the immediate loop is "continued".
+///
+/// The error of an `nb::Error::Other` is returned as `Err(e.into())`, like `?` does, or it can be
+/// bound and used to produce the return value.
+/// ```
+/// use early_returns::nb_ok_or_continue;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Overrun;
+///
+/// fn read_frame(mut read: impl FnMut() -> nb::Result<u8, Overrun>) -> Result<[u8; 2], Overrun> {
+///     let mut frame = [0; 2];
+///     let mut filled = 0;
+///     while filled < frame.len() {
+///         frame[filled] = nb_ok_or_continue!(read());
+///         filled += 1;
+///     }
+///     Ok(frame)
+/// }
+///
+/// let mut bytes = vec![Ok(1), Err(nb::Error::WouldBlock), Ok(2)].into_iter();
+/// assert_eq!(read_frame(|| bytes.next().unwrap()), Ok([1, 2]));
+/// let mut bytes = vec![Ok(1), Err(nb::Error::Other(Overrun))].into_iter();
+/// assert_eq!(read_frame(|| bytes.next().unwrap()), Err(Overrun));
+/// ```
+#[macro_export]
+macro_rules! nb_ok_or_continue {
+    ($from:expr, $e:ident => $result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($crate::__private::nb::Error::WouldBlock) => {
+                $crate::__private::silent_continue();
+                continue
+            }
+            Err($crate::__private::nb::Error::Other($e)) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, $e:ident => $result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($crate::__private::nb::Error::WouldBlock) => {
+                $crate::__private::silent_continue();
+                continue $lt
+            }
+            Err($crate::__private::nb::Error::Other($e)) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::nb_ok_or_continue!($from, e => Err(::core::convert::From::from(e)))
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::nb_ok_or_continue!($from, $lt, e => Err(::core::convert::From::from(e)))
+    };
+}
+
+/// Either get the Ok value of an `nb::Result` or return from the current function, which is then
+/// pending as well if the operation would block.
+///
+/// Without further arguments, `Err(nb::Error::WouldBlock)` is returned if the operation would
+/// block, and the error of an `nb::Error::Other` is returned as `Err(nb::Error::Other(e.into()))`,
+/// so non-blocking drivers can be composed like `?` composes ordinary Results. The error can be
+/// bound to produce the return value instead (the function then still returns
+/// `Err(nb::Error::WouldBlock)` if the operation would block), a value can be given for each of
+/// the two cases, or a default return value for both.
+/// ```
+/// use early_returns::nb_ok_or_return;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Error {
+///     Bus,
+///     Checksum,
+/// }
+///
+/// fn read_checked(read: impl FnOnce() -> nb::Result<(u8, u8), Error>) -> nb::Result<u8, Error> {
+///     let (value, checksum) = nb_ok_or_return!(read());
+///     if value != !checksum {
+///         return Err(nb::Error::Other(Error::Checksum));
+///     }
+///     Ok(value)
+/// }
+///
+/// fn status(read: impl FnOnce() -> nb::Result<(u8, u8), Error>) -> &'static str {
+///     nb_ok_or_return!(read_checked(read), would_block => "busy", _e => "failed");
+///     "ready"
+/// }
+///
+/// assert_eq!(read_checked(|| Ok((1, !1))), Ok(1));
+/// assert_eq!(read_checked(|| Err(nb::Error::WouldBlock)), Err(nb::Error::WouldBlock));
+/// let bus = || Err(nb::Error::Other(Error::Bus));
+/// assert_eq!(read_checked(bus), Err(nb::Error::Other(Error::Bus)));
+/// assert_eq!(status(|| Ok((1, !1))), "ready");
+/// assert_eq!(status(|| Ok((1, 1))), "failed");
+/// assert_eq!(status(|| Err(nb::Error::WouldBlock)), "busy");
+/// ```
+#[macro_export]
+macro_rules! nb_ok_or_return {
+    ($from:expr, would_block => $pending:expr, $e:ident => $result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($crate::__private::nb::Error::WouldBlock) => {
+                $crate::__mark_early_exit!();
+                return $pending;
+            }
+            Err($crate::__private::nb::Error::Other($e)) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($from:expr, $e:ident => $result:expr) => {
+        $crate::nb_ok_or_return!(
+            $from,
+            would_block => Err($crate::__private::nb::Error::WouldBlock),
+            $e => $result
+        )
+    };
+
+    ($from:expr) => {
+        $crate::nb_ok_or_return!(
+            $from,
+            e => Err($crate::__private::nb::Error::Other(::core::convert::From::from(e)))
+        )
+    };
+
+    ($from:expr, $default_result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug, PartialEq)]
+    struct Overrun;
+
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        Serial(Overrun),
+        Empty,
+    }
+
+    impl From<Overrun> for Error {
+        fn from(e: Overrun) -> Self {
+            Error::Serial(e)
+        }
+    }
+
+    fn poll<T>(results: Vec<nb::Result<T, Overrun>>) -> impl FnMut() -> nb::Result<T, Overrun> {
+        let mut results = results.into_iter();
+        move || results.next().unwrap()
+    }
+
+    fn sum(mut read: impl FnMut() -> nb::Result<u8, Overrun>, count: u8) -> Result<u8, Error> {
+        let mut sum = 0;
+        for _i in 0..count {
+            sum += nb_ok_or_continue!(read());
+        }
+        Ok(sum)
+    }
+
+    fn sum_labeled(mut read: impl FnMut() -> nb::Result<u8, Overrun>) -> Result<u8, Error> {
+        let mut sum = 0;
+        'l: for _i in 0..3 {
+            for _j in 0..2 {
+                sum += nb_ok_or_continue!(read(), 'l, _e => Err(Error::Empty));
+            }
+        }
+        Ok(sum)
+    }
+
+    fn first(read: impl FnOnce() -> nb::Result<u8, Overrun>) -> nb::Result<u8, Error> {
+        Ok(nb_ok_or_return!(read()))
+    }
+
+    fn first_or(read: impl FnOnce() -> nb::Result<u8, Overrun>) -> nb::Result<u8, Error> {
+        Ok(nb_ok_or_return!(read(), _e => Err(nb::Error::Other(Error::Empty))))
+    }
+
+    fn first_or_zero(read: impl FnOnce() -> nb::Result<u8, Overrun>) -> u8 {
+        nb_ok_or_return!(read(), 0)
+    }
+
+    #[test]
+    fn should_continue_while_blocking() {
+        let read = poll(vec![Ok(1), Err(nb::Error::WouldBlock), Ok(2)]);
+        assert_eq!(sum(read, 3), Ok(3));
+        let read = poll(vec![Ok(1), Err(nb::Error::WouldBlock), Ok(2), Ok(3), Ok(4), Ok(5)]);
+        assert_eq!(sum_labeled(read), Ok(15));
+    }
+
+    #[test]
+    fn should_return_other_errors_from_loops() {
+        let read = poll(vec![Ok(1), Err(nb::Error::Other(Overrun)), Ok(2)]);
+        assert_eq!(sum(read, 3), Err(Error::Serial(Overrun)));
+        let read = poll(vec![Ok(1), Err(nb::Error::Other(Overrun))]);
+        assert_eq!(sum_labeled(read), Err(Error::Empty));
+    }
+
+    #[test]
+    fn should_return_pending_or_errors() {
+        assert_eq!(first(|| Ok(1)), Ok(1));
+        assert_eq!(first(|| Err(nb::Error::WouldBlock)), Err(nb::Error::WouldBlock));
+        let other = first(|| Err(nb::Error::Other(Overrun)));
+        assert_eq!(other, Err(nb::Error::Other(Error::Serial(Overrun))));
+        assert_eq!(first_or(|| Err(nb::Error::WouldBlock)), Err(nb::Error::WouldBlock));
+        let other = first_or(|| Err(nb::Error::Other(Overrun)));
+        assert_eq!(other, Err(nb::Error::Other(Error::Empty)));
+        assert_eq!(first_or_zero(|| Ok(1)), 1);
+        assert_eq!(first_or_zero(|| Err(nb::Error::WouldBlock)), 0);
+        assert_eq!(first_or_zero(|| Err(nb::Error::Other(Overrun))), 0);
+    }
+}
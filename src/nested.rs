@@ -0,0 +1,488 @@
+/// Either get the value from a `Result<Option<T>, E>` or return from the current function if it
+/// is an `Err` or `Ok(None)`. A default return value can be provided.
+///
+/// The two escapes can also be configured separately with `err(e) => ...` and `none => ...`,
+/// where each side is an expression that either diverges (e.g. `return Err(e)`, `break` or
+/// `continue`) or produces a fallback value.
+/// ```
+/// use early_returns::some_ok_or_return;
+/// use std::collections::HashMap;
+///
+/// fn lookup(db: &HashMap<u32, String>, id: u32) -> Result<Option<String>, String> {
+///     if id == 0 {
+///         return Err("invalid id".to_string());
+///     }
+///     Ok(db.get(&id).cloned())
+/// }
+///
+/// fn greet(db: &HashMap<u32, String>, id: u32) -> Option<String> {
+///     let name = some_ok_or_return!(lookup(db, id), None);
+///     Some(format!("hello {name}"))
+/// }
+///
+/// fn greet_all(db: &HashMap<u32, String>, ids: &[u32]) -> Result<Vec<String>, String> {
+///     let mut greetings = Vec::new();
+///     for id in ids {
+///         let name =
+///             some_ok_or_return!(lookup(db, *id), err(e) => return Err(e), none => continue);
+///         greetings.push(format!("hello {name}"));
+///     }
+///     Ok(greetings)
+/// }
+///
+/// let db = HashMap::from([(1, "ada".to_string())]);
+/// assert_eq!(greet(&db, 1), Some("hello ada".to_string()));
+/// assert_eq!(greet(&db, 2), None);
+/// assert_eq!(greet_all(&db, &[1, 2]), Ok(vec!["hello ada".to_string()]));
+/// assert_eq!(greet_all(&db, &[1, 0]), Err("invalid id".to_string()));
+/// ```
+#[macro_export]
+macro_rules! some_ok_or_return {
+    ($from:expr, err($e:ident) => $err_escape:expr, none => $none_escape:expr) => {{
+        match $from {
+            Ok(Some(f)) => f,
+            Ok(None) => $none_escape,
+            Err($e) => $err_escape,
+        }
+    }};
+
+    ($from:expr, none => $none_escape:expr, err($e:ident) => $err_escape:expr) => {
+        $crate::some_ok_or_return!($from, err($e) => $err_escape, none => $none_escape)
+    };
+
+    ($from:expr) => {{
+        match $from {
+            Ok(Some(f)) => f,
+            _ => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr) => {{
+        match $from {
+            Ok(Some(f)) => f,
+            _ => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from a `Result<Option<T>, E>` or break out of a loop if it is an `Err` or
+/// `Ok(None)`. If a loop lifetime is specified, that loop will be exited, otherwise the immediate
+/// loop is exited.
+/// ```
+/// use early_returns::some_ok_or_break;
+///
+/// let pages: Vec<Result<Option<i32>, ()>> = vec![Ok(Some(1)), Ok(Some(2)), Ok(None), Ok(Some(3))];
+/// let mut fetched = Vec::new();
+/// for page in pages {
+///     fetched.push(some_ok_or_break!(page));
+/// }
+/// assert_eq!(fetched, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! some_ok_or_break {
+    ($from:expr) => {{
+        match $from {
+            Ok(Some(f)) => f,
+            _ => {
+                $crate::__private::silent_break();
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            Ok(Some(f)) => f,
+            _ => {
+                $crate::__private::silent_break();
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from a `Result<Option<T>, E>` or continue in a loop if it is an `Err` or
+/// `Ok(None)`. If a loop lifetime is specified, that loop will be "continued", otherwise the
+/// immediate loop is "continued".
+/// ```
+/// use early_returns::some_ok_or_continue;
+///
+/// let rows: Vec<Result<Option<i32>, ()>> = vec![Ok(Some(1)), Err(()), Ok(None), Ok(Some(3))];
+/// let mut found = Vec::new();
+/// for row in rows {
+///     found.push(some_ok_or_continue!(row));
+/// }
+/// assert_eq!(found, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! some_ok_or_continue {
+    ($from:expr) => {{
+        match $from {
+            Ok(Some(f)) => f,
+            _ => {
+                $crate::__private::silent_continue();
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            Ok(Some(f)) => f,
+            _ => {
+                $crate::__private::silent_continue();
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an `Option<Result<T, E>>` (as produced by `Iterator::next()` over a
+/// fallible iterator) or return from the current function if it is `None` or `Some(Err(_))`. A
+/// default return value can be provided.
+///
+/// The two escapes can also be configured separately with `none => ...` and `err(e) => ...`,
+/// where each side is an expression that either diverges (e.g. `break`, `continue` or
+/// `return Err(e)`) or produces a fallback value.
+/// ```
+/// use early_returns::ok_some_or_return;
+///
+/// fn first_line(lines: &mut impl Iterator<Item = Result<String, String>>) -> Option<String> {
+///     let line = ok_some_or_return!(lines.next(), None);
+///     Some(line)
+/// }
+///
+/// fn read_all(mut lines: impl Iterator<Item = Result<String, String>>) -> Vec<String> {
+///     let mut read = Vec::new();
+///     loop {
+///         let line = ok_some_or_return!(lines.next(), none => break, err(e) => {
+///             eprintln!("skipping unreadable line: {e}");
+///             continue;
+///         });
+///         read.push(line);
+///     }
+///     read
+/// }
+///
+/// let lines = vec![Ok("a".to_string()), Err("bad".to_string()), Ok("b".to_string())];
+/// assert_eq!(first_line(&mut lines.clone().into_iter()), Some("a".to_string()));
+/// assert_eq!(read_all(lines.into_iter()), vec!["a".to_string(), "b".to_string()]);
+/// ```
+#[macro_export]
+macro_rules! ok_some_or_return {
+    ($from:expr, none => $none_escape:expr, err($e:ident) => $err_escape:expr) => {{
+        match $from {
+            Some(Ok(f)) => f,
+            None => $none_escape,
+            Some(Err($e)) => $err_escape,
+        }
+    }};
+
+    ($from:expr, err($e:ident) => $err_escape:expr, none => $none_escape:expr) => {
+        $crate::ok_some_or_return!($from, none => $none_escape, err($e) => $err_escape)
+    };
+
+    ($from:expr) => {{
+        match $from {
+            Some(Ok(f)) => f,
+            _ => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr) => {{
+        match $from {
+            Some(Ok(f)) => f,
+            _ => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an `Option<Result<T, E>>` or break out of a loop if it is `None` or
+/// `Some(Err(_))`. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+/// ```
+/// use early_returns::ok_some_or_break;
+///
+/// let mut items = vec![Ok(1), Ok(2), Err(()), Ok(3)].into_iter();
+/// let mut read = Vec::new();
+/// loop {
+///     read.push(ok_some_or_break!(items.next()));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! ok_some_or_break {
+    ($from:expr) => {{
+        match $from {
+            Some(Ok(f)) => f,
+            _ => {
+                $crate::__private::silent_break();
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            Some(Ok(f)) => f,
+            _ => {
+                $crate::__private::silent_break();
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an `Option<Result<T, E>>` or continue in a loop if it is `None` or
+/// `Some(Err(_))`. If a loop lifetime is specified, that loop will be "continued", otherwise the
+/// immediate loop is "continued".
+/// ```
+/// use early_returns::ok_some_or_continue;
+///
+/// let items: Vec<Option<Result<i32, ()>>> = vec![Some(Ok(1)), None, Some(Err(())), Some(Ok(3))];
+/// let mut read = Vec::new();
+/// for item in items {
+///     read.push(ok_some_or_continue!(item));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! ok_some_or_continue {
+    ($from:expr) => {{
+        match $from {
+            Some(Ok(f)) => f,
+            _ => {
+                $crate::__private::silent_continue();
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            Some(Ok(f)) => f,
+            _ => {
+                $crate::__private::silent_continue();
+                continue $lt;
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    struct Tester {
+        value: i32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester { value: 0 }
+        }
+
+        fn add(&mut self, value: Result<Option<i32>, ()>) {
+            self.value += some_ok_or_return!(value);
+        }
+
+        fn add_ref(&mut self, value: &Result<Option<i32>, ()>) {
+            self.value += some_ok_or_return!(value);
+        }
+
+        fn add_with_break(&mut self, values: Vec<Result<Option<i32>, ()>>) {
+            for value in values {
+                self.value += some_ok_or_break!(value);
+            }
+        }
+
+        fn add_with_break_with_lifetime(&mut self, values: Vec<Result<Option<i32>, ()>>) {
+            'l: for value in values {
+                self.value += 1;
+                for _i in 0..1 {
+                    self.value += some_ok_or_break!(value, 'l);
+                }
+            }
+        }
+
+        fn add_with_continue(&mut self, values: Vec<Result<Option<i32>, ()>>) {
+            for value in values {
+                self.value += some_ok_or_continue!(value);
+            }
+        }
+
+        fn add_with_continue_with_lifetime(&mut self, values: Vec<Result<Option<i32>, ()>>) {
+            'l: for value in values {
+                self.value += 1;
+                for _i in 0..1 {
+                    self.value += some_ok_or_continue!(value, 'l);
+                }
+            }
+        }
+    }
+
+    fn value_or_default(value: Result<Option<i32>, &str>) -> i32 {
+        some_ok_or_return!(value, -1)
+    }
+
+    fn describe(value: Result<Option<i32>, &str>) -> String {
+        let value = some_ok_or_return!(value, err(e) => return e.to_string(), none => 0);
+        value.to_string()
+    }
+
+    fn sum_until_none(values: Vec<Result<Option<i32>, &str>>) -> Result<i32, &str> {
+        let mut sum = 0;
+        for value in values {
+            sum += some_ok_or_return!(value, none => break, err(e) => return Err(e));
+        }
+        Ok(sum)
+    }
+
+    #[test]
+    fn should_return_early_when_err_or_none() {
+        let mut tester = Tester::new();
+        tester.add(Ok(Some(1)));
+        tester.add(Ok(None));
+        tester.add(Err(()));
+        tester.add_ref(&Ok(Some(2)));
+        tester.add_ref(&Ok(None));
+        assert_eq!(tester.value, 3);
+        assert_eq!(value_or_default(Ok(Some(1))), 1);
+        assert_eq!(value_or_default(Ok(None)), -1);
+        assert_eq!(value_or_default(Err("error")), -1);
+    }
+
+    #[test]
+    fn should_escape_separately_when_err_or_none() {
+        assert_eq!(describe(Ok(Some(1))), "1");
+        assert_eq!(describe(Ok(None)), "0");
+        assert_eq!(describe(Err("error")), "error");
+        assert_eq!(
+            sum_until_none(vec![Ok(Some(1)), Ok(Some(2)), Ok(None), Err("x")]),
+            Ok(3)
+        );
+        assert_eq!(
+            sum_until_none(vec![Ok(Some(1)), Err("x"), Ok(None)]),
+            Err("x")
+        );
+    }
+
+    #[test]
+    fn should_break_when_err_or_none() {
+        let mut tester = Tester::new();
+        tester.add_with_break(vec![Ok(Some(1)), Ok(None), Ok(Some(2))]);
+        assert_eq!(tester.value, 1);
+        tester.add_with_break_with_lifetime(vec![Err(()), Ok(Some(2))]);
+        assert_eq!(tester.value, 2);
+    }
+
+    #[test]
+    fn should_continue_when_err_or_none() {
+        let mut tester = Tester::new();
+        tester.add_with_continue(vec![Ok(Some(1)), Ok(None), Err(()), Ok(Some(2))]);
+        assert_eq!(tester.value, 3);
+        tester.add_with_continue_with_lifetime(vec![Ok(None), Ok(Some(2))]);
+        assert_eq!(tester.value, 7);
+    }
+
+    fn next_or_default(items: &mut impl Iterator<Item = Result<i32, ()>>) -> i32 {
+        ok_some_or_return!(items.next(), -1)
+    }
+
+    fn next_unit(items: &mut impl Iterator<Item = Result<i32, ()>>, sum: &mut i32) {
+        *sum += ok_some_or_return!(items.next());
+    }
+
+    fn sum_skipping_errors(items: Vec<Result<i32, &str>>) -> (i32, Vec<&str>) {
+        let mut items = items.into_iter();
+        let mut sum = 0;
+        let mut errors = Vec::new();
+        loop {
+            sum += ok_some_or_return!(items.next(), none => break, err(e) => {
+                errors.push(e);
+                continue;
+            });
+        }
+        (sum, errors)
+    }
+
+    fn sum_or_err(items: Vec<Result<i32, &str>>) -> Result<i32, &str> {
+        let mut items = items.into_iter();
+        let mut sum = 0;
+        loop {
+            sum += ok_some_or_return!(items.next(), err(e) => return Err(e), none => break);
+        }
+        Ok(sum)
+    }
+
+    #[test]
+    fn should_return_early_when_none_or_some_err() {
+        let mut items = vec![Ok(1), Err(())].into_iter();
+        assert_eq!(next_or_default(&mut items), 1);
+        assert_eq!(next_or_default(&mut items), -1);
+        assert_eq!(next_or_default(&mut items), -1);
+        let mut sum = 0;
+        let mut items = vec![Ok(2), Err(())].into_iter();
+        next_unit(&mut items, &mut sum);
+        next_unit(&mut items, &mut sum);
+        next_unit(&mut items, &mut sum);
+        assert_eq!(sum, 2);
+    }
+
+    #[test]
+    fn should_escape_separately_when_none_or_some_err() {
+        assert_eq!(
+            sum_skipping_errors(vec![Ok(1), Err("a"), Ok(2), Err("b")]),
+            (3, vec!["a", "b"])
+        );
+        assert_eq!(sum_or_err(vec![Ok(1), Ok(2)]), Ok(3));
+        assert_eq!(sum_or_err(vec![Ok(1), Err("a"), Ok(2)]), Err("a"));
+    }
+
+    #[test]
+    fn should_break_when_none_or_some_err() {
+        let mut items = vec![Ok(1), Ok(2), Err(()), Ok(3)].into_iter();
+        let mut sum = 0;
+        loop {
+            sum += ok_some_or_break!(items.next());
+        }
+        assert_eq!(sum, 3);
+        'l: loop {
+            for _i in 0..2 {
+                sum += ok_some_or_break!(items.next(), 'l);
+            }
+        }
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn should_continue_when_none_or_some_err() {
+        let items = vec![Some(Ok(1)), None, Some(Err(())), Some(Ok(2))];
+        let mut sum = 0;
+        for item in items.clone() {
+            sum += ok_some_or_continue!(item);
+        }
+        assert_eq!(sum, 3);
+        'l: for item in items {
+            sum += 1;
+            for _i in 0..1 {
+                sum += ok_some_or_continue!(item, 'l);
+            }
+        }
+        assert_eq!(sum, 10);
+    }
+}
@@ -0,0 +1,145 @@
+/// Either get the `(rest, value)` pair from a nom `IResult` or return from the current function.
+/// A default return value can be provided, either for every error or separately for recoverable
+/// errors (`Err::Error` and `Err::Incomplete`) and unrecoverable ones (`Err::Failure`).
+/// ```
+/// use early_returns::nom_or_return;
+/// use nom::{bytes::complete::tag, IResult, Parser};
+///
+/// fn greeting(input: &str) -> IResult<&str, &str> {
+///     tag("hello ").parse(input)
+/// }
+///
+/// fn name_after_greeting(input: &str) -> Option<&str> {
+///     let (rest, _) = nom_or_return!(greeting(input), None);
+///     Some(rest)
+/// }
+///
+/// fn describe(input: &str) -> &'static str {
+///     let (_rest, _) = nom_or_return!(greeting(input), error => "no greeting", failure => "broken");
+///     "greeting"
+/// }
+/// ```
+#[macro_export]
+macro_rules! nom_or_return {
+    ($from:expr) => {
+        $crate::ok_or_return!($from)
+    };
+
+    ($from:expr, error => $error_result:expr, failure => $failure_result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($crate::__private::nom::Err::Failure(_)) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $failure_result;
+            }
+            Err(_) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $error_result;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr) => {
+        $crate::ok_or_return!($from, $default_result)
+    };
+}
+
+/// Either get the `(rest, value)` pair from a nom `IResult` or continue in a loop. If a loop
+/// lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued". Any error (including `Err::Failure`) takes the continue path, which makes this
+/// useful for scanning input for the positions where a parser matches.
+/// ```
+/// use early_returns::nom_or_continue;
+/// use nom::{character::complete::digit1, IResult};
+///
+/// fn number(input: &str) -> IResult<&str, &str> {
+///     digit1(input)
+/// }
+///
+/// fn numbers(words: &[&str]) -> Vec<String> {
+///     let mut found = Vec::new();
+///     for word in words {
+///         let (_rest, digits) = nom_or_continue!(number(word));
+///         found.push(digits.to_string());
+///     }
+///     found
+/// }
+/// ```
+#[macro_export]
+macro_rules! nom_or_continue {
+    ($from:expr) => {
+        $crate::ok_or_continue!($from)
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!($from, $lt)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::digit1;
+    use nom::combinator::cut;
+    use nom::{IResult, Parser};
+
+    fn greeting(input: &str) -> IResult<&str, &str> {
+        tag("hello ").parse(input)
+    }
+
+    fn committed_greeting(input: &str) -> IResult<&str, &str> {
+        cut(tag("hello ")).parse(input)
+    }
+
+    fn name_after_greeting(input: &str) -> Option<&str> {
+        let (rest, _) = nom_or_return!(greeting(input), None);
+        Some(rest)
+    }
+
+    fn classify<F: Fn(&str) -> IResult<&str, &str>>(parser: F, input: &str) -> &'static str {
+        let (_rest, _) = nom_or_return!(parser(input), error => "error", failure => "failure");
+        "ok"
+    }
+
+    #[test]
+    fn should_return_rest_and_value_when_parsed() {
+        assert_eq!(name_after_greeting("hello world"), Some("world"));
+    }
+
+    #[test]
+    fn should_return_default_when_parser_errors() {
+        assert_eq!(name_after_greeting("goodbye world"), None);
+    }
+
+    #[test]
+    fn should_distinguish_errors_from_failures() {
+        assert_eq!(classify(greeting, "hello world"), "ok");
+        assert_eq!(classify(greeting, "goodbye world"), "error");
+        assert_eq!(classify(committed_greeting, "goodbye world"), "failure");
+    }
+
+    #[test]
+    fn should_continue_when_parser_errors() {
+        let mut found = Vec::new();
+        for word in ["a1", "22", "b", "333"] {
+            let (_rest, digits) = nom_or_continue!(digit1::<_, nom::error::Error<_>>(word));
+            found.push(digits);
+        }
+        assert_eq!(found, vec!["22", "333"]);
+    }
+
+    #[test]
+    fn should_continue_with_lifetime_when_parser_errors() {
+        let mut found = Vec::new();
+        'l: for word in ["1", "x", "2"] {
+            for _i in 0..2 {
+                let (_rest, digits) =
+                    nom_or_continue!(digit1::<_, nom::error::Error<_>>(word), 'l);
+                found.push(digits);
+            }
+        }
+        assert_eq!(found, vec!["1", "1", "2", "2"]);
+    }
+}
@@ -0,0 +1,275 @@
+//! Early exits for the closures of parallel loops, such as rayon's `try_for_each`.
+//!
+//! The closure passed to `par_iter().try_for_each()` runs once per item, so the sequential macros
+//! cannot `break` or `continue` the loop from it. The `*_par` macros return from the closure
+//! instead: the `*_or_continue_par` macros skip the current item, and the `*_or_break_par` macros
+//! stop every worker and make the reason the result of the whole loop. The closure may return a
+//! `Result<(), E>`, a `ControlFlow<B>` or an `Option<()>`, through [`ParallelExit`].
+//! ```
+//! use std::sync::atomic::{AtomicU32, Ordering};
+//!
+//! use early_returns::{ok_or_break_par, some_or_continue_par};
+//! use rayon::prelude::*;
+//!
+//! fn total(rows: &[&str]) -> Result<u32, String> {
+//!     let total = AtomicU32::new(0);
+//!     rows.par_iter().try_for_each(|row| {
+//!         // Blank rows are skipped, while the first corrupt row stops every worker.
+//!         let row = some_or_continue_par!(Some(row.trim()).filter(|row| !row.is_empty()));
+//!         let value = ok_or_break_par!(row.parse(), _e => format!("corrupt row {row:?}"));
+//!         total.fetch_add(value, Ordering::Relaxed);
+//!         Ok(())
+//!     })?;
+//!     Ok(total.into_inner())
+//! }
+//!
+//! assert_eq!(total(&["1", "", "2", " "]), Ok(3));
+//! assert_eq!(total(&["1", "x", "2"]), Err("corrupt row \"x\"".to_string()));
+//! ```
+
+use core::ops::ControlFlow;
+
+/// The return types of a parallel loop's closure the `*_par` macros can skip an item or stop the
+/// loop with.
+pub trait ParallelExit {
+    /// Why the loop was stopped.
+    type Reason;
+
+    /// Move on to the next item.
+    fn proceed() -> Self;
+
+    /// Stop the loop, with `reason` as its result.
+    fn stop(reason: Self::Reason) -> Self;
+}
+
+impl<E> ParallelExit for Result<(), E> {
+    type Reason = E;
+
+    #[inline]
+    fn proceed() -> Self {
+        Ok(())
+    }
+
+    #[inline]
+    fn stop(reason: E) -> Self {
+        Err(reason)
+    }
+}
+
+impl<B> ParallelExit for ControlFlow<B> {
+    type Reason = B;
+
+    #[inline]
+    fn proceed() -> Self {
+        ControlFlow::Continue(())
+    }
+
+    #[inline]
+    fn stop(reason: B) -> Self {
+        ControlFlow::Break(reason)
+    }
+}
+
+impl ParallelExit for Option<()> {
+    type Reason = ();
+
+    #[inline]
+    fn proceed() -> Self {
+        Some(())
+    }
+
+    #[inline]
+    fn stop(_reason: ()) -> Self {
+        None
+    }
+}
+
+/// Either get the value from an Option type or skip the current item of a parallel loop, by
+/// returning from its closure. Requires the `rayon` feature.
+/// ```
+/// use std::sync::Mutex;
+///
+/// use early_returns::some_or_continue_par;
+/// use rayon::prelude::*;
+///
+/// let names = Mutex::new(Vec::new());
+/// let _: Option<()> = ["a=1", "b", "c=3"].par_iter().try_for_each(|entry| {
+///     let (name, _value) = some_or_continue_par!(entry.split_once('='));
+///     names.lock().unwrap().push(name);
+///     Some(())
+/// });
+/// let mut names = names.into_inner().unwrap();
+/// names.sort();
+/// assert_eq!(names, ["a", "c"]);
+/// ```
+#[macro_export]
+macro_rules! some_or_continue_par {
+    ($from:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            $crate::__private::silent_continue();
+            return $crate::parallel::ParallelExit::proceed();
+        })
+    };
+}
+
+/// Either get the value from an Option type or stop every worker of a parallel loop, by returning
+/// the given reason from its closure. Requires the `rayon` feature.
+/// ```
+/// use early_returns::some_or_break_par;
+/// use rayon::prelude::*;
+///
+/// let rows = ["1", "2", "x"];
+/// let checked = rows.par_iter().try_for_each(|row| {
+///     some_or_break_par!(row.parse::<u32>().ok(), format!("bad row {row:?}"));
+///     Ok(())
+/// });
+/// assert_eq!(checked, Err("bad row \"x\"".to_string()));
+/// ```
+#[macro_export]
+macro_rules! some_or_break_par {
+    ($from:expr, $reason:expr) => {
+        $crate::__extract_or!(Some, $from, {
+            return $crate::parallel::ParallelExit::stop($reason);
+        })
+    };
+}
+
+/// Either get the Ok value from a Result type or skip the current item of a parallel loop, by
+/// returning from its closure. The error can be bound and handled before skipping. Requires the
+/// `rayon` feature.
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use early_returns::ok_or_continue_par;
+/// use rayon::prelude::*;
+///
+/// let skipped = AtomicUsize::new(0);
+/// let _: Result<(), ()> = ["1", "x", "3"].par_iter().try_for_each(|row| {
+///     let _value: u32 = ok_or_continue_par!(row.parse(), _e => {
+///         skipped.fetch_add(1, Ordering::Relaxed);
+///     });
+///     Ok(())
+/// });
+/// assert_eq!(skipped.into_inner(), 1);
+/// ```
+#[macro_export]
+macro_rules! ok_or_continue_par {
+    ($from:expr, $e:ident => $handler:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $handler;
+                return $crate::parallel::ParallelExit::proceed();
+            }
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::__extract_or!(Ok, $from, {
+            $crate::__private::silent_continue();
+            return $crate::parallel::ParallelExit::proceed();
+        })
+    };
+}
+
+/// Either get the Ok value from a Result type or stop every worker of a parallel loop, by
+/// returning the error from its closure. The error is converted with `From`, like `?` does, or it
+/// can be bound and used to compute the reason. Requires the `rayon` feature.
+/// ```
+/// use std::ops::ControlFlow;
+///
+/// use early_returns::ok_or_break_par;
+/// use rayon::prelude::*;
+///
+/// let rows = ["1", "2", "x"];
+/// let checked = rows.par_iter().try_for_each(|row| {
+///     let _value: u32 = ok_or_break_par!(row.parse(), _e => *row);
+///     ControlFlow::Continue(())
+/// });
+/// assert_eq!(checked, ControlFlow::Break("x"));
+/// ```
+#[macro_export]
+macro_rules! ok_or_break_par {
+    ($from:expr, $e:ident => $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => return $crate::parallel::ParallelExit::stop($reason),
+        }
+    }};
+
+    ($from:expr) => {
+        $crate::ok_or_break_par!($from, e => ::core::convert::From::from(e))
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use core::ops::ControlFlow;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    use rayon::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Stopped(i32);
+
+    impl From<i32> for Stopped {
+        fn from(value: i32) -> Self {
+            Stopped(value)
+        }
+    }
+
+    fn checked(value: i32) -> Result<i32, i32> {
+        if value < 0 {
+            Err(value)
+        } else {
+            Ok(value)
+        }
+    }
+
+    #[test]
+    fn should_skip_items() {
+        let sum = AtomicI32::new(0);
+        let values = [Some(1), None, Some(-2), Some(3)];
+        let result: Result<(), ()> = values.par_iter().try_for_each(|value| {
+            let value = some_or_continue_par!(*value);
+            sum.fetch_add(ok_or_continue_par!(checked(value)), Ordering::Relaxed);
+            Ok(())
+        });
+        assert_eq!((result, sum.into_inner()), (Ok(()), 4));
+        let skipped = AtomicI32::new(0);
+        let result = [1, -2, -3].par_iter().try_for_each(|&value| {
+            ok_or_continue_par!(checked(value), e => skipped.fetch_add(e, Ordering::Relaxed));
+            Some(())
+        });
+        assert_eq!((result, skipped.into_inner()), (Some(()), -5));
+    }
+
+    #[test]
+    fn should_stop_on_the_first_error() {
+        let result = (0..100).into_par_iter().try_for_each(|value| {
+            ok_or_break_par!(checked(50 - value));
+            Ok::<_, Stopped>(())
+        });
+        assert!(matches!(result, Err(Stopped(error)) if error < 0));
+        let result = (0..100).into_par_iter().try_for_each(|value| {
+            let value = ok_or_break_par!(checked(value), e => e * 2);
+            some_or_break_par!(value.checked_sub(50).filter(|v| *v < 0), value);
+            ControlFlow::Continue(())
+        });
+        assert!(matches!(result, ControlFlow::Break(value) if value >= 50));
+        let result = (0..100).into_par_iter().try_for_each(|value| {
+            some_or_break_par!(Some(value).filter(|v| *v != 42), ());
+            Some(())
+        });
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn should_work_in_sequential_loops() {
+        let result = [1, 2, -3, 4].iter().try_for_each(|&value| {
+            ok_or_break_par!(checked(value), e => e * 10);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(result, ControlFlow::Break(-30));
+    }
+}
@@ -0,0 +1,428 @@
+/// Either bind every variable of a refutable pattern in the current scope or return from the
+/// current function. A default return value can be provided.
+/// ```
+/// use early_returns::let_or_return;
+///
+/// struct Config {
+///     port: u16,
+///     host: String,
+/// }
+///
+/// fn load() -> Option<Config> {
+///     Some(Config { port: 8080, host: "localhost".to_string() })
+/// }
+///
+/// fn print_port() {
+///     let_or_return!(Some(Config { port, .. }) = load());
+///     println!("{port}");
+/// }
+///
+/// fn address() -> String {
+///     let_or_return!(Some(Config { port, host }) = load(), String::new());
+///     format!("{host}:{port}")
+/// }
+/// ```
+#[macro_export]
+macro_rules! let_or_return {
+    ($pattern:pat = $from:expr) => {
+        let $pattern = $from else {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return;
+        };
+    };
+
+    ($pattern:pat = $from:expr, $default_result:expr) => {
+        let $pattern = $from else {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        };
+    };
+}
+
+/// Either bind every variable of a refutable pattern in the current scope or break out of a loop.
+/// If a loop lifetime is specified, that loop will be exited, otherwise the immediate loop is
+/// exited.
+/// ```
+/// use early_returns::let_or_break;
+/// fn sum_leading_pairs(vals: &Vec<Option<(i32, i32)>>) -> i32 {
+///     let mut sum = 0;
+///     for val in vals {
+///         let_or_break!(Some((a, b)) = val);
+///         sum += a + b;
+///     }
+///
+///     'l: for val in vals {
+///         for i in 0..5 {
+///             let_or_break!(Some((a, _)) = val, 'l);
+///             sum += a + i;
+///         }
+///     }
+///     sum
+/// }
+/// ```
+#[macro_export]
+macro_rules! let_or_break {
+    ($pattern:pat = $from:expr) => {
+        let $pattern = $from else {
+            $crate::__private::silent_break();
+            break;
+        };
+    };
+
+    ($pattern:pat = $from:expr, $lt:lifetime) => {
+        let $pattern = $from else {
+            $crate::__private::silent_break();
+            break $lt;
+        };
+    };
+}
+
+/// Either bind every variable of a refutable pattern in the current scope or continue in a loop.
+/// If a loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::let_or_continue;
+/// fn sum_pairs(vals: &Vec<Option<(i32, i32)>>) -> i32 {
+///     let mut sum = 0;
+///     for val in vals {
+///         let_or_continue!(Some((a, b)) = val);
+///         sum += a + b;
+///     }
+///
+///     'l: for val in vals {
+///         for i in 0..5 {
+///             let_or_continue!(Some((a, _)) = val, 'l);
+///             sum += a + i;
+///         }
+///     }
+///     sum
+/// }
+/// ```
+#[macro_export]
+macro_rules! let_or_continue {
+    ($pattern:pat = $from:expr) => {
+        let $pattern = $from else {
+            $crate::__private::silent_continue();
+            continue;
+        };
+    };
+
+    ($pattern:pat = $from:expr, $lt:lifetime) => {
+        let $pattern = $from else {
+            $crate::__private::silent_continue();
+            continue $lt;
+        };
+    };
+}
+
+/// Either get the value of a single-field tuple variant of an enum or return from the current
+/// function. A default return value can be provided.
+/// ```
+/// use early_returns::variant_or_return;
+///
+/// enum Msg {
+///     Ping,
+///     Data(Vec<u8>),
+/// }
+///
+/// fn payload_len(msg: Msg) -> usize {
+///     let payload = variant_or_return!(msg, Msg::Data, 0);
+///     payload.len()
+/// }
+/// ```
+#[macro_export]
+macro_rules! variant_or_return {
+    ($from:expr, $variant:path) => {
+        $crate::__extract_or!($variant, $from, {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return;
+        })
+    };
+
+    ($from:expr, $variant:path, $default_result:expr) => {
+        $crate::__extract_or!($variant, $from, {
+            $crate::__private::silent_return();
+            $crate::__mark_early_exit!();
+            return $default_result;
+        })
+    };
+}
+
+/// Either get the value of a single-field tuple variant of an enum or break out of a loop. If a
+/// loop lifetime is specified, that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::variant_or_break;
+///
+/// enum Msg {
+///     Stop,
+///     Data(i32),
+/// }
+///
+/// fn sum_until_stop(msgs: &Vec<Msg>) -> i32 {
+///     let mut sum = 0;
+///     for msg in msgs {
+///         sum += variant_or_break!(msg, Msg::Data);
+///     }
+///
+///     'l: for msg in msgs {
+///         for i in 0..5 {
+///             sum += variant_or_break!(msg, Msg::Data, 'l) + i;
+///         }
+///     }
+///     sum
+/// }
+/// ```
+#[macro_export]
+macro_rules! variant_or_break {
+    ($from:expr, $variant:path) => {
+        $crate::__extract_or!($variant, $from, {
+            $crate::__private::silent_break();
+            break;
+        })
+    };
+
+    ($from:expr, $variant:path, $lt:lifetime) => {
+        $crate::__extract_or!($variant, $from, {
+            $crate::__private::silent_break();
+            break $lt;
+        })
+    };
+}
+
+/// Either get the value of a single-field tuple variant of an enum or continue in a loop. If a
+/// loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::variant_or_continue;
+///
+/// enum Msg {
+///     Ping,
+///     Data(i32),
+/// }
+///
+/// fn sum_data(msgs: &Vec<Msg>) -> i32 {
+///     let mut sum = 0;
+///     for msg in msgs {
+///         sum += variant_or_continue!(msg, Msg::Data);
+///     }
+///
+///     'l: for msg in msgs {
+///         for i in 0..5 {
+///             sum += variant_or_continue!(msg, Msg::Data, 'l) + i;
+///         }
+///     }
+///     sum
+/// }
+/// ```
+#[macro_export]
+macro_rules! variant_or_continue {
+    ($from:expr, $variant:path) => {
+        $crate::__extract_or!($variant, $from, {
+            $crate::__private::silent_continue();
+            continue;
+        })
+    };
+
+    ($from:expr, $variant:path, $lt:lifetime) => {
+        $crate::__extract_or!($variant, $from, {
+            $crate::__private::silent_continue();
+            continue $lt;
+        })
+    };
+}
+
+#[cfg(test)]
+mod test {
+    struct Config {
+        port: u16,
+        host: &'static str,
+    }
+
+    enum Shape {
+        Circle { radius: i32 },
+        Square,
+    }
+
+    enum Msg {
+        Ping,
+        Data(i32),
+    }
+
+    fn port_of(config: Option<Config>) -> u16 {
+        let_or_return!(Some(Config { port, .. }) = config, 0);
+        port
+    }
+
+    fn describe(config: Option<Config>) -> String {
+        let_or_return!(Some(Config { port, host }) = config, String::new());
+        format!("{host}:{port}")
+    }
+
+    fn increment_with_radius(value: &mut i32, shape: Shape) {
+        let_or_return!(Shape::Circle { radius } = shape);
+        *value += radius;
+    }
+
+    #[test]
+    fn should_bind_pattern_variables_when_matched() {
+        assert_eq!(port_of(Some(Config { port: 80, host: "a" })), 80);
+        assert_eq!(describe(Some(Config { port: 80, host: "a" })), "a:80");
+    }
+
+    #[test]
+    fn should_return_default_when_not_matched() {
+        assert_eq!(port_of(None), 0);
+        assert_eq!(describe(None), "");
+    }
+
+    #[test]
+    fn should_return_early_when_enum_variant_not_matched() {
+        let mut value = 0;
+        increment_with_radius(&mut value, Shape::Square);
+        assert_eq!(value, 0);
+        increment_with_radius(&mut value, Shape::Circle { radius: 3 });
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn should_allow_mutable_bindings() {
+        fn doubled(pair: Option<(i32, i32)>) -> i32 {
+            let_or_return!(Some((mut a, b)) = pair, -1);
+            a += b;
+            a * 2
+        }
+        assert_eq!(doubled(Some((1, 2))), 6);
+        assert_eq!(doubled(None), -1);
+    }
+
+    #[test]
+    fn should_break_when_not_matched() {
+        let mut value = 0;
+        for pair in [Some((1, 2)), None, Some((3, 4))] {
+            let_or_break!(Some((a, b)) = pair);
+            value += a + b;
+        }
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn should_break_with_lifetime_when_not_matched() {
+        let mut value = 0;
+        'l: for pair in [Some((1, 2)), None, Some((3, 4))] {
+            value += 1;
+            for _i in 0..1 {
+                let_or_break!(Some((a, _)) = pair, 'l);
+                value += a;
+            }
+        }
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn should_continue_when_not_matched() {
+        let mut value = 0;
+        for pair in [Some((1, 2)), None, Some((3, 4))] {
+            let_or_continue!(Some((a, b)) = pair);
+            value += a + b;
+        }
+        assert_eq!(value, 10);
+    }
+
+    #[test]
+    fn should_continue_with_lifetime_when_not_matched() {
+        let mut value = 0;
+        'l: for pair in [Some((1, 2)), None, Some((3, 4))] {
+            value += 1;
+            for _i in 0..1 {
+                let_or_continue!(Some((a, _)) = pair, 'l);
+                value += a;
+            }
+        }
+        assert_eq!(value, 7);
+    }
+
+    fn data_or_default(msg: Msg) -> i32 {
+        variant_or_return!(msg, Msg::Data, -1)
+    }
+
+    fn increment_with_data(value: &mut i32, msg: &Msg) {
+        let data = variant_or_return!(msg, Msg::Data);
+        *value += data;
+    }
+
+    #[test]
+    fn should_return_early_when_variant_not_matched() {
+        let mut value = 0;
+        increment_with_data(&mut value, &Msg::Ping);
+        assert_eq!(value, 0);
+        increment_with_data(&mut value, &Msg::Data(2));
+        assert_eq!(value, 2);
+    }
+
+    #[test]
+    fn should_return_default_when_variant_not_matched() {
+        assert_eq!(data_or_default(Msg::Data(3)), 3);
+        assert_eq!(data_or_default(Msg::Ping), -1);
+    }
+
+    #[test]
+    fn should_break_when_variant_not_matched() {
+        let mut value = 0;
+        for msg in [Msg::Data(1), Msg::Ping, Msg::Data(2)] {
+            value += variant_or_break!(msg, Msg::Data);
+        }
+        assert_eq!(value, 1);
+    }
+
+    #[test]
+    fn should_break_with_lifetime_when_variant_not_matched() {
+        let mut value = 0;
+        'l: for msg in [Msg::Data(1), Msg::Ping, Msg::Data(2)] {
+            value += 1;
+            for _i in 0..1 {
+                value += variant_or_break!(&msg, Msg::Data, 'l);
+            }
+        }
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn should_continue_when_variant_not_matched() {
+        let mut value = 0;
+        for msg in [Msg::Data(1), Msg::Ping, Msg::Data(2)] {
+            value += variant_or_continue!(msg, Msg::Data);
+        }
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn should_continue_with_lifetime_when_variant_not_matched() {
+        let mut value = 0;
+        'l: for msg in [Msg::Data(1), Msg::Ping, Msg::Data(2)] {
+            value += 1;
+            for _i in 0..1 {
+                value += variant_or_continue!(&msg, Msg::Data, 'l);
+            }
+        }
+        assert_eq!(value, 6);
+    }
+
+    #[cfg(feature = "let-else")]
+    #[test]
+    fn should_release_lock_before_escaping() {
+        let queue = std::sync::Mutex::new(vec![Msg::Ping]);
+        let first_data_or_unlocked = || -> Result<i32, bool> {
+            let unlocked = || queue.try_lock().is_ok();
+            let data = variant_or_return!(
+                queue.lock().unwrap().pop().unwrap_or(Msg::Ping),
+                Msg::Data,
+                Err(unlocked())
+            );
+            Ok(data)
+        };
+        assert_eq!(first_data_or_unlocked(), Err(true));
+    }
+}
@@ -0,0 +1,164 @@
+/// Either get the value of a `Poll::Ready` or return from the current function, which is then
+/// pending as well (`Poll::Pending`) if the value is not ready yet, like `std::task::ready!`. A
+/// default return value can be provided instead, for functions that do not return a `Poll`.
+/// ```
+/// use core::task::Poll;
+///
+/// use early_returns::ready_or_return;
+///
+/// fn sum(left: Poll<u32>, right: Poll<u32>) -> Poll<u32> {
+///     let left = ready_or_return!(left);
+///     let right = ready_or_return!(right);
+///     Poll::Ready(left + right)
+/// }
+///
+/// fn ready_sum(left: Poll<u32>, right: Poll<u32>) -> Option<u32> {
+///     let sum = ready_or_return!(sum(left, right), None);
+///     Some(sum)
+/// }
+///
+/// assert_eq!(sum(Poll::Ready(1), Poll::Ready(2)), Poll::Ready(3));
+/// assert_eq!(sum(Poll::Ready(1), Poll::Pending), Poll::Pending);
+/// assert_eq!(ready_sum(Poll::Pending, Poll::Ready(2)), None);
+/// ```
+#[macro_export]
+macro_rules! ready_or_return {
+    ($from:expr) => {{
+        match $from {
+            ::core::task::Poll::Ready(f) => f,
+            ::core::task::Poll::Pending => {
+                $crate::__mark_early_exit!();
+                return ::core::task::Poll::Pending;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr) => {{
+        match $from {
+            ::core::task::Poll::Ready(f) => f,
+            ::core::task::Poll::Pending => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value of a `Poll::Ready` or break out of a loop if it is still pending. If a loop
+/// lifetime is specified, that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use core::task::Poll;
+///
+/// use early_returns::ready_or_break;
+///
+/// let polls = [Poll::Ready(1), Poll::Ready(2), Poll::Pending, Poll::Ready(4)];
+/// let mut ready = Vec::new();
+/// for poll in polls {
+///     ready.push(ready_or_break!(poll));
+/// }
+/// assert_eq!(ready, [1, 2]);
+/// ```
+#[macro_export]
+macro_rules! ready_or_break {
+    ($from:expr) => {{
+        match $from {
+            ::core::task::Poll::Ready(f) => f,
+            ::core::task::Poll::Pending => {
+                $crate::__private::silent_break();
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            ::core::task::Poll::Ready(f) => f,
+            ::core::task::Poll::Pending => {
+                $crate::__private::silent_break();
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value of a `Poll::Ready` or continue in a loop if it is still pending, so the
+/// ready values are handled and the pending ones are polled again later. If a loop lifetime is
+/// specified, that loop will be "continued", otherwise the immediate loop is "continued".
+/// ```
+/// use core::task::Poll;
+///
+/// use early_returns::ready_or_continue;
+///
+/// let polls = [Poll::Ready(1), Poll::Pending, Poll::Ready(3)];
+/// let mut ready = Vec::new();
+/// for poll in polls {
+///     ready.push(ready_or_continue!(poll));
+/// }
+/// assert_eq!(ready, [1, 3]);
+/// ```
+#[macro_export]
+macro_rules! ready_or_continue {
+    ($from:expr) => {{
+        match $from {
+            ::core::task::Poll::Ready(f) => f,
+            ::core::task::Poll::Pending => {
+                $crate::__private::silent_continue();
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime) => {{
+        match $from {
+            ::core::task::Poll::Ready(f) => f,
+            ::core::task::Poll::Pending => {
+                $crate::__private::silent_continue();
+                continue $lt;
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use core::task::Poll;
+
+    fn double(value: Poll<i32>) -> Poll<i32> {
+        Poll::Ready(ready_or_return!(value) * 2)
+    }
+
+    fn double_or(value: Poll<i32>) -> i32 {
+        ready_or_return!(value, -1) * 2
+    }
+
+    #[test]
+    fn should_return_pending() {
+        assert_eq!(double(Poll::Ready(2)), Poll::Ready(4));
+        assert_eq!(double(Poll::Pending), Poll::Pending);
+        assert_eq!(double_or(Poll::Ready(2)), 4);
+        assert_eq!(double_or(Poll::Pending), -1);
+    }
+
+    #[test]
+    fn should_break_when_pending() {
+        let mut seen = Vec::new();
+        'l: for poll in [Poll::Ready(1), Poll::Pending, Poll::Ready(3)] {
+            for _i in 0..2 {
+                seen.push(ready_or_break!(poll, 'l));
+            }
+        }
+        assert_eq!(seen, [1, 1]);
+    }
+
+    #[test]
+    fn should_continue_when_pending() {
+        let mut seen = Vec::new();
+        'l: for poll in [Poll::Ready(1), Poll::Pending, Poll::Ready(3)] {
+            for _i in 0..2 {
+                seen.push(ready_or_continue!(poll, 'l));
+            }
+        }
+        assert_eq!(seen, [1, 1, 3, 3]);
+    }
+}
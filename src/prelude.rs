@@ -0,0 +1,193 @@
+//! Every macro of the crate (behind the same features as the macros themselves) along with its
+//! extension traits and the `EarlyExit` and `EarlyExitReason` types, for a single glob import.
+//!
+#![cfg_attr(all(feature = "option", feature = "result"), doc = "```")]
+#![cfg_attr(not(all(feature = "option", feature = "result")), doc = "```ignore")]
+//! use early_returns::prelude::*;
+//!
+//! fn total(rows: &[&str]) -> Option<u32> {
+//!     let first = some_or_return!(rows.first(), None);
+//!     let mut total = ok_or_return!(first.parse::<u32>(), None);
+//!     for row in rows.iter().skip(1).map(|row| row.parse::<u32>()).take_while_ok() {
+//!         total += row;
+//!     }
+//!     Some(total)
+//! }
+//!
+//! assert_eq!(total(&["1", "2", "x", "4"]), Some(3));
+//! ```
+
+pub use crate::defer;
+#[cfg(feature = "instrumented")]
+pub use crate::{break_early, continue_early, return_early};
+#[cfg(feature = "defer")]
+pub use crate::defer_on_early_exit;
+#[cfg(feature = "bool")]
+pub use crate::{ensure_or_break, ensure_or_continue};
+#[cfg(all(feature = "conversions", feature = "std"))]
+pub use crate::env_parse_or_return;
+#[cfg(feature = "windows")]
+pub use crate::hr_ok_or_return;
+#[cfg(all(feature = "alloc", feature = "result"))]
+pub use crate::ok_or_return_fmt;
+#[cfg(all(feature = "loops", feature = "tokio"))]
+pub use crate::retry_async;
+#[cfg(all(feature = "loops", feature = "std"))]
+pub use crate::retry_backoff;
+#[cfg(feature = "tokio")]
+pub use crate::{acquire_or_return, oneshot_or_return, timeout_or_return, try_acquire_or_continue};
+#[cfg(feature = "patterns")]
+pub use crate::{
+    all_ok_or_return, all_some_or_break, all_some_or_continue, all_some_or_return,
+    bind_all_or_return, first_some_or_return, let_or_break, let_or_continue, let_or_return,
+    ok_mut_or_break, ok_mut_or_continue, ok_mut_or_return, ok_some_or_break, ok_some_or_continue,
+    ok_some_or_return, some_cloned_or_break, some_cloned_or_continue, some_cloned_or_return,
+    some_copied_or_break, some_copied_or_continue, some_copied_or_return, some_deref_or_break,
+    some_deref_or_continue, some_deref_or_return, some_mut_or_break, some_mut_or_continue,
+    some_mut_or_return, some_ok_or_break, some_ok_or_continue, some_ok_or_return, take_or_break,
+    take_or_continue, take_or_return, variant_or_break, variant_or_continue, variant_or_return,
+};
+#[cfg(feature = "collections")]
+pub use crate::{
+    cached_or_compute_or_return, first_or_break, first_or_continue, first_or_return, get_or_break,
+    get_or_continue, get_or_return, last_or_break, last_or_continue, last_or_return,
+    ok_or_continue_collect, pop_back_or_break, pop_front_or_break, pop_or_break,
+    some_or_continue_collect, split_first_or_break, split_first_or_continue, split_first_or_return,
+    split_last_or_break, split_last_or_continue, split_last_or_return,
+};
+#[cfg(feature = "conversions")]
+pub use crate::{
+    char_or_break, char_or_continue, char_or_return, checked_or_break, checked_or_continue,
+    checked_or_return, digit_or_break, digit_or_continue, digit_or_return, div_or_return,
+    downcast_or_continue, downcast_or_return, nonnull_or_return, nonzero_or_return,
+    parse_or_continue, parse_or_return, try_into_or_break, try_into_or_continue,
+    try_into_or_return, upgrade_or_break, upgrade_or_continue, upgrade_or_return, utf8_or_continue,
+    utf8_or_return,
+};
+#[cfg(feature = "std")]
+pub use crate::{
+    cstr_or_return, cstring_or_return, env_or_return, ffi_ok_or_return, join_or_return,
+    lock_or_return, ok_or_continue_if, ok_or_return_if, osstr_or_continue, osstr_or_return,
+    path_str_or_continue, path_str_or_return, read_or_return, recv_or_break, recv_or_return,
+    recv_timeout_or_continue, retry_on_interrupted, send_or_break, send_or_return,
+    try_lock_or_continue, try_lock_or_return, try_read_or_continue, try_read_or_return,
+    try_recv_or_continue, try_write_or_continue, try_write_or_return, write_or_return,
+};
+#[cfg(feature = "result")]
+pub use crate::{
+    debug_ok_or_break, debug_ok_or_continue, debug_ok_or_return, ok_or_break, ok_or_break_cleanup,
+    ok_or_continue, ok_or_continue_cleanup, ok_or_return, ok_or_return_boxed, ok_or_return_cleanup,
+    ok_or_break_mapped, ok_or_break_store, ok_or_continue_store, ok_or_return_inspect_err,
+    ok_or_return_mapped, ok_or_return_store,
+};
+#[cfg(feature = "option")]
+pub use crate::{
+    debug_some_or_break, debug_some_or_continue, debug_some_or_return, some_or_break,
+    some_or_break_cleanup, some_or_continue, some_or_continue_cleanup, some_or_return,
+    some_or_return_cleanup, some_or_return_inspect,
+};
+#[cfg(feature = "loops")]
+pub use crate::{branch_or_break, for_some, loop_until_some, retry, while_ok};
+#[cfg(feature = "either")]
+pub use crate::{
+    left_or_break, left_or_continue, left_or_return, right_or_break, right_or_continue,
+    right_or_return,
+};
+#[cfg(feature = "async-stream")]
+pub use crate::{ok_or_yield, some_or_yield};
+#[cfg(feature = "futures")]
+pub use crate::{try_next_or_break, try_next_or_return};
+#[cfg(feature = "heapless")]
+pub use crate::{push_or_break, push_or_continue, push_or_return};
+#[cfg(feature = "iterators")]
+pub use crate::{next_or_break, next_or_return, peek_or_break, peek_or_return};
+#[cfg(feature = "nb")]
+pub use crate::{nb_ok_or_continue, nb_ok_or_return};
+#[cfg(feature = "nom")]
+pub use crate::{nom_or_continue, nom_or_return};
+#[cfg(feature = "poll")]
+pub use crate::{ready_or_break, ready_or_continue, ready_or_return};
+#[cfg(feature = "tokio-util")]
+pub use crate::{not_cancelled_or_break, not_cancelled_or_return};
+#[cfg(feature = "rayon")]
+pub use crate::{ok_or_break_par, ok_or_continue_par, some_or_break_par, some_or_continue_par};
+#[cfg(feature = "metrics")]
+pub use crate::{
+    ok_or_break_counted, ok_or_continue_counted, ok_or_return_counted, some_or_break_counted,
+    some_or_continue_counted, some_or_return_counted,
+};
+#[cfg(all(feature = "instrumented", feature = "result"))]
+pub use crate::{ok_or_break_reason, ok_or_continue_reason, ok_or_return_reason};
+#[cfg(all(feature = "instrumented", feature = "option"))]
+pub use crate::{some_or_break_reason, some_or_continue_reason, some_or_return_reason};
+#[cfg(all(feature = "instrumented", feature = "std"))]
+pub use crate::{
+    ok_or_break_hooked, ok_or_continue_hooked, ok_or_return_hooked, some_or_break_hooked,
+    some_or_continue_hooked, some_or_return_hooked,
+};
+#[cfg(feature = "log")]
+pub use crate::{
+    ok_or_break_logged, ok_or_continue_logged, ok_or_return_logged, some_or_break_logged,
+    some_or_continue_logged, some_or_return_logged,
+};
+#[cfg(feature = "tracing")]
+pub use crate::{
+    ok_or_break_recorded, ok_or_break_traced, ok_or_continue_recorded, ok_or_continue_traced,
+    ok_or_return_recorded, ok_or_return_traced, some_or_break_recorded, some_or_break_traced,
+    some_or_continue_recorded, some_or_continue_traced, some_or_return_recorded,
+    some_or_return_traced,
+};
+#[cfg(feature = "anyhow")]
+pub use crate::{ok_or_return_context, some_or_return_context};
+#[cfg(feature = "miette")]
+pub use crate::{ok_or_return_diag, some_or_return_diag};
+#[cfg(feature = "eyre")]
+pub use crate::{ok_or_return_eyre, some_or_return_eyre};
+#[cfg(feature = "aliases")]
+pub use crate::{oob, ooc, oor, sob, soc, sor};
+
+pub use crate::control_flow::{OptionEarlyExt, ResultEarlyExt};
+pub use crate::early_exit::{EarlyExit, ResolveEarlyExit};
+pub use crate::iter::EarlyIteratorExt;
+#[cfg(feature = "instrumented")]
+pub use crate::reason::EarlyExitReason;
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "aliases")]
+    #[test]
+    fn should_provide_aliases() {
+        use super::*;
+
+        fn sum(values: &[Option<Result<u32, ()>>]) -> u32 {
+            let mut sum = 0;
+            for value in values {
+                let value = soc!(value);
+                sum += oor!(value, 0);
+            }
+            sum
+        }
+
+        assert_eq!(sum(&[Some(Ok(1)), None, Some(Ok(2))]), 3);
+        assert_eq!(sum(&[Some(Ok(1)), Some(Err(()))]), 0);
+    }
+
+    #[test]
+    fn every_exported_macro_is_in_the_prelude() {
+        let this = include_str!("prelude.rs");
+        let names: Vec<_> = this
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .collect();
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let source = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            for exported in source.split("#[macro_export]\nmacro_rules! ").skip(1) {
+                let name = exported.split_whitespace().next().unwrap();
+                assert!(
+                    name.starts_with("__") || names.contains(&name),
+                    "`{name}!` is missing from the prelude"
+                );
+            }
+        }
+    }
+}
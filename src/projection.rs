@@ -0,0 +1,746 @@
+/// Either get a copy of the value from an `Option<&T>` (e.g. from a map lookup) or return from
+/// the current function if there is none. A default return value can be provided.
+///
+/// This is `some_or_return!(opt.copied())`, making by-value extraction explicit at the call
+/// site.
+/// ```
+/// use early_returns::some_copied_or_return;
+/// use std::collections::HashMap;
+///
+/// fn double(counts: &HashMap<&str, u32>, key: &str) -> u32 {
+///     let count = some_copied_or_return!(counts.get(key), 0);
+///     count * 2
+/// }
+///
+/// let counts = HashMap::from([("a", 2)]);
+/// assert_eq!(double(&counts, "a"), 4);
+/// assert_eq!(double(&counts, "b"), 0);
+/// ```
+#[macro_export]
+macro_rules! some_copied_or_return {
+    ($from:expr) => {
+        $crate::some_or_return!($from.copied())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::some_or_return!($from.copied(), $default_result)
+    };
+}
+
+/// Either get a copy of the value from an `Option<&T>` or break out of a loop if there is none. If
+/// a loop lifetime is specified, that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::some_copied_or_break;
+///
+/// let values = [1, 2, 3];
+/// let mut total = 0;
+/// for i in 0.. {
+///     total += some_copied_or_break!(values.get(i));
+/// }
+/// assert_eq!(total, 6);
+/// ```
+#[macro_export]
+macro_rules! some_copied_or_break {
+    ($from:expr) => {
+        $crate::some_or_break!($from.copied())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_break!($from.copied(), $lt)
+    };
+}
+
+/// Either get a copy of the value from an `Option<&T>` or continue in a loop if there is none. If
+/// a loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::some_copied_or_continue;
+/// use std::collections::HashMap;
+///
+/// let prices = HashMap::from([("apple", 3), ("pear", 4)]);
+/// let mut total = 0;
+/// for item in ["apple", "plum", "pear"] {
+///     total += some_copied_or_continue!(prices.get(item));
+/// }
+/// assert_eq!(total, 7);
+/// ```
+#[macro_export]
+macro_rules! some_copied_or_continue {
+    ($from:expr) => {
+        $crate::some_or_continue!($from.copied())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($from.copied(), $lt)
+    };
+}
+
+/// Either get a clone of the value from an `Option<&T>` (e.g. from a map lookup) or return from
+/// the current function if there is none. A default return value can be provided.
+///
+/// This is `some_or_return!(opt.cloned())`, making by-value extraction explicit at the call
+/// site.
+/// ```
+/// use early_returns::some_cloned_or_return;
+/// use std::collections::HashMap;
+///
+/// fn owned_name(names: &HashMap<u32, String>, id: u32) -> Option<String> {
+///     let mut name = some_cloned_or_return!(names.get(&id), None);
+///     name.push('!');
+///     Some(name)
+/// }
+///
+/// let names = HashMap::from([(1, "ada".to_string())]);
+/// assert_eq!(owned_name(&names, 1), Some("ada!".to_string()));
+/// assert_eq!(owned_name(&names, 2), None);
+/// ```
+#[macro_export]
+macro_rules! some_cloned_or_return {
+    ($from:expr) => {
+        $crate::some_or_return!($from.cloned())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::some_or_return!($from.cloned(), $default_result)
+    };
+}
+
+/// Either get a clone of the value from an `Option<&T>` or break out of a loop if there is none. If
+/// a loop lifetime is specified, that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::some_cloned_or_break;
+///
+/// let words = vec!["a".to_string(), "b".to_string()];
+/// let mut owned: Vec<String> = Vec::new();
+/// for i in 0.. {
+///     owned.push(some_cloned_or_break!(words.get(i)));
+/// }
+/// assert_eq!(owned, words);
+/// ```
+#[macro_export]
+macro_rules! some_cloned_or_break {
+    ($from:expr) => {
+        $crate::some_or_break!($from.cloned())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_break!($from.cloned(), $lt)
+    };
+}
+
+/// Either get a clone of the value from an `Option<&T>` or continue in a loop if there is none. If
+/// a loop lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::some_cloned_or_continue;
+/// use std::collections::HashMap;
+///
+/// let names = HashMap::from([(1, "ada".to_string()), (3, "grace".to_string())]);
+/// let mut owned: Vec<String> = Vec::new();
+/// for id in 1..=3 {
+///     owned.push(some_cloned_or_continue!(names.get(&id)));
+/// }
+/// assert_eq!(owned, vec!["ada".to_string(), "grace".to_string()]);
+/// ```
+#[macro_export]
+macro_rules! some_cloned_or_continue {
+    ($from:expr) => {
+        $crate::some_or_continue!($from.cloned())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($from.cloned(), $lt)
+    };
+}
+
+/// Either get a dereferenced borrow of the value from an `Option` (e.g. `&str` from an
+/// `Option<String>` field or `&T` from an `Option<Box<T>>`) or return from the current function if
+/// there is none. A default return value can be provided.
+///
+/// This is `some_or_return!(opt.as_deref())`. With the `mut` marker (e.g.
+/// `some_deref_or_return!(mut self.name)`), `as_deref_mut` is used instead.
+/// ```
+/// use early_returns::some_deref_or_return;
+///
+/// struct User {
+///     nickname: Option<String>,
+/// }
+///
+/// fn shout(user: &User) -> Option<String> {
+///     let nickname: &str = some_deref_or_return!(user.nickname, None);
+///     Some(nickname.to_uppercase())
+/// }
+///
+/// fn capitalize(user: &mut User) {
+///     let nickname: &mut str = some_deref_or_return!(mut user.nickname);
+///     nickname[..1].make_ascii_uppercase();
+/// }
+///
+/// let mut user = User { nickname: Some("ada".to_string()) };
+/// assert_eq!(shout(&user), Some("ADA".to_string()));
+/// capitalize(&mut user);
+/// assert_eq!(user.nickname.as_deref(), Some("Ada"));
+/// assert_eq!(shout(&User { nickname: None }), None);
+/// ```
+#[macro_export]
+macro_rules! some_deref_or_return {
+    (mut $from:expr) => {
+        $crate::some_or_return!($from.as_deref_mut())
+    };
+
+    (mut $from:expr, $default_result:expr) => {
+        $crate::some_or_return!($from.as_deref_mut(), $default_result)
+    };
+
+    ($from:expr) => {
+        $crate::some_or_return!($from.as_deref())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::some_or_return!($from.as_deref(), $default_result)
+    };
+}
+
+/// Either get a dereferenced borrow of the value from an `Option` or break out of a loop if there
+/// is none. If a loop lifetime is specified, that loop will be exited, otherwise the immediate
+/// loop is exited. With the `mut` marker, `as_deref_mut` is used instead of `as_deref`.
+/// ```
+/// use early_returns::some_deref_or_break;
+///
+/// let names = vec![Some("ada".to_string()), None, Some("grace".to_string())];
+/// let mut lengths = Vec::new();
+/// for name in &names {
+///     let name: &str = some_deref_or_break!(name);
+///     lengths.push(name.len());
+/// }
+/// assert_eq!(lengths, vec![3]);
+/// ```
+#[macro_export]
+macro_rules! some_deref_or_break {
+    (mut $from:expr) => {
+        $crate::some_or_break!($from.as_deref_mut())
+    };
+
+    (mut $from:expr, $lt:lifetime) => {
+        $crate::some_or_break!($from.as_deref_mut(), $lt)
+    };
+
+    ($from:expr) => {
+        $crate::some_or_break!($from.as_deref())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_break!($from.as_deref(), $lt)
+    };
+}
+
+/// Either get a dereferenced borrow of the value from an `Option` or continue in a loop if there
+/// is none. If a loop lifetime is specified, that loop will be "continued", otherwise the
+/// immediate loop is "continued". With the `mut` marker, `as_deref_mut` is used instead of
+/// `as_deref`.
+/// ```
+/// use early_returns::some_deref_or_continue;
+///
+/// let mut names = vec![Some("ada".to_string()), None, Some("grace".to_string())];
+/// for name in names.iter_mut() {
+///     let name: &mut str = some_deref_or_continue!(mut name);
+///     name.make_ascii_uppercase();
+/// }
+/// assert_eq!(names, vec![Some("ADA".to_string()), None, Some("GRACE".to_string())]);
+/// ```
+#[macro_export]
+macro_rules! some_deref_or_continue {
+    (mut $from:expr) => {
+        $crate::some_or_continue!($from.as_deref_mut())
+    };
+
+    (mut $from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($from.as_deref_mut(), $lt)
+    };
+
+    ($from:expr) => {
+        $crate::some_or_continue!($from.as_deref())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($from.as_deref(), $lt)
+    };
+}
+
+/// Either get a mutable reference to the value of a `&mut Option<T>` or return from the current
+/// function if there is none. A default return value can be provided.
+///
+/// This is `some_or_return!(slot.as_mut())`, for updating optional fields in place.
+/// ```
+/// use early_returns::some_mut_or_return;
+///
+/// struct Session {
+///     retries: Option<u32>,
+/// }
+///
+/// impl Session {
+///     fn record_retry(&mut self) -> bool {
+///         let retries = some_mut_or_return!(self.retries, false);
+///         *retries += 1;
+///         true
+///     }
+/// }
+///
+/// let mut session = Session { retries: Some(0) };
+/// assert!(session.record_retry());
+/// assert_eq!(session.retries, Some(1));
+/// assert!(!Session { retries: None }.record_retry());
+/// ```
+#[macro_export]
+macro_rules! some_mut_or_return {
+    ($from:expr) => {
+        $crate::some_or_return!($from.as_mut())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::some_or_return!($from.as_mut(), $default_result)
+    };
+}
+
+/// Either get a mutable reference to the value of a `&mut Option<T>` or break out of a loop if
+/// there is none. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+/// ```
+/// use early_returns::some_mut_or_break;
+///
+/// let mut slots = vec![Some(1), Some(2), None, Some(4)];
+/// for slot in slots.iter_mut() {
+///     *some_mut_or_break!(slot) *= 10;
+/// }
+/// assert_eq!(slots, vec![Some(10), Some(20), None, Some(4)]);
+/// ```
+#[macro_export]
+macro_rules! some_mut_or_break {
+    ($from:expr) => {
+        $crate::some_or_break!($from.as_mut())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_break!($from.as_mut(), $lt)
+    };
+}
+
+/// Either get a mutable reference to the value of a `&mut Option<T>` or continue in a loop if
+/// there is none. If a loop lifetime is specified, that loop will be "continued", otherwise the
+/// immediate loop is "continued".
+/// ```
+/// use early_returns::some_mut_or_continue;
+///
+/// let mut slots = vec![Some(1), None, Some(3)];
+/// for slot in slots.iter_mut() {
+///     *some_mut_or_continue!(slot) *= 10;
+/// }
+/// assert_eq!(slots, vec![Some(10), None, Some(30)]);
+/// ```
+#[macro_export]
+macro_rules! some_mut_or_continue {
+    ($from:expr) => {
+        $crate::some_or_continue!($from.as_mut())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($from.as_mut(), $lt)
+    };
+}
+
+/// Either get a mutable reference to the value of a `&mut Result<T, E>` or return from the current
+/// function if it is an `Err`. A default return value can be provided.
+///
+/// This is `ok_or_return!(slot.as_mut())`, for updating optional fields in place.
+/// ```
+/// use early_returns::ok_mut_or_return;
+///
+/// fn append(buffer: &mut Result<String, ()>, text: &str) {
+///     let buffer = ok_mut_or_return!(buffer);
+///     buffer.push_str(text);
+/// }
+///
+/// let mut buffer = Ok("a".to_string());
+/// append(&mut buffer, "b");
+/// assert_eq!(buffer, Ok("ab".to_string()));
+/// let mut failed = Err(());
+/// append(&mut failed, "b");
+/// assert_eq!(failed, Err(()));
+/// ```
+#[macro_export]
+macro_rules! ok_mut_or_return {
+    ($from:expr) => {
+        $crate::ok_or_return!($from.as_mut())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::ok_or_return!($from.as_mut(), $default_result)
+    };
+}
+
+/// Either get a mutable reference to the value of a `&mut Result<T, E>` or break out of a loop if
+/// it is an `Err`. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+/// ```
+/// use early_returns::ok_mut_or_break;
+///
+/// let mut results: Vec<Result<i32, ()>> = vec![Ok(1), Err(()), Ok(3)];
+/// for result in results.iter_mut() {
+///     *ok_mut_or_break!(result) += 1;
+/// }
+/// assert_eq!(results, vec![Ok(2), Err(()), Ok(3)]);
+/// ```
+#[macro_export]
+macro_rules! ok_mut_or_break {
+    ($from:expr) => {
+        $crate::ok_or_break!($from.as_mut())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::ok_or_break!($from.as_mut(), $lt)
+    };
+}
+
+/// Either get a mutable reference to the value of a `&mut Result<T, E>` or continue in a loop if
+/// it is an `Err`. If a loop lifetime is specified, that loop will be "continued", otherwise the
+/// immediate loop is "continued".
+/// ```
+/// use early_returns::ok_mut_or_continue;
+///
+/// let mut results: Vec<Result<i32, ()>> = vec![Ok(1), Err(()), Ok(3)];
+/// for result in results.iter_mut() {
+///     *ok_mut_or_continue!(result) += 1;
+/// }
+/// assert_eq!(results, vec![Ok(2), Err(()), Ok(4)]);
+/// ```
+#[macro_export]
+macro_rules! ok_mut_or_continue {
+    ($from:expr) => {
+        $crate::ok_or_continue!($from.as_mut())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::ok_or_continue!($from.as_mut(), $lt)
+    };
+}
+
+/// Either move the value out of an `Option` slot with `take` (leaving `None` behind) or return
+/// from the current function if the slot was already empty. A default return value can be
+/// provided.
+///
+/// This is the usual way for a state machine to move a value out of `self` exactly once.
+/// ```
+/// use early_returns::take_or_return;
+///
+/// struct Connection {
+///     handshake: Option<String>,
+/// }
+///
+/// impl Connection {
+///     fn finish_handshake(&mut self) -> Option<usize> {
+///         let handshake = take_or_return!(self.handshake, None);
+///         Some(handshake.len())
+///     }
+/// }
+///
+/// let mut conn = Connection { handshake: Some("hello".to_string()) };
+/// assert_eq!(conn.finish_handshake(), Some(5));
+/// assert_eq!(conn.handshake, None);
+/// assert_eq!(conn.finish_handshake(), None);
+/// ```
+#[macro_export]
+macro_rules! take_or_return {
+    ($from:expr) => {
+        $crate::some_or_return!($from.take())
+    };
+
+    ($from:expr, $default_result:expr) => {
+        $crate::some_or_return!($from.take(), $default_result)
+    };
+}
+
+/// Either move the value out of an `Option` slot with `take` (leaving `None` behind) or break
+/// out of a loop if the slot was empty. If a loop lifetime is specified, that loop will be
+/// exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::take_or_break;
+///
+/// let mut pending = Some(3);
+/// let mut handled = Vec::new();
+/// loop {
+///     let value = take_or_break!(pending);
+///     handled.push(value);
+///     if value > 1 {
+///         pending = Some(value - 1);
+///     }
+/// }
+/// assert_eq!(handled, vec![3, 2, 1]);
+/// ```
+#[macro_export]
+macro_rules! take_or_break {
+    ($from:expr) => {
+        $crate::some_or_break!($from.take())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_break!($from.take(), $lt)
+    };
+}
+
+/// Either move the value out of an `Option` slot with `take` (leaving `None` behind) or continue
+/// in a loop if the slot was empty. If a loop lifetime is specified, that loop will be
+/// "continued", otherwise the immediate loop is "continued".
+/// ```
+/// use early_returns::take_or_continue;
+///
+/// let mut slots = vec![Some(1), None, Some(3)];
+/// let mut taken = Vec::new();
+/// for slot in slots.iter_mut() {
+///     taken.push(take_or_continue!(slot));
+/// }
+/// assert_eq!(taken, vec![1, 3]);
+/// assert_eq!(slots, vec![None, None, None]);
+/// ```
+#[macro_export]
+macro_rules! take_or_continue {
+    ($from:expr) => {
+        $crate::some_or_continue!($from.take())
+    };
+
+    ($from:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($from.take(), $lt)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    fn copied_or_default(values: &[i32], i: usize) -> i32 {
+        some_copied_or_return!(values.get(i), -1)
+    }
+
+    fn cloned_into(values: &[String], i: usize, out: &mut Vec<String>) {
+        out.push(some_cloned_or_return!(values.get(i)));
+    }
+
+    #[test]
+    fn should_return_early_when_none() {
+        assert_eq!(copied_or_default(&[1, 2], 1), 2);
+        assert_eq!(copied_or_default(&[1, 2], 2), -1);
+        let values = vec!["a".to_string()];
+        let mut out = Vec::new();
+        cloned_into(&values, 0, &mut out);
+        cloned_into(&values, 1, &mut out);
+        assert_eq!(out, values);
+    }
+
+    #[test]
+    fn should_break_when_none() {
+        let map = HashMap::from([(0, 1), (1, 2), (3, 4)]);
+        let mut sum = 0;
+        for i in 0..4 {
+            sum += some_copied_or_break!(map.get(&i));
+        }
+        assert_eq!(sum, 3);
+        let mut out: Vec<String> = Vec::new();
+        let words = ["a".to_string()];
+        'l: for i in 0..3 {
+            for _j in 0..2 {
+                out.push(some_cloned_or_break!(words.get(i), 'l));
+            }
+        }
+        assert_eq!(out, vec!["a".to_string(), "a".to_string()]);
+    }
+
+    #[test]
+    fn should_continue_when_none() {
+        let map = HashMap::from([(0, 1), (1, 2), (3, 4)]);
+        let mut sum = 0;
+        for i in 0..4 {
+            sum += some_copied_or_continue!(map.get(&i));
+        }
+        assert_eq!(sum, 7);
+        let mut out: Vec<String> = Vec::new();
+        let words = ["a".to_string(), "b".to_string()];
+        'l: for i in 0..3 {
+            for _j in 0..2 {
+                out.push(some_cloned_or_continue!(words.get(i), 'l));
+            }
+        }
+        assert_eq!(out, vec!["a", "a", "b", "b"]);
+    }
+
+    struct Node {
+        label: Option<String>,
+        child: Option<Box<i32>>,
+    }
+
+    fn label_len(node: &Node) -> usize {
+        some_deref_or_return!(node.label, 0).len()
+    }
+
+    fn bump_child(node: &mut Node) -> bool {
+        let child: &mut i32 = some_deref_or_return!(mut node.child, false);
+        *child += 1;
+        true
+    }
+
+    #[test]
+    fn should_return_early_when_none_to_deref() {
+        let mut node = Node {
+            label: Some("abc".to_string()),
+            child: Some(Box::new(1)),
+        };
+        assert_eq!(label_len(&node), 3);
+        assert!(bump_child(&mut node));
+        assert_eq!(node.child.as_deref(), Some(&2));
+        let mut empty = Node {
+            label: None,
+            child: None,
+        };
+        assert_eq!(label_len(&empty), 0);
+        assert!(!bump_child(&mut empty));
+    }
+
+    #[test]
+    fn should_break_or_continue_when_none_to_deref() {
+        let mut labels = vec![Some("a".to_string()), None, Some("b".to_string())];
+        let mut seen = String::new();
+        for label in &labels {
+            seen.push_str(some_deref_or_break!(label));
+        }
+        assert_eq!(seen, "a");
+        'l: for label in labels.iter_mut() {
+            for _i in 0..2 {
+                some_deref_or_continue!(mut label, 'l).make_ascii_uppercase();
+                seen.push_str(some_deref_or_continue!(label, 'l));
+            }
+        }
+        assert_eq!(seen, "aAABB");
+        'l: for label in labels.iter_mut() {
+            for _i in 0..2 {
+                seen.push_str(some_deref_or_break!(mut label, 'l));
+            }
+        }
+        assert_eq!(seen, "aAABBAA");
+    }
+
+    struct Counters {
+        hits: Option<u32>,
+        total: Result<u32, ()>,
+    }
+
+    impl Counters {
+        fn hit(&mut self) {
+            *some_mut_or_return!(self.hits) += 1;
+        }
+
+        fn add(&mut self, value: u32) -> bool {
+            *ok_mut_or_return!(self.total, false) += value;
+            true
+        }
+    }
+
+    #[test]
+    fn should_return_early_when_no_mutable_value() {
+        let mut counters = Counters {
+            hits: Some(0),
+            total: Ok(0),
+        };
+        counters.hit();
+        assert!(counters.add(5));
+        assert_eq!(counters.hits, Some(1));
+        assert_eq!(counters.total, Ok(5));
+        let mut empty = Counters {
+            hits: None,
+            total: Err(()),
+        };
+        empty.hit();
+        assert!(!empty.add(5));
+        assert_eq!(empty.hits, None);
+        assert_eq!(empty.total, Err(()));
+    }
+
+    #[test]
+    fn should_break_or_continue_when_no_mutable_value() {
+        let mut slots = vec![Some(1), None, Some(3)];
+        'l: for slot in slots.iter_mut() {
+            for _i in 0..2 {
+                *some_mut_or_continue!(slot, 'l) += 1;
+            }
+        }
+        assert_eq!(slots, vec![Some(3), None, Some(5)]);
+        'l: for slot in slots.iter_mut() {
+            for _i in 0..2 {
+                *some_mut_or_break!(slot, 'l) += 1;
+            }
+        }
+        assert_eq!(slots, vec![Some(5), None, Some(5)]);
+        let mut results: Vec<Result<i32, ()>> = vec![Ok(1), Err(()), Ok(3)];
+        'l: for result in results.iter_mut() {
+            for _i in 0..2 {
+                *ok_mut_or_continue!(result, 'l) += 1;
+            }
+        }
+        assert_eq!(results, vec![Ok(3), Err(()), Ok(5)]);
+        'l: for result in results.iter_mut() {
+            for _i in 0..2 {
+                *ok_mut_or_break!(result, 'l) += 1;
+            }
+        }
+        assert_eq!(results, vec![Ok(5), Err(()), Ok(5)]);
+    }
+
+    struct Machine {
+        payload: Option<String>,
+        sent: Vec<String>,
+    }
+
+    impl Machine {
+        fn send(&mut self) {
+            let payload = take_or_return!(self.payload);
+            self.sent.push(payload);
+        }
+
+        fn send_len(&mut self) -> usize {
+            take_or_return!(self.payload, 0).len()
+        }
+    }
+
+    #[test]
+    fn should_take_once_then_return_early() {
+        let mut machine = Machine {
+            payload: Some("a".to_string()),
+            sent: Vec::new(),
+        };
+        machine.send();
+        machine.send();
+        assert_eq!(machine.sent, vec!["a".to_string()]);
+        assert_eq!(machine.payload, None);
+        assert_eq!(machine.send_len(), 0);
+        machine.payload = Some("abc".to_string());
+        assert_eq!(machine.send_len(), 3);
+        assert_eq!(machine.payload, None);
+    }
+
+    #[test]
+    fn should_break_or_continue_when_slot_empty() {
+        let mut slots = [Some(1), None, Some(3)];
+        let mut taken = Vec::new();
+        'l: for slot in slots.iter_mut() {
+            for _i in 0..2 {
+                taken.push(take_or_continue!(slot, 'l));
+            }
+        }
+        assert_eq!(taken, vec![1, 3]);
+        let mut slots = vec![Some(1), None, Some(3)];
+        'l: for slot in slots.iter_mut() {
+            for _i in 0..2 {
+                taken.push(take_or_break!(slot, 'l));
+            }
+        }
+        assert_eq!(taken, vec![1, 3, 1]);
+        assert_eq!(slots, [None, None, Some(3)]);
+    }
+}
@@ -0,0 +1,118 @@
+/// Either convert a raw pointer into a `NonNull` with `NonNull::new` or return from the current
+/// function if it is null. A default return value (such as an error code) can be provided.
+///
+/// With the `ref` or `mut` marker (e.g. `nonnull_or_return!(ref ptr)`), the pointer is converted
+/// into a shared or mutable reference with `as_ref` or `as_mut` instead. As these are unsafe, the
+/// macro has to be used inside an `unsafe` block, whose caller vouches for the pointer being
+/// aligned, pointing to a valid value, and not being aliased for the chosen lifetime.
+/// ```
+/// use early_returns::nonnull_or_return;
+///
+/// extern "C" fn on_event(user_data: *mut u32) -> i32 {
+///     let counter = nonnull_or_return!(user_data, -1);
+///     // SAFETY: the caller passes a pointer to a live counter.
+///     unsafe { *counter.as_ptr() += 1 };
+///     0
+/// }
+///
+/// extern "C" fn read_config(config: *const u32) -> i32 {
+///     // SAFETY: the caller passes either null or a pointer to a live config.
+///     let config = unsafe { nonnull_or_return!(ref config, -1) };
+///     *config as i32
+/// }
+///
+/// let mut counter = 0;
+/// assert_eq!(on_event(&mut counter), 0);
+/// assert_eq!(on_event(std::ptr::null_mut()), -1);
+/// assert_eq!(counter, 1);
+/// assert_eq!(read_config(&7), 7);
+/// assert_eq!(read_config(std::ptr::null()), -1);
+/// ```
+#[macro_export]
+macro_rules! nonnull_or_return {
+    (ref $ptr:expr) => {
+        $crate::some_or_return!($ptr.as_ref())
+    };
+
+    (ref $ptr:expr, $default_result:expr) => {
+        $crate::some_or_return!($ptr.as_ref(), $default_result)
+    };
+
+    (mut $ptr:expr) => {
+        $crate::some_or_return!($ptr.as_mut())
+    };
+
+    (mut $ptr:expr, $default_result:expr) => {
+        $crate::some_or_return!($ptr.as_mut(), $default_result)
+    };
+
+    ($ptr:expr) => {
+        $crate::some_or_return!(::core::ptr::NonNull::new($ptr))
+    };
+
+    ($ptr:expr, $default_result:expr) => {
+        $crate::some_or_return!(::core::ptr::NonNull::new($ptr), $default_result)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::ptr::{self, NonNull};
+
+    struct Tester {
+        value: i32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester { value: 0 }
+        }
+
+        fn add(&mut self, ptr: *mut i32) {
+            let ptr = nonnull_or_return!(ptr);
+            self.value += unsafe { *ptr.as_ptr() };
+        }
+
+        fn add_ref(&mut self, ptr: *const i32) {
+            self.value += unsafe { *nonnull_or_return!(ref ptr) };
+        }
+
+        fn increment(&mut self, ptr: *mut i32) {
+            unsafe { *nonnull_or_return!(mut ptr) += 1 };
+        }
+    }
+
+    fn nonnull_or_default(ptr: *mut i32) -> Option<NonNull<i32>> {
+        Some(nonnull_or_return!(ptr, None))
+    }
+
+    fn ref_or_default(ptr: *const i32) -> i32 {
+        unsafe { *nonnull_or_return!(ref ptr, -1) }
+    }
+
+    fn mut_or_default(ptr: *mut i32) -> i32 {
+        let value = unsafe { nonnull_or_return!(mut ptr, -1) };
+        *value *= 2;
+        *value
+    }
+
+    #[test]
+    fn should_return_early_on_null_pointers() {
+        let mut value = 2;
+        let mut tester = Tester::new();
+        tester.add(&mut value);
+        tester.add(ptr::null_mut());
+        tester.add_ref(&value);
+        tester.add_ref(ptr::null());
+        tester.increment(&mut value);
+        tester.increment(ptr::null_mut());
+        assert_eq!(tester.value, 4);
+        assert_eq!(value, 3);
+        assert!(nonnull_or_default(&mut value).is_some());
+        assert!(nonnull_or_default(ptr::null_mut()).is_none());
+        assert_eq!(ref_or_default(&value), 3);
+        assert_eq!(ref_or_default(ptr::null()), -1);
+        assert_eq!(mut_or_default(&mut value), 6);
+        assert_eq!(mut_or_default(ptr::null_mut()), -1);
+    }
+}
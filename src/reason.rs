@@ -0,0 +1,351 @@
+//! Reason-carrying escapes, which describe which guard fired with an [`EarlyExitReason`].
+//!
+//! The `*_reason` macros build an `EarlyExitReason { kind, location, message }` on their escape
+//! path, and then either return it as the error of the current function, store it into a slot
+//! provided by the caller, or pass it to the hook installed with `early_returns::hooks::set_hook`
+//! (with the `std` feature). This gives a standard, machine-readable answer to "which guard
+//! fired" without an ad-hoc enum of reasons.
+//!
+#![cfg_attr(all(feature = "option", feature = "result"), doc = "```")]
+#![cfg_attr(not(all(feature = "option", feature = "result")), doc = "```ignore")]
+//! use early_returns::reason::{EarlyExitReason, EarlyReturnKind};
+//! use early_returns::{ok_or_continue_reason, some_or_return_reason};
+//!
+//! fn port(config: &[(&str, &str)]) -> Result<u16, EarlyExitReason> {
+//!     let (_, port) = some_or_return_reason!(
+//!         config.iter().find(|(key, _)| *key == "port"),
+//!         "missing port"
+//!     );
+//!     Ok(port.parse().unwrap_or(80))
+//! }
+//!
+//! let reason = port(&[]).unwrap_err();
+//! assert_eq!((reason.kind, reason.message), (EarlyReturnKind::Return, "missing port"));
+//!
+//! let mut skipped = Vec::new();
+//! for value in ["1", "x", "3"] {
+//!     ok_or_continue_reason!(value.parse::<i32>(), "not a number", store: &mut skipped);
+//! }
+//! assert_eq!(skipped.len(), 1);
+//! assert_eq!(skipped[0].kind, EarlyReturnKind::Continue);
+//! ```
+
+use core::fmt;
+use core::panic::Location;
+
+/// Which escape path an instrumented macro took.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EarlyReturnKind {
+    Return,
+    Break,
+    Continue,
+}
+
+impl EarlyReturnKind {
+    /// The keyword of the escape path (`"return"`, `"break"` or `"continue"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EarlyReturnKind::Return => "return",
+            EarlyReturnKind::Break => "break",
+            EarlyReturnKind::Continue => "continue",
+        }
+    }
+}
+
+/// Which guard fired: the escape path taken, the location of the macro invocation and the message
+/// given there. It is displayed like the records of the `*_logged` macros
+/// (`missing port [early return at src/config.rs:12:25]`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct EarlyExitReason {
+    /// The escape path that was taken.
+    pub kind: EarlyReturnKind,
+    /// The location of the macro invocation.
+    pub location: &'static Location<'static>,
+    /// The message given at the macro invocation.
+    pub message: &'static str,
+}
+
+impl EarlyExitReason {
+    /// A reason for an escape at the location of the caller, which is the invocation of the
+    /// outermost macro when called from macro-expanded code.
+    #[track_caller]
+    pub fn new(kind: EarlyReturnKind, message: &'static str) -> Self {
+        EarlyExitReason { kind, location: Location::caller(), message }
+    }
+
+    /// Calls the hook installed with `early_returns::hooks::set_hook`, if any, with this reason
+    /// as the reason of the early exit.
+    #[cfg(feature = "std")]
+    pub fn call_hook(&self) {
+        crate::hooks::call_hook_at(self.location, self.kind, self.message);
+    }
+}
+
+impl fmt::Display for EarlyExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [early {} at {}]", self.message, self.kind.as_str(), self.location)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EarlyExitReason {}
+
+/// Either get the value from an Option type or return from the current function with an
+/// [`EarlyExitReason`] carrying the message.
+///
+/// By default the reason is returned as the error, converted with `From`, so the function can
+/// return `Result<T, EarlyExitReason>` or any error type convertible from it. With `store:` it is
+/// stored into a slot provided by the caller instead (a `&mut Option<EarlyExitReason>` or any
+/// collection implementing `Extend` for it), and with `hook` (requiring the `std` feature) it is
+/// passed to the installed hook; both can be followed by a default return value.
+/// ```
+/// use early_returns::reason::EarlyExitReason;
+/// use early_returns::some_or_return_reason;
+///
+/// fn width(columns: Option<u16>, last: &mut Option<EarlyExitReason>) -> u16 {
+///     some_or_return_reason!(columns, "terminal size unknown", store: last, 80)
+/// }
+///
+/// let mut last = None;
+/// assert_eq!(width(Some(120), &mut last), 120);
+/// assert_eq!(width(None, &mut last), 80);
+/// assert_eq!(last.unwrap().message, "terminal size unknown");
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! some_or_return_reason {
+    ($from:expr, $($reason:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => $crate::__escape_with_reason!(return; $($reason)+),
+        }
+    }};
+}
+
+/// Either get the value from an Option type or break out of a loop with an [`EarlyExitReason`]
+/// carrying the message, which is stored into a slot (`store: &mut slot`) or passed to the
+/// installed hook (`hook`, requiring the `std` feature) like with `some_or_return_reason!`. If a
+/// loop lifetime is specified (last), that loop will be exited, otherwise the immediate loop is
+/// exited.
+/// ```
+/// use early_returns::some_or_break_reason;
+///
+/// let mut read = Vec::new();
+/// let mut stopped = None;
+/// for value in [Some(1), Some(2), None, Some(4)] {
+///     read.push(some_or_break_reason!(value, "end of input", store: &mut stopped));
+/// }
+/// assert_eq!(read, [1, 2]);
+/// assert_eq!(stopped.unwrap().message, "end of input");
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! some_or_break_reason {
+    ($from:expr, $($reason:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => $crate::__escape_with_reason!(break; $($reason)+),
+        }
+    }};
+}
+
+/// Either get the value from an Option type or continue in a loop with an [`EarlyExitReason`]
+/// carrying the message, which is stored into a slot (`store: &mut slot`) or passed to the
+/// installed hook (`hook`, requiring the `std` feature) like with `some_or_return_reason!`. If a
+/// loop lifetime is specified (last), that loop will be "continued", otherwise the immediate loop
+/// is "continued".
+/// ```
+/// use early_returns::some_or_continue_reason;
+///
+/// let mut read = Vec::new();
+/// let mut skipped = Vec::new();
+/// for value in [Some(1), None, Some(3)] {
+///     read.push(some_or_continue_reason!(value, "blank row", store: &mut skipped));
+/// }
+/// assert_eq!(read, [1, 3]);
+/// assert_eq!(skipped.len(), 1);
+/// ```
+#[cfg(feature = "option")]
+#[macro_export]
+macro_rules! some_or_continue_reason {
+    ($from:expr, $($reason:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => $crate::__escape_with_reason!(continue; $($reason)+),
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or return from the current function with an
+/// [`EarlyExitReason`] carrying the message, which is returned, stored or passed to the hook like
+/// with `some_or_return_reason!`. The error itself is discarded.
+/// ```
+/// use early_returns::ok_or_return_reason;
+/// use early_returns::reason::EarlyExitReason;
+///
+/// fn port(value: &str) -> Result<u16, EarlyExitReason> {
+///     Ok(ok_or_return_reason!(value.parse(), "invalid port"))
+/// }
+///
+/// assert_eq!(port("80"), Ok(80));
+/// assert!(port("http").unwrap_err().to_string().starts_with("invalid port [early return at "));
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_return_reason {
+    ($from:expr, $($reason:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => $crate::__escape_with_reason!(return; $($reason)+),
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or break out of a loop with an [`EarlyExitReason`]
+/// carrying the message, which is stored or passed to the hook like with `some_or_break_reason!`.
+/// The error itself is discarded.
+/// ```
+/// use early_returns::ok_or_break_reason;
+///
+/// let mut total = 0;
+/// let mut stopped = None;
+/// for value in ["1", "2", "x", "4"] {
+///     total += ok_or_break_reason!(value.parse::<i32>(), "not a number", store: &mut stopped);
+/// }
+/// assert_eq!(total, 3);
+/// assert!(stopped.is_some());
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_break_reason {
+    ($from:expr, $($reason:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => $crate::__escape_with_reason!(break; $($reason)+),
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or continue in a loop with an [`EarlyExitReason`]
+/// carrying the message, which is stored or passed to the hook like with
+/// `some_or_continue_reason!`. The error itself is discarded.
+/// ```
+/// use early_returns::ok_or_continue_reason;
+///
+/// let mut total = 0;
+/// let mut skipped = Vec::new();
+/// for value in ["1", "x", "3"] {
+///     total += ok_or_continue_reason!(value.parse::<i32>(), "not a number", store: &mut skipped);
+/// }
+/// assert_eq!((total, skipped.len()), (4, 1));
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_continue_reason {
+    ($from:expr, $($reason:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => $crate::__escape_with_reason!(continue; $($reason)+),
+        }
+    }};
+}
+
+// The escape path of the `*_reason` macros, which builds the reason and returns, stores or hooks
+// it before escaping.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __escape_with_reason {
+    (return; $message:expr, store: $slot:expr $(, $default_result:expr)?) => {{
+        $crate::__store!($slot, $crate::__exit_reason!(Return, $message));
+        $crate::__mark_early_exit!();
+        return $($default_result)?;
+    }};
+
+    (return; $message:expr, hook $(, $default_result:expr)?) => {{
+        $crate::__exit_reason!(Return, $message).call_hook();
+        $crate::__mark_early_exit!();
+        return $($default_result)?;
+    }};
+
+    (return; $message:expr) => {{
+        $crate::__mark_early_exit!();
+        return Err(::core::convert::From::from($crate::__exit_reason!(Return, $message)));
+    }};
+
+    (break; $message:expr, store: $slot:expr $(, $lt:lifetime)?) => {{
+        $crate::__store!($slot, $crate::__exit_reason!(Break, $message));
+        break $($lt)?;
+    }};
+
+    (break; $message:expr, hook $(, $lt:lifetime)?) => {{
+        $crate::__exit_reason!(Break, $message).call_hook();
+        break $($lt)?;
+    }};
+
+    (continue; $message:expr, store: $slot:expr $(, $lt:lifetime)?) => {{
+        $crate::__store!($slot, $crate::__exit_reason!(Continue, $message));
+        continue $($lt)?;
+    }};
+
+    (continue; $message:expr, hook $(, $lt:lifetime)?) => {{
+        $crate::__exit_reason!(Continue, $message).call_hook();
+        continue $($lt)?;
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __exit_reason {
+    ($kind:ident, $message:expr) => {
+        $crate::reason::EarlyExitReason::new($crate::reason::EarlyReturnKind::$kind, $message)
+    };
+}
+
+#[cfg(all(test, any(feature = "option", feature = "result")))]
+mod test {
+    use super::{EarlyExitReason, EarlyReturnKind};
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    fn parse(value: &str, reasons: &mut Vec<EarlyExitReason>) -> i32 {
+        ok_or_return_reason!(value.parse(), "invalid", store: reasons, -1)
+    }
+
+    #[cfg(feature = "option")]
+    #[test]
+    fn should_return_reasons() {
+        let first = |values: &[i32]| -> Result<i32, EarlyExitReason> {
+            Ok(*some_or_return_reason!(values.first(), "empty"))
+        };
+        assert_eq!(first(&[1]), Ok(1));
+        let (reason, line) = (first(&[]).unwrap_err(), line!() - 3);
+        assert_eq!((reason.kind, reason.message), (EarlyReturnKind::Return, "empty"));
+        assert_eq!((reason.location.file(), reason.location.line()), ("src/reason.rs", line));
+        assert_eq!(
+            reason.to_string(),
+            format!("empty [early return at src/reason.rs:{line}:{}]", reason.location.column())
+        );
+    }
+
+    #[cfg(all(feature = "option", feature = "result"))]
+    #[test]
+    fn should_store_reasons() {
+        let mut reasons = Vec::new();
+        assert_eq!((parse("1", &mut reasons), parse("x", &mut reasons)), (1, -1));
+        assert_eq!(reasons.len(), 1);
+        let mut reasons = Vec::new();
+        let mut seen = Vec::new();
+        'l: for value in [Some(1), None, Some(3)] {
+            for _i in 0..2 {
+                seen.push(some_or_continue_reason!(value, "none", store: &mut reasons, 'l));
+            }
+        }
+        let mut last = None;
+        for value in [Ok(4), Err(())] {
+            seen.push(ok_or_break_reason!(value, "error", store: &mut last));
+        }
+        assert_eq!(seen, [1, 1, 3, 3, 4]);
+        let kinds: Vec<_> = reasons.iter().chain(&last).map(|r| (r.kind, r.message)).collect();
+        assert_eq!(kinds, [(EarlyReturnKind::Continue, "none"), (EarlyReturnKind::Break, "error")]);
+    }
+}
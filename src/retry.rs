@@ -0,0 +1,461 @@
+/// Evaluate a Result-producing expression up to a number of attempts and get the first Ok value,
+/// or return from the current function once every attempt has failed.
+///
+/// The expression is always evaluated at least once. By default the error of the last attempt is
+/// returned, converted with `From` like the `?` operator does; a default return value can be
+/// provided instead. An `inspect` hook can be given as a last argument, which is called with the
+/// (1-based) attempt number and a reference to the error of every failed attempt.
+/// ```
+/// use early_returns::retry;
+///
+/// fn connect(attempts_needed: &mut i32) -> Result<&'static str, String> {
+///     *attempts_needed -= 1;
+///     if *attempts_needed > 0 {
+///         Err(format!("{attempts_needed} more"))
+///     } else {
+///         Ok("connected")
+///     }
+/// }
+///
+/// fn connect_with_retries(mut attempts_needed: i32) -> Result<&'static str, String> {
+///     let connection = retry!(3, connect(&mut attempts_needed), inspect = |attempt, err| {
+///         eprintln!("attempt {attempt} failed: {err}");
+///     });
+///     Ok(connection)
+/// }
+///
+/// fn connect_or_default(mut attempts_needed: i32) -> &'static str {
+///     retry!(3, connect(&mut attempts_needed), "offline")
+/// }
+///
+/// assert_eq!(connect_with_retries(3), Ok("connected"));
+/// assert_eq!(connect_with_retries(4), Err("1 more".to_string()));
+/// assert_eq!(connect_or_default(4), "offline");
+/// ```
+#[macro_export]
+macro_rules! retry {
+    // `$between` runs with the number of the failed attempt bound to `$a`, before the next attempt.
+    (@retry $attempts:expr, $from:expr, $hook:expr, |$a:ident| $between:block, |$e:ident| $escape:block) => {{
+        let attempts: usize = $attempts;
+        let mut attempt: usize = 0;
+        loop {
+            attempt += 1;
+            match $from {
+                Ok(f) => break f,
+                Err($e) => {
+                    ($hook)(attempt, &$e);
+                    if attempt >= attempts {
+                        $escape
+                    }
+                    let $a = attempt;
+                    $between
+                }
+            }
+        }
+    }};
+
+    ($attempts:expr, $from:expr, inspect = $hook:expr) => {
+        $crate::retry!(@retry $attempts, $from, $hook, |_attempt| {}, |e| {
+            $crate::__mark_early_exit!();
+            return Err(::core::convert::From::from(e));
+        })
+    };
+
+    ($attempts:expr, $from:expr, $default_result:expr, inspect = $hook:expr) => {
+        $crate::retry!(@retry $attempts, $from, $hook, |_attempt| {}, |_e| {
+            $crate::__mark_early_exit!();
+            return $default_result;
+        })
+    };
+
+    ($attempts:expr, $from:expr) => {
+        $crate::retry!($attempts, $from, inspect = |_, _| {})
+    };
+
+    ($attempts:expr, $from:expr, $default_result:expr) => {
+        $crate::retry!($attempts, $from, $default_result, inspect = |_, _| {})
+    };
+}
+
+/// Like [`retry!`], but sleeps the current thread between attempts with an exponential backoff:
+/// the first retry waits for `base`, the second for twice `base`, and so on.
+///
+/// The `base` delay is evaluated once, before the first attempt. The delays are computed with
+/// saturating arithmetic, so large attempt counts can't overflow. Requires the `std` feature
+/// (enabled by default).
+/// ```
+/// use early_returns::retry_backoff;
+/// use std::time::Duration;
+///
+/// fn fetch(remaining_failures: &mut i32) -> Result<&'static str, String> {
+///     if *remaining_failures > 0 {
+///         *remaining_failures -= 1;
+///         Err("busy".to_string())
+///     } else {
+///         Ok("data")
+///     }
+/// }
+///
+/// fn fetch_with_backoff(mut remaining_failures: i32) -> Result<&'static str, String> {
+///     let data = retry_backoff!(
+///         attempts = 5,
+///         base = Duration::from_millis(1),
+///         fetch(&mut remaining_failures)
+///     );
+///     Ok(data)
+/// }
+///
+/// assert_eq!(fetch_with_backoff(2), Ok("data"));
+/// assert_eq!(fetch_with_backoff(5), Err("busy".to_string()));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! retry_backoff {
+    (attempts = $attempts:expr, base = $base:expr, $from:expr, inspect = $hook:expr) => {{
+        let base: $crate::__private::Duration = $base;
+        $crate::retry!(@retry $attempts, $from, $hook, |attempt| {
+            $crate::__private::thread::sleep($crate::__private::backoff_delay(base, attempt));
+        }, |e| {
+            $crate::__mark_early_exit!();
+            return Err(::core::convert::From::from(e));
+        })
+    }};
+
+    (attempts = $attempts:expr, base = $base:expr, $from:expr, $default_result:expr, inspect = $hook:expr) => {{
+        let base: $crate::__private::Duration = $base;
+        $crate::retry!(@retry $attempts, $from, $hook, |attempt| {
+            $crate::__private::thread::sleep($crate::__private::backoff_delay(base, attempt));
+        }, |_e| {
+            $crate::__mark_early_exit!();
+            return $default_result;
+        })
+    }};
+
+    (attempts = $attempts:expr, base = $base:expr, $from:expr) => {
+        $crate::retry_backoff!(attempts = $attempts, base = $base, $from, inspect = |_, _| {})
+    };
+
+    (attempts = $attempts:expr, base = $base:expr, $from:expr, $default_result:expr) => {
+        $crate::retry_backoff!(
+            attempts = $attempts,
+            base = $base,
+            $from,
+            $default_result,
+            inspect = |_, _| {}
+        )
+    };
+}
+
+/// Like [`retry_backoff!`], but for async code: the expression (a future resolving to a Result) is
+/// awaited for every attempt and the backoff between attempts uses `tokio::time::sleep`, so the
+/// executor isn't blocked. On exhaustion the enclosing async function is returned from.
+///
+/// Requires the `tokio` feature, and must be used within a tokio runtime with the time driver
+/// enabled.
+/// ```
+/// use early_returns::retry_async;
+/// use std::time::Duration;
+///
+/// async fn fetch(remaining_failures: &mut i32) -> Result<&'static str, String> {
+///     if *remaining_failures > 0 {
+///         *remaining_failures -= 1;
+///         Err("busy".to_string())
+///     } else {
+///         Ok("data")
+///     }
+/// }
+///
+/// async fn fetch_with_retries(mut remaining_failures: i32) -> Result<&'static str, String> {
+///     let data = retry_async!(
+///         attempts = 3,
+///         base = Duration::from_millis(1),
+///         fetch(&mut remaining_failures)
+///     );
+///     Ok(data)
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+/// assert_eq!(fetch_with_retries(2).await, Ok("data"));
+/// assert_eq!(fetch_with_retries(3).await, Err("busy".to_string()));
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+#[macro_export]
+macro_rules! retry_async {
+    (attempts = $attempts:expr, base = $base:expr, $from:expr, inspect = $hook:expr) => {{
+        let base: $crate::__private::Duration = $base;
+        $crate::retry!(@retry $attempts, $from.await, $hook, |attempt| {
+            $crate::__private::tokio::time::sleep($crate::__private::backoff_delay(base, attempt)).await;
+        }, |e| {
+            $crate::__mark_early_exit!();
+            return Err(::core::convert::From::from(e));
+        })
+    }};
+
+    (attempts = $attempts:expr, base = $base:expr, $from:expr, $default_result:expr, inspect = $hook:expr) => {{
+        let base: $crate::__private::Duration = $base;
+        $crate::retry!(@retry $attempts, $from.await, $hook, |attempt| {
+            $crate::__private::tokio::time::sleep($crate::__private::backoff_delay(base, attempt)).await;
+        }, |_e| {
+            $crate::__mark_early_exit!();
+            return $default_result;
+        })
+    }};
+
+    (attempts = $attempts:expr, base = $base:expr, $from:expr) => {
+        $crate::retry_async!(attempts = $attempts, base = $base, $from, inspect = |_, _| {})
+    };
+
+    (attempts = $attempts:expr, base = $base:expr, $from:expr, $default_result:expr) => {
+        $crate::retry_async!(
+            attempts = $attempts,
+            base = $base,
+            $from,
+            $default_result,
+            inspect = |_, _| {}
+        )
+    };
+}
+
+/// The delay before the retry that follows the given (1-based) failed attempt: `base` doubled for
+/// every attempt after the first.
+#[doc(hidden)]
+pub fn backoff_delay(base: core::time::Duration, attempt: usize) -> core::time::Duration {
+    let factor =
+        u32::try_from(attempt - 1).map_or(u32::MAX, |exponent| 2u32.saturating_pow(exponent));
+    base.saturating_mul(factor)
+}
+
+#[cfg(test)]
+mod test {
+    use super::backoff_delay;
+    use std::time::Duration;
+    #[cfg(feature = "std")]
+    use std::time::Instant;
+
+    struct Flaky {
+        failures: i32,
+        attempts: i32,
+    }
+
+    impl Flaky {
+        fn new(failures: i32) -> Flaky {
+            Flaky {
+                failures,
+                attempts: 0,
+            }
+        }
+
+        fn call(&mut self) -> Result<i32, String> {
+            self.attempts += 1;
+            if self.attempts <= self.failures {
+                Err(format!("failure {}", self.attempts))
+            } else {
+                Ok(self.attempts * 10)
+            }
+        }
+
+        fn propagate(&mut self, attempts: usize) -> Result<i32, String> {
+            let value = retry!(attempts, self.call());
+            Ok(value)
+        }
+
+        fn with_default(&mut self, attempts: usize) -> i32 {
+            retry!(attempts, self.call(), -1)
+        }
+
+        fn inspected(
+            &mut self,
+            attempts: usize,
+            seen: &mut Vec<(usize, String)>,
+        ) -> Result<i32, String> {
+            let value = retry!(
+                attempts,
+                self.call(),
+                inspect = |attempt, err: &String| {
+                    seen.push((attempt, err.clone()));
+                }
+            );
+            Ok(value)
+        }
+
+        fn inspected_with_default(&mut self, attempts: usize, seen: &mut Vec<usize>) -> i32 {
+            retry!(
+                attempts,
+                self.call(),
+                -1,
+                inspect = |attempt, _err| seen.push(attempt)
+            )
+        }
+    }
+
+    #[test]
+    fn should_get_first_ok_value() {
+        let mut flaky = Flaky::new(2);
+        assert_eq!(flaky.propagate(3), Ok(30));
+        assert_eq!(flaky.attempts, 3);
+    }
+
+    #[test]
+    fn should_not_retry_when_first_attempt_succeeds() {
+        let mut flaky = Flaky::new(0);
+        assert_eq!(flaky.propagate(3), Ok(10));
+        assert_eq!(flaky.attempts, 1);
+    }
+
+    #[test]
+    fn should_return_last_error_when_attempts_exhausted() {
+        let mut flaky = Flaky::new(5);
+        assert_eq!(flaky.propagate(3), Err("failure 3".to_string()));
+        assert_eq!(flaky.attempts, 3);
+    }
+
+    #[test]
+    fn should_attempt_at_least_once() {
+        let mut flaky = Flaky::new(5);
+        assert_eq!(flaky.propagate(0), Err("failure 1".to_string()));
+        assert_eq!(flaky.attempts, 1);
+    }
+
+    #[test]
+    fn should_return_default_when_attempts_exhausted() {
+        let mut flaky = Flaky::new(5);
+        assert_eq!(flaky.with_default(2), -1);
+        assert_eq!(flaky.attempts, 2);
+        assert_eq!(flaky.with_default(4), 60);
+    }
+
+    #[test]
+    fn should_inspect_every_failed_attempt() {
+        let mut seen = Vec::new();
+        let mut flaky = Flaky::new(2);
+        assert_eq!(flaky.inspected(3, &mut seen), Ok(30));
+        assert_eq!(
+            seen,
+            vec![(1, "failure 1".to_string()), (2, "failure 2".to_string())]
+        );
+    }
+
+    #[test]
+    fn should_inspect_failed_attempts_before_returning_default() {
+        let mut seen = Vec::new();
+        let mut flaky = Flaky::new(5);
+        assert_eq!(flaky.inspected_with_default(3, &mut seen), -1);
+        assert_eq!(seen, vec![1, 2, 3]);
+    }
+
+    #[cfg(feature = "std")]
+    fn with_backoff(flaky: &mut Flaky, attempts: usize) -> Result<i32, String> {
+        let value = retry_backoff!(
+            attempts = attempts,
+            base = Duration::from_millis(2),
+            flaky.call()
+        );
+        Ok(value)
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_back_off_exponentially_between_attempts() {
+        let mut flaky = Flaky::new(3);
+        let start = Instant::now();
+        assert_eq!(with_backoff(&mut flaky, 4), Ok(40));
+        assert!(start.elapsed() >= Duration::from_millis(2 + 4 + 8));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_not_sleep_after_last_attempt() {
+        let mut flaky = Flaky::new(5);
+        let start = Instant::now();
+        assert_eq!(with_backoff(&mut flaky, 1), Err("failure 1".to_string()));
+        assert!(start.elapsed() < Duration::from_millis(2));
+    }
+
+    #[cfg(feature = "std")]
+    fn with_backoff_or_default(flaky: &mut Flaky, seen: &mut Vec<usize>) -> i32 {
+        retry_backoff!(
+            attempts = 2,
+            base = Duration::from_millis(1),
+            flaky.call(),
+            -1,
+            inspect = |attempt, _err| seen.push(attempt)
+        )
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_return_default_and_inspect_with_backoff() {
+        let mut seen = Vec::new();
+        let mut flaky = Flaky::new(5);
+        assert_eq!(with_backoff_or_default(&mut flaky, &mut seen), -1);
+        assert_eq!(seen, vec![1, 2]);
+    }
+
+    #[test]
+    fn should_double_backoff_delay_for_every_attempt() {
+        let base = Duration::from_millis(10);
+        assert_eq!(backoff_delay(base, 1), base);
+        assert_eq!(backoff_delay(base, 2), base * 2);
+        assert_eq!(backoff_delay(base, 4), base * 8);
+        assert_eq!(backoff_delay(base, 100), base * u32::MAX);
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn call_async(flaky: &mut Flaky) -> Result<i32, String> {
+        flaky.call()
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn with_async_retries(flaky: &mut Flaky, attempts: usize) -> Result<i32, String> {
+        let value = retry_async!(
+            attempts = attempts,
+            base = Duration::from_millis(2),
+            call_async(flaky)
+        );
+        Ok(value)
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn with_async_retries_or_default(flaky: &mut Flaky, seen: &mut Vec<usize>) -> i32 {
+        retry_async!(
+            attempts = 2,
+            base = Duration::from_millis(1),
+            call_async(flaky),
+            -1,
+            inspect = |attempt, _err| seen.push(attempt)
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn should_retry_async_with_backoff() {
+        let mut flaky = Flaky::new(2);
+        let start = std::time::Instant::now();
+        assert_eq!(with_async_retries(&mut flaky, 3).await, Ok(30));
+        assert!(start.elapsed() >= Duration::from_millis(2 + 4));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn should_return_last_error_when_async_attempts_exhausted() {
+        let mut flaky = Flaky::new(5);
+        assert_eq!(
+            with_async_retries(&mut flaky, 2).await,
+            Err("failure 2".to_string())
+        );
+        assert_eq!(flaky.attempts, 2);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn should_return_default_and_inspect_async_attempts() {
+        let mut seen = Vec::new();
+        let mut flaky = Flaky::new(5);
+        assert_eq!(
+            with_async_retries_or_default(&mut flaky, &mut seen).await,
+            -1
+        );
+        assert_eq!(seen, vec![1, 2]);
+    }
+}
@@ -0,0 +1,136 @@
+/// Either await a permit of a `tokio::sync::Semaphore`, or return from the current async function
+/// if the semaphore has been closed. A default return value can be provided.
+///
+/// Requires the `tokio` feature.
+/// ```
+/// use early_returns::acquire_or_return;
+/// use tokio::sync::Semaphore;
+///
+/// async fn upload(uploads: &Semaphore, name: &str) -> Option<String> {
+///     let _permit = acquire_or_return!(uploads, None);
+///     Some(format!("uploaded {name}"))
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let uploads = Semaphore::new(2);
+/// assert_eq!(upload(&uploads, "a").await, Some("uploaded a".to_string()));
+/// uploads.close();
+/// assert_eq!(upload(&uploads, "b").await, None);
+/// # });
+/// ```
+#[macro_export]
+macro_rules! acquire_or_return {
+    ($semaphore:expr) => {
+        $crate::ok_or_return!($semaphore.acquire().await)
+    };
+
+    ($semaphore:expr, $default_result:expr) => {
+        $crate::ok_or_return!($semaphore.acquire().await, $default_result)
+    };
+}
+
+/// Either take a permit of a `tokio::sync::Semaphore` without waiting, or continue in a loop when
+/// no permit is available, or break out of the loop once the semaphore has been closed. If a loop
+/// lifetime is specified, that loop will be "continued" or exited, otherwise the immediate loop is.
+///
+/// Requires the `tokio` feature.
+/// ```
+/// use early_returns::try_acquire_or_continue;
+/// use tokio::sync::Semaphore;
+///
+/// let in_flight = Semaphore::new(2);
+/// let mut permits = Vec::new();
+/// let mut served = Vec::new();
+/// for request in 0..4 {
+///     permits.push(try_acquire_or_continue!(in_flight));
+///     served.push(request);
+/// }
+/// assert_eq!(served, [0, 1]);
+/// ```
+#[macro_export]
+macro_rules! try_acquire_or_continue {
+    ($semaphore:expr) => {{
+        match $semaphore.try_acquire() {
+            Ok(f) => f,
+            Err($crate::__private::tokio::sync::TryAcquireError::Closed) => {
+                $crate::__private::silent_break();
+                break
+            }
+            Err($crate::__private::tokio::sync::TryAcquireError::NoPermits) => {
+                $crate::__private::silent_continue();
+                continue
+            }
+        }
+    }};
+
+    ($semaphore:expr, $lt:lifetime) => {{
+        match $semaphore.try_acquire() {
+            Ok(f) => f,
+            Err($crate::__private::tokio::sync::TryAcquireError::Closed) => {
+                $crate::__private::silent_break();
+                break $lt
+            }
+            Err($crate::__private::tokio::sync::TryAcquireError::NoPermits) => {
+                $crate::__private::silent_continue();
+                continue $lt
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use tokio::sync::Semaphore;
+
+    async fn record(semaphore: &Semaphore, available: &mut Vec<usize>) {
+        let _permit = acquire_or_return!(semaphore);
+        available.push(semaphore.available_permits());
+    }
+
+    async fn acquired(semaphore: Arc<Semaphore>) -> bool {
+        let _permit = acquire_or_return!(semaphore, false);
+        true
+    }
+
+    #[tokio::test]
+    async fn should_return_when_closed() {
+        let semaphore = Semaphore::new(2);
+        let mut available = Vec::new();
+        record(&semaphore, &mut available).await;
+        semaphore.close();
+        record(&semaphore, &mut available).await;
+        assert_eq!(available, [1]);
+        let semaphore = Arc::new(Semaphore::new(1));
+        assert!(acquired(semaphore.clone()).await);
+        semaphore.close();
+        assert!(!acquired(semaphore).await);
+    }
+
+    #[test]
+    fn should_continue_without_permits_and_break_when_closed() {
+        let semaphore = Semaphore::new(1);
+        let mut attempts = 0;
+        let mut permits = Vec::new();
+        for i in 0..5 {
+            if i == 3 {
+                semaphore.close();
+            }
+            attempts += 1;
+            permits.push(try_acquire_or_continue!(semaphore));
+        }
+        assert_eq!((permits.len(), attempts), (1, 4));
+        let semaphore = Semaphore::new(3);
+        let mut permits = Vec::new();
+        'l: for i in 0..4 {
+            if i == 3 {
+                semaphore.close();
+            }
+            for _j in 0..2 {
+                permits.push(try_acquire_or_continue!(semaphore, 'l));
+            }
+        }
+        assert_eq!(permits.len(), 3);
+    }
+}
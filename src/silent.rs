@@ -0,0 +1,101 @@
+//! Shims called on the escape path of every silent (non-instrumented) macro.
+//!
+//! When the compiler provides `core::hint::cold_path`, they also mark the escape path as the
+//! unlikely branch, so the optimizer lays out the happy path of hot loops first.
+//!
+//! With the `lint-silent-exits` feature enabled these are deprecated, so the compiler warns at
+//! every call site of a macro whose early exit would otherwise go unobserved.
+//!
+#![cfg_attr(all(feature = "option", feature = "lint-silent-exits"), doc = "```compile_fail")]
+#![cfg_attr(all(feature = "option", not(feature = "lint-silent-exits")), doc = "```")]
+#![cfg_attr(not(feature = "option"), doc = "```ignore")]
+//! #![deny(deprecated)]
+//! use early_returns::some_or_return;
+//!
+//! fn print(i: Option<i32>) {
+//!     let i = some_or_return!(i);
+//!     println!("{i}");
+//! }
+//! ```
+//!
+//! With the `strict` feature enabled, `silent_return` panics instead, pointing at the invocation
+//! of the macro whose silent early return was taken. Enabling it in CI test runs catches guards
+//! that mask real bugs. Silent breaks and continues are unaffected, as they usually drive the
+//! normal termination of a loop.
+//!
+#![cfg_attr(all(feature = "option", feature = "strict"), doc = "```should_panic")]
+#![cfg_attr(all(feature = "option", not(feature = "strict")), doc = "```")]
+#![cfg_attr(not(feature = "option"), doc = "```ignore")]
+//! # #![allow(deprecated)]
+//! use early_returns::some_or_return;
+//!
+//! fn print(i: Option<i32>) {
+//!     let i = some_or_return!(i);
+//!     println!("{i}");
+//! }
+//!
+//! print(None);
+//! ```
+
+#[cfg(not(feature = "lint-silent-exits"))]
+#[cfg_attr(feature = "strict", track_caller)]
+#[inline(always)]
+pub const fn silent_return() {
+    #[cfg(has_cold_path)]
+    #[allow(clippy::incompatible_msrv)] // only compiled where the build script found it
+    ::core::hint::cold_path();
+    #[cfg(feature = "strict")]
+    panic!("silent early return taken with the `strict` feature enabled");
+}
+
+#[cfg(not(feature = "lint-silent-exits"))]
+#[inline(always)]
+pub const fn silent_break() {
+    #[cfg(has_cold_path)]
+    #[allow(clippy::incompatible_msrv)] // only compiled where the build script found it
+    ::core::hint::cold_path();
+}
+
+#[cfg(not(feature = "lint-silent-exits"))]
+#[inline(always)]
+pub const fn silent_continue() {
+    #[cfg(has_cold_path)]
+    #[allow(clippy::incompatible_msrv)] // only compiled where the build script found it
+    ::core::hint::cold_path();
+}
+
+#[cfg(feature = "lint-silent-exits")]
+#[deprecated(
+    note = "this early return is silent; use an observable variant or `#[allow(deprecated)]` it"
+)]
+#[cfg_attr(feature = "strict", track_caller)]
+#[inline(always)]
+pub const fn silent_return() {
+    #[cfg(has_cold_path)]
+    #[allow(clippy::incompatible_msrv)] // only compiled where the build script found it
+    ::core::hint::cold_path();
+    #[cfg(feature = "strict")]
+    panic!("silent early return taken with the `strict` feature enabled");
+}
+
+#[cfg(feature = "lint-silent-exits")]
+#[deprecated(
+    note = "this early break is silent; use an observable variant or `#[allow(deprecated)]` it"
+)]
+#[inline(always)]
+pub const fn silent_break() {
+    #[cfg(has_cold_path)]
+    #[allow(clippy::incompatible_msrv)] // only compiled where the build script found it
+    ::core::hint::cold_path();
+}
+
+#[cfg(feature = "lint-silent-exits")]
+#[deprecated(
+    note = "this early continue is silent; use an observable variant or `#[allow(deprecated)]` it"
+)]
+#[inline(always)]
+pub const fn silent_continue() {
+    #[cfg(has_cold_path)]
+    #[allow(clippy::incompatible_msrv)] // only compiled where the build script found it
+    ::core::hint::cold_path();
+}
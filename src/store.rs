@@ -0,0 +1,250 @@
+/// Either get the Ok value from a Result type or store the error into a slot provided by the
+/// caller and return from the current function, so the caller can inspect why it bailed without
+/// changing its return type. The slot can be a `&mut Option<E>`, which keeps the last error, or
+/// any collection implementing `Extend` for the error type. A default return value can be
+/// provided, and the error can be bound and mapped before it is stored
+/// (`ok_or_return_store!(res, &mut last_err, e => e.to_string(), None)`).
+/// ```
+/// use early_returns::ok_or_return_store;
+/// use std::num::ParseIntError;
+///
+/// fn port(value: &str, last_err: &mut Option<ParseIntError>) -> Option<u16> {
+///     let port = ok_or_return_store!(value.parse(), last_err, None);
+///     Some(port)
+/// }
+///
+/// let mut last_err = None;
+/// assert_eq!(port("80", &mut last_err), Some(80));
+/// assert_eq!(port("http", &mut last_err), None);
+/// assert_eq!(last_err.unwrap().to_string(), "invalid digit found in string");
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_return_store {
+    ($from:expr, $slot:expr, $e:ident => $mapped:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__store!($slot, $mapped);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $slot:expr, $e:ident => $mapped:expr, $default_result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__store!($slot, $mapped);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+
+    ($from:expr, $slot:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__store!($slot, e);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $slot:expr, $default_result:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__store!($slot, e);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or store the error into a slot provided by the
+/// caller and break out of a loop. The slot and the mapping of the error work like those of
+/// `ok_or_return_store!`. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+/// ```
+/// use early_returns::ok_or_break_store;
+///
+/// let mut total = 0;
+/// let mut error = None;
+/// for value in ["1", "2", "x", "4"] {
+///     let parsed = value.parse::<i32>();
+///     total += ok_or_break_store!(parsed, &mut error, e => format!("{value:?}: {e}"));
+/// }
+/// assert_eq!(total, 3);
+/// assert_eq!(error.as_deref(), Some("\"x\": invalid digit found in string"));
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_break_store {
+    ($from:expr, $slot:expr, $e:ident => $mapped:expr $(, $lt:lifetime)?) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__store!($slot, $mapped);
+                break $($lt)?
+            }
+        }
+    }};
+
+    ($from:expr, $slot:expr $(, $lt:lifetime)?) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__store!($slot, e);
+                break $($lt)?
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or store the error into a slot provided by the
+/// caller and continue in a loop. The slot and the mapping of the error work like those of
+/// `ok_or_return_store!`, so with a `&mut Option<E>` only the last error is kept. If a loop
+/// lifetime is specified, that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::ok_or_continue_store;
+/// use std::num::ParseIntError;
+///
+/// let mut ports = Vec::new();
+/// let mut last_err: Option<ParseIntError> = None;
+/// for value in ["80", "http", "443", "ftp"] {
+///     ports.push(ok_or_continue_store!(value.parse::<u16>(), &mut last_err));
+/// }
+/// assert_eq!(ports, [80, 443]);
+/// assert!(last_err.is_some());
+/// ```
+#[cfg(feature = "result")]
+#[macro_export]
+macro_rules! ok_or_continue_store {
+    ($from:expr, $slot:expr, $e:ident => $mapped:expr $(, $lt:lifetime)?) => {{
+        match $from {
+            Ok(f) => f,
+            Err($e) => {
+                $crate::__store!($slot, $mapped);
+                continue $($lt)?
+            }
+        }
+    }};
+
+    ($from:expr, $slot:expr $(, $lt:lifetime)?) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__store!($slot, e);
+                continue $($lt)?
+            }
+        }
+    }};
+}
+
+// Stores an error into a `&mut Option<E>` or a `&mut impl Extend<E>`. The two cannot be covered
+// by blanket impls of one trait, so the Option impl is preferred by method resolution on the
+// receiver as is, and the `Extend` impl is only found after an autoref.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store {
+    ($slot:expr, $error:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::__private::{StoreExtend as _, StoreOption as _};
+        (&mut $crate::__private::Slot($slot)).store($error);
+    }};
+}
+
+#[doc(hidden)]
+pub struct Slot<'a, S: ?Sized>(pub &'a mut S);
+
+#[doc(hidden)]
+pub trait StoreOption<E> {
+    fn store(self, error: E);
+}
+
+impl<E> StoreOption<E> for &mut Slot<'_, Option<E>> {
+    #[inline]
+    fn store(self, error: E) {
+        *self.0 = Some(error);
+    }
+}
+
+#[doc(hidden)]
+pub trait StoreExtend<E> {
+    fn store(self, error: E);
+}
+
+impl<E, C: Extend<E> + ?Sized> StoreExtend<E> for &mut &mut Slot<'_, C> {
+    #[inline]
+    fn store(self, error: E) {
+        self.0.extend(core::iter::once(error));
+    }
+}
+
+#[cfg(all(test, feature = "result"))]
+mod test {
+    use std::collections::BTreeSet;
+
+    fn double(value: Result<i32, &'static str>, last_err: &mut Option<&'static str>) -> i32 {
+        ok_or_return_store!(value, last_err, -1) * 2
+    }
+
+    fn record(value: Result<i32, &str>, errors: &mut Vec<String>, values: &mut Vec<i32>) {
+        values.push(ok_or_return_store!(value, errors, e => e.to_uppercase()));
+    }
+
+    #[test]
+    fn should_store_errors_before_returning() {
+        let mut last_err = None;
+        assert_eq!(double(Ok(2), &mut last_err), 4);
+        assert_eq!(last_err, None);
+        assert_eq!(double(Err("a"), &mut last_err), -1);
+        assert_eq!(double(Err("b"), &mut last_err), -1);
+        assert_eq!(last_err, Some("b"));
+        let (mut errors, mut values) = (Vec::new(), Vec::new());
+        record(Ok(1), &mut errors, &mut values);
+        record(Err("bad"), &mut errors, &mut values);
+        assert_eq!((errors, values), (vec!["BAD".to_string()], vec![1]));
+    }
+
+    #[test]
+    fn should_store_errors_before_breaking() {
+        let mut errors = BTreeSet::new();
+        let mut seen = Vec::new();
+        'l: for value in [Ok(1), Err("a"), Ok(2)] {
+            for _i in 0..2 {
+                seen.push(ok_or_break_store!(value, &mut errors, 'l));
+            }
+        }
+        assert_eq!((seen, errors.into_iter().collect::<Vec<_>>()), (vec![1, 1], vec!["a"]));
+        let mut last_err = None;
+        for value in [Ok(1), Err("b")] {
+            ok_or_break_store!(value, &mut last_err, e => e.len());
+        }
+        assert_eq!(last_err, Some(1));
+    }
+
+    #[test]
+    fn should_store_errors_before_continuing() {
+        let mut last_err = None;
+        let mut seen = Vec::new();
+        for value in [Err("a"), Ok(1), Err("b"), Ok(2)] {
+            seen.push(ok_or_continue_store!(value, &mut last_err));
+        }
+        assert_eq!((seen, last_err), (vec![1, 2], Some("b")));
+        let mut errors = Vec::new();
+        let mut seen = Vec::new();
+        'l: for value in [Ok(1), Err("a"), Ok(2)] {
+            for _i in 0..2 {
+                seen.push(ok_or_continue_store!(value, &mut errors, e => e.len(), 'l));
+            }
+        }
+        assert_eq!((seen, errors), (vec![1, 1, 2, 2], vec![1]));
+    }
+}
@@ -0,0 +1,196 @@
+/// Either get the next Ok item of a fallible `Stream` (through `futures::TryStreamExt::try_next`)
+/// or return from the current async function, once the stream ends or when it yields an error.
+///
+/// The error is returned as `Err(e.into())`, like `?` does, or it can be bound and used to produce
+/// the return value. When the stream ends, the given value is returned, or `Ok(Default::default())`
+/// without one (`Ok(())` in a function returning `Result<(), E>`).
+/// ```
+/// use early_returns::try_next_or_return;
+/// use futures_util::stream::{self, TryStream};
+///
+/// async fn first_two<S>(mut rows: S) -> Result<Option<(u8, u8)>, String>
+/// where
+///     S: TryStream<Ok = u8, Error = String> + Unpin,
+/// {
+///     let a = try_next_or_return!(rows, Ok(None));
+///     let b = try_next_or_return!(rows, Ok(None));
+///     Ok(Some((a, b)))
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let rows = stream::iter([Ok(1), Ok(2), Ok(3)]);
+/// assert_eq!(first_two(rows).await, Ok(Some((1, 2))));
+/// let rows = stream::iter([Ok(1)]);
+/// assert_eq!(first_two(rows).await, Ok(None));
+/// let rows = stream::iter([Ok(1), Err("reset".to_string())]);
+/// assert_eq!(first_two(rows).await, Err("reset".to_string()));
+/// # });
+/// ```
+#[macro_export]
+macro_rules! try_next_or_return {
+    ($stream:expr, $end_result:expr, $e:ident => $result:expr) => {{
+        match $crate::__private::futures::TryStreamExt::try_next(&mut $stream).await {
+            Ok(Some(f)) => f,
+            Ok(None) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $end_result;
+            }
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($stream:expr) => {
+        $crate::try_next_or_return!(
+            $stream,
+            Ok(::core::default::Default::default()),
+            e => Err(::core::convert::From::from(e))
+        )
+    };
+
+    ($stream:expr, $end_result:expr) => {
+        $crate::try_next_or_return!($stream, $end_result, e => Err(::core::convert::From::from(e)))
+    };
+}
+
+/// Either get the next Ok item of a fallible `Stream` (through `futures::TryStreamExt::try_next`),
+/// or break out of a loop once the stream ends, or return from the current async function when it
+/// yields an error. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+///
+/// The error is returned as `Err(e.into())`, like `?` does, or it can be bound and used to produce
+/// the return value.
+/// ```
+/// use early_returns::try_next_or_break;
+/// use futures_util::stream::{self, TryStream};
+///
+/// async fn total<S>(mut rows: S) -> Result<u32, String>
+/// where
+///     S: TryStream<Ok = u32, Error = String> + Unpin,
+/// {
+///     let mut total = 0;
+///     loop {
+///         total += try_next_or_break!(rows);
+///     }
+///     Ok(total)
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// assert_eq!(total(stream::iter([Ok(1), Ok(2)])).await, Ok(3));
+/// let rows = stream::iter([Ok(1), Err("reset".to_string()), Ok(2)]);
+/// assert_eq!(total(rows).await, Err("reset".to_string()));
+/// # });
+/// ```
+#[macro_export]
+macro_rules! try_next_or_break {
+    ($stream:expr, $e:ident => $result:expr) => {{
+        match $crate::__private::futures::TryStreamExt::try_next(&mut $stream).await {
+            Ok(Some(f)) => f,
+            Ok(None) => {
+                $crate::__private::silent_break();
+                break
+            }
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($stream:expr, $lt:lifetime, $e:ident => $result:expr) => {{
+        match $crate::__private::futures::TryStreamExt::try_next(&mut $stream).await {
+            Ok(Some(f)) => f,
+            Ok(None) => {
+                $crate::__private::silent_break();
+                break $lt
+            }
+            Err($e) => {
+                $crate::__mark_early_exit!();
+                return $result;
+            }
+        }
+    }};
+
+    ($stream:expr) => {
+        $crate::try_next_or_break!($stream, e => Err(::core::convert::From::from(e)))
+    };
+
+    ($stream:expr, $lt:lifetime) => {
+        $crate::try_next_or_break!($stream, $lt, e => Err(::core::convert::From::from(e)))
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::stream::{self, Stream, TryStream};
+
+    async fn first_or_zero(mut numbers: impl Stream<Item = i32> + Unpin) -> i32 {
+        crate::next_or_return!(async numbers, 0)
+    }
+
+    async fn count_pairs(mut numbers: impl Stream<Item = i32> + Unpin) -> usize {
+        let mut pairs = 0;
+        'l: loop {
+            for _i in 0..2 {
+                crate::next_or_break!(async numbers, 'l);
+            }
+            pairs += 1;
+        }
+        pairs
+    }
+
+    async fn collect<S>(mut rows: S, into: &mut Vec<i32>) -> Result<(), String>
+    where
+        S: TryStream<Ok = i32, Error = &'static str> + Unpin,
+    {
+        loop {
+            into.push(try_next_or_return!(rows));
+        }
+    }
+
+    async fn first<S>(mut rows: S) -> Result<i32, usize>
+    where
+        S: TryStream<Ok = i32, Error = &'static str> + Unpin,
+    {
+        Ok(try_next_or_return!(rows, Ok(-1), e => Err(e.len())))
+    }
+
+    async fn sum_labeled<S>(mut rows: S) -> Result<i32, usize>
+    where
+        S: TryStream<Ok = i32, Error = &'static str> + Unpin,
+    {
+        let mut sum = 0;
+        'l: loop {
+            for _i in 0..2 {
+                sum += try_next_or_break!(rows, 'l, e => Err(e.len()));
+            }
+            sum *= 10;
+        }
+        Ok(sum)
+    }
+
+    #[tokio::test]
+    async fn should_await_next_or_escape() {
+        assert_eq!(first_or_zero(stream::iter([1, 2])).await, 1);
+        assert_eq!(first_or_zero(stream::empty()).await, 0);
+        assert_eq!(count_pairs(stream::iter([1, 2, 3, 4, 5])).await, 2);
+    }
+
+    #[tokio::test]
+    async fn should_return_errors_and_end_of_stream() {
+        let mut rows = Vec::new();
+        assert_eq!(collect(stream::iter([Ok(1), Ok(2)]), &mut rows).await, Ok(()));
+        let failing = stream::iter([Ok(3), Err("reset"), Ok(4)]);
+        assert_eq!(collect(failing, &mut rows).await, Err("reset".to_string()));
+        assert_eq!(rows, [1, 2, 3]);
+        assert_eq!(first(stream::iter([Ok(1)])).await, Ok(1));
+        assert_eq!(first(stream::iter([])).await, Ok(-1));
+        assert_eq!(first(stream::iter([Err("reset")])).await, Err(5));
+        assert_eq!(sum_labeled(stream::iter([Ok(1), Ok(2), Ok(3)])).await, Ok(33));
+        let rows = stream::iter([Ok(1), Err("reset"), Ok(2)]);
+        assert_eq!(sum_labeled(rows).await, Err(5));
+    }
+}
@@ -0,0 +1,138 @@
+/// Await a Result-producing future with a time limit and either get the Ok value or return from
+/// the current async function, both when the time limit elapses and when the future resolves to
+/// an error. A default return value can be provided, either for both cases or separately for an
+/// elapsed time limit and an error.
+///
+/// Requires the `tokio` feature, and must be used within a tokio runtime with the time driver
+/// enabled.
+/// ```
+/// use early_returns::timeout_or_return;
+/// use std::time::Duration;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum StatusReport {
+///     Up(u16),
+///     TimedOut,
+///     Failed,
+/// }
+///
+/// async fn get_status(delay: Duration, status: Result<u16, ()>) -> Result<u16, ()> {
+///     tokio::time::sleep(delay).await;
+///     status
+/// }
+///
+/// async fn report(delay: Duration, status: Result<u16, ()>) -> StatusReport {
+///     let status = timeout_or_return!(
+///         Duration::from_millis(50),
+///         get_status(delay, status),
+///         elapsed => StatusReport::TimedOut,
+///         error => StatusReport::Failed
+///     );
+///     StatusReport::Up(status)
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+/// assert_eq!(report(Duration::ZERO, Ok(200)).await, StatusReport::Up(200));
+/// assert_eq!(report(Duration::ZERO, Err(())).await, StatusReport::Failed);
+/// assert_eq!(report(Duration::from_secs(5), Ok(200)).await, StatusReport::TimedOut);
+/// # });
+/// ```
+#[macro_export]
+macro_rules! timeout_or_return {
+    ($duration:expr, $from:expr) => {
+        $crate::ok_or_return!($crate::ok_or_return!(
+            $crate::__private::tokio::time::timeout($duration, $from).await
+        ))
+    };
+
+    ($duration:expr, $from:expr, elapsed => $elapsed_result:expr, error => $error_result:expr) => {{
+        match $crate::__private::tokio::time::timeout($duration, $from).await {
+            Ok(Ok(f)) => f,
+            Ok(Err(_)) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $error_result;
+            }
+            Err(_) => {
+                $crate::__private::silent_return();
+                $crate::__mark_early_exit!();
+                return $elapsed_result;
+            }
+        }
+    }};
+
+    ($duration:expr, $from:expr, $default_result:expr) => {
+        $crate::ok_or_return!(
+            $crate::ok_or_return!(
+                $crate::__private::tokio::time::timeout($duration, $from).await,
+                $default_result
+            ),
+            $default_result
+        )
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    const LIMIT: Duration = Duration::from_millis(50);
+    const TOO_LONG: Duration = Duration::from_secs(5);
+
+    async fn respond(
+        delay: Duration,
+        response: Result<i32, &'static str>,
+    ) -> Result<i32, &'static str> {
+        tokio::time::sleep(delay).await;
+        response
+    }
+
+    async fn increment(value: &mut i32, delay: Duration, response: Result<i32, &'static str>) {
+        *value += timeout_or_return!(LIMIT, respond(delay, response));
+    }
+
+    async fn with_default(delay: Duration, response: Result<i32, &'static str>) -> i32 {
+        timeout_or_return!(LIMIT, respond(delay, response), -1)
+    }
+
+    async fn classify(delay: Duration, response: Result<i32, &'static str>) -> &'static str {
+        timeout_or_return!(
+            LIMIT,
+            respond(delay, response),
+            elapsed => "elapsed",
+            error => "error"
+        );
+        "ok"
+    }
+
+    #[tokio::test]
+    async fn should_get_value_within_time_limit() {
+        let mut value = 0;
+        increment(&mut value, Duration::ZERO, Ok(2)).await;
+        assert_eq!(value, 2);
+        assert_eq!(with_default(Duration::ZERO, Ok(3)).await, 3);
+    }
+
+    #[tokio::test]
+    async fn should_return_early_when_time_limit_elapses() {
+        let mut value = 0;
+        increment(&mut value, TOO_LONG, Ok(2)).await;
+        assert_eq!(value, 0);
+        assert_eq!(with_default(TOO_LONG, Ok(3)).await, -1);
+    }
+
+    #[tokio::test]
+    async fn should_return_early_when_future_fails() {
+        let mut value = 0;
+        increment(&mut value, Duration::ZERO, Err("failed")).await;
+        assert_eq!(value, 0);
+        assert_eq!(with_default(Duration::ZERO, Err("failed")).await, -1);
+    }
+
+    #[tokio::test]
+    async fn should_distinguish_elapsed_from_error() {
+        assert_eq!(classify(Duration::ZERO, Ok(1)).await, "ok");
+        assert_eq!(classify(Duration::ZERO, Err("failed")).await, "error");
+        assert_eq!(classify(TOO_LONG, Ok(1)).await, "elapsed");
+    }
+}
@@ -0,0 +1,744 @@
+/// Either get the value from an Option type or return from the current function, emitting a
+/// `tracing` event at the given level first if there is none. A default return value can be
+/// provided before the level. The event is recorded inside the active span, with the message, the
+/// kind of exit as the `early_exit` field and the location (file, line and column) of the macro
+/// invocation as the `location` field.
+/// ```
+/// use early_returns::some_or_return_traced;
+///
+/// #[tracing::instrument]
+/// fn frobnicate(frobnicator: Option<&'static str>) -> Option<usize> {
+///     let frobnicator =
+///         some_or_return_traced!(frobnicator, None, level: warn, "missing frobnicator");
+///     Some(frobnicator.len())
+/// }
+///
+/// assert_eq!(frobnicate(Some("abc")), Some(3));
+/// assert_eq!(frobnicate(None), None);
+/// ```
+#[macro_export]
+macro_rules! some_or_return_traced {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "return",
+                    location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "return",
+                    location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or break out of a loop, emitting a `tracing` event at
+/// the given level first if there is none. If a loop lifetime is specified (before the level), that
+/// loop will be exited, otherwise the immediate loop is exited. The event is recorded inside the
+/// active span, with the message, the kind of exit as the `early_exit` field and the location
+/// (file, line and column) of the macro invocation as the `location` field.
+/// ```
+/// use early_returns::some_or_break_traced;
+///
+/// let mut read = Vec::new();
+/// for value in [Some(1), Some(2), None, Some(4)] {
+///     read.push(some_or_break_traced!(value, level: info, "stopped reading"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! some_or_break_traced {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "break",
+                    location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "break",
+                    location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or continue in a loop, emitting a `tracing` event at
+/// the given level first if there is none. If a loop lifetime is specified (before the level), that
+/// loop will be "continued", otherwise the immediate loop is "continued". The event is recorded
+/// inside the active span, with the message, the kind of exit as the `early_exit` field and the
+/// location (file, line and column) of the macro invocation as the `location` field.
+/// ```
+/// use early_returns::some_or_continue_traced;
+///
+/// let mut read = Vec::new();
+/// for (row, value) in [Some(1), None, Some(3)].into_iter().enumerate() {
+///     read.push(some_or_continue_traced!(value, level: debug, row, "skipping row"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! some_or_continue_traced {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "continue",
+                    location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "continue",
+                    location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or return from the current function, emitting a
+/// `tracing` event at the given level first if it is an `Err`. A default return value can be
+/// provided before the level. The event is recorded inside the active span, with the message, the
+/// kind of exit as the `early_exit` field, the `Debug` of the error as the `error` field and the
+/// location (file, line and column) of the macro invocation as the `location` field.
+/// ```
+/// use early_returns::ok_or_return_traced;
+///
+/// fn port(value: &str) {
+///     let port: u16 = ok_or_return_traced!(value.parse(), level: error, value, "bad port");
+///     println!("listening on {port}");
+/// }
+///
+/// port("8080");
+/// port("http");
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_traced {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "return",
+                    error = ?e, location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "return",
+                    error = ?e, location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or break out of a loop, emitting a `tracing` event at
+/// the given level first if it is an `Err`. If a loop lifetime is specified (before the level),
+/// that loop will be exited, otherwise the immediate loop is exited. The event is recorded inside
+/// the active span, with the message, the kind of exit as the `early_exit` field, the `Debug` of
+/// the error as the `error` field and the location (file, line and column) of the macro invocation
+/// as the `location` field.
+/// ```
+/// use early_returns::ok_or_break_traced;
+///
+/// let mut read = Vec::new();
+/// for value in [Ok(1), Ok(2), Err("eof"), Ok(4)] {
+///     read.push(ok_or_break_traced!(value, level: info, "stopped reading"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_break_traced {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "break",
+                    error = ?e, location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "break",
+                    error = ?e, location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or continue in a loop, emitting a `tracing` event at
+/// the given level first if it is an `Err`. If a loop lifetime is specified (before the level),
+/// that loop will be "continued", otherwise the immediate loop is "continued". The event is
+/// recorded inside the active span, with the message, the kind of exit as the `early_exit` field,
+/// the `Debug` of the error as the `error` field and the location (file, line and column) of the
+/// macro invocation as the `location` field.
+/// ```
+/// use early_returns::ok_or_continue_traced;
+///
+/// let mut read = Vec::new();
+/// for (row, value) in [Ok(1), Err("bad row"), Ok(3)].into_iter().enumerate() {
+///     read.push(ok_or_continue_traced!(value, level: debug, row, "skipping row"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_continue_traced {
+    ($from:expr, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "continue",
+                    error = ?e, location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, level: $level:ident, $($arg:tt)+) => {{
+        match $from {
+            Ok(f) => f,
+            Err(e) => {
+                $crate::__private::tracing::$level!(
+                    early_exit = "continue",
+                    error = ?e, location = %$crate::__private::caller_location(),
+                    $($arg)+
+                );
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or return from the current function, recording the
+/// given reason as the `early_return` field of the current span first if there is none. A default
+/// return value can be provided before the reason.
+///
+/// As with any `tracing` field, the span has to declare `early_return` up front (e.g. with
+/// `early_return = tracing::field::Empty`) for the recorded value to show up.
+/// ```
+/// use early_returns::some_or_return_recorded;
+///
+/// #[tracing::instrument(fields(early_return = tracing::field::Empty))]
+/// fn load(config: Option<&'static str>) -> Option<usize> {
+///     let config = some_or_return_recorded!(config, None, reason: "config_missing");
+///     Some(config.len())
+/// }
+///
+/// assert_eq!(load(Some("abc")), Some(3));
+/// assert_eq!(load(None), None);
+/// ```
+#[macro_export]
+macro_rules! some_or_return_recorded {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::record_early_return($reason);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::record_early_return($reason);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or break out of a loop, recording the given reason as
+/// the `early_return` field of the current span first if there is none. If a loop lifetime is
+/// specified (before the reason), that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::some_or_break_recorded;
+///
+/// let _span = tracing::info_span!("read", early_return = tracing::field::Empty).entered();
+/// let mut read = Vec::new();
+/// for value in [Some(1), Some(2), None, Some(4)] {
+///     read.push(some_or_break_recorded!(value, reason: "end_of_input"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! some_or_break_recorded {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::record_early_return($reason);
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::record_early_return($reason);
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the value from an Option type or continue in a loop, recording the given reason as
+/// the `early_return` field of the current span first if there is none. If a loop lifetime is
+/// specified (before the reason), that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::some_or_continue_recorded;
+///
+/// let _span = tracing::info_span!("read", early_return = tracing::field::Empty).entered();
+/// let mut read = Vec::new();
+/// for value in [Some(1), None, Some(3)] {
+///     read.push(some_or_continue_recorded!(value, reason: "skipped_row"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! some_or_continue_recorded {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::record_early_return($reason);
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, reason: $reason:expr) => {{
+        match $from {
+            Some(f) => f,
+            None => {
+                $crate::__private::record_early_return($reason);
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or return from the current function, recording the
+/// given reason as the `early_return` field of the current span first if it is an `Err`. A default
+/// return value can be provided before the reason.
+/// ```
+/// use early_returns::ok_or_return_recorded;
+///
+/// #[tracing::instrument(fields(early_return = tracing::field::Empty))]
+/// fn port(value: &str) {
+///     let port: u16 = ok_or_return_recorded!(value.parse(), reason: "bad_port");
+///     println!("listening on {port}");
+/// }
+///
+/// port("8080");
+/// port("http");
+/// ```
+#[macro_export]
+macro_rules! ok_or_return_recorded {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::record_early_return($reason);
+                $crate::__mark_early_exit!();
+                return;
+            }
+        }
+    }};
+
+    ($from:expr, $default_result:expr, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::record_early_return($reason);
+                $crate::__mark_early_exit!();
+                return $default_result;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or break out of a loop, recording the given reason as
+/// the `early_return` field of the current span first if it is an `Err`. If a loop lifetime is
+/// specified (before the reason), that loop will be exited, otherwise the immediate loop is exited.
+/// ```
+/// use early_returns::ok_or_break_recorded;
+///
+/// let _span = tracing::info_span!("read", early_return = tracing::field::Empty).entered();
+/// let mut read = Vec::new();
+/// for value in [Ok(1), Ok(2), Err("eof"), Ok(4)] {
+///     read.push(ok_or_break_recorded!(value, reason: "end_of_input"));
+/// }
+/// assert_eq!(read, vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_break_recorded {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::record_early_return($reason);
+                break;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::record_early_return($reason);
+                break $lt;
+            }
+        }
+    }};
+}
+
+/// Either get the Ok value from a Result type or continue in a loop, recording the given reason as
+/// the `early_return` field of the current span first if it is an `Err`. If a loop lifetime is
+/// specified (before the reason), that loop will be "continued", otherwise the immediate loop is
+/// "continued".
+/// ```
+/// use early_returns::ok_or_continue_recorded;
+///
+/// let _span = tracing::info_span!("read", early_return = tracing::field::Empty).entered();
+/// let mut read = Vec::new();
+/// for value in [Ok(1), Err("bad row"), Ok(3)] {
+///     read.push(ok_or_continue_recorded!(value, reason: "skipped_row"));
+/// }
+/// assert_eq!(read, vec![1, 3]);
+/// ```
+#[macro_export]
+macro_rules! ok_or_continue_recorded {
+    ($from:expr, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::record_early_return($reason);
+                continue;
+            }
+        }
+    }};
+
+    ($from:expr, $lt:lifetime, reason: $reason:expr) => {{
+        match $from {
+            Ok(f) => f,
+            Err(_) => {
+                $crate::__private::record_early_return($reason);
+                continue $lt;
+            }
+        }
+    }};
+}
+
+/// Records `reason` as the `early_return` field of the current span.
+#[doc(hidden)]
+pub fn record_early_return(reason: &str) {
+    ::tracing::Span::current().record("early_return", reason);
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_core::span::Current;
+    use tracing::{Event, Metadata, Subscriber};
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        events: Arc<Mutex<Vec<String>>>,
+        entered: Arc<Mutex<Vec<&'static Metadata<'static>>>>,
+    }
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            let value = format!("{value:?}");
+            let value = match field.name() {
+                "location" => value.split(':').next().unwrap().to_string(),
+                _ => value,
+            };
+            self.0.push_str(&format!(" {}={}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.entered.lock().unwrap().push(span.metadata());
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &Id, values: &Record<'_>) {
+            let mut fields = Fields("SPAN".to_string());
+            values.record(&mut fields);
+            self.events.lock().unwrap().push(fields.0);
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(event.metadata().level().to_string());
+            event.record(&mut fields);
+            self.events.lock().unwrap().push(fields.0);
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {
+            self.entered.lock().unwrap().pop();
+        }
+
+        fn current_span(&self) -> Current {
+            match self.entered.lock().unwrap().last() {
+                Some(metadata) => Current::new(Id::from_u64(1), metadata),
+                None => Current::none(),
+            }
+        }
+    }
+
+    fn record<F: FnOnce()>(f: F) -> Vec<String> {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), f);
+        let events = recorder.events.lock().unwrap().clone();
+        events
+    }
+
+    fn value_or_default(value: Option<i32>) -> i32 {
+        some_or_return_traced!(value, -1, level: warn, "missing value")
+    }
+
+    fn push_ok(value: Result<i32, &str>, values: &mut Vec<i32>) {
+        values.push(ok_or_return_traced!(value, level: error, attempt = 2, "push failed"));
+    }
+
+    #[test]
+    fn should_emit_event_and_return_early() {
+        let mut values = Vec::new();
+        let events = record(|| {
+            assert_eq!(value_or_default(Some(2)), 2);
+            assert_eq!(value_or_default(None), -1);
+            push_ok(Ok(1), &mut values);
+            push_ok(Err("disk full"), &mut values);
+        });
+        assert_eq!(values, vec![1]);
+        assert_eq!(
+            events,
+            vec![
+                "WARN message=missing value early_exit=\"return\" location=src/tracing.rs",
+                "ERROR message=push failed early_exit=\"return\" error=\"disk full\" location=src/tracing.rs attempt=2",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_emit_event_and_break() {
+        let mut sum = 0;
+        let events = record(|| {
+            'l: for value in [Some(1), None, Some(3)] {
+                for _i in 0..2 {
+                    sum += some_or_break_traced!(value, 'l, level: info, "break some");
+                }
+            }
+            for value in [Ok(4), Err(5), Ok(6)] {
+                sum += ok_or_break_traced!(value, level: info, "break ok");
+            }
+        });
+        assert_eq!(sum, 6);
+        assert_eq!(
+            events,
+            vec![
+                "INFO message=break some early_exit=\"break\" location=src/tracing.rs",
+                "INFO message=break ok early_exit=\"break\" error=5 location=src/tracing.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_emit_event_and_continue() {
+        let mut sum = 0;
+        let events = record(|| {
+            for value in [Some(1), None, Some(3)] {
+                sum += some_or_continue_traced!(value, level: debug, "continue some");
+            }
+            'l: for value in [Ok(4), Err(5), Ok(6)] {
+                for _i in 0..2 {
+                    sum += ok_or_continue_traced!(value, 'l, level: trace, "continue ok");
+                }
+            }
+        });
+        assert_eq!(sum, 24);
+        assert_eq!(
+            events,
+            vec![
+                "DEBUG message=continue some early_exit=\"continue\" location=src/tracing.rs",
+                "TRACE message=continue ok early_exit=\"continue\" error=5 location=src/tracing.rs",
+            ]
+        );
+    }
+
+    fn load(config: Option<&str>) -> usize {
+        let span = tracing::info_span!("load", early_return = tracing::field::Empty);
+        let _entered = span.enter();
+        some_or_return_recorded!(config, 0, reason: "config_missing").len()
+    }
+
+    fn parse(value: &str, parsed: &mut Vec<u16>) {
+        let span = tracing::info_span!("parse", early_return = tracing::field::Empty);
+        let _entered = span.enter();
+        parsed.push(ok_or_return_recorded!(value.parse(), reason: "bad_number"));
+    }
+
+    #[test]
+    fn should_record_span_field_and_return_early() {
+        let mut parsed = Vec::new();
+        let events = record(|| {
+            assert_eq!(load(Some("abc")), 3);
+            assert_eq!(load(None), 0);
+            parse("1", &mut parsed);
+            parse("x", &mut parsed);
+        });
+        assert_eq!(parsed, vec![1]);
+        assert_eq!(
+            events,
+            vec![
+                "SPAN early_return=\"config_missing\"",
+                "SPAN early_return=\"bad_number\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_record_span_field_and_break_or_continue() {
+        let mut sum = 0;
+        let events = record(|| {
+            let span = tracing::info_span!("sum", early_return = tracing::field::Empty);
+            let _entered = span.enter();
+            for value in [Some(1), None, Some(3)] {
+                sum += some_or_continue_recorded!(value, reason: "skip some");
+            }
+            'l: for value in [Ok(4), Err(5), Ok(6)] {
+                for _i in 0..2 {
+                    sum += ok_or_break_recorded!(value, 'l, reason: "stop ok");
+                }
+            }
+            for value in [Err(()), Ok(1)] {
+                sum += ok_or_continue_recorded!(value, reason: "skip ok");
+            }
+            'l: for value in [None, Some(1)] {
+                for _i in 0..2 {
+                    sum += some_or_break_recorded!(value, 'l, reason: "stop some");
+                }
+            }
+        });
+        assert_eq!(sum, 13);
+        assert_eq!(
+            events,
+            vec![
+                "SPAN early_return=\"skip some\"",
+                "SPAN early_return=\"stop ok\"",
+                "SPAN early_return=\"skip ok\"",
+                "SPAN early_return=\"stop some\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_emit_event_for_unconditional_escapes() {
+        let events = record(|| {
+            for value in [1, 2, 3] {
+                if value == 2 {
+                    crate::continue_early!(trace debug "skip two");
+                }
+            }
+        });
+        assert_eq!(
+            events,
+            vec!["DEBUG message=skip two early_exit=\"continue\" location=src/tracing.rs"]
+        );
+    }
+}
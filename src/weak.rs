@@ -0,0 +1,199 @@
+/// Either upgrade a weak pointer to a strong one or return from the current function if the value
+/// has already been dropped. A default return value can be provided.
+///
+/// Works with both `std::rc::Weak` and `std::sync::Weak`.
+/// ```
+/// use early_returns::upgrade_or_return;
+/// use std::rc::{Rc, Weak};
+///
+/// fn notify(listener: &Weak<String>) -> Option<usize> {
+///     let listener = upgrade_or_return!(listener, None);
+///     Some(listener.len())
+/// }
+///
+/// let listener = Rc::new("listener".to_string());
+/// let weak = Rc::downgrade(&listener);
+/// assert_eq!(notify(&weak), Some(8));
+/// drop(listener);
+/// assert_eq!(notify(&weak), None);
+/// ```
+#[macro_export]
+macro_rules! upgrade_or_return {
+    ($weak:expr) => {
+        $crate::some_or_return!($weak.upgrade())
+    };
+
+    ($weak:expr, $default_result:expr) => {
+        $crate::some_or_return!($weak.upgrade(), $default_result)
+    };
+}
+
+/// Either upgrade a weak pointer to a strong one or break out of a loop if the value has already
+/// been dropped. If a loop lifetime is specified, that loop will be exited, otherwise the
+/// immediate loop is exited.
+///
+/// Works with both `std::rc::Weak` and `std::sync::Weak`.
+/// ```
+/// use early_returns::upgrade_or_break;
+/// use std::sync::{Arc, Weak};
+///
+/// let owner = Arc::new(1);
+/// let weak: Weak<i32> = Arc::downgrade(&owner);
+/// let mut owner = Some(owner);
+///
+/// let mut ticks = 0;
+/// loop {
+///     let value = upgrade_or_break!(weak);
+///     ticks += *value;
+///     owner = None;
+/// }
+/// assert_eq!(ticks, 1);
+/// assert!(owner.is_none());
+/// ```
+#[macro_export]
+macro_rules! upgrade_or_break {
+    ($weak:expr) => {
+        $crate::some_or_break!($weak.upgrade())
+    };
+
+    ($weak:expr, $lt:lifetime) => {
+        $crate::some_or_break!($weak.upgrade(), $lt)
+    };
+}
+
+/// Either upgrade a weak pointer to a strong one or continue in a loop if the value has already
+/// been dropped. If a loop lifetime is specified, that loop will be "continued", otherwise the
+/// immediate loop is "continued".
+///
+/// Works with both `std::rc::Weak` and `std::sync::Weak`.
+/// ```
+/// use early_returns::upgrade_or_continue;
+/// use std::rc::Rc;
+///
+/// let alive = Rc::new(1);
+/// let dropped = Rc::new(2);
+/// let observers = vec![Rc::downgrade(&alive), Rc::downgrade(&dropped)];
+/// drop(dropped);
+///
+/// let mut notified = Vec::new();
+/// for observer in &observers {
+///     let observer = upgrade_or_continue!(observer);
+///     notified.push(*observer);
+/// }
+/// assert_eq!(notified, vec![1]);
+/// ```
+#[macro_export]
+macro_rules! upgrade_or_continue {
+    ($weak:expr) => {
+        $crate::some_or_continue!($weak.upgrade())
+    };
+
+    ($weak:expr, $lt:lifetime) => {
+        $crate::some_or_continue!($weak.upgrade(), $lt)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::{self, Rc};
+    use std::sync::{self, Arc};
+
+    struct Tester {
+        value: i32,
+    }
+
+    impl Tester {
+        fn new() -> Tester {
+            Tester { value: 0 }
+        }
+
+        fn increment_with_rc(&mut self, weak: &rc::Weak<i32>) {
+            let value = upgrade_or_return!(weak);
+            self.value += *value;
+        }
+
+        fn increment_with_arc(&mut self, weak: &sync::Weak<i32>) {
+            let value = upgrade_or_return!(weak);
+            self.value += *value;
+        }
+
+        fn increment_with_break(&mut self, weaks: &[rc::Weak<i32>]) {
+            for weak in weaks {
+                self.value += *upgrade_or_break!(weak);
+            }
+        }
+
+        fn increment_with_break_with_lifetime(&mut self, weaks: &[sync::Weak<i32>]) {
+            'l: for weak in weaks {
+                self.value += 1;
+                for _i in 0..1 {
+                    self.value += *upgrade_or_break!(weak, 'l);
+                }
+            }
+        }
+
+        fn increment_with_continue(&mut self, weaks: &[rc::Weak<i32>]) {
+            for weak in weaks {
+                self.value += *upgrade_or_continue!(weak);
+            }
+        }
+
+        fn increment_with_continue_with_lifetime(&mut self, weaks: &[sync::Weak<i32>]) {
+            'l: for weak in weaks {
+                self.value += 1;
+                for _i in 0..1 {
+                    self.value += *upgrade_or_continue!(weak, 'l);
+                }
+            }
+        }
+    }
+
+    fn upgraded_or_default(weak: &rc::Weak<i32>) -> i32 {
+        *upgrade_or_return!(weak, -1)
+    }
+
+    #[test]
+    fn should_upgrade_live_pointers() {
+        let rc = Rc::new(1);
+        let arc = Arc::new(2);
+        let mut tester = Tester::new();
+        tester.increment_with_rc(&Rc::downgrade(&rc));
+        tester.increment_with_arc(&Arc::downgrade(&arc));
+        assert_eq!(tester.value, 3);
+        assert_eq!(upgraded_or_default(&Rc::downgrade(&rc)), 1);
+    }
+
+    #[test]
+    fn should_return_early_when_dropped() {
+        let mut tester = Tester::new();
+        tester.increment_with_rc(&rc::Weak::new());
+        let arc = Arc::new(2);
+        let weak = Arc::downgrade(&arc);
+        drop(arc);
+        tester.increment_with_arc(&weak);
+        assert_eq!(tester.value, 0);
+        assert_eq!(upgraded_or_default(&rc::Weak::new()), -1);
+    }
+
+    #[test]
+    fn should_break_when_dropped() {
+        let rc = Rc::new(1);
+        let arc = Arc::new(2);
+        let mut tester = Tester::new();
+        tester.increment_with_break(&[rc::Weak::new(), Rc::downgrade(&rc)]);
+        assert_eq!(tester.value, 0);
+        tester.increment_with_break_with_lifetime(&[sync::Weak::new(), Arc::downgrade(&arc)]);
+        assert_eq!(tester.value, 1);
+    }
+
+    #[test]
+    fn should_continue_when_dropped() {
+        let rc = Rc::new(1);
+        let arc = Arc::new(2);
+        let mut tester = Tester::new();
+        tester.increment_with_continue(&[rc::Weak::new(), Rc::downgrade(&rc)]);
+        assert_eq!(tester.value, 1);
+        tester.increment_with_continue_with_lifetime(&[sync::Weak::new(), Arc::downgrade(&arc)]);
+        assert_eq!(tester.value, 5);
+    }
+}